@@ -163,13 +163,13 @@ fn perform_decl_refactoring(
                     syn::UseTree::Path(
                         syn::UsePath {
                             ident: module_ident.clone(), // Use clone to avoid moving
-                            colon2_token: Some(syn::token::Colon2::new(Span::call_site())),
+                            colon2_token: Default::default(),
                             tree: Box::new(group_items.into_iter().next().unwrap())
                         }
                     )
                 } else {
                     syn::UseTree::Group(syn::UseGroup {
-                        brace_token: syn::token::Brace(Span::call_site()), // Corrected field name
+                        brace_token: Default::default(),
                         items: group_items,
                     })
                 };
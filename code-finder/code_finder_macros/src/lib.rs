@@ -6,7 +6,7 @@ use syn::{parse_macro_input, LitStr, Ident, Token, parse::{Parse, ParseStream},
 
 // Placeholder for the rg! macro
 #[proc_macro]
-#[decl(fn, name = "rg", vis = "pub", hash = "c5559555")]
+#[decl(fn, name = "rg", vis = "pub", hash = "09620b1c")]
 pub fn rg(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let description_content = description.value();
@@ -22,7 +22,7 @@ pub fn rg(input: TokenStream) -> TokenStream {
 
 // Placeholder for the model_shell_script! macro
 #[proc_macro]
-#[decl(fn, name = "model_shell_script", vis = "pub", hash = "79ebbce5")]
+#[decl(fn, name = "model_shell_script", vis = "pub", hash = "eef552ac")]
 pub fn model_shell_script(input: TokenStream) -> TokenStream {
     // This is a placeholder. In a real scenario, this would parse a JSON description
     // of a shell script and generate Rust code to model its behavior.
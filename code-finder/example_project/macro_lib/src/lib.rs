@@ -53,7 +53,7 @@ impl Parse for ProcessMatchInput {
 }
 
 #[proc_macro]
-#[decl(fn, name = "process_match", vis = "pub", hash = "c80b5286")]
+#[decl(fn, name = "process_match", vis = "pub", hash = "735d52b3")]
 pub fn process_match(input: TokenStream) -> TokenStream {
     let ProcessMatchInput {
         file,
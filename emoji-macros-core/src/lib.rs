@@ -8,7 +8,7 @@ use proc_macro2::{Ident, TokenTree}; // Corrected import for TokenTree, and Iden
 // For now, it will simply parse its input and expand to nothing to allow compilation.
 // A full implementation would parse the arguments and generate a new #[proc_macro] definition.
 #[proc_macro]
-#[decl(fn, name = "define_emoji_macro", vis = "pub", hash = "10d99e5c")]
+#[decl(fn, name = "define_emoji_macro", vis = "pub", hash = "a8d1040e")]
 pub fn define_emoji_macro(input: TokenStream) -> TokenStream {
     // Convert the input proc_macro::TokenStream to proc_macro2::TokenStream
     let input2: proc_macro2::TokenStream = input.into();
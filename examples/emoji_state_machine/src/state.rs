@@ -1,4 +1,4 @@
 // This is the base state of our emoji state machine.
 // It starts as an empty struct, and the emoji macros will add fields to it.
-#[decl(struct, name = "MemeState", vis = "pub", hash = "718a68f5")]
+#[decl(struct, name = "MemeState", vis = "pub", hash = "d63a6938")]
 pub struct MemeState {}
\ No newline at end of file
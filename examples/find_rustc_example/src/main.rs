@@ -1,24 +1,38 @@
 // examples/find_rustc_example.rs
 // This file demonstrates the usage of the find_nix_rustc! macro.
+// `store/` is a fixture store, so the example builds without Nix; drop the
+// `store = ...` arguments to search /nix/store instead.
 
 use nix2proc_macros::find_nix_rustc;
 
 fn main() {
-    println!("Searching for Rust compilers in /nix/store...");
+    println!("Searching for Rust compilers in the fixture store...");
 
-    // Example 1: Find all rustc paths
-    let all_rustc_paths: Vec<String> = find_nix_rustc!();
+    // Example 1: Find all rustc paths, best first
+    let all_rustc_paths: Vec<String> = find_nix_rustc!(store = "store");
     println!("\nFound ALL Rust compilers:");
     for path in all_rustc_paths {
-        println!("  {}\n", path);
+        println!("  {}", path);
     }
 
-    // Example 2: Find rustc paths containing "1.91"
-    let rustc_191_paths: Vec<String> = find_nix_rustc!("1.91");
-    println!("\nFound Rust compilers containing '1.91':");
-    for path in rustc_191_paths {
-        println!("  {}\n", path);
+    // Example 2: Find rustc paths containing "1.79"
+    let rustc_179_paths: Vec<String> = find_nix_rustc!(store = "store", contains = "1.79");
+    println!("\nFound Rust compilers containing '1.79':");
+    for path in rustc_179_paths {
+        println!("  {}", path);
     }
-    
+
+    // Example 3: The newest stable release in a version range
+    let stable = find_nix_rustc!(store = "store", version = ">=1.70, <1.80", channel = "stable");
+    println!("\nNewest stable 1.70..1.80: {}", stable[0]);
+
+    // Example 4: A recent nightly
+    let nightly = find_nix_rustc!(store = "store", channel = "nightly", date = ">=2024-04-01");
+    println!("Nightly since 2024-04-01: {}", nightly[0]);
+
+    // Example 5: A compiler that ships std for wasm
+    let wasm = find_nix_rustc!(store = ["store", "/nix/store"], target = "wasm32-unknown-unknown");
+    println!("With wasm32 std: {}", wasm[0]);
+
     println!("\nSearch complete.");
-}
\ No newline at end of file
+}
@@ -1,212 +1,56 @@
 // Macro Usage Report - Tower of Reflection + Grep2Code Morphism
-// Uses existing framework to generate real macro usage statistics
+// Parses every crate in the workspace and reports real macro usage statistics
 
-use patch_build_rs_macros::{
-    pure_reflect, grast_extract, find_nix_rustc, 
-    unified_codebase, llm_redundancy
+use introspector_core::macro_report::{
+    analyze_workspace_macros, find_workspace_root, generate_rdf_turtle_report, print_macro_report,
 };
+use std::path::PathBuf;
 
 fn main() {
     println!("📊 Macro Usage Report - Tower of Reflection Analysis");
-    
-    // 1. Source Ingestion and Reflective Ascent
-    let rustc_source = find_nix_rustc!("stable");
-    let current_repo = ".";
-    
-    // Lift code through Tower of Reflection (Level 3: Total Reflection)
-    let reflected_repo = pure_reflect!(current_repo);
-    
-    // 2. Semantic Indexing via VFS
-    let vfs_structure = unified_codebase!(current_repo);
-    
-    // 3. Structural Extraction with grast!
-    let macro_patterns = grast_extract!("MacroInvocation");
-    
-    // 4. Statistical Summarization via AI Macros
-    let usage_analysis = llm_redundancy!("Generate macro usage statistics sorted by frequency");
-    
-    println!("📊 Analysis complete:");
-    println!("🔍 Source reflection: {} chars", reflected_repo.len());
-    println!("📁 VFS structure: {} lines", vfs_structure.lines().count());
-    println!("🌳 GRAST patterns: {} macro invocations found", macro_patterns.matches(":MacroInvocation").count());
-    println!("🤖 LLM analysis: {} statistical insights", usage_analysis.lines().count());
-    
-    // Generate comprehensive macro usage report
-    let macro_usage_report = format!(
-        r#"# 📊 Macro Usage Report - Tower of Reflection Analysis
 
-## Methodology
-
-This report uses the **patch-build-rs** framework's architectural components:
-
-### 1. Source Ingestion and Reflective Ascent
-{}
-
-### 2. Semantic Indexing via VFS
-{}
-
-### 3. Structural Extraction with GRAST
-{}
-
-### 4. Statistical Summarization via AI
-{}
-
-## Real Macro Usage Statistics (from actual analysis)
-
-Based on our repository analysis using `grep` commands:
-
-### Top 10 Most Used Macros in Our Codebase:
-
-1. **quote!** - 247 occurrences
-   - Usage: TokenStream generation in procedural macros
-   - Pattern: `quote! {{ ... }}.into()`
-   - Files: All macro implementation files
-
-2. **println!** (cargo warnings) - 203 occurrences  
-   - Usage: Build script warnings and debug output
-   - Pattern: `println!("cargo:warning=...")`
-   - Files: All macro implementations
-
-3. **format!** - ~150+ occurrences (estimated)
-   - Usage: String formatting in macro output
-   - Pattern: `format!(r###"..."###, ...)`
-   - Files: Most macro implementations
-
-4. **parse_macro_input!** - ~100+ occurrences (estimated)
-   - Usage: Input parsing in procedural macros
-   - Pattern: `parse_macro_input!(input as LitStr)`
-   - Files: All macro implementations
-
-5. **vec!** - ~80+ occurrences (estimated)
-   - Usage: Vector creation in analysis code
-   - Pattern: `vec![item1, item2, ...]`
-   - Files: Analysis and data structure code
-
-6. **format_args!** - ~60+ occurrences (estimated)
-   - Usage: Efficient string formatting
-   - Pattern: Internal to format! macro
-   - Files: Generated by format! usage
-
-7. **include_str!** - ~40+ occurrences (estimated)
-   - Usage: Including template strings
-   - Pattern: `include_str!("template.txt")`
-   - Files: Template-based macros
-
-8. **concat!** - ~30+ occurrences (estimated)
-   - Usage: Compile-time string concatenation
-   - Pattern: `concat!("prefix", suffix)`
-   - Files: Path and identifier generation
-
-9. **stringify!** - ~25+ occurrences (estimated)
-   - Usage: Converting tokens to strings
-   - Pattern: `stringify!(token)`
-   - Files: Debug and introspection code
-
-10. **cfg!** - ~20+ occurrences (estimated)
-    - Usage: Conditional compilation
-    - Pattern: `cfg!(feature = "...")`
-    - Files: Feature-gated code
-
-## VFS Structure Analysis
-
-The functional VFS at `/proc/grast/rust_code/` contains:
-
-```
-/proc/grast/rust_code/
-├── macros/
-│   ├── quote_invocations.json (247 entries)
-│   ├── println_invocations.json (203 entries)
-│   └── format_invocations.json (150+ entries)
-├── call_graph_in.json (macro dependency graph)
-└── semantic_hash.txt (macro identity mapping)
-```
-
-## GRAST RDF Analysis
-
-Structural patterns identified:
-```turtle
-@prefix macro: <http://rust-lang.org/macro/> .
-
-macro:quote a :MacroInvocation ;
-    :frequency 247 ;
-    :pattern "quote! { ... }.into()" ;
-    :usage_type "TokenStream generation" .
-
-macro:println a :MacroInvocation ;
-    :frequency 203 ;
-    :pattern "println!(\"cargo:warning=...\")" ;
-    :usage_type "Build warnings" .
-```
-
-## Performance Characteristics
-
-- **Analysis Time**: ~2 seconds (cached via #[lru])
-- **Memory Usage**: ~50MB for complete reflection
-- **Accuracy**: 100% (real file analysis)
-- **Coverage**: Complete repository scan
-
-## Refactoring Opportunities
-
-1. **Extract quote! patterns** → Create quote_helper! macro
-2. **Standardize println! warnings** → Create warning! macro  
-3. **Template format! strings** → Create template! macro
-4. **Unify parse_macro_input!** → Create input_parser! macro
-
-## Verification
-
-All statistics can be verified with:
-```bash
-grep -r "quote!" . --include="*.rs" | wc -l  # Should show 247
-grep -r "println!(\"cargo:warning=" . --include="*.rs" | wc -l  # Should show 203
-```
-
-**🎯 Real macro usage analysis using Tower of Reflection architecture!**
-        "#,
-        reflected_repo.lines().take(10).collect::<Vec<_>>().join("\n"),
-        vfs_structure.lines().take(15).collect::<Vec<_>>().join("\n"),
-        macro_patterns.lines().take(10).collect::<Vec<_>>().join("\n"),
-        usage_analysis.lines().take(10).collect::<Vec<_>>().join("\n")
-    );
-    
-    // Save the comprehensive report
-    std::fs::write("MACRO_USAGE_REPORT.md", &macro_usage_report).ok();
-    
-    // Save individual analysis components
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let root = find_workspace_root(&cwd).unwrap_or(cwd);
+    let report = analyze_workspace_macros(&root);
+    print_macro_report(&report);
+
+    let mut md = String::new();
+    md.push_str("# 📊 Macro Usage Report - Tower of Reflection Analysis\n\n");
+    md.push_str("> Generated by `cargo run --example macro_usage_report` from the workspace sources.\n\n");
+
+    md.push_str("## Global Statistics\n\n| Metric | Count |\n|--------|-------|\n");
+    md.push_str(&format!("| 📦 Crates | {} |\n", report.crates.len()));
+    md.push_str(&format!("| ⚙️ Proc Macros Exported | {} |\n", report.total_proc_macros));
+    md.push_str(&format!("| 📜 macro_rules! Definitions | {} |\n", report.total_macro_rules));
+    md.push_str(&format!("| 🔧 Implementation Functions | {} |\n", report.total_impl_functions));
+    md.push_str(&format!("| 📞 Invocation Sites | {} |\n", report.total_invocations()));
+    md.push_str(&format!("| 📁 Modules | {} |\n\n", report.modules.len()));
+
+    md.push_str("## Most Used Macros\n\n| Macro | Sites | Modules |\n|-------|-------|---------|\n");
+    for usage in report.top_macros(20) {
+        let mut modules: Vec<&str> = usage.sites.iter().map(|s| s.module.as_str()).collect();
+        modules.sort();
+        modules.dedup();
+        md.push_str(&format!("| `{}` | {} | {} |\n", usage.name, usage.sites.len(), modules.len()));
+    }
+
+    let unused = report.unused_macros();
+    md.push_str(&format!("\n## Unused Macros ({})\n\n", unused.len()));
+    for definition in unused {
+        md.push_str(&format!("- {} `{}` — `{}:{}`\n", definition.kind.emoji(), definition.name, definition.file, definition.line));
+    }
+
+    if !report.errors.is_empty() {
+        md.push_str(&format!("\n## Skipped Files ({})\n\n", report.errors.len()));
+        for error in &report.errors {
+            md.push_str(&format!("- {}\n", error));
+        }
+    }
+
+    std::fs::write("MACRO_USAGE_REPORT.md", &md).ok();
     std::fs::create_dir_all("usage_analysis").ok();
-    std::fs::write("usage_analysis/reflected_code.txt", &reflected_repo).ok();
-    std::fs::write("usage_analysis/vfs_structure.txt", &vfs_structure).ok();
-    std::fs::write("usage_analysis/grast_patterns.ttl", &macro_patterns).ok();
-    std::fs::write("usage_analysis/llm_statistics.txt", &usage_analysis).ok();
-    
-    // Create call_graph_in.json simulation
-    std::fs::write("usage_analysis/call_graph_in.json", r#"{
-  "quote!": {
-    "frequency": 247,
-    "invocation_sites": ["lib.rs", "rustc_ring.rs", "dao_governance.rs"],
-    "pattern": "quote! { ... }.into()",
-    "semantic_hash": "a7f3b2c1d8e9f4a6"
-  },
-  "println!": {
-    "frequency": 203,
-    "invocation_sites": ["all macro files"],
-    "pattern": "println!(\"cargo:warning=...\")",
-    "semantic_hash": "f2b8c4d6e1a9f7b3"
-  },
-  "format!": {
-    "frequency": 150,
-    "invocation_sites": ["most macro implementations"],
-    "pattern": "format!(r###\"...\"###, ...)",
-    "semantic_hash": "d8e2f4a6b5c9d1e7"
-  }
-}"#).ok();
-    
-    println!("💾 Macro usage report complete!");
-    println!("📊 Used Tower of Reflection architecture:");
-    println!("  1. ✅ Source Ingestion and Reflective Ascent");
-    println!("  2. ✅ Semantic Indexing via VFS");
-    println!("  3. ✅ Structural Extraction with GRAST");
-    println!("  4. ✅ Statistical Summarization via AI");
+    std::fs::write("usage_analysis/macro_usage.ttl", generate_rdf_turtle_report(&report)).ok();
+
     println!("📋 Report: MACRO_USAGE_REPORT.md");
-    println!("📁 Analysis: usage_analysis/ directory");
-    println!("🎯 Real statistics from actual repository analysis!");
+    println!("🌳 RDF: usage_analysis/macro_usage.ttl");
 }
@@ -1,6 +1,8 @@
 // mkbuildrs Demo - Nix-integrated build system with Rust version caching
 // Generates build.rs that manages multiple Rust versions via Nix
 
+use std::path::PathBuf;
+
 use patch_build_rs_macros::{
     mkbuildrs, nix_rust_version, rust_cache
 };
@@ -9,8 +11,7 @@ fn main() {
     println!("🔧 mkbuildrs Demo - Nix-Integrated Build System");
     
     // Generate complete build.rs with Nix integration
-    let config = "nix_integration=true,cache_versions=true,auto_download=true";
-    let build_rs = mkbuildrs!(config);
+    let build_rs = mkbuildrs!("nix_integration=true,cache_versions=true,auto_download=true");
     
     // Generate Nix expressions for specific Rust versions
     let rust_1_70 = nix_rust_version!("1.70.0");
@@ -18,13 +19,25 @@ fn main() {
     let rust_stable = nix_rust_version!("stable");
     let rust_nightly = nix_rust_version!("nightly");
     
-    // Generate Rust version cache system
-    let cache_config = "versions=[1.70.0,1.75.0,1.80.0,stable],methods=[nix,rustup,github]";
-    let cache_system = rust_cache!(cache_config);
+    // Open the content-addressed toolchain cache and seed it from rustup
+    let cache = rust_cache!("target/rust-cache").expect("toolchain cache");
+    let rustup_home = std::env::var_os("RUSTUP_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".rustup")));
+    if let Some(rustup_home) = rustup_home {
+        match cache.import_rustup(&rustup_home, Some("stable")) {
+            Ok(imported) => {
+                for (name, hash) in &imported {
+                    println!("✅ {} cached as sysroot {}", name, &hash[..16]);
+                }
+            }
+            Err(e) => println!("⚠️  {}", e),
+        }
+    }
+    let cache_entries = cache.entries();
     
     println!("🔧 Build system: {} lines", build_rs.lines().count());
     println!("🦀 Nix expressions: 4 versions");
-    println!("📦 Cache system: {} lines", cache_system.lines().count());
+    println!("📦 Toolchain cache: {} entries at {}", cache_entries.len(), cache.root().display());
     
     // Save all generated components
     std::fs::create_dir_all("nix").ok();
@@ -39,9 +52,6 @@ fn main() {
     std::fs::write("nix/rust-stable.nix", &rust_stable).ok();
     std::fs::write("nix/rust-nightly.nix", &rust_nightly).ok();
     
-    // Save cache system
-    std::fs::write("src/rust_cache.rs", &cache_system).ok();
-    
     // Create shell.nix for development
     std::fs::write("shell.nix", r#"
 { pkgs ? import <nixpkgs> {} }:
@@ -74,30 +84,41 @@ pkgs.mkShell {
     
     // Create usage example
     std::fs::write("src/main.rs", r#"
-// Example usage of cached Rust versions
-mod rust_cache;
+// Example usage of the toolchain cache
+use std::path::Path;
 
-use rust_cache::RustCache;
+use introspector_core::{EntryKind, ToolchainCache};
 
-fn main() {
+fn main() -> Result<(), String> {
     println!("🔧 mkbuildrs - Rust Version Manager");
     
-    let mut cache = RustCache::new();
+    let cache = ToolchainCache::open("target/rust-cache")?;
     
-    // Cache different Rust versions
-    let versions = ["1.70.0", "1.75.0", "stable"];
-    
-    for version in &versions {
-        match cache.cache_version(version) {
-            Ok(path) => println!("✅ Rust {} cached at: {:?}", version, path),
-            Err(e) => println!("❌ Failed to cache Rust {}: {}", version, e),
+    // Cache downloaded dist tarballs and pin their sysroots
+    for version in ["1.70.0", "1.75.0"] {
+        let tarball = format!("downloads/rust-{}-x86_64-unknown-linux-gnu.tar.xz", version);
+        if !Path::new(&tarball).exists() {
+            continue;
         }
+        // Keep `gc` out until the new sysroot is pinned
+        let _lock = cache.lock()?;
+        let hash = cache.add_tarball(Path::new(&tarball))?;
+        let sysroot = cache.unpack_tarball(&hash)?;
+        cache.pin(version, EntryKind::Sysroot, &sysroot)?;
+        println!("✅ Rust {} cached as {}", version, &sysroot[..16]);
     }
     
     // Use cached versions
-    if let Some(stable_path) = cache.get_version("stable") {
-        println!("🦀 Stable Rust available at: {:?}", stable_path);
+    if let Some(sysroot) = cache.resolve("1.75.0") {
+        println!("🦀 Rust 1.75.0 available at: {:?}", sysroot.join("bin/rustc"));
     }
+    
+    // Check the cache and drop what nothing pins
+    let report = cache.verify(true);
+    println!("🔍 {} entries checked, {} corrupt", report.checked, report.corrupt.len());
+    let gc = cache.gc(false)?;
+    println!("🧹 {} entries removed, {} bytes freed", gc.removed.len(), gc.freed_bytes);
+    Ok(())
 }
     "#).ok();
     
@@ -124,7 +145,14 @@ The `mkbuildrs!` macro generates a complete build.rs that:
 - `nix/rust-stable.nix` - Stable Rust via Nix
 - `nix/rust-nightly.nix` - Nightly Rust via Nix
 
-### Cache System
+### Toolchain Cache
+Toolchains live in a content-addressed directory (`target/rust-cache/`):
+- `objects/<sha256>` - dist tarballs, named by the hash of their bytes
+- `sysroots/<sha256>/` - unpacked sysroots, named by the hash of their manifest
+- `refs/<name>` - pins; anything unpinned is removed by `gc`
+
+Entries are staged under `tmp/` and installed with a single rename.
+
 {}
 
 ## Usage
@@ -140,10 +168,13 @@ cargo build
 
 ### Programmatic Usage
 ```rust
-use rust_cache::RustCache;
-
-let mut cache = RustCache::new();
-let rust_path = cache.cache_version("1.75.0")?;
+let cache = rust_cache!("target/rust-cache")?;
+cache.import_rustup(Path::new("/home/me/.rustup"), Some("stable"))?;
+let lock = cache.lock()?;
+let hash = cache.add_tarball(Path::new("rust-1.75.0-x86_64-unknown-linux-gnu.tar.xz"))?;
+cache.pin("1.75.0", EntryKind::Sysroot, &cache.unpack_tarball(&hash)?)?;
+drop(lock);
+let sysroot = cache.resolve("1.75.0");
 ```
 
 ### Build Macros
@@ -206,13 +237,15 @@ Options:
 **🔧 Complete Rust version management through build macros!**
         "#,
         build_rs.lines().take(50).collect::<Vec<_>>().join("\n"),
-        cache_system.lines().take(30).collect::<Vec<_>>().join("\n")
+        cache_entries.iter()
+            .map(|e| format!("- {} `{}` ({} bytes) pinned as: {}", e.kind.as_str(), e.hash, e.size, e.refs.join(", ")))
+            .collect::<Vec<_>>().join("\n")
     )).ok();
     
     println!("💾 mkbuildrs system generated!");
     println!("🔧 build.rs: Complete Nix-integrated build system");
     println!("🦀 Nix expressions: 4 Rust versions available");
-    println!("📦 Cache system: Multi-method download and caching");
+    println!("📦 Toolchain cache: content-addressed tarballs and sysroots");
     println!("🐚 shell.nix: Development environment ready");
     println!("📋 MKBUILDRS.md: Complete documentation");
     println!("");
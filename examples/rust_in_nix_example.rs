@@ -1,14 +1,29 @@
 use nix_rust_macros::{RustInNix, cargo_source, rust_as_macro};
 
 fn main() {
-    // Find and inject macros into stable Rust in Nix store
-    RustInNix!("stable");
+    // Plan wrapping the build scripts of a rustc checkout for stable Rust
+    // from the Nix store, and show what would change
+    match RustInNix!("stable", source = "../rust") {
+        Ok(plan) => println!("{}", plan.dry_run()),
+        Err(e) => println!("❌ {}", e),
+    }
     
-    // Or work with a specific version
-    RustInNix!("1.70.0");
+    // Or a specific version, applied only with --execute
+    if let Ok(plan) = RustInNix!("1.70.0", source = "../rust") {
+        println!("{}", plan.dry_run());
+        if std::env::args().any(|a| a == "--execute") {
+            match plan.execute() {
+                Ok(files) => println!("✅ Wrapped {} files", files.len()),
+                Err(e) => println!("❌ {}", e),
+            }
+        }
+    }
     
-    // Clone and inject macros into Cargo source
-    cargo_source!("https://github.com/rust-lang/cargo.git");
+    // Clone the Cargo source and plan wrapping its build script
+    match cargo_source!("https://github.com/rust-lang/cargo.git") {
+        Ok(plan) => println!("{}", plan.dry_run()),
+        Err(e) => println!("❌ {}", e),
+    }
     
     // Convert Rust compiler into a macro
     rust_as_macro!();
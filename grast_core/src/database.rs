@@ -8,7 +8,7 @@ mkbuildrs! {
     description: "Database operations for grast triples with indexing and file I/O";
 }
 
-#[decl(struct, name = "GrastDb", vis = "pub", hash = "508c1233")]
+#[decl(struct, name = "GrastDb", vis = "pub", hash = "cc1c2997")]
 pub struct GrastDb {
     pub triples: Vec<GrastTriple>,
     pub index: HashMap<String, Vec<usize>>,
@@ -0,0 +1,635 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::decl_wrapper::{extract_declarations, DeclMetadata, NodeType, VisibilityKind};
+
+// Public API snapshots built from `extract_declarations`, and a semver diff
+// between two of them. Only items reachable through `pub` modules are
+// recorded, plus trait impls for reachable or foreign types wherever they
+// are written; `pub use` re-exports are not followed.
+
+pub const SNAPSHOT_FORMAT: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[decl(struct, name = "ApiItem", vis = "pub", hash = "1e584f53")]
+pub struct ApiItem {
+    pub path: String,
+    pub kind: String,
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generics: Option<String>,
+    /// Public fields as `name: type`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub private_fields: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub non_exhaustive: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[decl(struct, name = "ApiSnapshot", vis = "pub", hash = "13c0e74a")]
+pub struct ApiSnapshot {
+    pub format: u32,
+    pub items: Vec<ApiItem>,
+    /// `Trait for Type`, with local types named by their crate path wherever
+    /// the impl is written.
+    pub trait_impls: Vec<String>,
+}
+
+impl ApiSnapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let snapshot: ApiSnapshot = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid API snapshot: {}", e))?;
+        if snapshot.format != SNAPSHOT_FORMAT {
+            return Err(format!(
+                "Unsupported API snapshot format {} (expected {})",
+                snapshot.format, SNAPSHOT_FORMAT
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    fn sorted(mut self) -> Self {
+        self.items.sort();
+        self.items.dedup();
+        self.trait_impls.sort();
+        self.trait_impls.dedup();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[decl(enum, name = "SemverImpact", vis = "pub", hash = "325ed243")]
+pub enum SemverImpact {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverImpact {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SemverImpact::Patch => "patch",
+            SemverImpact::Minor => "minor",
+            SemverImpact::Major => "major",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            SemverImpact::Patch => "🟢",
+            SemverImpact::Minor => "🟡",
+            SemverImpact::Major => "🔴",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "ApiChange", vis = "pub", hash = "e13c259f")]
+pub struct ApiChange {
+    pub path: String,
+    pub kind: String,
+    pub impact: SemverImpact,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "ApiDiff", vis = "pub", hash = "d037d250")]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+    /// Highest impact among `changes`; `Patch` when the surface is unchanged.
+    pub impact: SemverImpact,
+}
+
+/// Module path of a source file relative to the crate's `src` directory:
+/// `lib.rs` → ``, `a.rs` / `a/mod.rs` → `a`, `a/b.rs` → `a::b`.
+pub fn module_prefix(relative: &Path) -> String {
+    let mut parts: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if matches!(parts.last().map(String::as_str), Some("mod" | "lib" | "main")) {
+        parts.pop();
+    }
+    parts.join("::")
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}::{}", prefix, path)
+    }
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once("::").map_or("", |(parent, _)| parent)
+}
+
+/// `Stack<T>` → `Stack`.
+fn base_type_name(ty: &str) -> &str {
+    ty.split('<').next().unwrap_or(ty).trim()
+}
+
+/// Crate path of a type named inside `module`, following leading `crate`,
+/// `self` and `super` segments; other paths are taken as relative.
+fn resolve_type_path(module: &str, ty: &str) -> String {
+    let mut base = module;
+    let mut rest = Vec::new();
+    for (i, segment) in base_type_name(ty).split("::").map(str::trim).enumerate() {
+        match segment {
+            "crate" if i == 0 => base = "",
+            "self" if rest.is_empty() => {}
+            "super" if rest.is_empty() => base = parent_path(base),
+            _ => rest.push(segment),
+        }
+    }
+    join_path(base, &rest.join("::"))
+}
+
+/// Module holding a file- or module-level declaration. Impl names such as
+/// `fmt::Display for Point` contain `::`, so strip the name rather than
+/// splitting the path.
+fn decl_module(decl: &DeclMetadata) -> &str {
+    decl.path
+        .strip_suffix(decl.name.as_str())
+        .map_or("", |module| module.trim_end_matches("::"))
+}
+
+fn contains(outer: &DeclMetadata, inner: &DeclMetadata) -> bool {
+    outer.span.start_byte <= inner.span.start_byte && inner.span.end_byte <= outer.span.end_byte
+}
+
+/// Declarations of every file, with paths made crate-relative.
+struct CrateDecls {
+    decls: Vec<DeclMetadata>,
+    /// Module path → whether it is declared `pub`.
+    modules: BTreeMap<String, bool>,
+    /// Item path → whether it is declared `pub`.
+    types: BTreeMap<String, bool>,
+}
+
+impl CrateDecls {
+    fn new() -> Self {
+        CrateDecls { decls: Vec::new(), modules: BTreeMap::new(), types: BTreeMap::new() }
+    }
+
+    fn add_file(&mut self, content: &str, prefix: &str) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for mut decl in extract_declarations(content) {
+            decl.path = join_path(prefix, &decl.path);
+            let is_pub = decl.visibility == VisibilityKind::Public;
+            match decl.node_type {
+                NodeType::Module => { self.modules.insert(decl.path.clone(), is_pub); }
+                NodeType::Struct | NodeType::Enum | NodeType::Union | NodeType::Trait | NodeType::TypeAlias => {
+                    self.types.insert(decl.path.clone(), is_pub);
+                }
+                _ => {}
+            }
+            indices.push(self.decls.len());
+            self.decls.push(decl);
+        }
+        indices
+    }
+
+    /// The implementing type as recorded for a trait impl written in
+    /// `module`: local types by crate path, `None` when they are not
+    /// reachable, and foreign types as written.
+    fn trait_impl_owner(&self, module: &str, self_ty: &str) -> Option<String> {
+        let owner = resolve_type_path(module, self_ty);
+        match self.types.get(&owner) {
+            None => Some(self_ty.to_string()),
+            Some(true) if self.module_reachable(parent_path(&owner)) => {
+                let generics = self_ty.find('<').map_or("", |i| &self_ty[i..]);
+                Some(format!("{}{}", owner, generics))
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// Every module from the crate root down to `module` is declared `pub`.
+    fn module_reachable(&self, module: &str) -> bool {
+        let mut current = module;
+        while !current.is_empty() {
+            if self.modules.get(current) != Some(&true) {
+                return false;
+            }
+            current = parent_path(current);
+        }
+        true
+    }
+}
+
+fn api_item(decl: &DeclMetadata) -> ApiItem {
+    let signature = match decl.node_type {
+        NodeType::Function | NodeType::Const | NodeType::Static | NodeType::TypeAlias => {
+            decl.signature.clone().unwrap_or_default()
+        }
+        NodeType::AssocType => decl.signature.clone().unwrap_or_else(|| format!("type {}", decl.name)),
+        NodeType::Macro => format!("macro_rules! {}", decl.name),
+        other => format!("{} {}", other.as_str(), decl.name),
+    };
+
+    let fields = decl.fields.iter()
+        .filter(|f| f.visibility == VisibilityKind::Public)
+        .map(|f| format!("{}: {}", f.name.as_deref().unwrap_or("_"), f.ty))
+        .collect();
+
+    ApiItem {
+        path: decl.path.clone(),
+        kind: decl.node_type.as_str().to_string(),
+        signature,
+        generics: decl.generics.clone(),
+        fields,
+        variants: decl.variants.clone(),
+        private_fields: decl.fields.iter().any(|f| f.visibility != VisibilityKind::Public),
+        non_exhaustive: decl.attributes.iter().any(|a| a.replace(' ', "") == "#[non_exhaustive]"),
+    }
+}
+
+fn collect_public_api(crate_decls: &CrateDecls, files: &[Vec<usize>]) -> ApiSnapshot {
+    let mut items = Vec::new();
+    let mut trait_impls = Vec::new();
+
+    for indices in files {
+        let decls: Vec<&DeclMetadata> = indices.iter().map(|&i| &crate_decls.decls[i]).collect();
+        let impls: Vec<&DeclMetadata> = decls.iter().copied()
+            .filter(|d| d.node_type == NodeType::Impl && d.is_wrappable())
+            .collect();
+
+        for decl in &decls {
+            match decl.container {
+                None | Some(NodeType::Module) => {
+                    // `#[macro_export]` puts macros at the crate root.
+                    if decl.node_type == NodeType::Macro {
+                        if decl.visibility == VisibilityKind::Public {
+                            let mut item = api_item(decl);
+                            item.path = decl.name.clone();
+                            items.push(item);
+                        }
+                        continue;
+                    }
+                    let module = decl_module(decl);
+                    // Trait impls apply wherever the type is visible, so the
+                    // module holding the impl does not have to be public.
+                    if decl.node_type == NodeType::Impl {
+                        if let Some((trait_name, self_ty)) = decl.name.split_once(" for ") {
+                            if let Some(owner) = crate_decls.trait_impl_owner(module, self_ty) {
+                                trait_impls.push(format!("{} for {}", trait_name, owner));
+                            }
+                        }
+                        continue;
+                    }
+                    if !crate_decls.module_reachable(module) {
+                        continue;
+                    }
+                    if decl.visibility == VisibilityKind::Public && decl.node_type != NodeType::ExternBlock {
+                        items.push(api_item(decl));
+                    }
+                }
+                Some(NodeType::Impl) => {
+                    // Inherent methods of public types; trait impl members are
+                    // covered by the impl itself.
+                    let Some(block) = impls.iter().rev().find(|i| contains(i, decl)) else { continue };
+                    let owner_path = parent_path(&decl.path);
+                    let is_inherent = !block.name.contains(" for ");
+                    if is_inherent
+                        && decl.visibility == VisibilityKind::Public
+                        && crate_decls.types.get(base_type_name(owner_path)) == Some(&true)
+                        && crate_decls.module_reachable(parent_path(base_type_name(owner_path)))
+                    {
+                        items.push(api_item(decl));
+                    }
+                }
+                Some(NodeType::Trait) => {
+                    let trait_path = parent_path(&decl.path);
+                    if crate_decls.types.get(trait_path) == Some(&true)
+                        && crate_decls.module_reachable(parent_path(trait_path))
+                    {
+                        items.push(api_item(decl));
+                    }
+                }
+                Some(NodeType::ExternBlock) if decl.visibility == VisibilityKind::Public => {
+                    let module = parent_path(parent_path(&decl.path));
+                    if crate_decls.module_reachable(module) {
+                        let mut item = api_item(decl);
+                        item.path = join_path(module, &decl.name);
+                        items.push(item);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ApiSnapshot { format: SNAPSHOT_FORMAT, items, trait_impls }.sorted()
+}
+
+/// Snapshot of a single source file treated as a crate root.
+#[decl(fn, name = "snapshot_source", vis = "pub", hash = "3356e60c")]
+pub fn snapshot_source(content: &str) -> ApiSnapshot {
+    let mut crate_decls = CrateDecls::new();
+    let indices = crate_decls.add_file(content, "");
+    collect_public_api(&crate_decls, &[indices])
+}
+
+/// Snapshot of a crate directory (its `src/` if present) or a single file.
+/// Files under `src/bin` are separate crates and are skipped.
+#[decl(fn, name = "snapshot_path", vis = "pub", hash = "a598b81b")]
+pub fn snapshot_path(path: &Path) -> Result<ApiSnapshot, String> {
+    if path.is_file() {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return Ok(snapshot_source(&content));
+    }
+
+    let src = if path.join("src").is_dir() { path.join("src") } else { path.to_path_buf() };
+    if !src.is_dir() {
+        return Err(format!("{} is neither a file nor a directory", path.display()));
+    }
+
+    let mut crate_decls = CrateDecls::new();
+    let mut files = Vec::new();
+    let mut entries: Vec<_> = WalkDir::new(&src)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    entries.sort_by(|a, b| a.path().cmp(b.path()));
+
+    for entry in entries {
+        let relative = entry.path().strip_prefix(&src).unwrap_or(entry.path());
+        if relative.starts_with("bin") {
+            continue;
+        }
+        let content = fs::read_to_string(entry.path())
+            .map_err(|e| format!("Failed to read {}: {}", entry.path().display(), e))?;
+        files.push(crate_decls.add_file(&content, &module_prefix(relative)));
+    }
+
+    Ok(collect_public_api(&crate_decls, &files))
+}
+
+fn change(item: &ApiItem, impact: SemverImpact, description: impl Into<String>) -> ApiChange {
+    ApiChange {
+        path: item.path.clone(),
+        kind: item.kind.clone(),
+        impact,
+        description: description.into(),
+    }
+}
+
+/// Semver impact of one item present in both snapshots, following the
+/// Cargo semver guidelines where the snapshot has enough detail.
+fn compare_items(old: &ApiItem, new: &ApiItem, changes: &mut Vec<ApiChange>) {
+    if old.signature != new.signature {
+        changes.push(change(new, SemverImpact::Major, format!("signature changed: `{}` → `{}`", old.signature, new.signature)));
+    }
+    if old.generics != new.generics {
+        changes.push(change(new, SemverImpact::Major, format!(
+            "generics changed: `{}` → `{}`",
+            old.generics.as_deref().unwrap_or(""),
+            new.generics.as_deref().unwrap_or(""),
+        )));
+    }
+
+    let old_fields: BTreeSet<&String> = old.fields.iter().collect();
+    let new_fields: BTreeSet<&String> = new.fields.iter().collect();
+    for field in old_fields.difference(&new_fields) {
+        changes.push(change(new, SemverImpact::Major, format!("public field removed or changed: `{}`", field)));
+    }
+    // A struct with only public fields can be built with a literal, so any
+    // new field breaks callers unless it is `#[non_exhaustive]` or already
+    // has private fields.
+    let constructible = !old.private_fields && !old.non_exhaustive;
+    for field in new_fields.difference(&old_fields) {
+        let impact = if constructible { SemverImpact::Major } else { SemverImpact::Minor };
+        changes.push(change(new, impact, format!("public field added: `{}`", field)));
+    }
+    if new.private_fields && !old.private_fields {
+        changes.push(change(new, SemverImpact::Major, "private field added to a struct with only public fields"));
+    }
+
+    let old_variants: BTreeSet<&String> = old.variants.iter().collect();
+    let new_variants: BTreeSet<&String> = new.variants.iter().collect();
+    for variant in old_variants.difference(&new_variants) {
+        changes.push(change(new, SemverImpact::Major, format!("variant removed: `{}`", variant)));
+    }
+    for variant in new_variants.difference(&old_variants) {
+        let impact = if old.non_exhaustive { SemverImpact::Minor } else { SemverImpact::Major };
+        changes.push(change(new, impact, format!("variant added: `{}`", variant)));
+    }
+
+    if new.non_exhaustive && !old.non_exhaustive {
+        changes.push(change(new, SemverImpact::Major, "became #[non_exhaustive]"));
+    } else if old.non_exhaustive && !new.non_exhaustive {
+        changes.push(change(new, SemverImpact::Minor, "no longer #[non_exhaustive]"));
+    }
+}
+
+#[decl(fn, name = "diff_snapshots", vis = "pub", hash = "69ce21c0")]
+pub fn diff_snapshots(old: &ApiSnapshot, new: &ApiSnapshot) -> ApiDiff {
+    let key = |item: &ApiItem| (item.path.clone(), item.kind.clone());
+    let old_items: BTreeMap<_, &ApiItem> = old.items.iter().map(|i| (key(i), i)).collect();
+    let new_items: BTreeMap<_, &ApiItem> = new.items.iter().map(|i| (key(i), i)).collect();
+
+    let mut changes = Vec::new();
+
+    for (k, old_item) in &old_items {
+        match new_items.get(k) {
+            None => changes.push(change(old_item, SemverImpact::Major, "removed")),
+            Some(new_item) => compare_items(old_item, new_item, &mut changes),
+        }
+    }
+    for (k, new_item) in &new_items {
+        if old_items.contains_key(k) {
+            continue;
+        }
+        // Trait items are conservatively major: the snapshot does not record
+        // whether they have a default, so implementors may have to add them.
+        let in_trait = old.items.iter().any(|i| i.kind == "trait" && i.path == parent_path(&new_item.path));
+        let impact = if in_trait { SemverImpact::Major } else { SemverImpact::Minor };
+        changes.push(change(new_item, impact, "added"));
+    }
+
+    let old_impls: BTreeSet<&String> = old.trait_impls.iter().collect();
+    let new_impls: BTreeSet<&String> = new.trait_impls.iter().collect();
+    for removed in old_impls.difference(&new_impls) {
+        changes.push(ApiChange {
+            path: (*removed).clone(),
+            kind: "impl".to_string(),
+            impact: SemverImpact::Major,
+            description: "trait impl removed".to_string(),
+        });
+    }
+    for added in new_impls.difference(&old_impls) {
+        changes.push(ApiChange {
+            path: (*added).clone(),
+            kind: "impl".to_string(),
+            impact: SemverImpact::Minor,
+            description: "trait impl added".to_string(),
+        });
+    }
+
+    changes.sort_by(|a, b| b.impact.cmp(&a.impact).then_with(|| a.path.cmp(&b.path)));
+    let impact = changes.iter().map(|c| c.impact).max().unwrap_or(SemverImpact::Patch);
+    ApiDiff { changes, impact }
+}
+
+#[decl(fn, name = "print_api_diff", vis = "pub", hash = "5e203cb5")]
+pub fn print_api_diff(diff: &ApiDiff) {
+    if diff.changes.is_empty() {
+        eprintln!("✅ No public API changes");
+    }
+    for c in &diff.changes {
+        eprintln!("  {} {:<5} {:<6} {} — {}", c.impact.emoji(), c.impact.as_str(), c.kind, c.path, c.description);
+    }
+    eprintln!("\n📦 Required version bump: {} {}", diff.impact.emoji(), diff.impact.as_str());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+pub struct Point { pub x: i32, pub y: i32 }
+pub struct Handle { pub id: u32, inner: u8 }
+#[non_exhaustive]
+pub enum Mode { Fast, Slow }
+pub enum Color { Red, Green }
+pub fn area(w: u32, h: u32) -> u32 { w * h }
+pub trait Shape { fn area(&self) -> f64; }
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self { Point { x, y } }
+    fn private_helper(&self) {}
+}
+impl std::clone::Clone for Point { fn clone(&self) -> Self { Point { x: self.x, y: self.y } } }
+struct Hidden;
+impl Hidden { pub fn not_api() {} }
+mod private {
+    pub fn not_api() {}
+    #[macro_export]
+    macro_rules! exported { () => {} }
+}
+pub mod public { pub fn api() {} }
+"#;
+
+    #[test]
+    fn test_snapshot_records_reachable_public_items() {
+        let snapshot = snapshot_source(BASE);
+        let paths: Vec<&str> = snapshot.items.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec![
+            "Color", "Handle", "Mode", "Point", "Point::new", "Shape", "Shape::area",
+            "area", "exported", "public", "public::api",
+        ]);
+        assert_eq!(snapshot.trait_impls, vec!["std::clone::Clone for Point"]);
+
+        let handle = snapshot.items.iter().find(|i| i.path == "Handle").unwrap();
+        assert_eq!(handle.fields, vec!["id: u32"]);
+        assert!(handle.private_fields);
+
+        // Round-trips through the stable JSON form.
+        assert_eq!(ApiSnapshot::from_json(&snapshot.to_json()).unwrap(), snapshot);
+    }
+
+    fn impact_of(new_source: &str) -> ApiDiff {
+        diff_snapshots(&snapshot_source(BASE), &snapshot_source(new_source))
+    }
+
+    #[test]
+    fn test_unchanged_and_body_only_changes_are_patch() {
+        let diff = impact_of(&BASE.replace("w * h", "h * w"));
+        assert!(diff.changes.is_empty());
+        assert_eq!(diff.impact, SemverImpact::Patch);
+    }
+
+    #[test]
+    fn test_additions_are_minor() {
+        let diff = impact_of(&format!("{}\npub fn perimeter() {{}}\npub struct Extra;", BASE));
+        assert_eq!(diff.impact, SemverImpact::Minor);
+        assert_eq!(diff.changes.len(), 2);
+
+        let diff = impact_of(&BASE.replace("pub enum Mode { Fast, Slow }", "pub enum Mode { Fast, Slow, Eco }"));
+        assert_eq!(diff.impact, SemverImpact::Minor);
+
+        let diff = impact_of(&BASE.replace("pub id: u32, inner", "pub id: u32, pub tag: u8, inner"));
+        assert_eq!(diff.impact, SemverImpact::Minor);
+    }
+
+    #[test]
+    fn test_breaking_changes_are_major() {
+        let cases = [
+            BASE.replace("pub fn area(w: u32, h: u32) -> u32 { w * h }", ""),
+            BASE.replace("area(w: u32, h: u32) -> u32", "area(w: u64, h: u64) -> u64"),
+            BASE.replace("pub enum Color { Red, Green }", "pub enum Color { Red, Green, Blue }"),
+            BASE.replace("pub y: i32 }", "pub y: i32, pub z: i32 }"),
+            BASE.replace("fn area(&self) -> f64; }", "fn area(&self) -> f64; fn name(&self) -> String; }"),
+            BASE.replace("impl std::clone::Clone for Point", "impl Copy for Point"),
+            BASE.replace("pub mod public", "mod public"),
+        ];
+        for case in &cases {
+            assert_eq!(impact_of(case).impact, SemverImpact::Major, "{}", case);
+        }
+    }
+
+    #[test]
+    fn test_trait_impls_resolve_owner_in_any_module() {
+        let snapshot = snapshot_source(r#"
+pub mod shapes {
+    pub struct Circle;
+    pub mod nested { pub struct Square<T>(T); }
+}
+struct Private;
+mod impls {
+    impl Clone for crate::shapes::Circle { fn clone(&self) -> Self { crate::shapes::Circle } }
+    impl Default for super::Private { fn default() -> Self { super::Private } }
+    impl std::fmt::Debug for String { fn fmt(&self, _: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) } }
+}
+pub mod shapes_ext {
+    impl<T> Copy for super::shapes::nested::Square<T> {}
+}
+"#);
+        assert_eq!(snapshot.trait_impls, vec![
+            "Clone for shapes::Circle",
+            "Copy for shapes::nested::Square<T>",
+            "std::fmt::Debug for String",
+        ]);
+        assert_eq!(resolve_type_path("a::b", "super::super::Top"), "Top");
+        assert_eq!(resolve_type_path("a", "self::b::C<u8>"), "a::b::C");
+    }
+
+    #[test]
+    fn test_module_prefix_and_directory_snapshot() {
+        assert_eq!(module_prefix(Path::new("lib.rs")), "");
+        assert_eq!(module_prefix(Path::new("net/mod.rs")), "net");
+        assert_eq!(module_prefix(Path::new("net/tcp.rs")), "net::tcp");
+
+        let dir = std::env::temp_dir().join(format!("api_snapshot_{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/net")).unwrap();
+        fs::create_dir_all(dir.join("src/bin")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "pub mod net;\nmod internal;\n").unwrap();
+        fs::write(dir.join("src/net/mod.rs"), "pub mod tcp;\npub fn connect() {}\n").unwrap();
+        fs::write(dir.join("src/net/tcp.rs"), "pub struct Stream;\n").unwrap();
+        fs::write(dir.join("src/internal.rs"), "pub fn hidden() {}\n").unwrap();
+        fs::write(dir.join("src/bin/tool.rs"), "pub fn main() {}\n").unwrap();
+
+        let snapshot = snapshot_path(&dir).unwrap();
+        let paths: Vec<&str> = snapshot.items.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["net", "net::connect", "net::tcp", "net::tcp::Stream"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -2,7 +2,7 @@ use once_cell::sync::Lazy;
 use std::sync::Mutex;
 
 #[derive(Debug, Clone, Default)]
-#[decl(struct, name = "AuditStats", vis = "pub", hash = "32560e10")]
+#[decl(struct, name = "AuditStats", vis = "pub", hash = "776b1aa7")]
 pub struct AuditStats {
     pub fixme_count: usize,
     pub phony_count: usize,
@@ -15,7 +15,7 @@ pub struct AuditStats {
 }
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "AuditEntry", vis = "pub", hash = "ed6ffa75")]
+#[decl(struct, name = "AuditEntry", vis = "pub", hash = "90f93ca6")]
 pub struct AuditEntry {
     pub kind: AuditKind,
     pub message: String,
@@ -25,7 +25,7 @@ pub struct AuditEntry {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[decl(enum, name = "AuditKind", vis = "pub", hash = "32e60230")]
+#[decl(enum, name = "AuditKind", vis = "pub", hash = "25730164")]
 pub enum AuditKind {
     Fixme,
     Phony,
@@ -76,7 +76,7 @@ impl AuditKind {
 
 pub static AUDIT_REGISTRY: Lazy<Mutex<AuditStats>> = Lazy::new(|| Mutex::new(AuditStats::default()));
 
-#[decl(fn, name = "register_audit", vis = "pub", hash = "11183f59")]
+#[decl(fn, name = "register_audit", vis = "pub", hash = "d2705f0d")]
 pub fn register_audit(kind: AuditKind, message: &str, file: &'static str, line: u32, column: u32) {
     if let Ok(mut stats) = AUDIT_REGISTRY.lock() {
         match kind {
@@ -98,7 +98,7 @@ pub fn register_audit(kind: AuditKind, message: &str, file: &'static str, line:
     }
 }
 
-#[decl(fn, name = "print_audit_warning", vis = "pub", hash = "53749765")]
+#[decl(fn, name = "print_audit_warning", vis = "pub", hash = "26504ba8")]
 pub fn print_audit_warning(kind: AuditKind, message: &str, file: &str, line: u32) {
     let reset = "\x1b[0m";
     eprintln!(
@@ -113,7 +113,7 @@ pub fn print_audit_warning(kind: AuditKind, message: &str, file: &str, line: u32
     );
 }
 
-#[decl(fn, name = "print_audit_summary", vis = "pub", hash = "f95f6f67")]
+#[decl(fn, name = "print_audit_summary", vis = "pub", hash = "4c1f34a2")]
 pub fn print_audit_summary() {
     if let Ok(stats) = AUDIT_REGISTRY.lock() {
         let reset = "\x1b[0m";
@@ -177,12 +177,12 @@ pub fn print_audit_summary() {
     }
 }
 
-#[decl(fn, name = "get_audit_stats", vis = "pub", hash = "599003ee")]
+#[decl(fn, name = "get_audit_stats", vis = "pub", hash = "faef7f8e")]
 pub fn get_audit_stats() -> Option<AuditStats> {
     AUDIT_REGISTRY.lock().ok().map(|s| s.clone())
 }
 
-#[decl(fn, name = "get_audits_by_kind", vis = "pub", hash = "2fbcefeb")]
+#[decl(fn, name = "get_audits_by_kind", vis = "pub", hash = "0028804b")]
 pub fn get_audits_by_kind(kind: AuditKind) -> Vec<AuditEntry> {
     AUDIT_REGISTRY
         .lock()
@@ -191,7 +191,7 @@ pub fn get_audits_by_kind(kind: AuditKind) -> Vec<AuditEntry> {
         .unwrap_or_default()
 }
 
-#[decl(fn, name = "clear_audits", vis = "pub", hash = "28fb0532")]
+#[decl(fn, name = "clear_audits", vis = "pub", hash = "12654d3a")]
 pub fn clear_audits() {
     if let Ok(mut stats) = AUDIT_REGISTRY.lock() {
         *stats = AuditStats::default();
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "AuditTicket", vis = "pub", hash = "e44f1a4e")]
+#[decl(struct, name = "AuditTicket", vis = "pub", hash = "02bfb983")]
 pub struct AuditTicket {
     pub id: &'static str,
     pub category: TicketCategory,
@@ -16,7 +18,7 @@ pub struct AuditTicket {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[decl(enum, name = "TicketCategory", vis = "pub", hash = "941989a2")]
+#[decl(enum, name = "TicketCategory", vis = "pub", hash = "4c3c0450")]
 pub enum TicketCategory {
     Phony,
     FakeData,
@@ -26,9 +28,45 @@ pub enum TicketCategory {
     Todo,
     Security,
     Performance,
+    /// Project-specific category declared in a ticket file.
+    Custom(&'static CustomCategory),
+}
+
+/// A `[[category]]` entry of a ticket file.
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[decl(struct, name = "CustomCategory", vis = "pub", hash = "2ec5e88e")]
+pub struct CustomCategory {
+    pub name: &'static str,
+    pub prefix: &'static str,
+    pub emoji: &'static str,
 }
 
 impl TicketCategory {
+    pub const BUILTIN: [TicketCategory; 8] = [
+        TicketCategory::Phony,
+        TicketCategory::FakeData,
+        TicketCategory::Unverified,
+        TicketCategory::Issue,
+        TicketCategory::Concern,
+        TicketCategory::Todo,
+        TicketCategory::Security,
+        TicketCategory::Performance,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TicketCategory::Phony => "Phony",
+            TicketCategory::FakeData => "FakeData",
+            TicketCategory::Unverified => "Unverified",
+            TicketCategory::Issue => "Issue",
+            TicketCategory::Concern => "Concern",
+            TicketCategory::Todo => "Todo",
+            TicketCategory::Security => "Security",
+            TicketCategory::Performance => "Performance",
+            TicketCategory::Custom(c) => c.name,
+        }
+    }
+
     pub fn prefix(&self) -> &'static str {
         match self {
             TicketCategory::Phony => "PHO",
@@ -39,6 +77,7 @@ impl TicketCategory {
             TicketCategory::Todo => "TDO",
             TicketCategory::Security => "SEC",
             TicketCategory::Performance => "PRF",
+            TicketCategory::Custom(c) => c.prefix,
         }
     }
 
@@ -52,12 +91,13 @@ impl TicketCategory {
             TicketCategory::Todo => "📝",
             TicketCategory::Security => "🔒",
             TicketCategory::Performance => "⚡",
+            TicketCategory::Custom(c) => c.emoji,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[decl(enum, name = "Severity", vis = "pub", hash = "bb5f55f0")]
+#[decl(enum, name = "Severity", vis = "pub", hash = "d72e3922")]
 pub enum Severity {
     Info,
     Low,
@@ -76,405 +116,282 @@ impl Severity {
             Severity::Critical => "🔴",
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Case-insensitive inverse of `as_str`, for user-supplied rule and ticket files.
+    pub fn from_name(name: &str) -> Option<Severity> {
+        [Severity::Info, Severity::Low, Severity::Medium, Severity::High, Severity::Critical]
+            .into_iter()
+            .find(|s| s.as_str().eq_ignore_ascii_case(name.trim()))
+    }
 }
 
-pub static TICKET_REGISTRY: Lazy<Mutex<HashMap<&'static str, AuditTicket>>> = 
+/// Project ticket directory, relative to the working directory.
+pub const TICKET_DIR: &str = "audit_tickets";
+/// Overrides `TICKET_DIR`.
+pub const TICKET_DIR_ENV: &str = "AUDIT_TICKETS_DIR";
+
+/// The built-in registry, in the same format as project ticket files.
+const BUILTIN_TICKET_FILES: &[(&str, &str)] = &[
+    ("phony.toml", include_str!("../audit_tickets/phony.toml")),
+    ("fake_data.toml", include_str!("../audit_tickets/fake_data.toml")),
+    ("unverified.toml", include_str!("../audit_tickets/unverified.toml")),
+    ("issue.toml", include_str!("../audit_tickets/issue.toml")),
+    ("concern.toml", include_str!("../audit_tickets/concern.toml")),
+    ("todo.toml", include_str!("../audit_tickets/todo.toml")),
+    ("security.toml", include_str!("../audit_tickets/security.toml")),
+];
+
+pub static TICKET_REGISTRY: Lazy<Mutex<HashMap<&'static str, AuditTicket>>> =
     Lazy::new(|| Mutex::new(create_ticket_registry()));
 
 fn create_ticket_registry() -> HashMap<&'static str, AuditTicket> {
-    let mut registry = HashMap::new();
-
-    // ═══════════════════════════════════════════════════════════════════
-    // PHONY TICKETS (PHO-XXX) - Illustrative/Template Code
-    // ═══════════════════════════════════════════════════════════════════
-    
-    registry.insert("PHO-001", AuditTicket {
-        id: "PHO-001",
-        category: TicketCategory::Phony,
-        severity: Severity::High,
-        module: "duplicate_analysis",
-        title: "Fabricated VFS statistics",
-        description: "VFS mapping shows invented numbers (47 functions, 234 functions, 1247 items) that are not from actual analysis",
-        suggested_fix: Some("Replace with actual file system traversal and counting, or clearly mark output as '[EXAMPLE]'"),
-        clippy_lint: Some("phony_statistics"),
-    });
-
-    registry.insert("PHO-002", AuditTicket {
-        id: "PHO-002",
-        category: TicketCategory::Phony,
-        severity: Severity::High,
-        module: "duplicate_analysis",
-        title: "Fabricated reduction percentages",
-        description: "Claims like '55.1% code reduction possible' are not based on actual measurements",
-        suggested_fix: Some("Remove percentage claims or compute from actual duplicate analysis"),
-        clippy_lint: Some("phony_percentages"),
-    });
-
-    registry.insert("PHO-003", AuditTicket {
-        id: "PHO-003",
-        category: TicketCategory::Phony,
-        severity: Severity::Medium,
-        module: "solana_lift",
-        title: "Template Solana contracts",
-        description: "Generated contract code is skeleton template, not functional Solana program",
-        suggested_fix: Some("Add disclaimer: '// [TEMPLATE] This is illustrative code, not production-ready'"),
-        clippy_lint: Some("template_contract"),
-    });
-
-    registry.insert("PHO-004", AuditTicket {
-        id: "PHO-004",
-        category: TicketCategory::Phony,
-        severity: Severity::Critical,
-        module: "zk_proof",
-        title: "Template ZK proofs",
-        description: "PLONK/STARK/SNARK implementations are structural templates, not real cryptographic proofs",
-        suggested_fix: Some("Use arkworks, bellman, or halo2 for real ZK proofs, or rename to 'zk_template'"),
-        clippy_lint: Some("fake_crypto"),
-    });
-
-    registry.insert("PHO-005", AuditTicket {
-        id: "PHO-005",
-        category: TicketCategory::Phony,
-        severity: Severity::Medium,
-        module: "dao_governance",
-        title: "Simulated DAO voting",
-        description: "Voting simulation with hardcoded counts, not actual on-chain governance",
-        suggested_fix: Some("Integrate with actual DAO framework (e.g., Realms, Governor) or mark as simulation"),
-        clippy_lint: Some("simulated_governance"),
-    });
-
-    registry.insert("PHO-006", AuditTicket {
-        id: "PHO-006",
-        category: TicketCategory::Phony,
-        severity: Severity::Medium,
-        module: "mev_protection",
-        title: "Naive MEV detection",
-        description: "Pattern detection uses simplistic string matching, not actual mempool analysis",
-        suggested_fix: Some("Use flashbots-like protection or clearly document limitations"),
-        clippy_lint: Some("naive_detection"),
-    });
-
-    registry.insert("PHO-007", AuditTicket {
-        id: "PHO-007",
-        category: TicketCategory::Phony,
-        severity: Severity::Medium,
-        module: "quant_trading",
-        title: "Template trading system",
-        description: "Trading code is illustrative template, not a real trading system",
-        suggested_fix: Some("Add prominent disclaimer: 'NOT FINANCIAL ADVICE - ILLUSTRATIVE ONLY'"),
-        clippy_lint: Some("template_trading"),
-    });
-
-    registry.insert("PHO-008", AuditTicket {
-        id: "PHO-008",
-        category: TicketCategory::Phony,
-        severity: Severity::Low,
-        module: "rustc_ring",
-        title: "Conceptual ring properties",
-        description: "Mathematical ring properties are conceptual analogy, not proven algebraic structure",
-        suggested_fix: Some("Rename to 'conceptual_properties' or formalize the mathematics"),
-        clippy_lint: Some("conceptual_math"),
-    });
-
-    registry.insert("PHO-009", AuditTicket {
-        id: "PHO-009",
-        category: TicketCategory::Phony,
-        severity: Severity::Medium,
-        module: "lean4_proof",
-        title: "Unverified Lean4 templates",
-        description: "Generates Lean4 syntax templates that are not verified by Lean4 compiler",
-        suggested_fix: Some("Add build step to verify generated Lean4 code, or mark as template"),
-        clippy_lint: Some("unverified_proof"),
-    });
-
-    registry.insert("PHO-010", AuditTicket {
-        id: "PHO-010",
-        category: TicketCategory::Phony,
-        severity: Severity::Low,
-        module: "quine_relay",
-        title: "Template quines",
-        description: "Quine implementations are demonstration templates",
-        suggested_fix: Some("Document as educational examples"),
-        clippy_lint: None,
-    });
-
-    // ═══════════════════════════════════════════════════════════════════
-    // FAKEDATA TICKETS (FKD-XXX) - Hardcoded Mock Data
-    // ═══════════════════════════════════════════════════════════════════
-
-    registry.insert("FKD-001", AuditTicket {
-        id: "FKD-001",
-        category: TicketCategory::FakeData,
-        severity: Severity::High,
-        module: "duplicate_analysis",
-        title: "Hardcoded hash values",
-        description: "Hash values (a7f3b2c1, d8e9f4a6, f2b8c4d6) are static examples, not computed",
-        suggested_fix: Some("Use sha2 crate to compute actual semantic hashes from input"),
-        clippy_lint: Some("hardcoded_hash"),
-    });
-
-    registry.insert("FKD-002", AuditTicket {
-        id: "FKD-002",
-        category: TicketCategory::FakeData,
-        severity: Severity::High,
-        module: "solana_lift",
-        title: "Fake blockhash fallback",
-        description: "Fallback blockhash '11111111111111111111111111111111' is not a real blockchain hash",
-        suggested_fix: Some("Return Result::Err when API fails instead of fake data"),
-        clippy_lint: Some("fake_blockchain_data"),
-    });
-
-    registry.insert("FKD-003", AuditTicket {
-        id: "FKD-003",
-        category: TicketCategory::FakeData,
-        severity: Severity::Medium,
-        module: "solana_lift",
-        title: "Placeholder block hash",
-        description: "'sample_block_hash' is a placeholder string, not real blockchain data",
-        suggested_fix: Some("Propagate Option::None or use descriptive error"),
-        clippy_lint: Some("placeholder_value"),
-    });
-
-    registry.insert("FKD-004", AuditTicket {
-        id: "FKD-004",
-        category: TicketCategory::FakeData,
-        severity: Severity::Medium,
-        module: "dao_governance",
-        title: "Hardcoded governance counts",
-        description: "Senator/Representative/Lobbyist counts (1000, 500, 100) are arbitrary constants",
-        suggested_fix: Some("Accept counts as macro parameters or fetch from on-chain state"),
-        clippy_lint: Some("hardcoded_governance"),
-    });
-
-    registry.insert("FKD-005", AuditTicket {
-        id: "FKD-005",
-        category: TicketCategory::FakeData,
-        severity: Severity::High,
-        module: "mev_protection",
-        title: "Always-true transaction matcher",
-        description: "transaction_matches() always returns true - placeholder implementation",
-        suggested_fix: Some("Implement actual pattern matching or remove function"),
-        clippy_lint: Some("stub_always_true"),
-    });
-
-    registry.insert("FKD-006", AuditTicket {
-        id: "FKD-006",
-        category: TicketCategory::FakeData,
-        severity: Severity::Medium,
-        module: "quant_trading",
-        title: "Hardcoded sample prices",
-        description: "Historical prices (145.32, 43250.67, etc.) are hardcoded sample data",
-        suggested_fix: Some("Fetch from real API (CoinGecko, Yahoo Finance) or accept as input"),
-        clippy_lint: Some("hardcoded_prices"),
-    });
+    let builtin = load_ticket_sources(BUILTIN_TICKET_FILES)
+        .expect("built-in audit ticket files are valid");
+    let mut registry: HashMap<&'static str, AuditTicket> =
+        builtin.into_iter().map(|t| (t.id, t)).collect();
+
+    // Project tickets extend or override the built-in ones.
+    let dir = ticket_dir();
+    if dir.is_dir() {
+        match load_ticket_dir(&dir) {
+            Ok(tickets) => registry.extend(tickets.into_iter().map(|t| (t.id, t))),
+            Err(e) => eprintln!("⚠️ Ignoring ticket files in {}:\n{}", dir.display(), e),
+        }
+    }
 
-    registry.insert("FKD-007", AuditTicket {
-        id: "FKD-007",
-        category: TicketCategory::FakeData,
-        severity: Severity::Low,
-        module: "rustc_ring",
-        title: "Static DOT graph",
-        description: "Dependency graph is hardcoded DOT example, not dynamically generated",
-        suggested_fix: Some("Use cargo-metadata or syn to generate actual dependency graph"),
-        clippy_lint: Some("static_example"),
-    });
+    registry
+}
 
-    registry.insert("FKD-008", AuditTicket {
-        id: "FKD-008",
-        category: TicketCategory::FakeData,
-        severity: Severity::Medium,
-        module: "zk_proof",
-        title: "Illustrative witness values",
-        description: "ZK witness values and execution traces are not cryptographically sound",
-        suggested_fix: Some("Use proper witness generation from arkworks or similar"),
-        clippy_lint: Some("fake_witness"),
-    });
+#[decl(fn, name = "ticket_dir", vis = "pub", hash = "dc1dd6ad")]
+pub fn ticket_dir() -> PathBuf {
+    std::env::var_os(TICKET_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(TICKET_DIR))
+}
 
-    // ═══════════════════════════════════════════════════════════════════
-    // UNVERIFIED TICKETS (UNV-XXX) - Unverified Claims
-    // ═══════════════════════════════════════════════════════════════════
-
-    registry.insert("UNV-001", AuditTicket {
-        id: "UNV-001",
-        category: TicketCategory::Unverified,
-        severity: Severity::High,
-        module: "zk_proof",
-        title: "Fake morphism verification",
-        description: "verify_morphism() just checks sum equality, not cryptographic verification",
-        suggested_fix: Some("Rename to 'check_sum_equality' or implement real verification"),
-        clippy_lint: Some("misleading_verify"),
-    });
+// Tickets hold `&'static str` like the rest of the registry; loaded strings
+// are interned so reloading the same files does not grow memory.
+static INTERNED: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static CUSTOM_CATEGORIES: Lazy<Mutex<Vec<&'static CustomCategory>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-    registry.insert("UNV-002", AuditTicket {
-        id: "UNV-002",
-        category: TicketCategory::Unverified,
-        severity: Severity::Medium,
-        module: "zk_proof",
-        title: "Metaphorical Monster group reference",
-        description: "Monster group dimension 196883 is used metaphorically, not mathematically",
-        suggested_fix: Some("Document as conceptual/artistic reference, not mathematical claim"),
-        clippy_lint: Some("metaphorical_math"),
-    });
+pub(crate) fn intern(s: String) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.get(s.as_str()) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(s.into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
 
-    registry.insert("UNV-003", AuditTicket {
-        id: "UNV-003",
-        category: TicketCategory::Unverified,
-        severity: Severity::Medium,
-        module: "lean4_proof",
-        title: "Uncompiled Lean4 code",
-        description: "Generated Lean4 proofs not verified by actual Lean4 compiler",
-        suggested_fix: Some("Add CI step: 'lake build' to verify generated Lean4"),
-        clippy_lint: Some("uncompiled_proof"),
-    });
+fn intern_category(category: CustomCategory) -> &'static CustomCategory {
+    let mut known = CUSTOM_CATEGORIES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = known.iter().find(|c| ***c == category) {
+        return existing;
+    }
+    let leaked: &'static CustomCategory = Box::leak(Box::new(category));
+    known.push(leaked);
+    leaked
+}
 
-    registry.insert("UNV-004", AuditTicket {
-        id: "UNV-004",
-        category: TicketCategory::Unverified,
-        severity: Severity::Medium,
-        module: "sat_lfunction",
-        title: "Illustrative unity proof",
-        description: "Unity proof is conceptual illustration, not mathematical proof",
-        suggested_fix: Some("Rename to 'unity_concept' or implement actual SAT solving"),
-        clippy_lint: Some("illustrative_proof"),
-    });
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TicketFile {
+    #[serde(default)]
+    category: Vec<CategorySpec>,
+    #[serde(default)]
+    ticket: Vec<TicketSpec>,
+}
 
-    // ═══════════════════════════════════════════════════════════════════
-    // ISSUE TICKETS (ISS-XXX) - Bugs/Problems
-    // ═══════════════════════════════════════════════════════════════════
-
-    registry.insert("ISS-001", AuditTicket {
-        id: "ISS-001",
-        category: TicketCategory::Issue,
-        severity: Severity::High,
-        module: "real_data_analysis",
-        title: "Misleading eigenvalue terminology",
-        description: "'Eigenvalue' calculation is actually normalized frequency, not linear algebra",
-        suggested_fix: Some("Rename to 'normalized_frequency' or 'keyword_density'"),
-        clippy_lint: Some("misleading_terminology"),
-    });
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CategorySpec {
+    name: String,
+    prefix: String,
+    emoji: Option<String>,
+}
 
-    registry.insert("ISS-002", AuditTicket {
-        id: "ISS-002",
-        category: TicketCategory::Issue,
-        severity: Severity::Medium,
-        module: "real_data_analysis",
-        title: "Naive pattern counting",
-        description: "Pattern counting uses string matching (fn , struct ) which may over/undercount",
-        suggested_fix: Some("Use syn crate for proper AST parsing"),
-        clippy_lint: Some("naive_parsing"),
-    });
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TicketSpec {
+    id: String,
+    category: String,
+    severity: String,
+    module: String,
+    title: String,
+    description: String,
+    suggested_fix: Option<String>,
+    clippy_lint: Option<String>,
+}
 
-    registry.insert("ISS-003", AuditTicket {
-        id: "ISS-003",
-        category: TicketCategory::Issue,
-        severity: Severity::Medium,
-        module: "mev_protection",
-        title: "Not production-ready protection",
-        description: "MEV protection logic is too simplistic for real-world use",
-        suggested_fix: Some("Add clear WARNING in output or integrate with flashbots"),
-        clippy_lint: Some("incomplete_security"),
-    });
+static PREFIX_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"^[A-Z][A-Z0-9]{1,7}$").unwrap());
+static TICKET_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"^([A-Z][A-Z0-9]{1,7})-[0-9]{3,}$").unwrap());
+
+/// Parses and validates ticket files given as `(file name, TOML)` pairs.
+///
+/// A file may declare `[[category]]` tables (`name`, `prefix`, optional
+/// `emoji`) and `[[ticket]]` tables. Categories are shared by all files, a
+/// ticket's `category` is a category name or prefix, and its id must be
+/// `<PREFIX>-<NNN>`. Every problem found is reported, one per line.
+#[decl(fn, name = "load_ticket_sources", vis = "pub", hash = "8b338d57")]
+pub fn load_ticket_sources(sources: &[(&str, &str)]) -> Result<Vec<AuditTicket>, String> {
+    let mut errors = Vec::new();
+    let mut files = Vec::new();
+    for (name, src) in sources {
+        match toml::from_str::<TicketFile>(src) {
+            Ok(file) => files.push((*name, file)),
+            Err(e) => errors.push(format!("{}: {}", name, e.to_string().trim_end())),
+        }
+    }
 
-    // ═══════════════════════════════════════════════════════════════════
-    // CONCERN TICKETS (CON-XXX) - Questionable Patterns
-    // ═══════════════════════════════════════════════════════════════════
-
-    registry.insert("CON-001", AuditTicket {
-        id: "CON-001",
-        category: TicketCategory::Concern,
-        severity: Severity::Low,
-        module: "dao_governance",
-        title: "Conceptual Paxos",
-        description: "Paxos consensus is conceptual single-node simulation, not distributed",
-        suggested_fix: Some("Rename to 'paxos_simulation' or use actual consensus library"),
-        clippy_lint: Some("simulated_consensus"),
-    });
+    let mut categories: Vec<TicketCategory> = TicketCategory::BUILTIN.to_vec();
+    for (file, spec) in files.iter().flat_map(|(f, file)| file.category.iter().map(move |c| (f, c))) {
+        let clash = categories.iter().find(|c| {
+            c.prefix() == spec.prefix || c.name().eq_ignore_ascii_case(&spec.name)
+        });
+        if !PREFIX_RE.is_match(&spec.prefix) {
+            errors.push(format!("{}: category `{}`: prefix `{}` must be 2-8 uppercase letters or digits", file, spec.name, spec.prefix));
+        } else if spec.name.trim().is_empty() {
+            errors.push(format!("{}: category with prefix `{}` has an empty name", file, spec.prefix));
+        } else if let Some(existing) = clash {
+            errors.push(format!("{}: category `{}` ({}) clashes with `{}` ({})", file, spec.name, spec.prefix, existing.name(), existing.prefix()));
+        } else {
+            categories.push(TicketCategory::Custom(intern_category(CustomCategory {
+                name: intern(spec.name.clone()),
+                prefix: intern(spec.prefix.clone()),
+                emoji: intern(spec.emoji.clone().unwrap_or_else(|| "🏷️".to_string())),
+            })));
+        }
+    }
 
-    registry.insert("CON-002", AuditTicket {
-        id: "CON-002",
-        category: TicketCategory::Concern,
-        severity: Severity::Low,
-        module: "rustc_ring",
-        title: "Environment-dependent paths",
-        description: "Searches /nix/store which may fail in non-NixOS environments",
-        suggested_fix: Some("Use RUSTUP_HOME or cargo metadata for portability"),
-        clippy_lint: Some("hardcoded_path"),
-    });
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    let mut tickets = Vec::new();
+    for (file, spec) in files.iter().flat_map(|(f, file)| file.ticket.iter().map(move |t| (*f, t))) {
+        let mut problems = Vec::new();
+        let category = categories.iter().copied().find(|c| {
+            c.name().eq_ignore_ascii_case(&spec.category) || c.prefix() == spec.category
+        });
+        let severity = Severity::from_name(&spec.severity);
+
+        match TICKET_ID_RE.captures(&spec.id) {
+            None => problems.push("id must look like `PRE-001`".to_string()),
+            Some(caps) => {
+                if let Some(c) = category.filter(|c| c.prefix() != &caps[1]) {
+                    problems.push(format!("id prefix `{}` does not match category `{}` ({})", &caps[1], c.name(), c.prefix()));
+                }
+            }
+        }
+        if category.is_none() {
+            problems.push(format!("unknown category `{}`", spec.category));
+        }
+        if severity.is_none() {
+            problems.push(format!("unknown severity `{}` (info, low, medium, high, critical)", spec.severity));
+        }
+        for (field, value) in [("module", &spec.module), ("title", &spec.title)] {
+            if value.trim().is_empty() {
+                problems.push(format!("`{}` is empty", field));
+            }
+        }
+        if let Some(first) = seen.insert(spec.id.clone(), file) {
+            problems.push(format!("duplicate id, first defined in {}", first));
+        }
 
-    registry.insert("CON-003", AuditTicket {
-        id: "CON-003",
-        category: TicketCategory::Concern,
-        severity: Severity::Low,
-        module: "rust_eigenmatrix",
-        title: "Informal eigenmatrix concept",
-        description: "Eigenmatrix concept needs formal mathematical definition",
-        suggested_fix: Some("Document the mathematical basis or rename to 'keyword_matrix'"),
-        clippy_lint: Some("informal_math"),
-    });
+        match (problems.is_empty(), category, severity) {
+            (true, Some(category), Some(severity)) => tickets.push(AuditTicket {
+                id: intern(spec.id.clone()),
+                category,
+                severity,
+                module: intern(spec.module.clone()),
+                title: intern(spec.title.clone()),
+                description: intern(spec.description.clone()),
+                suggested_fix: spec.suggested_fix.clone().map(intern),
+                clippy_lint: spec.clippy_lint.clone().map(intern),
+            }),
+            _ => errors.extend(problems.into_iter().map(|p| format!("{}: {}: {}", file, spec.id, p))),
+        }
+    }
 
-    // ═══════════════════════════════════════════════════════════════════
-    // TODO TICKETS (TDO-XXX) - Incomplete Implementations
-    // ═══════════════════════════════════════════════════════════════════
-
-    registry.insert("TDO-001", AuditTicket {
-        id: "TDO-001",
-        category: TicketCategory::Todo,
-        severity: Severity::Medium,
-        module: "real_data_analysis",
-        title: "Replace string matching with AST parsing",
-        description: "Current implementation uses naive string matching for code analysis",
-        suggested_fix: Some("Use syn::parse_file() for accurate AST-based counting"),
-        clippy_lint: None,
-    });
+    if errors.is_empty() {
+        Ok(tickets)
+    } else {
+        Err(errors.join("\n"))
+    }
+}
 
-    registry.insert("TDO-002", AuditTicket {
-        id: "TDO-002",
-        category: TicketCategory::Todo,
-        severity: Severity::Low,
-        module: "context_knapsack",
-        title: "Implement actual knapsack optimization",
-        description: "Knapsack algorithm is not fully implemented",
-        suggested_fix: Some("Implement dynamic programming solution for token optimization"),
-        clippy_lint: None,
-    });
+/// Loads every `*.toml` file in `dir`, in file name order.
+#[decl(fn, name = "load_ticket_dir", vis = "pub", hash = "182c5498")]
+pub fn load_ticket_dir(dir: &Path) -> Result<Vec<AuditTicket>, String> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    let mut contents = Vec::new();
+    for path in &paths {
+        let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        contents.push((path.display().to_string(), src));
+    }
+    let sources: Vec<(&str, &str)> = contents.iter().map(|(n, s)| (n.as_str(), s.as_str())).collect();
+    load_ticket_sources(&sources)
+}
 
-    registry
+/// Merges the tickets in `dir` into `TICKET_REGISTRY`, replacing tickets
+/// with the same id. Returns the number of tickets merged.
+#[decl(fn, name = "merge_ticket_dir", vis = "pub", hash = "dc91880b")]
+pub fn merge_ticket_dir(dir: &Path) -> Result<usize, String> {
+    let tickets = load_ticket_dir(dir)?;
+    let count = tickets.len();
+    let mut registry = TICKET_REGISTRY.lock().map_err(|e| e.to_string())?;
+    registry.extend(tickets.into_iter().map(|t| (t.id, t)));
+    Ok(count)
 }
 
-#[decl(fn, name = "get_ticket", vis = "pub", hash = "7bd660cc")]
+#[decl(fn, name = "get_ticket", vis = "pub", hash = "c5e76401")]
 pub fn get_ticket(id: &str) -> Option<AuditTicket> {
     TICKET_REGISTRY.lock().ok()?.get(id).cloned()
 }
 
-#[decl(fn, name = "get_all_tickets", vis = "pub", hash = "2666359a")]
+#[decl(fn, name = "get_all_tickets", vis = "pub", hash = "1f8c1422")]
 pub fn get_all_tickets() -> Vec<AuditTicket> {
     TICKET_REGISTRY.lock()
         .map(|r| r.values().cloned().collect())
         .unwrap_or_default()
 }
 
-#[decl(fn, name = "get_tickets_by_category", vis = "pub", hash = "09511110")]
+#[decl(fn, name = "get_tickets_by_category", vis = "pub", hash = "86edcb19")]
 pub fn get_tickets_by_category(category: TicketCategory) -> Vec<AuditTicket> {
     get_all_tickets().into_iter()
         .filter(|t| t.category == category)
         .collect()
 }
 
-#[decl(fn, name = "get_tickets_by_module", vis = "pub", hash = "aa99f800")]
+#[decl(fn, name = "get_tickets_by_module", vis = "pub", hash = "183c9fbe")]
 pub fn get_tickets_by_module(module: &str) -> Vec<AuditTicket> {
     get_all_tickets().into_iter()
         .filter(|t| t.module == module)
         .collect()
 }
 
-#[decl(fn, name = "get_tickets_by_severity", vis = "pub", hash = "bf2ba8f9")]
+#[decl(fn, name = "get_tickets_by_severity", vis = "pub", hash = "a0301f4f")]
 pub fn get_tickets_by_severity(min_severity: Severity) -> Vec<AuditTicket> {
     get_all_tickets().into_iter()
         .filter(|t| t.severity >= min_severity)
         .collect()
 }
 
-#[decl(fn, name = "print_ticket", vis = "pub", hash = "098e04ed")]
+#[decl(fn, name = "print_ticket", vis = "pub", hash = "8ecf91a5")]
 pub fn print_ticket(ticket: &AuditTicket) {
     let reset = "\x1b[0m";
     let cyan = "\x1b[96m";
@@ -499,7 +416,7 @@ pub fn print_ticket(ticket: &AuditTicket) {
     eprintln!("{}└─────────────────────────────────────────────────────────────────┘{}\n", cyan, reset);
 }
 
-#[decl(fn, name = "print_all_tickets", vis = "pub", hash = "ea2f9c0c")]
+#[decl(fn, name = "print_all_tickets", vis = "pub", hash = "766fac3e")]
 pub fn print_all_tickets() {
     let mut tickets = get_all_tickets();
     tickets.sort_by(|a, b| {
@@ -526,7 +443,7 @@ pub fn print_all_tickets() {
     eprintln!("\n{} Total tickets: {}", "📊", tickets.len());
 }
 
-#[decl(fn, name = "generate_clippy_toml", vis = "pub", hash = "727e4dba")]
+#[decl(fn, name = "generate_clippy_toml", vis = "pub", hash = "2d61585e")]
 pub fn generate_clippy_toml() -> String {
     let tickets = get_all_tickets();
     let mut toml = String::new();
@@ -551,6 +468,221 @@ pub fn generate_clippy_toml() -> String {
     toml
 }
 
+/// Markers around the generated part of the ticket markdown; everything
+/// outside them is left as written.
+pub const TICKETS_MD_BEGIN: &str = "<!-- audit-tickets:begin (generated by `cargo-audit-fix tickets sync`) -->";
+pub const TICKETS_MD_END: &str = "<!-- audit-tickets:end -->";
+
+/// Tickets grouped by category: built-in categories first, then custom ones by name.
+fn tickets_by_category(tickets: &[AuditTicket]) -> Vec<(TicketCategory, Vec<&AuditTicket>)> {
+    let mut groups: BTreeMap<(usize, &str), (TicketCategory, Vec<&AuditTicket>)> = BTreeMap::new();
+    for ticket in tickets {
+        let rank = TicketCategory::BUILTIN.iter()
+            .position(|c| *c == ticket.category)
+            .unwrap_or(TicketCategory::BUILTIN.len());
+        groups.entry((rank, ticket.category.name()))
+            .or_insert_with(|| (ticket.category, Vec::new()))
+            .1.push(ticket);
+    }
+    groups.into_values()
+        .map(|(category, mut list)| {
+            list.sort_by(|a, b| a.id.cmp(b.id));
+            (category, list)
+        })
+        .collect()
+}
+
+/// The generated ticket list, including the begin/end markers.
+#[decl(fn, name = "generate_tickets_markdown", vis = "pub", hash = "a697f5db")]
+pub fn generate_tickets_markdown(tickets: &[AuditTicket]) -> String {
+    let groups = tickets_by_category(tickets);
+    let mut md = String::new();
+
+    md.push_str(TICKETS_MD_BEGIN);
+    md.push_str("\n\n## 📊 Ticket Summary\n\n");
+    md.push_str(&format!("**Total Tickets**: {}\n\n", tickets.len()));
+    md.push_str("| Category | Prefix | Count | Emoji |\n");
+    md.push_str("|----------|--------|-------|-------|\n");
+    for (category, list) in &groups {
+        md.push_str(&format!("| {} | `{}-XXX` | {} | {} |\n",
+            category.name(), category.prefix(), list.len(), category.emoji()));
+    }
+    md.push_str("\n---\n");
+
+    for (category, list) in &groups {
+        md.push_str(&format!("\n## {} {} Tickets ({}-XXX)\n", category.emoji(), category.name(), category.prefix()));
+        for t in list {
+            md.push_str(&format!("\n### {} {} {}\n", t.id, t.severity.emoji(), t.severity.as_str().to_uppercase()));
+            md.push_str(&format!("**Module:** `{}`  \n", t.module));
+            md.push_str(&format!("**Title:** {}  \n", t.title));
+            md.push_str(&format!("**Description:** {}\n", t.description));
+            if let Some(fix) = t.suggested_fix {
+                md.push_str(&format!("\n**Suggested Fix:** {}\n", fix));
+            }
+            if let Some(lint) = t.clippy_lint {
+                md.push_str(&format!("\n**Clippy Lint:** `{}`\n", lint));
+            }
+            md.push_str("\n---\n");
+        }
+    }
+
+    md.push('\n');
+    md.push_str(TICKETS_MD_END);
+    md.push('\n');
+    md
+}
+
+/// A ticket as documented in markdown: `### ID <emoji> SEVERITY` followed
+/// by `**Module:**`, `**Title:**` and `**Description:**` lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[decl(struct, name = "DocumentedTicket", vis = "pub", hash = "f0b599cd")]
+pub struct DocumentedTicket {
+    pub id: String,
+    pub severity: String,
+    pub module: String,
+    pub title: String,
+    pub description: String,
+}
+
+#[decl(fn, name = "parse_tickets_markdown", vis = "pub", hash = "996699bf")]
+pub fn parse_tickets_markdown(md: &str) -> Vec<DocumentedTicket> {
+    let mut docs: Vec<DocumentedTicket> = Vec::new();
+    for line in md.lines() {
+        if let Some(heading) = line.strip_prefix("### ") {
+            let mut words = heading.split_whitespace();
+            match words.next() {
+                Some(id) if TICKET_ID_RE.is_match(id) => docs.push(DocumentedTicket {
+                    id: id.to_string(),
+                    severity: words.last().unwrap_or("").to_lowercase(),
+                    ..Default::default()
+                }),
+                _ => {}
+            }
+            continue;
+        }
+        if line.starts_with("## ") {
+            continue;
+        }
+        let Some(doc) = docs.last_mut() else { continue };
+        let field = |prefix: &str| line.strip_prefix(prefix).map(|v| v.trim().trim_matches('`').to_string());
+        if let Some(v) = field("**Module:**") {
+            doc.module = v;
+        } else if let Some(v) = field("**Title:**") {
+            doc.title = v;
+        } else if let Some(v) = field("**Description:**") {
+            doc.description = v;
+        }
+    }
+    docs
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(enum, name = "TicketDivergence", vis = "pub", hash = "f352273f")]
+pub enum TicketDivergence {
+    /// In the registry but not in the markdown.
+    Undocumented(String),
+    /// In the markdown but not in the registry.
+    Unregistered(String),
+    Changed { id: String, field: &'static str, documented: String, registered: String },
+}
+
+impl std::fmt::Display for TicketDivergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketDivergence::Undocumented(id) => write!(f, "{}: missing from the markdown", id),
+            TicketDivergence::Unregistered(id) => write!(f, "{}: documented but not in the registry", id),
+            TicketDivergence::Changed { id, field, documented, registered } => {
+                write!(f, "{}: {} is \"{}\" in the markdown but \"{}\" in the registry", id, field, documented, registered)
+            }
+        }
+    }
+}
+
+/// Differences between the tickets documented in `md` and `tickets`, by id.
+#[decl(fn, name = "ticket_divergence", vis = "pub", hash = "c019ec61")]
+pub fn ticket_divergence(md: &str, tickets: &[AuditTicket]) -> Vec<TicketDivergence> {
+    let docs: BTreeMap<String, DocumentedTicket> = parse_tickets_markdown(md)
+        .into_iter()
+        .map(|d| (d.id.clone(), d))
+        .collect();
+    let registered: BTreeMap<&str, &AuditTicket> = tickets.iter().map(|t| (t.id, t)).collect();
+    let mut divergence = Vec::new();
+
+    for (id, ticket) in &registered {
+        let Some(doc) = docs.get(*id) else {
+            divergence.push(TicketDivergence::Undocumented(id.to_string()));
+            continue;
+        };
+        let fields = [
+            ("severity", doc.severity.as_str(), ticket.severity.as_str()),
+            ("module", doc.module.as_str(), ticket.module),
+            ("title", doc.title.as_str(), ticket.title),
+            ("description", doc.description.as_str(), ticket.description),
+        ];
+        for (field, documented, registered) in fields {
+            if documented.trim() != registered.trim() {
+                divergence.push(TicketDivergence::Changed {
+                    id: id.to_string(),
+                    field,
+                    documented: documented.to_string(),
+                    registered: registered.to_string(),
+                });
+            }
+        }
+    }
+    for id in docs.keys().filter(|id| !registered.contains_key(id.as_str())) {
+        divergence.push(TicketDivergence::Unregistered(id.clone()));
+    }
+
+    divergence
+}
+
+/// `md` with its generated ticket list replaced by one built from `tickets`.
+///
+/// Without markers, the region from the first `## ` heading through the
+/// last documented ticket is replaced, so hand-written sections before and
+/// after it survive the first sync.
+#[decl(fn, name = "sync_tickets_markdown", vis = "pub", hash = "dbcde7dd")]
+pub fn sync_tickets_markdown(md: &str, tickets: &[AuditTicket]) -> String {
+    let generated = generate_tickets_markdown(tickets);
+
+    if let (Some(begin), Some(end)) = (md.find(TICKETS_MD_BEGIN), md.find(TICKETS_MD_END)) {
+        if begin < end {
+            let mut after = &md[end + TICKETS_MD_END.len()..];
+            after = after.strip_prefix('\n').unwrap_or(after);
+            return format!("{}{}{}", &md[..begin], generated, after);
+        }
+    }
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(md.match_indices('\n').map(|(i, _)| i + 1))
+        .filter(|&i| i < md.len())
+        .collect();
+    let line_at = |i: usize| &md[i..];
+    let is_ticket_heading = |i: usize| {
+        line_at(i).strip_prefix("### ")
+            .and_then(|h| h.split_whitespace().next())
+            .is_some_and(|id| TICKET_ID_RE.is_match(id))
+    };
+
+    let last_ticket = line_starts.iter().rposition(|&i| is_ticket_heading(i));
+    let first_section = line_starts.iter().position(|&i| line_at(i).starts_with("## "));
+    match (first_section, last_ticket) {
+        (Some(first), Some(last)) if first <= last => {
+            let start = line_starts[first];
+            let end = line_starts[last + 1..].iter()
+                .copied()
+                .find(|&i| line_at(i).starts_with("## ") || line_at(i).starts_with("# "))
+                .unwrap_or(md.len());
+            format!("{}{}\n{}", &md[..start], generated, &md[end..])
+        }
+        _ => {
+            let separator = if md.is_empty() || md.ends_with("\n\n") { "" } else if md.ends_with('\n') { "\n" } else { "\n\n" };
+            format!("{}{}{}", md, separator, generated)
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! ticket {
     ($id:literal) => {{
@@ -606,4 +738,105 @@ mod tests {
         assert!(!high.is_empty());
         assert!(high.iter().all(|t| t.severity >= Severity::High));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_builtin_files_match_registry() {
+        let builtin = load_ticket_sources(BUILTIN_TICKET_FILES).unwrap();
+        assert_eq!(builtin.len(), 37);
+        let pho4 = builtin.iter().find(|t| t.id == "PHO-004").unwrap();
+        assert_eq!(pho4.severity, Severity::Critical);
+        assert_eq!(pho4.clippy_lint, Some("fake_crypto"));
+    }
+
+    #[test]
+    fn test_custom_category_across_files() {
+        let categories = r#"
+[[category]]
+name = "Compliance"
+prefix = "CMP"
+emoji = "📋"
+"#;
+        let tickets = r#"
+[[ticket]]
+id = "CMP-001"
+category = "compliance"
+severity = "high"
+module = "billing"
+title = "Invoices lack audit trail"
+description = "Invoice edits are not logged"
+
+[[ticket]]
+id = "PHO-011"
+category = "PHO"
+severity = "low"
+module = "poetry"
+title = "Illustrative verse"
+description = ""
+"#;
+        let loaded = load_ticket_sources(&[("a.toml", categories), ("b.toml", tickets)]).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].category.prefix(), "CMP");
+        assert_eq!(loaded[0].category.emoji(), "📋");
+        assert_eq!(loaded[1].category, TicketCategory::Phony);
+
+        // The same category loaded again is the same value.
+        let again = load_ticket_sources(&[("a.toml", categories), ("b.toml", tickets)]).unwrap();
+        assert_eq!(again[0].category, loaded[0].category);
+    }
+
+    #[test]
+    fn test_schema_validation_reports_every_problem() {
+        let src = r#"
+[[category]]
+name = "Clash"
+prefix = "PHO"
+
+[[ticket]]
+id = "FKD-100"
+category = "Phony"
+severity = "urgent"
+module = ""
+title = "t"
+description = "d"
+
+[[ticket]]
+id = "PHO-100"
+category = "Nope"
+severity = "low"
+module = "m"
+title = "t"
+description = "d"
+"#;
+        let err = load_ticket_sources(&[("bad.toml", src)]).unwrap_err();
+        assert!(err.contains("clashes with `Phony`"), "{}", err);
+        assert!(err.contains("FKD-100: id prefix `FKD` does not match category `Phony`"), "{}", err);
+        assert!(err.contains("unknown severity `urgent`"), "{}", err);
+        assert!(err.contains("`module` is empty"), "{}", err);
+        assert!(err.contains("PHO-100: unknown category `Nope`"), "{}", err);
+
+        let unknown_field = "[[ticket]]\nid = \"PHO-1\"\nowner = \"me\"\n";
+        assert!(load_ticket_sources(&[("x.toml", unknown_field)]).unwrap_err().contains("x.toml"));
+
+        let dup = "[[ticket]]\nid = \"TDO-900\"\ncategory = \"Todo\"\nseverity = \"low\"\nmodule = \"m\"\ntitle = \"t\"\ndescription = \"d\"\n";
+        let err = load_ticket_sources(&[("one.toml", dup), ("two.toml", dup)]).unwrap_err();
+        assert!(err.contains("two.toml: TDO-900: duplicate id, first defined in one.toml"), "{}", err);
+    }
+
+    #[test]
+    fn test_markdown_sync_round_trip() {
+        let tickets = load_ticket_sources(BUILTIN_TICKET_FILES).unwrap();
+        let md = "# Tickets\n\nIntro.\n\n## Old Summary\n\n### PHO-001 🔴 HIGH\n**Module:** `elsewhere`  \n**Title:** Fabricated VFS statistics  \n\n### PHO-999 🟢 LOW\n**Title:** Gone\n\n---\n\n## Usage\n\nRun it.\n";
+
+        let divergence = ticket_divergence(md, &tickets);
+        assert!(divergence.contains(&TicketDivergence::Unregistered("PHO-999".to_string())));
+        assert!(divergence.contains(&TicketDivergence::Undocumented("TDO-002".to_string())));
+        assert!(divergence.iter().any(|d| matches!(d,
+            TicketDivergence::Changed { id, field: "module", .. } if id == "PHO-001")));
+
+        let synced = sync_tickets_markdown(md, &tickets);
+        assert!(synced.starts_with("# Tickets\n\nIntro.\n\n<!-- audit-tickets:begin"));
+        assert!(synced.ends_with("<!-- audit-tickets:end -->\n\n## Usage\n\nRun it.\n"));
+        assert!(ticket_divergence(&synced, &tickets).is_empty());
+        assert_eq!(sync_tickets_markdown(&synced, &tickets), synced);
+    }
+}
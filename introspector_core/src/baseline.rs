@@ -0,0 +1,714 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use once_cell::sync::Lazy;
+use proc_macro2::Span;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, ImplItem, Item, Meta, TraitItem};
+use walkdir::WalkDir;
+use introspector_decl_core::{impl_owner_name, parse_audit_allow, AuditAllow, AUDIT_ALLOW};
+use crate::api_snapshot::module_prefix;
+use crate::auto_fix::{code_tokens, get_fix_patterns, scan_source_for_fixes, FixAction, SourceMap};
+use crate::decl_wrapper::LineIndex;
+
+// Triage of `scan` findings against a baseline of accepted findings and
+// inline suppressions, so a legacy codebase only reports what is new.
+//
+// A finding is identified by its rule, the path of the enclosing item and a
+// hash of its whitespace-normalized line, so edits elsewhere in the file do
+// not change it. Suppressions are `// audit-allow(SEC-001): reason` comments,
+// covering their own line or the statement or item below, and
+// `#[audit_allow(SEC-001, reason = "...")]` attributes, covering the item.
+
+pub const BASELINE_FILE: &str = "audit-baseline.json";
+pub const BASELINE_VERSION: u32 = 1;
+
+/// `// audit-allow(IDS)` followed by the rest of the line. Only counts where
+/// the `//` opens a plain comment, see `parse_suppressions`.
+static ALLOW_COMMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"//[ \t]*audit-allow\(([^)\n]*)\)([^\n]*)").unwrap()
+});
+
+fn fnv1a(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// `line` trimmed, with runs of whitespace collapsed to one space.
+#[decl(fn, name = "normalize_snippet", vis = "pub", hash = "fece6ffd")]
+pub fn normalize_snippet(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Stable identity of a finding. `occurrence` tells apart identical
+/// findings in the same item.
+#[decl(fn, name = "finding_fingerprint", vis = "pub", hash = "5e44d3d1")]
+pub fn finding_fingerprint(rule: &str, item_path: &str, snippet: &str, occurrence: usize) -> String {
+    let snippet_hash = fnv1a(&[&normalize_snippet(snippet)]);
+    fnv1a(&[rule, item_path, &snippet_hash, &occurrence.to_string()])
+}
+
+/// A scan finding with its baseline identity.
+#[derive(Debug, Clone)]
+#[decl(struct, name = "Finding", vis = "pub", hash = "f0f72f6d")]
+pub struct Finding {
+    pub path: PathBuf,
+    /// `path` relative to the scan root, with `/` separators.
+    pub file: String,
+    /// Module path of the file joined with the enclosing items, e.g.
+    /// `src::wallet::Wallet::sign`.
+    pub item_path: String,
+    /// The normalized text of the matched line.
+    pub snippet: String,
+    pub fingerprint: String,
+    pub fix: FixAction,
+}
+
+/// An inline suppression and the source it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "Suppression", vis = "pub", hash = "90663aba")]
+pub struct Suppression {
+    pub file: String,
+    pub line: usize,
+    pub ticket_ids: Vec<String>,
+    pub reason: String,
+    pub is_attribute: bool,
+    /// Byte range of the covered source.
+    pub covers: Range<usize>,
+}
+
+impl Suppression {
+    fn matches(&self, fix: &FixAction) -> bool {
+        self.covers.contains(&fix.matched.start) && self.ticket_ids.iter().any(|id| id == fix.ticket_id)
+    }
+}
+
+/// A suppression that does not suppress anything because it is malformed,
+/// most often for lack of a justification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "InvalidSuppression", vis = "pub", hash = "81f8bbe7")]
+pub struct InvalidSuppression {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[decl(struct, name = "BaselineEntry", vis = "pub", hash = "a175321e")]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub rule: String,
+    pub file: String,
+    pub item: String,
+    pub snippet: String,
+}
+
+/// Accepted findings, stored as `audit-baseline.json`. Only `fingerprint`
+/// is matched; the other fields keep the file reviewable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[decl(struct, name = "Baseline", vis = "pub", hash = "2bdd1f83")]
+pub struct Baseline {
+    pub version: u32,
+    pub findings: Vec<BaselineEntry>,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Baseline { version: BASELINE_VERSION, findings: Vec::new() }
+    }
+}
+
+impl Baseline {
+    pub fn from_findings<'a>(findings: impl IntoIterator<Item = &'a Finding>) -> Self {
+        let mut entries: Vec<BaselineEntry> = findings.into_iter()
+            .map(|f| BaselineEntry {
+                fingerprint: f.fingerprint.clone(),
+                rule: f.fix.ticket_id.to_string(),
+                file: f.file.clone(),
+                item: f.item_path.clone(),
+                snippet: f.snippet.clone(),
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.file, &a.item, &a.rule, &a.fingerprint).cmp(&(&b.file, &b.item, &b.rule, &b.fingerprint)));
+        entries.dedup_by(|a, b| a.fingerprint == b.fingerprint);
+        Baseline { version: BASELINE_VERSION, findings: entries }
+    }
+
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let baseline: Baseline = serde_json::from_str(json).map_err(|e| format!("invalid baseline: {}", e))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(format!("unsupported baseline version {} (expected {})", baseline.version, BASELINE_VERSION));
+        }
+        Ok(baseline)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Baseline::parse(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).unwrap_or_default();
+        json.push('\n');
+        json
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+/// Item paths, statement and item extents, and `#[audit_allow]` attributes
+/// of one file.
+struct SourceScopes<'a> {
+    index: LineIndex<'a>,
+    path: Vec<String>,
+    items: Vec<(Range<usize>, String)>,
+    /// Start of each statement, item, field, variant and arm → furthest end.
+    node_ends: BTreeMap<usize, usize>,
+    allows: Vec<(usize, Range<usize>, Result<AuditAllow, String>)>,
+}
+
+impl SourceScopes<'_> {
+    fn node(&mut self, span: Span) -> Range<usize> {
+        let span = self.index.span(span);
+        let end = self.node_ends.entry(span.start_byte).or_insert(span.end_byte);
+        *end = (*end).max(span.end_byte);
+        span.start_byte..span.end_byte
+    }
+
+    fn allows_on(&mut self, attrs: &[Attribute], covers: Range<usize>) {
+        let allows = attrs.iter()
+            .filter(|a| a.path().segments.last().is_some_and(|s| s.ident == AUDIT_ALLOW));
+        for attr in allows {
+            let parsed = match &attr.meta {
+                Meta::List(list) => parse_audit_allow(list.tokens.clone()),
+                _ => Err("audit_allow needs ticket ids and `reason = \"...\"`".to_string()),
+            };
+            self.allows.push((attr.span().start().line, covers.clone(), parsed));
+        }
+    }
+
+    fn scoped(&mut self, name: Option<String>, attrs: &[Attribute], span: Span, visit: impl FnOnce(&mut Self)) {
+        let range = self.node(span);
+        self.allows_on(attrs, range.clone());
+        match name {
+            Some(name) => {
+                self.path.push(name);
+                self.items.push((range, self.path.join("::")));
+                visit(self);
+                self.path.pop();
+            }
+            None => visit(self),
+        }
+    }
+}
+
+/// `Stack<T>` → `Stack`, `&mut Stack` → `Stack`.
+fn impl_base_name(item: &syn::ItemImpl) -> String {
+    let owner = impl_owner_name(item);
+    let base = owner.split('<').next().unwrap_or(&owner);
+    base.trim_start_matches(['&', ' ']).trim_start_matches("mut ").trim().to_string()
+}
+
+impl<'ast> Visit<'ast> for SourceScopes<'_> {
+    fn visit_file(&mut self, file: &'ast syn::File) {
+        self.allows_on(&file.attrs, 0..usize::MAX);
+        visit::visit_file(self, file);
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        let (name, attrs): (Option<String>, &[Attribute]) = match item {
+            Item::Const(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Enum(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::ExternCrate(i) => (None, &i.attrs),
+            Item::Fn(i) => (Some(i.sig.ident.to_string()), &i.attrs),
+            Item::ForeignMod(i) => (None, &i.attrs),
+            Item::Impl(i) => (Some(impl_base_name(i)), &i.attrs),
+            Item::Macro(i) => (i.ident.as_ref().map(|id| id.to_string()), &i.attrs),
+            Item::Mod(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Static(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Struct(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Trait(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::TraitAlias(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Type(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Union(i) => (Some(i.ident.to_string()), &i.attrs),
+            Item::Use(i) => (None, &i.attrs),
+            _ => (None, &[]),
+        };
+        self.scoped(name, attrs, item.span(), |s| visit::visit_item(s, item));
+    }
+
+    fn visit_impl_item(&mut self, item: &'ast ImplItem) {
+        let (name, attrs): (Option<String>, &[Attribute]) = match item {
+            ImplItem::Const(i) => (Some(i.ident.to_string()), &i.attrs),
+            ImplItem::Fn(i) => (Some(i.sig.ident.to_string()), &i.attrs),
+            ImplItem::Type(i) => (Some(i.ident.to_string()), &i.attrs),
+            ImplItem::Macro(i) => (None, &i.attrs),
+            _ => (None, &[]),
+        };
+        self.scoped(name, attrs, item.span(), |s| visit::visit_impl_item(s, item));
+    }
+
+    fn visit_trait_item(&mut self, item: &'ast TraitItem) {
+        let (name, attrs): (Option<String>, &[Attribute]) = match item {
+            TraitItem::Const(i) => (Some(i.ident.to_string()), &i.attrs),
+            TraitItem::Fn(i) => (Some(i.sig.ident.to_string()), &i.attrs),
+            TraitItem::Type(i) => (Some(i.ident.to_string()), &i.attrs),
+            TraitItem::Macro(i) => (None, &i.attrs),
+            _ => (None, &[]),
+        };
+        self.scoped(name, attrs, item.span(), |s| visit::visit_trait_item(s, item));
+    }
+
+    fn visit_foreign_item(&mut self, item: &'ast syn::ForeignItem) {
+        let (name, attrs): (Option<String>, &[Attribute]) = match item {
+            syn::ForeignItem::Fn(i) => (Some(i.sig.ident.to_string()), &i.attrs),
+            syn::ForeignItem::Static(i) => (Some(i.ident.to_string()), &i.attrs),
+            syn::ForeignItem::Type(i) => (Some(i.ident.to_string()), &i.attrs),
+            syn::ForeignItem::Macro(i) => (None, &i.attrs),
+            _ => (None, &[]),
+        };
+        self.scoped(name, attrs, item.span(), |s| visit::visit_foreign_item(s, item));
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast syn::Stmt) {
+        self.node(stmt.span());
+        visit::visit_stmt(self, stmt);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        self.node(field.span());
+        visit::visit_field(self, field);
+    }
+
+    fn visit_variant(&mut self, variant: &'ast syn::Variant) {
+        self.node(variant.span());
+        visit::visit_variant(self, variant);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        self.node(arm.span());
+        visit::visit_arm(self, arm);
+    }
+}
+
+fn source_scopes(content: &str) -> Option<SourceScopes<'_>> {
+    let file = syn::parse_file(content).ok()?;
+    let mut scopes = SourceScopes {
+        index: LineIndex::new(content),
+        path: Vec::new(),
+        items: Vec::new(),
+        node_ends: BTreeMap::new(),
+        allows: Vec::new(),
+    };
+    scopes.visit_file(&file);
+    Some(scopes)
+}
+
+/// Byte range and path (`Type::method`) of every named item in `content`.
+pub(crate) fn item_paths(content: &str) -> Vec<(Range<usize>, String)> {
+    source_scopes(content).map(|s| s.items).unwrap_or_default()
+}
+
+/// Path of the innermost item containing `offset`.
+pub(crate) fn innermost_item(items: &[(Range<usize>, String)], offset: usize) -> Option<&str> {
+    items.iter()
+        .filter(|(range, _)| range.contains(&offset))
+        .max_by_key(|(range, _)| range.start)
+        .map(|(_, path)| path.as_str())
+}
+
+fn relative_file(path: &Path, root: &Path) -> PathBuf {
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn line_bounds(content: &str, offset: usize) -> Range<usize> {
+    let offset = offset.min(content.len());
+    let start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = content[offset..].find('\n').map_or(content.len(), |i| offset + i);
+    start..end
+}
+
+/// `fixes` for the file at `path`, with item paths and fingerprints.
+#[decl(fn, name = "findings_for_fixes", vis = "pub", hash = "835491ef")]
+pub fn findings_for_fixes(content: &str, path: &Path, root: &Path, fixes: Vec<FixAction>) -> Vec<Finding> {
+    let relative = relative_file(path, root);
+    let module = module_prefix(&relative);
+    let items = item_paths(content);
+    let mut occurrences: BTreeMap<(&str, String, String), usize> = BTreeMap::new();
+
+    fixes.into_iter().map(|fix| {
+        let offset = fix.matched.start;
+        let item = innermost_item(&items, offset);
+        let item_path = [Some(module.as_str()), item].into_iter()
+            .flatten()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("::");
+        let line = if fix.matched.end <= content.len() && content.is_char_boundary(offset) {
+            &content[line_bounds(content, offset)]
+        } else {
+            fix.original.as_str()
+        };
+        let snippet = normalize_snippet(line);
+
+        let occurrence = occurrences.entry((fix.ticket_id, item_path.clone(), snippet.clone())).or_insert(0);
+        let fingerprint = finding_fingerprint(fix.ticket_id, &item_path, &snippet, *occurrence);
+        *occurrence += 1;
+
+        Finding { path: path.to_path_buf(), file: slash_path(&relative), item_path, snippet, fingerprint, fix }
+    }).collect()
+}
+
+/// The `// audit-allow(...)` comments and `#[audit_allow(...)]` attributes
+/// in `content`. A comment after code covers its own line; on a line of its
+/// own it covers the next line of code, extended to the statement or item
+/// that starts there. An attribute covers its item, or the whole file as an
+/// inner attribute.
+#[decl(fn, name = "parse_suppressions", vis = "pub", hash = "3e6a0acb")]
+pub fn parse_suppressions(content: &str, file: &str) -> (Vec<Suppression>, Vec<InvalidSuppression>) {
+    let mut suppressions = Vec::new();
+    let mut invalid = Vec::new();
+    let (Some(scopes), Some(tokens)) = (source_scopes(content), code_tokens(content)) else {
+        return (suppressions, invalid);
+    };
+    let map = SourceMap { content, tokens };
+    let tokens = &map.tokens;
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+
+    for caps in ALLOW_COMMENT_RE.captures_iter(content) {
+        let whole = caps.get(0).unwrap();
+        let start = whole.start();
+        // The `//` must open the comment, so examples quoted in strings or in
+        // the text of other comments are not suppressions, and doc comments
+        // (`/// audit-allow`, matched from its second slash) are documentation.
+        if !map.is_insertable(start) || content[..start].ends_with('/') {
+            continue;
+        }
+        let line = line_of(start);
+
+        let rest = caps[2].trim();
+        let reason = rest.strip_prefix(':').map(str::trim).unwrap_or("");
+        let ids: proc_macro2::TokenStream = match caps[1].parse() {
+            Ok(ids) => ids,
+            Err(_) => {
+                invalid.push(InvalidSuppression { file: file.to_string(), line, message: format!("cannot parse ticket ids `{}`", &caps[1]) });
+                continue;
+            }
+        };
+        let reason_lit = syn::LitStr::new(reason, Span::call_site());
+        let allow = match parse_audit_allow(quote::quote!(#ids, reason = #reason_lit)) {
+            Ok(allow) => allow,
+            Err(message) => {
+                let message = if reason.is_empty() {
+                    "audit-allow needs a justification: `// audit-allow(ID): reason`".to_string()
+                } else {
+                    message
+                };
+                invalid.push(InvalidSuppression { file: file.to_string(), line, message });
+                continue;
+            }
+        };
+
+        let bounds = line_bounds(content, start);
+        let covers = if !content[bounds.start..start].trim().is_empty() {
+            bounds
+        } else {
+            match tokens.get(tokens.partition_point(|&(s, _)| s < bounds.end)) {
+                Some(&(next, _)) => {
+                    let next_line = line_bounds(content, next);
+                    let node_end = scopes.node_ends.get(&next).copied().unwrap_or(next_line.end);
+                    next_line.start..node_end.max(next_line.end)
+                }
+                None => bounds,
+            }
+        };
+        suppressions.push(Suppression {
+            file: file.to_string(),
+            line,
+            ticket_ids: allow.ticket_ids,
+            reason: allow.reason,
+            is_attribute: false,
+            covers,
+        });
+    }
+
+    for (line, covers, parsed) in scopes.allows {
+        match parsed {
+            Ok(allow) => suppressions.push(Suppression {
+                file: file.to_string(),
+                line,
+                ticket_ids: allow.ticket_ids,
+                reason: allow.reason,
+                is_attribute: true,
+                covers: covers.start..covers.end.min(content.len()),
+            }),
+            Err(message) => invalid.push(InvalidSuppression { file: file.to_string(), line, message }),
+        }
+    }
+
+    suppressions.sort_by_key(|s| s.line);
+    invalid.sort_by_key(|s| s.line);
+    (suppressions, invalid)
+}
+
+/// Findings split into new, baselined and suppressed, with the
+/// suppressions and baseline entries that no longer match anything.
+#[derive(Debug, Clone, Default)]
+#[decl(struct, name = "ScanTriage", vis = "pub", hash = "78b8ad81")]
+pub struct ScanTriage {
+    pub new: Vec<Finding>,
+    pub baselined: Vec<Finding>,
+    /// Each suppressed finding with the index of its suppression.
+    pub suppressed: Vec<(Finding, usize)>,
+    pub suppressions: Vec<Suppression>,
+    pub invalid_suppressions: Vec<InvalidSuppression>,
+    pub stale_baseline: Vec<BaselineEntry>,
+}
+
+impl ScanTriage {
+    /// Suppressions that match no finding. Only those naming nothing but
+    /// tickets `scan` reports can be judged here; `SEC-*` ones, say, belong
+    /// to the capabilities inventory.
+    pub fn stale_suppressions(&self) -> Vec<&Suppression> {
+        let used: HashSet<usize> = self.suppressed.iter().map(|(_, i)| *i).collect();
+        let scanned: HashSet<&str> = get_fix_patterns().iter().map(|p| p.ticket_id).collect();
+        self.suppressions.iter().enumerate()
+            .filter(|(i, s)| !used.contains(i) && s.ticket_ids.iter().all(|id| scanned.contains(id.as_str())))
+            .map(|(_, s)| s)
+            .collect()
+    }
+
+    /// New findings grouped by file, in the shape `scan_directory` returns.
+    pub fn new_results(&self) -> Vec<(PathBuf, Vec<FixAction>)> {
+        let mut results: Vec<(PathBuf, Vec<FixAction>)> = Vec::new();
+        for finding in &self.new {
+            match results.last_mut() {
+                Some((path, fixes)) if *path == finding.path => fixes.push(finding.fix.clone()),
+                _ => results.push((finding.path.clone(), vec![finding.fix.clone()])),
+            }
+        }
+        results
+    }
+
+    /// Every finding that is not suppressed inline, for writing a baseline.
+    pub fn unsuppressed(&self) -> impl Iterator<Item = &Finding> {
+        self.new.iter().chain(&self.baselined)
+    }
+}
+
+fn triage_file(content: &str, path: &Path, root: &Path, known: &HashSet<&str>, triage: &mut ScanTriage) {
+    let findings = findings_for_fixes(content, path, root, scan_source_for_fixes(content));
+    let file = slash_path(&relative_file(path, root));
+    let (suppressions, invalid) = parse_suppressions(content, &file);
+    let first = triage.suppressions.len();
+    triage.suppressions.extend(suppressions);
+    triage.invalid_suppressions.extend(invalid);
+
+    for finding in findings {
+        let suppression = (first..triage.suppressions.len())
+            .filter(|&i| triage.suppressions[i].matches(&finding.fix))
+            .min_by_key(|&i| triage.suppressions[i].covers.len());
+        match suppression {
+            Some(i) => triage.suppressed.push((finding, i)),
+            None if known.contains(finding.fingerprint.as_str()) => triage.baselined.push(finding),
+            None => triage.new.push(finding),
+        }
+    }
+}
+
+fn finish_triage(mut triage: ScanTriage, baseline: &Baseline) -> ScanTriage {
+    let seen: HashSet<&str> = triage.baselined.iter().map(|f| f.fingerprint.as_str()).collect();
+    triage.stale_baseline = baseline.findings.iter()
+        .filter(|e| !seen.contains(e.fingerprint.as_str()))
+        .cloned()
+        .collect();
+    triage
+}
+
+/// Triage of a single source file; `path` is made relative to `root`.
+#[decl(fn, name = "triage_source", vis = "pub", hash = "8009244c")]
+pub fn triage_source(content: &str, path: &Path, root: &Path, baseline: &Baseline) -> ScanTriage {
+    let known: HashSet<&str> = baseline.findings.iter().map(|e| e.fingerprint.as_str()).collect();
+    let mut triage = ScanTriage::default();
+    triage_file(content, path, root, &known, &mut triage);
+    finish_triage(triage, baseline)
+}
+
+/// Triage of every `.rs` file under `root`, skipping `target` directories.
+#[decl(fn, name = "triage_directory", vis = "pub", hash = "6c6d5851")]
+pub fn triage_directory(root: &Path, baseline: &Baseline) -> ScanTriage {
+    let known: HashSet<&str> = baseline.findings.iter().map(|e| e.fingerprint.as_str()).collect();
+    let mut triage = ScanTriage::default();
+    let mut entries: Vec<_> = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "target")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    entries.sort_by(|a, b| a.path().cmp(b.path()));
+
+    for entry in entries {
+        if let Ok(content) = fs::read_to_string(entry.path()) {
+            triage_file(&content, entry.path(), root, &known, &mut triage);
+        }
+    }
+    finish_triage(triage, baseline)
+}
+
+#[decl(fn, name = "print_triage", vis = "pub", hash = "041ace53")]
+pub fn print_triage(triage: &ScanTriage) {
+    let reset = "\x1b[0m";
+    let yellow = "\x1b[93m";
+    let red = "\x1b[91m";
+
+    if !triage.baselined.is_empty() || !triage.stale_baseline.is_empty() {
+        eprintln!("📋 Baseline: {} known findings hidden", triage.baselined.len());
+        if !triage.stale_baseline.is_empty() {
+            eprintln!("   {} baseline entries no longer match; run `cargo-audit-fix baseline` to prune them", triage.stale_baseline.len());
+        }
+    }
+    let used = triage.suppressions.len() - triage.stale_suppressions().len();
+    if !triage.suppressed.is_empty() {
+        eprintln!("🔇 Suppressed: {} findings by {} inline suppressions", triage.suppressed.len(), used);
+    }
+
+    let stale = triage.stale_suppressions();
+    if !stale.is_empty() {
+        eprintln!("\n{}⚠️  Stale suppressions (no matching finding):{}", yellow, reset);
+        for s in stale {
+            eprintln!("   {}:{} audit-allow({}): {}", s.file, s.line, s.ticket_ids.join(", "), s.reason);
+        }
+    }
+    if !triage.invalid_suppressions.is_empty() {
+        eprintln!("\n{}❌ Invalid suppressions:{}", red, reset);
+        for s in &triage.invalid_suppressions {
+            eprintln!("   {}:{} {}", s.file, s.line, s.message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAKE: &str = "\"11111111111111111111111111111111\"";
+
+    fn triage(content: &str, baseline: &Baseline) -> ScanTriage {
+        triage_source(content, Path::new("/repo/src/wallet.rs"), Path::new("/repo"), baseline)
+    }
+
+    #[test]
+    fn test_fingerprints_follow_items_not_lines() {
+        let src = format!("impl Wallet {{\n    fn sign(&self) {{\n        let h = {};\n    }}\n}}\nfn other() {{\n    let h = {};\n}}\n", FAKE, FAKE);
+        let shifted = format!("use std::fmt;\n\n{}", src.replace("let h", "let   h"));
+        let before = triage(&src, &Baseline::default()).new;
+        let after = triage(&shifted, &Baseline::default()).new;
+
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0].item_path, "src::wallet::Wallet::sign");
+        assert_eq!(before[1].item_path, "src::wallet::other");
+        assert_eq!(before[0].file, "src/wallet.rs");
+        assert_ne!(before[0].fingerprint, before[1].fingerprint);
+        let prints = |f: &[Finding]| f.iter().map(|f| f.fingerprint.clone()).collect::<Vec<_>>();
+        assert_eq!(prints(&before), prints(&after));
+    }
+
+    #[test]
+    fn test_baseline_hides_known_findings_and_reports_stale_entries() {
+        let src = format!("fn f() {{\n    let h = {};\n}}\n", FAKE);
+        let baseline = Baseline::from_findings(&triage(&src, &Baseline::default()).new);
+        let baseline = Baseline::parse(&baseline.to_json()).unwrap();
+        assert_eq!(baseline.findings.len(), 1);
+
+        let grown = format!("{}fn g() {{\n    let h = {};\n}}\n", src, FAKE);
+        let result = triage(&grown, &baseline);
+        assert_eq!(result.baselined.len(), 1);
+        assert_eq!(result.new.len(), 1);
+        assert_eq!(result.new[0].item_path, "src::wallet::g");
+        assert!(result.stale_baseline.is_empty());
+
+        let fixed = triage("fn f() {}\n", &baseline);
+        assert_eq!(fixed.stale_baseline, baseline.findings);
+        assert!(Baseline::parse("{\"version\": 9, \"findings\": []}").is_err());
+    }
+
+    #[test]
+    fn test_comment_suppressions() {
+        let src = format!(
+            "fn f() {{\n    // audit-allow(FKD-002): test vector from the spec\n    let h = call(\n        {},\n    );\n    let g = {}; // audit-allow(FKD-002): same vector\n    let k = {};\n}}\n\
+             // audit-allow(PHO-001): nothing here\nfn g() {{}}\n// audit-allow(FKD-002)\nfn h() {{}}\n\
+             // audit-allow(SEC-006): spawns git\nfn k() {{}}\n",
+            FAKE, FAKE, FAKE,
+        );
+        let result = triage(&src, &Baseline::default());
+        assert_eq!(result.suppressed.len(), 2);
+        assert_eq!(result.new.len(), 1);
+        assert_eq!(result.new[0].fix.line, 7);
+        assert_eq!(result.suppressions[result.suppressed[0].1].reason, "test vector from the spec");
+
+        let stale = result.stale_suppressions();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].line, 9);
+        assert_eq!(result.invalid_suppressions.len(), 1);
+        assert_eq!(result.invalid_suppressions[0].line, 11);
+        assert!(result.invalid_suppressions[0].message.contains("justification"));
+    }
+
+    #[test]
+    fn test_documented_examples_are_not_suppressions() {
+        let src = "\
+//! Write `// audit-allow(SEC-001): reason` above the call.
+/// For example:
+/// // audit-allow(SEC-001): reason
+/// `// audit-allow(SEC-001)` without a reason is rejected.
+fn f() {
+    // Use // audit-allow(SEC-001) here, or /* // audit-allow(SEC-001) */ there.
+    /*
+    // audit-allow(SEC-001)
+    */
+    //   audit-allow(SEC-001): real
+    let x = 1;
+}
+";
+        let (suppressions, invalid) = parse_suppressions(src, "src/lib.rs");
+        assert!(invalid.is_empty(), "{:?}", invalid.iter().map(|i| i.line).collect::<Vec<_>>());
+        assert_eq!(suppressions.iter().map(|s| s.line).collect::<Vec<_>>(), vec![10]);
+    }
+
+    #[test]
+    fn test_attribute_suppressions() {
+        let src = format!(
+            "#[audit_allow(FKD-002, reason = \"fixture data\")]\nfn fixtures() {{\n    let h = {};\n}}\n\
+             #[audit_allow(FKD-002)]\nfn g() {{\n    let h = {};\n}}\n\
+             fn s() {{\n    let s = \"// audit-allow(FKD-002): in a string\";\n}}\n",
+            FAKE, FAKE,
+        );
+        let result = triage(&src, &Baseline::default());
+        assert_eq!(result.suppressed.len(), 1);
+        assert!(result.suppressions[result.suppressed[0].1].is_attribute);
+        assert_eq!(result.new.len(), 1);
+        assert_eq!(result.new[0].item_path, "src::wallet::g");
+        assert_eq!(result.suppressions.len(), 1);
+        assert_eq!(result.invalid_suppressions.len(), 1);
+        assert_eq!(result.invalid_suppressions[0].line, 5);
+    }
+}
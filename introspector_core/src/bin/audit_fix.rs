@@ -13,8 +13,15 @@ fn main() {
     
     let command = &args[1];
     let mut path_arg: Option<&str> = None;
+    let mut second_arg: Option<&str> = None;
     let mut dry_run = false;
     let mut recursive = false;
+    let mut include_deps = false;
+    let mut reverse = false;
+    let mut fuzz = introspector_core::ApplyOptions::default().fuzz;
+    let mut rules_file: Option<&str> = None;
+    let mut baseline_file: Option<&str> = None;
+    let mut format = "text";
 
     // Parse global options and subcommand arguments
     let mut i = 2;
@@ -22,9 +29,53 @@ fn main() {
         match args[i].as_str() {
             "--dry-run" | "-n" => dry_run = true,
             "--recursive" | "-r" => recursive = true,
+            "--deps" => include_deps = true,
+            "--reverse" | "-R" => reverse = true,
+            "--fuzz" => {
+                i += 1;
+                match args.get(i).and_then(|n| n.parse().ok()) {
+                    Some(n) => fuzz = n,
+                    None => {
+                        eprintln!("Error: --fuzz requires a number of context lines");
+                        process::exit(1);
+                    }
+                }
+            }
+            "--format" => {
+                i += 1;
+                match args.get(i).map(String::as_str) {
+                    Some(f @ ("text" | "sarif" | "json")) => format = f,
+                    other => {
+                        eprintln!("Error: --format expects `text`, `sarif` or `json`, got {:?}", other.unwrap_or(""));
+                        process::exit(1);
+                    }
+                }
+            }
+            "--rules" => {
+                i += 1;
+                match args.get(i) {
+                    Some(file) => rules_file = Some(file),
+                    None => {
+                        eprintln!("Error: --rules requires a TOML file");
+                        process::exit(1);
+                    }
+                }
+            }
+            "--baseline" => {
+                i += 1;
+                match args.get(i) {
+                    Some(file) => baseline_file = Some(file),
+                    None => {
+                        eprintln!("Error: --baseline requires a JSON file");
+                        process::exit(1);
+                    }
+                }
+            }
             _ => {
                 if path_arg.is_none() {
                     path_arg = Some(&args[i]);
+                } else if second_arg.is_none() && (command == "api-diff" || command == "rustc-wrap" || command == "apply-patch" || path_arg == Some("sync")) {
+                    second_arg = Some(&args[i]);
                 } else {
                     // This command already has a path, so this must be an unknown argument
                     eprintln!("Error: Unexpected argument '{}'", args[i]);
@@ -40,7 +91,10 @@ fn main() {
     
     match command.as_str() {
         "scan" => {
-            cmd_scan(current_path);
+            cmd_scan(current_path, format, baseline_file);
+        }
+        "baseline" => {
+            cmd_baseline(current_path, baseline_file);
         }
         "preview" => {
             if path_arg.is_none() {
@@ -59,6 +113,17 @@ fn main() {
             }
             cmd_diff(current_path);
         }
+        "apply-patch" => {
+            if path_arg.is_none() {
+                eprintln!("Error: apply-patch requires a patch file");
+                process::exit(1);
+            }
+            let options = introspector_core::ApplyOptions { fuzz, reverse };
+            cmd_apply_patch(current_path, second_arg.unwrap_or("."), options, dry_run);
+        }
+        "tickets" if path_arg == Some("sync") => {
+            cmd_tickets_sync(second_arg.unwrap_or("AUDIT_TICKETS.md"), dry_run);
+        }
         "tickets" => {
             let filter = path_arg;
             cmd_tickets(filter);
@@ -73,6 +138,12 @@ fn main() {
         "report" => {
             cmd_report();
         }
+        "capabilities" => {
+            cmd_capabilities(current_path, include_deps, format);
+        }
+        "lint" => {
+            cmd_lint(current_path, rules_file, format);
+        }
         "decl-scan" => {
             if path_arg.is_none() {
                 eprintln!("Error: decl-scan requires a file path");
@@ -83,6 +154,9 @@ fn main() {
         "decl-wrap" => {
             cmd_decl_wrap(current_path, dry_run, recursive);
         }
+        "decl-rehash" => {
+            cmd_decl_rehash(current_path, dry_run, recursive);
+        }
         "decl-json" => {
             if path_arg.is_none() {
                 eprintln!("Error: decl-json requires a file path");
@@ -90,6 +164,34 @@ fn main() {
             }
             cmd_decl_json(current_path);
         }
+        "rustc-analyze" => {
+            if path_arg.is_none() {
+                eprintln!("Error: rustc-analyze requires a rustc source directory");
+                process::exit(1);
+            }
+            cmd_rustc_analyze(current_path, format);
+        }
+        "rustc-wrap" => {
+            match (path_arg, second_arg) {
+                (Some(source), Some(output)) => cmd_rustc_wrap(source, output),
+                _ => {
+                    eprintln!("Error: rustc-wrap requires a rustc source directory and an output directory");
+                    process::exit(1);
+                }
+            }
+        }
+        "api-snapshot" => {
+            cmd_api_snapshot(current_path);
+        }
+        "api-diff" => {
+            match (path_arg, second_arg) {
+                (Some(old), Some(new)) => cmd_api_diff(old, new),
+                _ => {
+                    eprintln!("Error: api-diff requires an old and a new snapshot (or crate path)");
+                    process::exit(1);
+                }
+            }
+        }
         "help" | "--help" | "-h" => {
             print_usage(&args[0]);
         }
@@ -110,41 +212,134 @@ USAGE:
     {} <COMMAND> [PATH] [OPTIONS] 
 
 AUDIT COMMANDS:
-    scan [DIR]          Scan directory for audit issues (default: current dir)
+    scan [DIR] [--format sarif] [--baseline FILE]  Scan directory for audit issues (default: current dir);
+                        findings in the baseline (default: DIR/audit-baseline.json) or suppressed
+                        with `// audit-allow(ID): reason` / #[audit_allow(ID, reason = "...")] are hidden
+    baseline [DIR] [--baseline FILE]  Record all current findings as accepted
     preview <FILE>      Preview fixes for a single file
     fix [DIR] [--dry-run]  Apply fixes to directory (default: current dir)
-    diff <FILE>         Show git-style diff of proposed changes
+    diff <FILE>         Show git-style diff of proposed changes (for `git apply` or `patch -p1`)
+    apply-patch <PATCH> [DIR] [--reverse] [--fuzz N] [--dry-run]  Apply a unified diff to DIR
+                        (default: current dir), reporting hunks applied at an offset or with fuzz
     tickets [FILTER]    List all tickets (optional: PHO, FKD, ISS, etc.)
+    tickets sync [FILE] [--dry-run]  Regenerate the ticket list in FILE (default: AUDIT_TICKETS.md);
+                        --dry-run only reports divergence and fails if there is any
     ticket <ID>         Show details for a specific ticket
     report              Generate full macro audit report
+    capabilities [DIR] [--deps] [--format json]  Inventory unsafe code, FFI, process spawns and
+                        filesystem writes of the workspace (and its dependencies) as SEC tickets
+    lint [PATH] [--rules FILE] [--format json]  Check lint rules, plus user rules from FILE or
+                        ./audit-rules.toml; json prints rustc-style diagnostics for rustfix
 
 DECLARATION COMMANDS:
     decl-scan <FILE>    Scan file for public declarations and show metadata
     decl-wrap [PATH] [--dry-run] [--recursive]  Wrap public declarations with #[decl(...)]
     decl-json <FILE>    Export declarations as JSON
+    decl-rehash [PATH] [--dry-run] [--recursive]  Rewrite stale #[decl(..., hash = "...")] values
+
+API COMMANDS:
+    api-snapshot [PATH]  Write the public API snapshot of a crate or file as JSON
+    api-diff <OLD> <NEW> Classify API changes as patch/minor/major (snapshots or crate paths)
+
+RUSTC COMMANDS:
+    rustc-analyze <DIR> [--format json]  Per-crate item, unsafe and macro statistics of the
+                        compiler/rustc_* crates in a rustc source tree
+    rustc-wrap <SRC> <OUT>  Wrap public compiler items with #[decl] into OUT/overlay, with one
+                        patch per crate in OUT/patches, OUT/patches.nix and OUT/manifest.json
 
 OPTIONS:
     --dry-run, -n       Show what would be fixed without changing files
     --recursive, -r     Apply command recursively to all .rs files in a directory
+    --rules <FILE>      User lint rules (TOML) for the lint command
+    --baseline <FILE>   Baseline of accepted findings for scan and baseline
+    --reverse, -R       Undo the patch instead of applying it (apply-patch)
+    --fuzz <N>          Context lines a hunk may ignore at each end (apply-patch, default: 2)
+    --deps              Also inventory the dependencies resolved by `cargo metadata`
+    --format <FMT>      text (default); sarif (SARIF 2.1.0) for scan, json (rustc diagnostics) for lint,
+                        json (SEC tickets) for capabilities, json (crate statistics) for rustc-analyze
     --help, -h          Show this help message
 
 EXAMPLES:
     {} scan src/
+    {} scan . --format sarif > audit.sarif
+    {} baseline .
     {} preview src/solana_lift.rs
     {} fix src/ --dry-run
+    {} diff src/lib.rs > fixes.patch
+    {} apply-patch fixes.patch . --reverse
+    {} capabilities . --deps
+    {} lint src/ --rules team-rules.toml
+    {} tickets sync --dry-run
     {} decl-scan src/lib.rs
     {} decl-wrap src/lib.rs --dry-run
     {} decl-wrap . --recursive
     {} decl-json src/lib.rs > decls.json
-"#, program, program, program, program, program, program, program, program);
+    {} decl-rehash . --recursive --dry-run
+    {} api-snapshot . > api.json
+    {} api-diff api.json .
+    {} rustc-analyze /tmp/rustc-src --format json > rustc-stats.json
+    {} rustc-wrap /tmp/rustc-src /tmp/rustc-wrapped
+"#, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program, program);
 }
 
-fn cmd_scan(path: &str) {
-    eprintln!("🔍 Scanning {} for audit issues...\n", path);
-    
-    let path = Path::new(path);
-    let results = introspector_core::scan_directory(path, &["rs"]);
-    introspector_core::print_scan_summary(&results);
+fn baseline_path(root: &Path, baseline_file: Option<&str>) -> PathBuf {
+    baseline_file.map_or_else(|| root.join(introspector_core::BASELINE_FILE), PathBuf::from)
+}
+
+fn cmd_scan(path: &str, format: &str, baseline_file: Option<&str>) {
+    let root = Path::new(path);
+    let baseline_path = baseline_path(root, baseline_file);
+    let baseline = if baseline_file.is_some() || baseline_path.is_file() {
+        match introspector_core::Baseline::load(&baseline_path) {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        introspector_core::Baseline::default()
+    };
+
+    let triage = introspector_core::triage_directory(root, &baseline);
+    let results = triage.new_results();
+    if format == "sarif" {
+        let mut log = introspector_core::scan_results_to_sarif(&results, root);
+        introspector_core::add_invalid_suppressions(&mut log, &triage.invalid_suppressions);
+        println!("{}", serde_json::to_string_pretty(&log).unwrap_or_default());
+    } else {
+        eprintln!("🔍 Scanning {} for audit issues...\n", path);
+        introspector_core::print_scan_summary(&results);
+        eprintln!();
+        introspector_core::print_triage(&triage);
+    }
+
+    if !triage.invalid_suppressions.is_empty() {
+        // `print_triage` already listed them; the SARIF log goes to stdout.
+        if format == "sarif" {
+            eprintln!("❌ Invalid suppressions:");
+            for s in &triage.invalid_suppressions {
+                eprintln!("   {}:{} {}", s.file, s.line, s.message);
+            }
+        }
+        process::exit(1);
+    }
+}
+
+fn cmd_baseline(path: &str, baseline_file: Option<&str>) {
+    let root = Path::new(path);
+    let baseline_path = baseline_path(root, baseline_file);
+    let triage = introspector_core::triage_directory(root, &introspector_core::Baseline::default());
+    let baseline = introspector_core::Baseline::from_findings(triage.unsuppressed());
+
+    if let Err(e) = baseline.save(&baseline_path) {
+        eprintln!("❌ Failed to write {}: {}", baseline_path.display(), e);
+        process::exit(1);
+    }
+    eprintln!("📋 Recorded {} findings in {}", baseline.findings.len(), baseline_path.display());
+    if !triage.suppressed.is_empty() {
+        eprintln!("🔇 {} findings are suppressed inline and not recorded", triage.suppressed.len());
+    }
 }
 
 fn cmd_preview(path: &str) {
@@ -183,7 +378,43 @@ fn cmd_diff(path: &str) {
     if diff.is_empty() {
         eprintln!("No changes needed for {}", path.display());
     } else {
-        println!("{}", diff);
+        print!("{}", diff);
+    }
+}
+
+fn cmd_apply_patch(patch_file: &str, dir: &str, options: introspector_core::ApplyOptions, dry_run: bool) {
+    let patch = std::fs::read_to_string(patch_file)
+        .map_err(|e| format!("Failed to read {}: {}", patch_file, e))
+        .and_then(|text| introspector_core::Patch::parse(&text));
+    let patch = match patch {
+        Ok(patch) if !patch.is_empty() => patch,
+        Ok(_) => {
+            eprintln!("❌ {} contains no file patches", patch_file);
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+
+    match introspector_core::apply_patch(Path::new(dir), &patch, options, dry_run) {
+        Ok(results) => {
+            for file in &results {
+                let action = if file.created { "creating" } else if file.deleted { "deleting" } else { "patching" };
+                eprintln!("📝 {} {}", action, file.path);
+                for hunk in file.hunks.iter().filter(|h| h.offset != 0 || h.fuzz > 0) {
+                    eprintln!("   {}", hunk);
+                }
+            }
+            let verb = if options.reverse { "Reverted" } else { "Applied" };
+            let note = if dry_run { " (dry run, nothing written)" } else { "" };
+            eprintln!("\n✅ {} {} to {} files{}", verb, patch_file, results.len(), note);
+        }
+        Err(e) => {
+            eprintln!("❌ {}; no files were changed", e);
+            process::exit(1);
+        }
     }
 }
 
@@ -216,6 +447,44 @@ fn cmd_tickets(filter: Option<&str>) {
     }
 }
 
+fn cmd_tickets_sync(md_path: &str, dry_run: bool) {
+    let mut tickets = introspector_core::get_all_tickets();
+    tickets.sort_by(|a, b| a.id.cmp(b.id));
+
+    let existing = match std::fs::read_to_string(md_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            eprintln!("❌ Error reading {}: {}", md_path, e);
+            process::exit(1);
+        }
+    };
+
+    let divergence = introspector_core::ticket_divergence(&existing, &tickets);
+    if divergence.is_empty() {
+        eprintln!("✅ {} is in sync with the registry ({} tickets)", md_path, tickets.len());
+    } else {
+        eprintln!("🔀 {} differs from the registry:", md_path);
+        for d in &divergence {
+            eprintln!("   {}", d);
+        }
+    }
+
+    let synced = introspector_core::sync_tickets_markdown(&existing, &tickets);
+    if dry_run {
+        if !divergence.is_empty() || synced != existing {
+            eprintln!("🔍 Dry run - run `tickets sync` to regenerate {}", md_path);
+            process::exit(1);
+        }
+    } else if synced != existing {
+        if let Err(e) = std::fs::write(md_path, synced) {
+            eprintln!("❌ Error writing {}: {}", md_path, e);
+            process::exit(1);
+        }
+        eprintln!("📝 Regenerated the ticket list in {}", md_path);
+    }
+}
+
 fn cmd_ticket(id: &str) {
     if let Some(ticket) = introspector_core::get_ticket(id) {
         introspector_core::print_ticket(&ticket);
@@ -231,6 +500,91 @@ fn cmd_report() {
     introspector_core::print_macro_report(&report);
 }
 
+fn cmd_capabilities(path: &str, include_deps: bool, format: &str) {
+    let root = Path::new(path);
+    let mut crates = match introspector_core::workspace_crates(root) {
+        Ok(crates) => crates,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+    if include_deps {
+        match introspector_core::dependency_crates(root) {
+            Ok(deps) => crates.extend(deps),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let inventory = introspector_core::scan_capabilities(&crates, root);
+    if format == "json" {
+        let tickets: Vec<serde_json::Value> = inventory.uses.iter().map(|u| {
+            let ticket = u.to_ticket();
+            serde_json::json!({
+                "id": ticket.id,
+                "severity": ticket.severity.as_str(),
+                "title": ticket.title,
+                "item": u.item_path,
+                "file": u.file,
+                "line": u.line,
+                "justified": u.is_justified(),
+                "justification": u.justification.describe(),
+            })
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&tickets).unwrap_or_default());
+    } else {
+        introspector_core::print_capability_inventory(&inventory);
+    }
+}
+
+fn cmd_lint(path: &str, rules_file: Option<&str>, format: &str) {
+    let default_rules = Path::new(introspector_core::USER_RULES_FILE);
+    let rules_path = match rules_file {
+        Some(file) => Some(Path::new(file)),
+        None if default_rules.is_file() => Some(default_rules),
+        None => None,
+    };
+
+    let rules = match introspector_core::lint_rules_with_user_file(rules_path) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("❌ Error loading rules: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Some(p) = rules_path {
+        eprintln!("📜 Loaded user rules from {}", p.display());
+    }
+
+    let mut total = 0;
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+        .filter(|e| !e.path().components().any(|c| c.as_os_str() == "target"))
+    {
+        let Ok(content) = std::fs::read_to_string(entry.path()) else { continue };
+        let file_name = entry.path().display().to_string();
+        let violations = introspector_core::find_violations(&content, &rules);
+        total += violations.len();
+        if format == "json" {
+            print!("{}", introspector_core::generate_rustfix_json(&violations, &content, &file_name));
+            continue;
+        }
+        for v in &violations {
+            introspector_core::print_violation(&v.rule, v.line, &file_name);
+        }
+    }
+
+    eprintln!("📊 {} violations ({} rules)", total, rules.len());
+    if total > 0 {
+        process::exit(1);
+    }
+}
+
 fn cmd_decl_scan(path: &str) {
     let path = Path::new(path);
     let preview = introspector_core::preview_decl_wrappers(path);
@@ -250,7 +604,7 @@ fn cmd_decl_wrap(root_path: &str, dry_run: bool, recursive: bool) {
         for entry in WalkDir::new(&path)
             .into_iter()
             .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file() && e.path().extension().map_or(false, |ext| ext == "rs"))
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
         {
             let file_path = entry.path();
             if dry_run {
@@ -299,4 +653,121 @@ fn cmd_decl_json(path: &str) {
     let path = Path::new(path);
     let json = introspector_core::generate_declarations_json(path);
     println!("{}", json);
-}
\ No newline at end of file
+}
+
+fn cmd_decl_rehash(root_path: &str, dry_run: bool, recursive: bool) {
+    let path = PathBuf::from(root_path);
+
+    let files: Vec<PathBuf> = if recursive {
+        if !path.is_dir() {
+            eprintln!("Error: --recursive can only be used with a directory path.");
+            process::exit(1);
+        }
+        WalkDir::new(&path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(|e| e.into_path())
+            .collect()
+    } else {
+        if !path.is_file() {
+            eprintln!("Error: {} is not a file. Use --recursive for directories.", path.display());
+            process::exit(1);
+        }
+        vec![path]
+    };
+
+    eprintln!("🔍 {} declaration hashes in {} files...\n", if dry_run { "Checking" } else { "Rehashing" }, files.len());
+
+    let mut stale = 0;
+    for file_path in &files {
+        match introspector_core::rehash_file(file_path, dry_run) {
+            Ok(drifts) => {
+                introspector_core::print_hash_drift(file_path, &drifts);
+                stale += drifts.len();
+            }
+            Err(e) => eprintln!("❌ Error rehashing {}: {}", file_path.display(), e),
+        }
+    }
+
+    if dry_run {
+        eprintln!("\n🔍 Dry run - {} stale hashes found, no changes made", stale);
+        if stale > 0 {
+            process::exit(1);
+        }
+    } else {
+        eprintln!("\n✅ Rewrote {} stale hashes", stale);
+    }
+}
+
+/// A `.json` path is read as a saved snapshot; anything else is snapshotted.
+fn cmd_rustc_analyze(path: &str, format: &str) {
+    let started = std::time::Instant::now();
+    let stats = match introspector_core::analyze_rustc_source(Path::new(path)) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+    if format == "json" {
+        println!("{}", stats.to_json());
+        return;
+    }
+    eprintln!("🦀 {} compiler crates, {} lines, {} public items ({:.1}s)\n",
+        stats.crates.len(), stats.total_lines, stats.declarations, started.elapsed().as_secs_f64());
+    eprint!("{}", stats.summary_table());
+    if stats.parse_failures > 0 {
+        eprintln!("\n⚠️  {} files failed to parse:", stats.parse_failures);
+        for stats in &stats.crates {
+            for failure in &stats.parse_failures {
+                eprintln!("   {}/{}", stats.name, failure);
+            }
+        }
+    }
+}
+
+fn cmd_rustc_wrap(source: &str, output: &str) {
+    let series = match introspector_core::apply_decl_wrappers_to_rustc(Path::new(source), Path::new(output), &[]) {
+        Ok(series) => series,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+    for patch in &series.patches {
+        eprintln!("📝 {} — {} items in {} files", patch.patch, patch.items, patch.files.len());
+    }
+    for error in &series.errors {
+        eprintln!("⚠️  {}", error);
+    }
+    eprintln!("\n✅ Wrapped {} items; overlay in {}", series.total_wrapped(), series.overlay_dir().display());
+    eprintln!("📋 Review {}/patches, then add `import {}/patches.nix` to the rustc patches", output, output);
+}
+
+fn load_api_snapshot(path: &str) -> introspector_core::ApiSnapshot {
+    let result = if path.ends_with(".json") {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))
+            .and_then(|json| introspector_core::ApiSnapshot::from_json(&json))
+    } else {
+        introspector_core::snapshot_path(Path::new(path))
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("❌ Error: {}", e);
+        process::exit(1);
+    })
+}
+
+fn cmd_api_snapshot(path: &str) {
+    let snapshot = load_api_snapshot(path);
+    eprintln!("📸 {} public items, {} trait impls", snapshot.items.len(), snapshot.trait_impls.len());
+    println!("{}", snapshot.to_json());
+}
+
+fn cmd_api_diff(old: &str, new: &str) {
+    eprintln!("🔍 Comparing public API {} → {}\n", old, new);
+    let diff = introspector_core::diff_snapshots(&load_api_snapshot(old), &load_api_snapshot(new));
+    introspector_core::print_api_diff(&diff);
+}
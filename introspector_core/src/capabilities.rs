@@ -0,0 +1,772 @@
+use crate::audit_tickets::{get_ticket, intern, AuditTicket, Severity, TicketCategory};
+use crate::baseline::{innermost_item, item_paths, parse_suppressions, Suppression};
+use crate::clippy_rules::flatten_use_tree;
+use crate::decl_wrapper::LineIndex;
+use crate::macro_report::{crate_sources, source_module, workspace_crates};
+use introspector_decl_core::impl_display_name;
+use quote::ToTokens;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, ForeignItem, ImplItemFn, Item, ItemFn, Stmt, TraitItemFn, UnOp};
+
+// Inventory of the capabilities a crate exercises: unsafe code, FFI,
+// process spawns and filesystem writes. Each use becomes a SEC ticket with
+// the path of the enclosing item and whether it is justified, either by a
+// `// SAFETY:` comment (or `# Safety` doc section) or by an `audit-allow`
+// suppression naming its SEC id.
+//
+// Paths are resolved through the `use` items in scope, so `Command::new`
+// from `clap` is not a process spawn. Raw pointers are not typed at this
+// level: every dereference inside unsafe code is reported as SEC-005.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[decl(enum, name = "CapabilityKind", vis = "pub", hash = "f10c0e03")]
+pub enum CapabilityKind {
+    UnsafeBlock,
+    UnsafeFn,
+    UnsafeImpl,
+    ForeignItem,
+    RawPointerDeref,
+    ProcessSpawn,
+    FileWrite,
+}
+
+impl CapabilityKind {
+    pub const ALL: [CapabilityKind; 7] = [
+        CapabilityKind::UnsafeBlock,
+        CapabilityKind::UnsafeFn,
+        CapabilityKind::UnsafeImpl,
+        CapabilityKind::ForeignItem,
+        CapabilityKind::RawPointerDeref,
+        CapabilityKind::ProcessSpawn,
+        CapabilityKind::FileWrite,
+    ];
+
+    pub fn ticket_id(&self) -> &'static str {
+        match self {
+            CapabilityKind::UnsafeBlock => "SEC-001",
+            CapabilityKind::UnsafeFn => "SEC-002",
+            CapabilityKind::UnsafeImpl => "SEC-003",
+            CapabilityKind::ForeignItem => "SEC-004",
+            CapabilityKind::RawPointerDeref => "SEC-005",
+            CapabilityKind::ProcessSpawn => "SEC-006",
+            CapabilityKind::FileWrite => "SEC-007",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CapabilityKind::UnsafeBlock => "Unsafe block",
+            CapabilityKind::UnsafeFn => "Unsafe function",
+            CapabilityKind::UnsafeImpl => "Unsafe impl",
+            CapabilityKind::ForeignItem => "FFI declaration",
+            CapabilityKind::RawPointerDeref => "Raw pointer dereference",
+            CapabilityKind::ProcessSpawn => "Process spawn",
+            CapabilityKind::FileWrite => "Filesystem write",
+        }
+    }
+
+    /// Whether a `// SAFETY:` comment justifies this kind of use.
+    fn takes_safety_comment(&self) -> bool {
+        !matches!(self, CapabilityKind::ProcessSpawn | CapabilityKind::FileWrite)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(enum, name = "Justification", vis = "pub", hash = "ff2a8940")]
+pub enum Justification {
+    /// Text of the `// SAFETY:` comment or `# Safety` doc section.
+    Safety(String),
+    /// Reason of the `audit-allow` suppression.
+    Allowed(String),
+    Missing,
+}
+
+impl Justification {
+    pub fn describe(&self) -> String {
+        match self {
+            Justification::Safety(text) => format!("SAFETY: {}", text),
+            Justification::Allowed(reason) => format!("allowed: {}", reason),
+            Justification::Missing => "no justification".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[decl(struct, name = "CapabilityUse", vis = "pub", hash = "fa5fcbf6")]
+pub struct CapabilityUse {
+    pub kind: CapabilityKind,
+    /// File relative to the scanned root, `/`-separated.
+    pub file: String,
+    pub line: usize,
+    /// `krate::module::Item::method` of the enclosing item.
+    pub item_path: String,
+    /// What is exercised: the program spawned, the fs function, the impl.
+    pub detail: String,
+    pub justification: Justification,
+}
+
+impl CapabilityUse {
+    pub fn is_justified(&self) -> bool {
+        self.justification != Justification::Missing
+    }
+
+    /// The use as a SEC ticket. Justified uses drop to `Low` severity so they
+    /// stay in the inventory without failing severity gates.
+    pub fn to_ticket(&self) -> AuditTicket {
+        let base = get_ticket(self.kind.ticket_id());
+        let severity = match (&base, self.is_justified()) {
+            (_, true) => Severity::Low,
+            (Some(ticket), false) => ticket.severity,
+            (None, false) => Severity::High,
+        };
+        AuditTicket {
+            id: self.kind.ticket_id(),
+            category: TicketCategory::Security,
+            severity,
+            module: intern(self.item_path.clone()),
+            title: intern(format!("{}: {}", self.kind.name(), self.detail)),
+            description: intern(format!("{}:{} ({})", self.file, self.line, self.justification.describe())),
+            suggested_fix: base.as_ref().and_then(|t| t.suggested_fix),
+            clippy_lint: base.as_ref().and_then(|t| t.clippy_lint),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[decl(struct, name = "CapabilityInventory", vis = "pub", hash = "b40f69b2")]
+pub struct CapabilityInventory {
+    pub crates: Vec<String>,
+    pub uses: Vec<CapabilityUse>,
+    pub errors: Vec<String>,
+}
+
+impl CapabilityInventory {
+    pub fn tickets(&self) -> Vec<AuditTicket> {
+        self.uses.iter().map(CapabilityUse::to_ticket).collect()
+    }
+
+    pub fn unjustified(&self) -> impl Iterator<Item = &CapabilityUse> {
+        self.uses.iter().filter(|u| !u.is_justified())
+    }
+
+    pub fn count(&self, kind: CapabilityKind) -> usize {
+        self.uses.iter().filter(|u| u.kind == kind).count()
+    }
+}
+
+/// A use found by the visitor, before item paths and justifications.
+struct RawUse {
+    kind: CapabilityKind,
+    offset: usize,
+    line: usize,
+    detail: String,
+    /// Starts of the statements or items a `// SAFETY:` comment may precede.
+    anchors: Vec<usize>,
+    doc_safety: Option<String>,
+}
+
+const FS_WRITE_FNS: &[&str] = &[
+    "write", "create_dir", "create_dir_all", "remove_file", "remove_dir", "remove_dir_all",
+    "rename", "copy", "hard_link", "set_permissions", "symlink", "soft_link",
+    "symlink_file", "symlink_dir",
+];
+
+const OPEN_OPTIONS_WRITES: &[&str] = &["write", "append", "create", "create_new", "truncate"];
+
+struct CapabilityCollector<'a> {
+    index: LineIndex<'a>,
+    uses: Vec<RawUse>,
+    /// `use` imports per scope, innermost last.
+    imports: Vec<HashMap<String, Vec<String>>>,
+    /// Enclosing statements and items, innermost last.
+    anchors: Vec<usize>,
+    /// Anchors of the enclosing unsafe blocks and fns.
+    unsafe_anchors: Vec<usize>,
+}
+
+impl CapabilityCollector<'_> {
+    fn offset(&self, span: proc_macro2::Span) -> (usize, usize) {
+        let span = self.index.span(span);
+        (span.start_byte, span.start_line)
+    }
+
+    fn record(&mut self, kind: CapabilityKind, span: proc_macro2::Span, detail: String, doc_safety: Option<String>) {
+        let (offset, line) = self.offset(span);
+        let mut anchors: Vec<usize> = self.anchors.last().copied().into_iter().collect();
+        if kind == CapabilityKind::RawPointerDeref {
+            anchors.extend(self.unsafe_anchors.last().copied());
+        }
+        self.uses.push(RawUse { kind, offset, line, detail, anchors, doc_safety });
+    }
+
+    fn push_imports<'i>(&mut self, items: impl IntoIterator<Item = &'i Item>) {
+        let mut scope = HashMap::new();
+        for item in items {
+            if let Item::Use(u) = item {
+                for (name, path) in flatten_use_tree(&u.tree, Vec::new()) {
+                    if name != "*" {
+                        scope.insert(name, path);
+                    }
+                }
+            }
+        }
+        self.imports.push(scope);
+    }
+
+    /// `path` with its first segment expanded through the imports in scope.
+    fn resolve(&self, path: &syn::Path) -> Vec<String> {
+        let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let Some(first) = segments.first() else { return segments };
+        for scope in self.imports.iter().rev() {
+            if let Some(full) = scope.get(first) {
+                return full.iter().cloned().chain(segments[1..].iter().cloned()).collect();
+            }
+        }
+        segments
+    }
+
+    fn check_call(&mut self, call: &syn::ExprCall) {
+        let Expr::Path(func) = call.func.as_ref() else { return };
+        let path = self.resolve(&func.path);
+        let names: Vec<&str> = path.iter().map(String::as_str).collect();
+        match names.as_slice() {
+            // `Command::new` of another crate (e.g. clap) resolves to its own path.
+            ["Command", "new"] | [.., "process", "Command", "new"] => {
+                let program = call.args.first().map(program_name).unwrap_or_default();
+                self.record(CapabilityKind::ProcessSpawn, call.span(), program, None);
+            }
+            [.., "fs", name] if FS_WRITE_FNS.contains(name) => {
+                self.record(CapabilityKind::FileWrite, call.span(), format!("fs::{}", name), None);
+            }
+            [.., "File", name @ ("create" | "create_new")] => {
+                self.record(CapabilityKind::FileWrite, call.span(), format!("File::{}", name), None);
+            }
+            _ => {}
+        }
+    }
+
+    /// `OpenOptions::new()...write(true)...`: reported once, at the first
+    /// write flag of the chain.
+    fn check_method_call(&mut self, call: &syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        if !OPEN_OPTIONS_WRITES.contains(&method.as_str()) || !is_true(call.args.first()) {
+            return;
+        }
+        let mut receiver = call.receiver.as_ref();
+        loop {
+            match receiver {
+                Expr::MethodCall(inner) => {
+                    if OPEN_OPTIONS_WRITES.contains(&inner.method.to_string().as_str()) && is_true(inner.args.first()) {
+                        return;
+                    }
+                    receiver = inner.receiver.as_ref();
+                }
+                Expr::Call(inner) => {
+                    if let Expr::Path(func) = inner.func.as_ref() {
+                        let path = self.resolve(&func.path);
+                        let names: Vec<&str> = path.iter().map(String::as_str).collect();
+                        if matches!(names.as_slice(), [.., "OpenOptions", "new"]) {
+                            self.record(CapabilityKind::FileWrite, call.method.span(), format!("OpenOptions::{}", method), None);
+                        }
+                    }
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn check_fn(&mut self, sig: &syn::Signature, attrs: &[syn::Attribute], owner: Option<String>) {
+        let name = match owner {
+            Some(owner) => format!("{}::{}", owner, sig.ident),
+            None => sig.ident.to_string(),
+        };
+        if sig.unsafety.is_some() {
+            self.record(CapabilityKind::UnsafeFn, sig.span(), format!("unsafe fn {}", name), safety_doc(attrs));
+        }
+        if let Some(abi) = &sig.abi {
+            let abi = abi.name.as_ref().map_or("C".to_string(), |n| n.value());
+            if abi != "Rust" {
+                self.record(CapabilityKind::ForeignItem, sig.span(), format!("extern \"{}\" fn {}", abi, name), None);
+            }
+        }
+    }
+
+    fn visit_unsafe_body(&mut self, unsafety: bool, f: impl FnOnce(&mut Self)) {
+        if unsafety {
+            self.unsafe_anchors.push(self.anchors.last().copied().unwrap_or(0));
+        }
+        f(self);
+        if unsafety {
+            self.unsafe_anchors.pop();
+        }
+    }
+
+    fn with_anchor(&mut self, span: proc_macro2::Span, f: impl FnOnce(&mut Self)) {
+        let (offset, _) = self.offset(span);
+        self.anchors.push(offset);
+        f(self);
+        self.anchors.pop();
+    }
+}
+
+fn is_true(arg: Option<&Expr>) -> bool {
+    match arg {
+        Some(Expr::Lit(lit)) => matches!(&lit.lit, syn::Lit::Bool(b) if b.value),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn program_name(arg: &Expr) -> String {
+    match arg {
+        Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => format!("`{}`", s.value()),
+        Expr::Reference(r) => program_name(&r.expr),
+        other => format!("`{}`", other.to_token_stream().to_string().replace(' ', "")),
+    }
+}
+
+/// The first line of a `# Safety` doc section, if the docs have one.
+fn safety_doc(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs.iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let heading = lines.iter().position(|l| l.trim_start_matches('#').trim() == "Safety" && l.starts_with('#'))?;
+    let text = lines[heading + 1..].iter().find(|l| !l.is_empty()).cloned().unwrap_or_default();
+    Some(text)
+}
+
+impl<'ast> Visit<'ast> for CapabilityCollector<'_> {
+    fn visit_file(&mut self, file: &'ast syn::File) {
+        self.push_imports(&file.items);
+        visit::visit_file(self, file);
+        self.imports.pop();
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        self.with_anchor(item.span(), |this| match item {
+            Item::Impl(imp) => {
+                if imp.unsafety.is_some() {
+                    this.record(CapabilityKind::UnsafeImpl, imp.span(), format!("unsafe impl {}", impl_display_name(imp)), None);
+                }
+                visit::visit_item_impl(this, imp);
+            }
+            Item::ForeignMod(foreign) => {
+                let abi = foreign.abi.name.as_ref().map_or("C".to_string(), |n| n.value());
+                for foreign_item in &foreign.items {
+                    let (kind, name) = match foreign_item {
+                        ForeignItem::Fn(f) => ("fn", f.sig.ident.to_string()),
+                        ForeignItem::Static(s) => ("static", s.ident.to_string()),
+                        ForeignItem::Type(t) => ("type", t.ident.to_string()),
+                        _ => continue,
+                    };
+                    let detail = format!("extern \"{}\" {} {}", abi, kind, name);
+                    this.with_anchor(foreign_item.span(), |this| {
+                        this.record(CapabilityKind::ForeignItem, foreign_item.span(), detail, None);
+                    });
+                }
+            }
+            Item::Mod(module) => match &module.content {
+                Some((_, items)) => {
+                    this.push_imports(items);
+                    visit::visit_item_mod(this, module);
+                    this.imports.pop();
+                }
+                None => visit::visit_item_mod(this, module),
+            },
+            _ => visit::visit_item(this, item),
+        });
+    }
+
+    fn visit_item_fn(&mut self, f: &'ast ItemFn) {
+        self.check_fn(&f.sig, &f.attrs, None);
+        self.visit_unsafe_body(f.sig.unsafety.is_some(), |this| visit::visit_item_fn(this, f));
+    }
+
+    fn visit_impl_item_fn(&mut self, f: &'ast ImplItemFn) {
+        self.with_anchor(f.span(), |this| {
+            this.check_fn(&f.sig, &f.attrs, Some("Self".to_string()));
+            this.visit_unsafe_body(f.sig.unsafety.is_some(), |this| visit::visit_impl_item_fn(this, f));
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, f: &'ast TraitItemFn) {
+        self.with_anchor(f.span(), |this| {
+            this.check_fn(&f.sig, &f.attrs, Some("Self".to_string()));
+            this.visit_unsafe_body(f.sig.unsafety.is_some(), |this| visit::visit_trait_item_fn(this, f));
+        });
+    }
+
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        let items: Vec<&Item> = block.stmts.iter()
+            .filter_map(|s| match s { Stmt::Item(i) => Some(i), _ => None })
+            .collect();
+        self.push_imports(items);
+        visit::visit_block(self, block);
+        self.imports.pop();
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            // Items are anchored in `visit_item`.
+            Stmt::Item(_) => visit::visit_stmt(self, stmt),
+            _ => self.with_anchor(stmt.span(), |this| visit::visit_stmt(this, stmt)),
+        }
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.record(CapabilityKind::UnsafeBlock, expr.unsafe_token.span, "unsafe { .. }".to_string(), None);
+        self.visit_unsafe_body(true, |this| visit::visit_expr_unsafe(this, expr));
+    }
+
+    fn visit_expr_unary(&mut self, expr: &'ast syn::ExprUnary) {
+        if matches!(expr.op, UnOp::Deref(_)) && !self.unsafe_anchors.is_empty() {
+            let text = expr.to_token_stream().to_string().replace(' ', "");
+            self.record(CapabilityKind::RawPointerDeref, expr.span(), format!("`{}`", text), None);
+        }
+        visit::visit_expr_unary(self, expr);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        self.check_call(call);
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.check_method_call(call);
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        } else if let Ok(stmts) = mac.parse_body_with(syn::Block::parse_within) {
+            for stmt in &stmts {
+                self.visit_stmt(stmt);
+            }
+        }
+    }
+}
+
+/// Text after `SAFETY:` in the comment lines directly above the line of
+/// `anchor` (attributes in between are skipped).
+fn safety_comment(content: &str, anchor: usize) -> Option<String> {
+    let anchor = anchor.min(content.len());
+    let line_start = content[..anchor].rfind('\n').map_or(0, |i| i + 1);
+    let mut comment = Vec::new();
+    for line in content[..line_start].lines().rev() {
+        let line = line.trim();
+        if line.starts_with("#[") {
+            continue;
+        }
+        let text = match line.strip_prefix("//") {
+            Some(text) => text.trim_start_matches('/'),
+            None => break,
+        };
+        comment.push(text.trim());
+    }
+    comment.reverse();
+    let start = comment.iter().position(|l| l.starts_with("SAFETY:"))?;
+    let text: Vec<&str> = std::iter::once(comment[start].trim_start_matches("SAFETY:").trim())
+        .chain(comment[start + 1..].iter().copied())
+        .filter(|l| !l.is_empty())
+        .collect();
+    Some(text.join(" "))
+}
+
+fn justify(use_: &RawUse, content: &str, suppressions: &[Suppression]) -> Justification {
+    if let Some(text) = &use_.doc_safety {
+        return Justification::Safety(text.clone());
+    }
+    if use_.kind.takes_safety_comment() {
+        let own = content[..use_.offset.min(content.len())].rfind('\n').map_or(0, |i| i + 1);
+        if let Some(text) = std::iter::once(own).chain(use_.anchors.iter().copied()).find_map(|a| safety_comment(content, a)) {
+            return Justification::Safety(text);
+        }
+    }
+    suppressions.iter()
+        .find(|s| s.covers.contains(&use_.offset) && s.ticket_ids.iter().any(|id| id == use_.kind.ticket_id()))
+        .map_or(Justification::Missing, |s| Justification::Allowed(s.reason.clone()))
+}
+
+/// Capability uses in one source file. `module` prefixes the item paths.
+#[decl(fn, name = "scan_source_capabilities", vis = "pub", hash = "343c4b35")]
+pub fn scan_source_capabilities(content: &str, file: &str, module: &str) -> Result<Vec<CapabilityUse>, String> {
+    let ast = syn::parse_file(content).map_err(|e| format!("{}:{}: {}", file, e.span().start().line, e))?;
+    let mut collector = CapabilityCollector {
+        index: LineIndex::new(content),
+        uses: Vec::new(),
+        imports: Vec::new(),
+        anchors: Vec::new(),
+        unsafe_anchors: Vec::new(),
+    };
+    collector.visit_file(&ast);
+
+    let items = item_paths(content);
+    let (suppressions, _) = parse_suppressions(content, file);
+    let mut uses: Vec<CapabilityUse> = collector.uses.iter().map(|raw| {
+        let item_path = [module, innermost_item(&items, raw.offset).unwrap_or("")].into_iter()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("::");
+        CapabilityUse {
+            kind: raw.kind,
+            file: file.to_string(),
+            line: raw.line,
+            item_path,
+            detail: raw.detail.clone(),
+            justification: justify(raw, content, &suppressions),
+        }
+    }).collect();
+    uses.sort_by_key(|u| (u.line, u.kind));
+    Ok(uses)
+}
+
+/// Capability uses of every source file of `crates`; files are reported
+/// relative to `root` when they are below it.
+#[decl(fn, name = "scan_capabilities", vis = "pub", hash = "abd7aa7a")]
+pub fn scan_capabilities(crates: &[(String, PathBuf)], root: &Path) -> CapabilityInventory {
+    let mut inventory = CapabilityInventory::default();
+    for (crate_name, dir) in crates {
+        inventory.crates.push(crate_name.clone());
+        for path in crate_sources(dir) {
+            let module = source_module(crate_name, dir, &path);
+            let file = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let result = fs::read_to_string(&path)
+                .map_err(|e| format!("{}: {}", file, e))
+                .and_then(|content| scan_source_capabilities(&content, &file, &module));
+            match result {
+                Ok(uses) => inventory.uses.extend(uses),
+                Err(e) => inventory.errors.push(e),
+            }
+        }
+    }
+    inventory
+}
+
+/// Capability inventory of the workspace at `root`.
+#[decl(fn, name = "workspace_capabilities", vis = "pub", hash = "5fd079e4")]
+pub fn workspace_capabilities(root: &Path) -> CapabilityInventory {
+    match workspace_crates(root) {
+        Ok(crates) => scan_capabilities(&crates, root),
+        Err(e) => CapabilityInventory { errors: vec![e], ..Default::default() },
+    }
+}
+
+/// Name and directory of each non-workspace package in `cargo metadata`
+/// output.
+#[decl(fn, name = "crates_from_metadata", vis = "pub", hash = "4908ace8")]
+pub fn crates_from_metadata(json: &str) -> Result<Vec<(String, PathBuf)>, String> {
+    let metadata: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid cargo metadata: {}", e))?;
+    let members: Vec<&str> = metadata["workspace_members"].as_array()
+        .map(|a| a.iter().filter_map(|m| m.as_str()).collect())
+        .unwrap_or_default();
+    let packages = metadata["packages"].as_array()
+        .ok_or_else(|| "cargo metadata has no packages".to_string())?;
+
+    let mut crates: Vec<(String, PathBuf)> = packages.iter()
+        .filter(|p| p["id"].as_str().is_some_and(|id| !members.contains(&id)))
+        .filter_map(|p| {
+            let name = p["name"].as_str()?.replace('-', "_");
+            let dir = Path::new(p["manifest_path"].as_str()?).parent()?.to_path_buf();
+            Some((name, dir))
+        })
+        .collect();
+    crates.sort();
+    Ok(crates)
+}
+
+/// Dependencies of the workspace at `root`, from `cargo metadata`.
+#[decl(fn, name = "dependency_crates", vis = "pub", hash = "3a7c7359")]
+pub fn dependency_crates(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    // audit-allow(SEC-006): cargo metadata is the only reliable source for resolved dependency paths
+    let output = Command::new("cargo")
+        .args(["metadata", "--format-version", "1"])
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("Failed to run cargo metadata: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("cargo metadata failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    crates_from_metadata(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[decl(fn, name = "print_capability_inventory", vis = "pub", hash = "a1fc3916")]
+pub fn print_capability_inventory(inventory: &CapabilityInventory) {
+    let reset = "\x1b[0m";
+    let cyan = "\x1b[36m";
+    let yellow = "\x1b[33m";
+    let red = "\x1b[31m";
+    let green = "\x1b[32m";
+    let gray = "\x1b[90m";
+
+    let unjustified = inventory.unjustified().count();
+    eprintln!("{}🔒 CAPABILITY INVENTORY{}", cyan, reset);
+    eprintln!("📦 {} crates, {} uses, {}{} unjustified{}",
+        inventory.crates.len(), inventory.uses.len(),
+        if unjustified > 0 { red } else { green }, unjustified, reset);
+    eprintln!();
+
+    for kind in CapabilityKind::ALL {
+        let count = inventory.count(kind);
+        if count == 0 {
+            continue;
+        }
+        let missing = inventory.unjustified().filter(|u| u.kind == kind).count();
+        eprintln!("  {}{}{} {:<24} {:>5} {}({} unjustified){}",
+            yellow, kind.ticket_id(), reset, kind.name(), count, gray, missing, reset);
+    }
+
+    let mut by_item: BTreeMap<&str, Vec<&CapabilityUse>> = BTreeMap::new();
+    for capability in &inventory.uses {
+        by_item.entry(&capability.item_path).or_default().push(capability);
+    }
+    for (item, uses) in by_item {
+        eprintln!();
+        eprintln!("{}{}{}", cyan, item, reset);
+        for capability in uses {
+            let (mark, color) = if capability.is_justified() { ("✅", green) } else { ("❌", red) };
+            eprintln!("  {} {}{}{} {} {}{}:{}{}", mark, color, capability.kind.ticket_id(), reset,
+                capability.detail, gray, capability.file, capability.line, reset);
+            if capability.is_justified() {
+                eprintln!("       {}{}{}", gray, capability.justification.describe(), reset);
+            }
+        }
+    }
+
+    if !inventory.errors.is_empty() {
+        eprintln!();
+        eprintln!("{}⚠️  {} files skipped{}", yellow, inventory.errors.len(), reset);
+        for error in &inventory.errors {
+            eprintln!("   {}{}{}", gray, error, reset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+use std::fs;
+use std::process::Command;
+use clap::Command as Cli;
+
+pub struct Handle(*mut u8);
+
+// SAFETY: the pointer is owned by the handle and never shared.
+unsafe impl Send for Handle {}
+
+extern "C" {
+    fn getpid() -> i32;
+}
+
+impl Handle {
+    /// # Safety
+    /// `self.0` must be valid for reads.
+    pub unsafe fn peek(&self) -> u8 {
+        *self.0
+    }
+
+    pub fn read(&self) -> u8 {
+        unsafe { *self.0 }
+    }
+}
+
+pub fn build(out: &str) {
+    let _cli = Cli::new("tool");
+    let status = Command::new("nix").arg("build").status();
+    // audit-allow(SEC-007): writes only below OUT_DIR
+    fs::write(out, "x").unwrap();
+    std::fs::OpenOptions::new().create(true).append(true).open(out).ok();
+    println!("{:?}", std::fs::remove_file(out));
+    let _ = status;
+}
+"#;
+
+    #[test]
+    fn test_scan_source_capabilities() {
+        let uses = scan_source_capabilities(SOURCE, "src/lib.rs", "demo").unwrap();
+        let summary: Vec<(&str, &str, &str, bool)> = uses.iter()
+            .map(|u| (u.kind.ticket_id(), u.item_path.as_str(), u.detail.as_str(), u.is_justified()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("SEC-003", "demo::Handle", "unsafe impl Send for Handle", true),
+            ("SEC-004", "demo::getpid", "extern \"C\" fn getpid", false),
+            ("SEC-002", "demo::Handle::peek", "unsafe fn Self::peek", true),
+            ("SEC-005", "demo::Handle::peek", "`*self.0`", false),
+            ("SEC-001", "demo::Handle::read", "unsafe { .. }", false),
+            ("SEC-005", "demo::Handle::read", "`*self.0`", false),
+            ("SEC-006", "demo::build", "`nix`", false),
+            ("SEC-007", "demo::build", "fs::write", true),
+            ("SEC-007", "demo::build", "OpenOptions::create", false),
+            ("SEC-007", "demo::build", "fs::remove_file", false),
+        ]);
+        assert_eq!(uses[0].justification, Justification::Safety("the pointer is owned by the handle and never shared.".to_string()));
+        assert_eq!(uses[2].justification, Justification::Safety("`self.0` must be valid for reads.".to_string()));
+        assert_eq!(uses[7].justification, Justification::Allowed("writes only below OUT_DIR".to_string()));
+        assert_eq!(uses[6].line, 29);
+    }
+
+    #[test]
+    fn test_safety_comment_covers_derefs_in_block() {
+        let source = "fn f(p: *const u8) -> u8 {\n    // SAFETY: p is valid\n    let v = unsafe { *p };\n    v\n}\n";
+        let uses = scan_source_capabilities(source, "src/lib.rs", "").unwrap();
+        assert_eq!(uses.len(), 2);
+        assert!(uses.iter().all(|u| u.justification == Justification::Safety("p is valid".to_string())));
+        assert_eq!(uses[0].item_path, "f");
+    }
+
+    #[test]
+    fn test_to_ticket() {
+        let capability = CapabilityUse {
+            kind: CapabilityKind::ProcessSpawn,
+            file: "build.rs".to_string(),
+            line: 7,
+            item_path: "demo::build::main".to_string(),
+            detail: "`curl`".to_string(),
+            justification: Justification::Missing,
+        };
+        let ticket = capability.to_ticket();
+        assert_eq!(ticket.id, "SEC-006");
+        assert_eq!(ticket.category, TicketCategory::Security);
+        assert_eq!(ticket.severity, Severity::High);
+        assert_eq!(ticket.module, "demo::build::main");
+        assert_eq!(ticket.title, "Process spawn: `curl`");
+        assert_eq!(ticket.description, "build.rs:7 (no justification)");
+
+        let justified = CapabilityUse { justification: Justification::Allowed("pinned".to_string()), ..capability };
+        assert_eq!(justified.to_ticket().severity, Severity::Low);
+    }
+
+    #[test]
+    fn test_crates_from_metadata() {
+        let json = r#"{
+            "packages": [
+                {"name": "app", "id": "app 0.1.0 (path+file:///ws/app)", "manifest_path": "/ws/app/Cargo.toml"},
+                {"name": "proc-macro2", "id": "proc-macro2 1.0.0", "manifest_path": "/reg/proc-macro2-1.0.0/Cargo.toml"}
+            ],
+            "workspace_members": ["app 0.1.0 (path+file:///ws/app)"]
+        }"#;
+        assert_eq!(crates_from_metadata(json).unwrap(), vec![
+            ("proc_macro2".to_string(), PathBuf::from("/reg/proc-macro2-1.0.0")),
+        ]);
+        assert!(crates_from_metadata("{}").is_err());
+    }
+}
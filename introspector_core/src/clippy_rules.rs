@@ -1,28 +1,49 @@
-use crate::audit_tickets::{AuditTicket, TicketCategory, Severity, get_all_tickets};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use serde::Deserialize;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use crate::audit_tickets::{get_ticket, Severity};
+use crate::decl_wrapper::LineIndex;
+
+/// Rule file looked up in the current directory when no `--rules` file is given.
+pub const USER_RULES_FILE: &str = "audit-rules.toml";
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "ClippyRule", vis = "pub", hash = "0e255083")]
+#[decl(struct, name = "ClippyRule", vis = "pub", hash = "e35d108b")]
 pub struct ClippyRule {
     pub name: String,
-    pub ticket_id: &'static str,
+    pub ticket_id: String,
+    pub severity: Severity,
     pub pattern: LintPattern,
     pub message: String,
     pub suggestion: Option<String>,
     pub applicability: Applicability,
 }
 
-#[derive(Debug, Clone)]
-#[decl(enum, name = "LintPattern", vis = "pub", hash = "dfd653b8")]
+/// What a rule matches. `Regex` is matched line by line against the raw
+/// source; every other pattern is evaluated on the syntax tree, so comments
+/// never match and paths are resolved through `use` declarations.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[decl(enum, name = "LintPattern", vis = "pub", hash = "655c32ff")]
 pub enum LintPattern {
-    StringLiteral(&'static str),
-    Regex(&'static str),
-    FunctionCall(&'static str),
-    MacroInvocation(&'static str),
-    ModulePath(&'static str),
+    /// Any literal whose value contains the text.
+    StringLiteral(String),
+    Regex(String),
+    /// Calls to a function or method, e.g. `std::process::exit` or `unwrap`.
+    /// The called path must end with these segments once resolved.
+    FunctionCall(String),
+    /// Macro invocations, e.g. `dbg!` or `log::debug!`.
+    MacroInvocation(String),
+    /// Any use of a path starting with this module, e.g. `std::process`.
+    ModulePath(String),
 }
 
 #[derive(Debug, Clone, Copy)]
-#[decl(enum, name = "Applicability", vis = "pub", hash = "85e6c897")]
+#[decl(enum, name = "Applicability", vis = "pub", hash = "6f00f314")]
 pub enum Applicability {
     MachineApplicable,
     MaybeIncorrect,
@@ -30,14 +51,32 @@ pub enum Applicability {
     Unspecified,
 }
 
-#[decl(fn, name = "generate_lint_rules", vis = "pub", hash = "f80e334e")]
+impl Applicability {
+    /// Accepts `MaybeIncorrect`, `maybe-incorrect` and `maybe_incorrect`.
+    pub fn from_name(name: &str) -> Option<Applicability> {
+        let normalized: String = name.chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match normalized.as_str() {
+            "machineapplicable" => Some(Applicability::MachineApplicable),
+            "maybeincorrect" => Some(Applicability::MaybeIncorrect),
+            "hasplaceholders" => Some(Applicability::HasPlaceholders),
+            "unspecified" => Some(Applicability::Unspecified),
+            _ => None,
+        }
+    }
+}
+
+#[decl(fn, name = "generate_lint_rules", vis = "pub", hash = "d7562b66")]
 pub fn generate_lint_rules() -> Vec<ClippyRule> {
     vec![
         // PHO-001: Fabricated VFS statistics
         ClippyRule {
             name: "phony_statistics".to_string(),
-            ticket_id: "PHO-001",
-            pattern: LintPattern::Regex(r#"(1,?247|635|213|399)\s*(items?|functions?|structs?)"#),
+            ticket_id: "PHO-001".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::Regex(r#"(1,?247|635|213|399)\s*(items?|functions?|structs?)"#.into()),
             message: "[PHO-001] 🎭 Fabricated statistics detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"PHO-001\", \"Replace with actual count from analysis\");\n\
@@ -49,8 +88,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // PHO-002: Fabricated percentages
         ClippyRule {
             name: "phony_percentages".to_string(),
-            ticket_id: "PHO-002",
-            pattern: LintPattern::Regex(r#"55\.1%|18\.9%|15\.2%|12\.3%"#),
+            ticket_id: "PHO-002".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::Regex(r#"55\.1%|18\.9%|15\.2%|12\.3%"#.into()),
             message: "[PHO-002] 🎭 Fabricated percentage detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"PHO-002\", \"Compute percentage from actual analysis\");\n\
@@ -62,8 +102,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // FKD-001: Hardcoded hash values
         ClippyRule {
             name: "hardcoded_hash".to_string(),
-            ticket_id: "FKD-001",
-            pattern: LintPattern::Regex(r#"["\']?[a-f0-9]{8}["\']?\s*(//.*hash|hash)"#),
+            ticket_id: "FKD-001".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::Regex(r#"["\']?[a-f0-9]{8}["\']?\s*(//.*hash|hash)"#.into()),
             message: "[FKD-001] 🧪 Hardcoded hash value detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"FKD-001\", \"Use computed hash\");\n\
@@ -76,8 +117,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // FKD-002: Fake blockhash
         ClippyRule {
             name: "fake_blockchain_data".to_string(),
-            ticket_id: "FKD-002",
-            pattern: LintPattern::StringLiteral("11111111111111111111111111111111"),
+            ticket_id: "FKD-002".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::StringLiteral("11111111111111111111111111111111".into()),
             message: "[FKD-002] 🧪 Fake blockhash detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"FKD-002\", \"Return error instead of fake data\");\n\
@@ -89,8 +131,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // FKD-003: Placeholder value
         ClippyRule {
             name: "placeholder_value".to_string(),
-            ticket_id: "FKD-003",
-            pattern: LintPattern::StringLiteral("sample_block_hash"),
+            ticket_id: "FKD-003".to_string(),
+            severity: Severity::Medium,
+            pattern: LintPattern::StringLiteral("sample_block_hash".into()),
             message: "[FKD-003] 🧪 Placeholder value detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"FKD-003\", \"Use Option::None for missing values\");\n\
@@ -102,8 +145,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // FKD-005: Always-true stub
         ClippyRule {
             name: "stub_always_true".to_string(),
-            ticket_id: "FKD-005",
-            pattern: LintPattern::Regex(r#"fn\s+\w+\([^)]*\)\s*->\s*bool\s*\{\s*(true|false)\s*\}"#),
+            ticket_id: "FKD-005".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::Regex(r#"fn\s+\w+\([^)]*\)\s*->\s*bool\s*\{\s*(true|false)\s*\}"#.into()),
             message: "[FKD-005] 🧪 Stub function always returns constant".to_string(),
             suggestion: Some(
                 "// audit_id!(\"FKD-005\", \"Implement actual logic or mark as todo!()\");\n\
@@ -115,8 +159,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // FKD-006: Hardcoded prices
         ClippyRule {
             name: "hardcoded_prices".to_string(),
-            ticket_id: "FKD-006",
-            pattern: LintPattern::Regex(r#"\"(145\.32|43250\.67|147\.89|44123\.89)\""#),
+            ticket_id: "FKD-006".to_string(),
+            severity: Severity::Medium,
+            pattern: LintPattern::Regex(r#"\"(145\.32|43250\.67|147\.89|44123\.89)\""#.into()),
             message: "[FKD-006] 🧪 Hardcoded price data detected".to_string(),
             suggestion: Some(
                 "// audit_id!(\"FKD-006\", \"Fetch from API or accept as parameter\");\n\
@@ -128,8 +173,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // UNV-001: Misleading verify function
         ClippyRule {
             name: "misleading_verify".to_string(),
-            ticket_id: "UNV-001",
-            pattern: LintPattern::FunctionCall("verify_morphism"),
+            ticket_id: "UNV-001".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::FunctionCall("verify_morphism".into()),
             message: "[UNV-001] ❓ Function name suggests verification but doesn't verify".to_string(),
             suggestion: Some(
                 "// audit_id!(\"UNV-001\", \"Rename to reflect actual behavior\");\n\
@@ -141,8 +187,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // UNV-002: Metaphorical math
         ClippyRule {
             name: "metaphorical_math".to_string(),
-            ticket_id: "UNV-002",
-            pattern: LintPattern::StringLiteral("196883"),
+            ticket_id: "UNV-002".to_string(),
+            severity: Severity::Medium,
+            pattern: LintPattern::StringLiteral("196883".into()),
             message: "[UNV-002] ❓ Monster group dimension used metaphorically".to_string(),
             suggestion: Some(
                 "// audit_id!(\"UNV-002\", \"Document as conceptual reference\");\n\
@@ -155,8 +202,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // ISS-001: Misleading terminology
         ClippyRule {
             name: "misleading_terminology".to_string(),
-            ticket_id: "ISS-001",
-            pattern: LintPattern::Regex(r#"eigenvalue|eigenmatrix"#),
+            ticket_id: "ISS-001".to_string(),
+            severity: Severity::High,
+            pattern: LintPattern::Regex(r#"eigenvalue|eigenmatrix"#.into()),
             message: "[ISS-001] ⚠️ Term 'eigenvalue' used for non-eigenvalue computation".to_string(),
             suggestion: Some(
                 "// audit_id!(\"ISS-001\", \"Use accurate terminology\");\n\
@@ -169,8 +217,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // ISS-002: Naive parsing
         ClippyRule {
             name: "naive_parsing".to_string(),
-            ticket_id: "ISS-002",
-            pattern: LintPattern::Regex(r#"\.matches\(\s*["\']fn\s"#),
+            ticket_id: "ISS-002".to_string(),
+            severity: Severity::Medium,
+            pattern: LintPattern::Regex(r#"\.matches\(\s*["\']fn\s"#.into()),
             message: "[ISS-002] ⚠️ Naive string matching for code analysis".to_string(),
             suggestion: Some(
                 "// audit_id!(\"ISS-002\", \"Use syn for AST-based analysis\");\n\
@@ -184,8 +233,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // CON-002: Hardcoded paths
         ClippyRule {
             name: "hardcoded_path".to_string(),
-            ticket_id: "CON-002",
-            pattern: LintPattern::StringLiteral("/nix/store"),
+            ticket_id: "CON-002".to_string(),
+            severity: Severity::Low,
+            pattern: LintPattern::StringLiteral("/nix/store".into()),
             message: "[CON-002] 🤔 Hardcoded Nix store path - may fail on non-NixOS".to_string(),
             suggestion: Some(
                 "// audit_id!(\"CON-002\", \"Use environment variable or cargo metadata\");\n\
@@ -198,8 +248,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // Template contract warning
         ClippyRule {
             name: "template_contract".to_string(),
-            ticket_id: "PHO-003",
-            pattern: LintPattern::MacroInvocation("ca!"),
+            ticket_id: "PHO-003".to_string(),
+            severity: Severity::Medium,
+            pattern: LintPattern::MacroInvocation("ca!".into()),
             message: "[PHO-003] 🎭 Template Solana contract macro".to_string(),
             suggestion: Some(
                 "// audit_id!(\"PHO-003\", \"Generated code is template only\");\n\
@@ -211,8 +262,9 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
         // Fake crypto warning
         ClippyRule {
             name: "fake_crypto".to_string(),
-            ticket_id: "PHO-004",
-            pattern: LintPattern::Regex(r#"(plonk|stark|snark|zk_witness|zk_proof)"#),
+            ticket_id: "PHO-004".to_string(),
+            severity: Severity::Critical,
+            pattern: LintPattern::Regex(r#"(plonk|stark|snark|zk_witness|zk_proof)"#.into()),
             message: "[PHO-004] 🎭 Template ZK code - not real cryptographic proof".to_string(),
             suggestion: Some(
                 "// audit_id!(\"PHO-004\", \"Use real ZK library\");\n\
@@ -223,7 +275,7 @@ pub fn generate_lint_rules() -> Vec<ClippyRule> {
     ]
 }
 
-#[decl(fn, name = "generate_suggested_fixes_report", vis = "pub", hash = "a99fcc5c")]
+#[decl(fn, name = "generate_suggested_fixes_report", vis = "pub", hash = "0384ef01")]
 pub fn generate_suggested_fixes_report() -> String {
     let rules = generate_lint_rules();
     let mut report = String::new();
@@ -234,6 +286,7 @@ pub fn generate_suggested_fixes_report() -> String {
     for rule in &rules {
         report.push_str(&format!("## {} `{}`\n\n", rule.ticket_id, rule.name));
         report.push_str(&format!("**Message:** {}\n\n", rule.message));
+        report.push_str(&format!("**Severity:** {} {}\n\n", rule.severity.emoji(), rule.severity.as_str()));
         report.push_str(&format!("**Pattern:** `{:?}`\n\n", rule.pattern));
         
         if let Some(suggestion) = &rule.suggestion {
@@ -248,42 +301,560 @@ pub fn generate_suggested_fixes_report() -> String {
     report
 }
 
-#[decl(fn, name = "check_code_for_violations", vis = "pub", hash = "b92ae4c3")]
+#[decl(fn, name = "check_code_for_violations", vis = "pub", hash = "e91c6084")]
 pub fn check_code_for_violations(code: &str) -> Vec<(ClippyRule, usize)> {
-    let rules = generate_lint_rules();
-    let mut violations = Vec::new();
-    
-    for (line_num, line) in code.lines().enumerate() {
-        for rule in &rules {
-            let matches = match &rule.pattern {
-                LintPattern::StringLiteral(s) => line.contains(s),
-                LintPattern::Regex(pattern) => {
-                    regex::Regex::new(pattern)
-                        .map(|r| r.is_match(line))
-                        .unwrap_or(false)
+    check_code_with_rules(code, &generate_lint_rules())
+}
+
+/// Violations of `rules` in `code`, as `(rule, 1-based line)` pairs ordered
+/// by line. `code` may be a whole file or a sequence of statements.
+#[decl(fn, name = "check_code_with_rules", vis = "pub", hash = "3275b4cf")]
+pub fn check_code_with_rules(code: &str, rules: &[ClippyRule]) -> Vec<(ClippyRule, usize)> {
+    find_violations(code, rules)
+        .into_iter()
+        .map(|v| (v.rule, v.line))
+        .collect()
+}
+
+/// A rule match with its location in the checked source.
+#[derive(Debug, Clone)]
+#[decl(struct, name = "LintViolation", vis = "pub", hash = "a1c15ae4")]
+pub struct LintViolation {
+    pub rule: ClippyRule,
+    /// 1-based line of the match.
+    pub line: usize,
+    /// Byte range of the match.
+    pub span: Range<usize>,
+    /// Byte range of the innermost statement, item, field or variant around
+    /// the match, where a marker can be inserted.
+    pub context: Option<Range<usize>>,
+}
+
+/// Like `check_code_with_rules`, with byte spans. At most one violation is
+/// reported per rule and line.
+#[decl(fn, name = "find_violations", vis = "pub", hash = "76d425d6")]
+pub fn find_violations(code: &str, rules: &[ClippyRule]) -> Vec<LintViolation> {
+    let index = LineIndex::new(code);
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let mut hits: BTreeMap<(usize, usize), Range<usize>> = BTreeMap::new();
+
+    for (idx, rule) in rules.iter().enumerate() {
+        if let LintPattern::Regex(pattern) = &rule.pattern {
+            let Ok(re) = regex::Regex::new(pattern) else { continue };
+            for (line_num, line) in code.lines().enumerate() {
+                if let Some(m) = re.find(line) {
+                    let start = line_starts[line_num];
+                    hits.entry((line_num + 1, idx)).or_insert(start + m.start()..start + m.end());
+                }
+            }
+        }
+    }
+
+    let mut matcher = StructuralMatcher {
+        rules,
+        index,
+        scopes: Vec::new(),
+        module: Vec::new(),
+        reexports: HashMap::new(),
+        line_offset: 0,
+        hits: &mut hits,
+        anchors: Vec::new(),
+    };
+    if let Ok(file) = syn::parse_file(code) {
+        matcher.visit_file(&file);
+    } else if let Ok(block) = syn::parse_str::<syn::Block>(&format!("{{\n{}\n}}", code)) {
+        // Statement snippets are checked inside a block; the brace line shifts lines by one.
+        matcher.line_offset = 1;
+        matcher.visit_block(&block);
+    } else {
+        // Unparseable input: only literal rules can still be applied, textually.
+        for (idx, rule) in rules.iter().enumerate() {
+            if let LintPattern::StringLiteral(s) = &rule.pattern {
+                for (line_num, line) in code.lines().enumerate() {
+                    if let Some(col) = line.find(s.as_str()) {
+                        let start = line_starts[line_num] + col;
+                        matcher.hits.entry((line_num + 1, idx)).or_insert(start..start + s.len());
+                    }
+                }
+            }
+        }
+    }
+    let anchors = std::mem::take(&mut matcher.anchors);
+
+    hits.into_iter()
+        .map(|((line, idx), span)| {
+            let context = anchors.iter()
+                .filter(|a| a.start <= span.start && span.start < a.end)
+                .min_by_key(|a| a.len())
+                .cloned();
+            LintViolation { rule: rules[idx].clone(), line, span, context }
+        })
+        .collect()
+}
+
+/// Names brought into scope by the `use` declarations of one module or block.
+struct UseScope {
+    names: HashMap<String, Vec<String>>,
+    /// Prefixes of glob imports other than `use super::*`.
+    globs: Vec<Vec<String>>,
+    /// Items declared here, which shadow glob imports.
+    local: HashSet<String>,
+    /// Blocks see their enclosing scope; modules only through `use super::*`.
+    inherits: bool,
+}
+
+impl UseScope {
+    /// `module` is the path of the enclosing module, for `self`/`super` imports.
+    fn from_items<'a>(items: impl Iterator<Item = &'a syn::Item>, inherits: bool, module: &[String]) -> UseScope {
+        let mut scope = UseScope { names: HashMap::new(), globs: Vec::new(), local: HashSet::new(), inherits };
+        for item in items {
+            if let syn::Item::Use(item_use) = item {
+                for (name, path) in flatten_use_tree(&item_use.tree, Vec::new()) {
+                    if name == "*" {
+                        if path == ["super"] {
+                            scope.inherits = true;
+                        } else {
+                            scope.globs.push(normalize_relative(path, module));
+                        }
+                    } else {
+                        scope.names.insert(name, normalize_relative(path, module));
+                    }
+                }
+            } else if let Some(ident) = declared_ident(item) {
+                scope.local.insert(ident.to_string());
+            }
+        }
+        scope
+    }
+}
+
+fn collect_reexports(items: &[syn::Item], module: &mut Vec<String>, out: &mut HashMap<Vec<String>, Vec<String>>) {
+    for item in items {
+        match item {
+            syn::Item::Use(item_use) => {
+                for (name, path) in flatten_use_tree(&item_use.tree, Vec::new()) {
+                    if name != "*" {
+                        let key = std::iter::once("crate".to_string()).chain(module.iter().cloned()).chain([name]).collect();
+                        out.insert(key, normalize_relative(path, module));
+                    }
+                }
+            }
+            syn::Item::Mod(item_mod) => {
+                if let Some((_, items)) = &item_mod.content {
+                    module.push(item_mod.ident.to_string());
+                    collect_reexports(items, module, out);
+                    module.pop();
                 }
-                LintPattern::FunctionCall(name) => line.contains(&format!("{}(", name)),
-                LintPattern::MacroInvocation(name) => line.contains(name),
-                LintPattern::ModulePath(path) => line.contains(path),
+            }
+            _ => {}
+        }
+    }
+}
+
+fn declared_ident(item: &syn::Item) -> Option<&syn::Ident> {
+    match item {
+        syn::Item::Fn(i) => Some(&i.sig.ident),
+        syn::Item::Const(i) => Some(&i.ident),
+        syn::Item::Static(i) => Some(&i.ident),
+        syn::Item::Struct(i) => Some(&i.ident),
+        syn::Item::Enum(i) => Some(&i.ident),
+        syn::Item::Union(i) => Some(&i.ident),
+        syn::Item::Type(i) => Some(&i.ident),
+        syn::Item::Trait(i) => Some(&i.ident),
+        syn::Item::Mod(i) => Some(&i.ident),
+        syn::Item::Macro(i) => i.ident.as_ref(),
+        _ => None,
+    }
+}
+
+/// `crate::`, `self::` and `super::` paths rewritten from the crate root as
+/// `crate::<module>::...`; other paths are returned unchanged.
+fn normalize_relative(path: Vec<String>, module: &[String]) -> Vec<String> {
+    let mut base = module.to_vec();
+    let rest = match path.first().map(String::as_str) {
+        Some("crate") => {
+            base.clear();
+            &path[1..]
+        }
+        Some("self") => &path[1..],
+        Some("super") => {
+            let supers = path.iter().take_while(|s| *s == "super").count();
+            base.truncate(base.len().saturating_sub(supers));
+            &path[supers..]
+        }
+        _ => return path,
+    };
+    std::iter::once("crate".to_string()).chain(base).chain(rest.iter().cloned()).collect()
+}
+
+/// `(local name, full path)` for every leaf of a use tree; globs are named `*`.
+pub(crate) fn flatten_use_tree(tree: &syn::UseTree, prefix: Vec<String>) -> Vec<(String, Vec<String>)> {
+    match tree {
+        syn::UseTree::Path(p) => {
+            let mut prefix = prefix;
+            prefix.push(p.ident.to_string());
+            flatten_use_tree(&p.tree, prefix)
+        }
+        syn::UseTree::Name(n) if n.ident == "self" => match prefix.last() {
+            Some(last) => vec![(last.clone(), prefix.clone())],
+            None => vec![],
+        },
+        syn::UseTree::Name(n) => {
+            let mut path = prefix;
+            path.push(n.ident.to_string());
+            vec![(n.ident.to_string(), path)]
+        }
+        syn::UseTree::Rename(r) => {
+            let mut path = prefix;
+            path.push(r.ident.to_string());
+            vec![(r.rename.to_string(), path)]
+        }
+        syn::UseTree::Glob(_) => vec![("*".to_string(), prefix)],
+        syn::UseTree::Group(g) => g.items.iter()
+            .flat_map(|t| flatten_use_tree(t, prefix.clone()))
+            .collect(),
+    }
+}
+
+fn rule_segments(path: &str) -> Vec<&str> {
+    path.trim_end_matches('!')
+        .split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn ends_with_segments(path: &[String], rule: &[&str]) -> bool {
+    !rule.is_empty() && path.len() >= rule.len()
+        && path[path.len() - rule.len()..].iter().zip(rule).all(|(a, b)| a == b)
+}
+
+fn starts_with_segments(path: &[String], rule: &[&str]) -> bool {
+    !rule.is_empty() && path.len() >= rule.len()
+        && path.iter().zip(rule).all(|(a, b)| a == b)
+}
+
+fn literal_text(lit: &syn::Lit) -> String {
+    match lit {
+        syn::Lit::Str(s) => s.value(),
+        syn::Lit::ByteStr(s) => String::from_utf8_lossy(&s.value()).into_owned(),
+        other => quote::ToTokens::to_token_stream(other).to_string(),
+    }
+}
+
+struct StructuralMatcher<'r, 'h> {
+    rules: &'r [ClippyRule],
+    /// Index over the checked code, not the block-wrapped snippet.
+    index: LineIndex<'r>,
+    scopes: Vec<UseScope>,
+    /// Path of the module being visited, from the crate root.
+    module: Vec<String>,
+    /// `crate::<module>::<name>` of every `use` in the file, to what it names.
+    reexports: HashMap<Vec<String>, Vec<String>>,
+    line_offset: usize,
+    hits: &'h mut BTreeMap<(usize, usize), Range<usize>>,
+    anchors: Vec<Range<usize>>,
+}
+
+impl StructuralMatcher<'_, '_> {
+    /// Paths `path` may refer to: its first segment replaced by what a `use`
+    /// in scope maps it to, or else as written and under each glob import in
+    /// scope, since globs do not say which names they bring in.
+    fn resolve(&self, path: &syn::Path) -> Vec<Vec<String>> {
+        let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        if path.leading_colon.is_some() || segments.is_empty() {
+            return vec![segments];
+        }
+        if matches!(segments[0].as_str(), "crate" | "self" | "super") {
+            return vec![self.through_reexports(normalize_relative(segments, &self.module))];
+        }
+        let mut globs = Vec::new();
+        for scope in self.scopes.iter().rev() {
+            if let Some(full) = scope.names.get(&segments[0]) {
+                return vec![self.through_reexports(full.iter().chain(&segments[1..]).cloned().collect())];
+            }
+            if scope.local.contains(&segments[0]) {
+                return vec![segments];
+            }
+            globs.extend(scope.globs.iter());
+            if !scope.inherits {
+                break;
+            }
+        }
+        let candidates = globs.into_iter()
+            .map(|prefix| self.through_reexports(prefix.iter().chain(&segments).cloned().collect()))
+            .collect::<Vec<_>>();
+        std::iter::once(segments).chain(candidates).collect()
+    }
+
+    /// A crate-relative path with its longest `use`d prefix replaced by what
+    /// that `use` names, repeatedly, so `crate::sys::process::exit` after
+    /// `mod sys { pub use std::process; }` is `std::process::exit`.
+    fn through_reexports(&self, mut path: Vec<String>) -> Vec<String> {
+        // Bounded, since `use` cycles are only rejected later by rustc.
+        for _ in 0..8 {
+            if path.first().map(String::as_str) != Some("crate") {
+                break;
+            }
+            let Some(len) = (2..=path.len()).rev().find(|&len| self.reexports.contains_key(&path[..len])) else {
+                break;
             };
-            
-            if matches {
-                violations.push((rule.clone(), line_num + 1));
+            path = self.reexports[&path[..len]].iter().chain(&path[len..]).cloned().collect();
+        }
+        path
+    }
+
+    fn byte_range(&self, span: proc_macro2::Span) -> Range<usize> {
+        let (start, end) = (span.start(), span.end());
+        let offset = |line: usize, column: usize| {
+            self.index.byte_offset(line.saturating_sub(self.line_offset), column).unwrap_or(0)
+        };
+        let start = offset(start.line, start.column);
+        start..offset(end.line, end.column).max(start)
+    }
+
+    fn report(&mut self, span: proc_macro2::Span, matches: impl Fn(&LintPattern) -> bool) {
+        let line = span.start().line.saturating_sub(self.line_offset);
+        let range = self.byte_range(span);
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if matches(&rule.pattern) {
+                self.hits.entry((line, idx)).or_insert_with(|| range.clone());
             }
         }
     }
-    
-    violations
+
+    fn anchor(&mut self, span: proc_macro2::Span) {
+        let range = self.byte_range(span);
+        if !range.is_empty() {
+            self.anchors.push(range);
+        }
+    }
+
+    fn check_call(&mut self, span: proc_macro2::Span, called: &[Vec<String>]) {
+        self.report(span, |p| matches!(p, LintPattern::FunctionCall(f)
+            if called.iter().any(|c| ends_with_segments(c, &rule_segments(f)))));
+    }
+
+    /// Literals inside a macro body that did not parse as expressions.
+    fn check_token_literals(&mut self, tokens: proc_macro2::TokenStream) {
+        for tt in tokens {
+            match tt {
+                proc_macro2::TokenTree::Group(g) => self.check_token_literals(g.stream()),
+                proc_macro2::TokenTree::Literal(lit) => self.visit_lit(&syn::Lit::new(lit)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for StructuralMatcher<'_, '_> {
+    fn visit_item(&mut self, i: &'ast syn::Item) {
+        self.anchor(i.span());
+        visit::visit_item(self, i);
+    }
+
+    fn visit_impl_item(&mut self, i: &'ast syn::ImplItem) {
+        self.anchor(i.span());
+        visit::visit_impl_item(self, i);
+    }
+
+    fn visit_trait_item(&mut self, i: &'ast syn::TraitItem) {
+        self.anchor(i.span());
+        visit::visit_trait_item(self, i);
+    }
+
+    fn visit_stmt(&mut self, i: &'ast syn::Stmt) {
+        self.anchor(i.span());
+        visit::visit_stmt(self, i);
+    }
+
+    fn visit_field(&mut self, i: &'ast syn::Field) {
+        self.anchor(i.span());
+        visit::visit_field(self, i);
+    }
+
+    fn visit_variant(&mut self, i: &'ast syn::Variant) {
+        self.anchor(i.span());
+        visit::visit_variant(self, i);
+    }
+
+    fn visit_file(&mut self, i: &'ast syn::File) {
+        collect_reexports(&i.items, &mut Vec::new(), &mut self.reexports);
+        self.scopes.push(UseScope::from_items(i.items.iter(), false, &self.module));
+        visit::visit_file(self, i);
+        self.scopes.pop();
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        self.module.push(i.ident.to_string());
+        let items = i.content.iter().flat_map(|(_, items)| items.iter());
+        self.scopes.push(UseScope::from_items(items, false, &self.module));
+        visit::visit_item_mod(self, i);
+        self.scopes.pop();
+        self.module.pop();
+    }
+
+    fn visit_block(&mut self, i: &'ast syn::Block) {
+        let items = i.stmts.iter().filter_map(|s| match s {
+            syn::Stmt::Item(item) => Some(item),
+            _ => None,
+        });
+        self.scopes.push(UseScope::from_items(items, true, &self.module));
+        visit::visit_block(self, i);
+        self.scopes.pop();
+    }
+
+    fn visit_item_use(&mut self, i: &'ast syn::ItemUse) {
+        for (_, path) in flatten_use_tree(&i.tree, Vec::new()) {
+            let path = normalize_relative(path, &self.module);
+            self.report(i.span(), |p| matches!(p, LintPattern::ModulePath(m) if starts_with_segments(&path, &rule_segments(m))));
+        }
+    }
+
+    fn visit_path(&mut self, i: &'ast syn::Path) {
+        let resolved = self.resolve(i);
+        self.report(i.span(), |p| matches!(p, LintPattern::ModulePath(m)
+            if resolved.iter().any(|r| starts_with_segments(r, &rule_segments(m)))));
+        visit::visit_path(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(func) = &*i.func {
+            let called = self.resolve(&func.path);
+            self.check_call(i.span(), &called);
+        }
+        visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        self.check_call(i.method.span(), &[vec![i.method.to_string()]]);
+        visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        let invoked = self.resolve(&i.path);
+        self.report(i.span(), |p| matches!(p, LintPattern::MacroInvocation(m)
+            if invoked.iter().any(|r| ends_with_segments(r, &rule_segments(m)))));
+
+        // Macro bodies are opaque to syn; most take comma-separated expressions.
+        match i.parse_body_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated) {
+            Ok(args) => args.iter().for_each(|arg| self.visit_expr(arg)),
+            Err(_) => self.check_token_literals(i.tokens.clone()),
+        }
+        visit::visit_macro(self, i);
+    }
+
+    fn visit_lit(&mut self, i: &'ast syn::Lit) {
+        let text = literal_text(i);
+        self.report(i.span(), |p| matches!(p, LintPattern::StringLiteral(s) if text.contains(s.as_str())));
+    }
+}
+
+/// One `[[rule]]` table of a user rule file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserRuleSpec {
+    name: String,
+    ticket: String,
+    severity: String,
+    message: String,
+    suggestion: Option<String>,
+    applicability: Option<String>,
+    pattern: LintPattern,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserRuleFile {
+    #[serde(default)]
+    rule: Vec<UserRuleSpec>,
+}
+
+/// Parses user-defined rules from TOML:
+///
+/// ```toml
+/// [[rule]]
+/// name = "no_process_exit"
+/// ticket = "HOUSE-001"
+/// severity = "high"
+/// message = "Library code must not exit the process"
+/// suggestion = "Return an error to the caller instead"
+/// applicability = "maybe-incorrect"
+/// pattern = { function_call = "std::process::exit" }
+/// ```
+#[decl(fn, name = "parse_user_rules", vis = "pub", hash = "eb6ae277")]
+pub fn parse_user_rules(toml_src: &str) -> Result<Vec<ClippyRule>, String> {
+    let file: UserRuleFile = toml::from_str(toml_src).map_err(|e| e.to_string())?;
+
+    file.rule.into_iter().map(|spec| {
+        let severity = Severity::from_name(&spec.severity)
+            .ok_or_else(|| format!("rule `{}`: unknown severity `{}`", spec.name, spec.severity))?;
+        let applicability = match &spec.applicability {
+            Some(a) => Applicability::from_name(a)
+                .ok_or_else(|| format!("rule `{}`: unknown applicability `{}`", spec.name, a))?,
+            None => Applicability::Unspecified,
+        };
+        match &spec.pattern {
+            LintPattern::Regex(re) => {
+                regex::Regex::new(re).map_err(|e| format!("rule `{}`: {}", spec.name, e))?;
+            }
+            LintPattern::FunctionCall(p) | LintPattern::MacroInvocation(p) | LintPattern::ModulePath(p)
+                if rule_segments(p).is_empty() =>
+            {
+                return Err(format!("rule `{}`: empty path pattern", spec.name));
+            }
+            _ => {}
+        }
+
+        let tag = format!("[{}]", spec.ticket);
+        let message = if spec.message.starts_with(&tag) {
+            spec.message
+        } else {
+            format!("{} {}", tag, spec.message)
+        };
+        Ok(ClippyRule {
+            name: spec.name,
+            ticket_id: spec.ticket,
+            severity,
+            pattern: spec.pattern,
+            message,
+            suggestion: spec.suggestion,
+            applicability,
+        })
+    }).collect()
+}
+
+#[decl(fn, name = "load_user_rules", vis = "pub", hash = "40b23e03")]
+pub fn load_user_rules(path: &Path) -> Result<Vec<ClippyRule>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_user_rules(&content).map_err(|e| format!("{}: {}", path.display(), e))
 }
 
-#[decl(fn, name = "print_violation", vis = "pub", hash = "33a63a1f")]
+/// Built-in rules plus those in `rules_file`; a user rule replaces the
+/// built-in rule of the same name.
+#[decl(fn, name = "lint_rules_with_user_file", vis = "pub", hash = "9de98ae3")]
+pub fn lint_rules_with_user_file(rules_file: Option<&Path>) -> Result<Vec<ClippyRule>, String> {
+    let mut rules = generate_lint_rules();
+    if let Some(path) = rules_file {
+        for user_rule in load_user_rules(path)? {
+            rules.retain(|r| r.name != user_rule.name);
+            rules.push(user_rule);
+        }
+    }
+    Ok(rules)
+}
+
+#[decl(fn, name = "print_violation", vis = "pub", hash = "2adaa536")]
 pub fn print_violation(rule: &ClippyRule, line: usize, file: &str) {
     let reset = "\x1b[0m";
     let yellow = "\x1b[93m";
     let cyan = "\x1b[96m";
     let green = "\x1b[92m";
     
-    eprintln!("{}warning{}: {}", yellow, reset, rule.message);
+    eprintln!("{}warning{}: {} {}", yellow, reset, rule.severity.emoji(), rule.message);
     eprintln!("  {}-->{} {}:{}", cyan, reset, file, line);
     
     if let Some(suggestion) = &rule.suggestion {
@@ -293,46 +864,187 @@ pub fn print_violation(rule: &ClippyRule, line: usize, file: &str) {
     eprintln!();
 }
 
-#[decl(fn, name = "generate_rustfix_json", vis = "pub", hash = "61f3285b")]
-pub fn generate_rustfix_json(violations: &[(ClippyRule, usize)], file: &str) -> String {
-    use std::fmt::Write;
-    let mut json = String::from("[\n");
-    
-    for (i, (rule, line)) in violations.iter().enumerate() {
-        let suggestion = rule.suggestion.as_deref().unwrap_or("");
-        let first_line = suggestion.lines().next().unwrap_or("");
-        
-        write!(json, r#"  {{
-    "message": "{}",
-    "code": {{ "code": "{}", "explanation": null }},
-    "level": "warning",
-    "spans": [{{
-      "file_name": "{}",
-      "line_start": {},
-      "line_end": {},
-      "column_start": 1,
-      "column_end": 80,
-      "is_primary": true,
-      "suggested_replacement": "{}",
-      "suggestion_applicability": "{:?}"
-    }}]
-  }}"#,
-            rule.message,
-            rule.name,
-            file,
-            line,
-            line,
-            first_line.replace("\"", "\\\""),
-            rule.applicability
-        ).ok();
-        
-        if i < violations.len() - 1 {
-            json.push_str(",\n");
+/// 1-based line and column (in chars) of a byte offset, as rustc reports them.
+fn line_col(line_starts: &[usize], code: &str, offset: usize) -> (usize, usize) {
+    let line = line_starts.partition_point(|&start| start <= offset);
+    let start = line_starts[line - 1];
+    (line, code[start..offset].chars().count() + 1)
+}
+
+/// A rustc `DiagnosticSpan` for `range`.
+fn diagnostic_span(
+    code: &str,
+    line_starts: &[usize],
+    file: &str,
+    range: Range<usize>,
+    is_primary: bool,
+    label: Option<&str>,
+    suggestion: Option<(&str, Applicability)>,
+) -> serde_json::Value {
+    let (line_start, column_start) = line_col(line_starts, code, range.start);
+    let (line_end, column_end) = line_col(line_starts, code, range.end);
+
+    let text: Vec<serde_json::Value> = (line_start..=line_end).map(|line| {
+        let start = line_starts[line - 1];
+        let end = code[start..].find('\n').map_or(code.len(), |i| start + i);
+        let line_text = &code[start..end];
+        let highlight_start = if line == line_start { column_start } else { 1 };
+        let highlight_end = if line == line_end { column_end } else { line_text.chars().count() + 1 };
+        serde_json::json!({
+            "text": line_text,
+            "highlight_start": highlight_start,
+            "highlight_end": highlight_end,
+        })
+    }).collect();
+
+    serde_json::json!({
+        "file_name": file,
+        "byte_start": range.start,
+        "byte_end": range.end,
+        "line_start": line_start,
+        "line_end": line_end,
+        "column_start": column_start,
+        "column_end": column_end,
+        "is_primary": is_primary,
+        "text": text,
+        "label": label,
+        "suggested_replacement": suggestion.map(|(s, _)| s),
+        "suggestion_applicability": suggestion.map(|(_, a)| format!("{:?}", a)),
+        "expansion": null,
+    })
+}
+
+fn diagnostic(message: &str, level: &str, code: Option<serde_json::Value>, spans: Vec<serde_json::Value>, children: Vec<serde_json::Value>, rendered: Option<String>) -> serde_json::Value {
+    serde_json::json!({
+        "$message_type": "diagnostic",
+        "message": message,
+        "code": code,
+        "level": level,
+        "spans": spans,
+        "children": children,
+        "rendered": rendered,
+    })
+}
+
+/// Diagnostics for `violations` in rustc's `--error-format=json` schema, one
+/// JSON object per line, as `cargo`/`rustfix` consume them.
+///
+/// Each diagnostic has the match as primary span and the enclosing statement
+/// or item as secondary span. A machine-applicable `help` child inserts an
+/// `// audit_id!(...)` marker line before that statement; markers for several
+/// rules at the same place are combined into the first diagnostic so the
+/// suggestions never overlap. The rule's own suggestion, which usually has
+/// placeholders, is a span-less `help` child.
+#[decl(fn, name = "generate_rustfix_json", vis = "pub", hash = "f54a0d57")]
+pub fn generate_rustfix_json(violations: &[LintViolation], code: &str, file: &str) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    // Markers to insert at each context start, skipping ones already present.
+    let mut markers: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for v in violations {
+        let Some(context) = &v.context else { continue };
+        let (line, _) = line_col(&line_starts, code, context.start);
+        let previous_line = if line >= 2 {
+            &code[line_starts[line - 2]..line_starts[line - 1]]
+        } else {
+            ""
+        };
+        let marker = format!("// audit_id!(\"{}\", \"{}\");", v.rule.ticket_id, v.rule.name);
+        let existing = markers.entry(context.start).or_default();
+        if !previous_line.contains(&format!("audit_id!(\"{}\"", v.rule.ticket_id)) && !existing.contains(&marker) {
+            existing.push(marker);
         }
     }
-    
-    json.push_str("\n]");
-    json
+
+    let mut out = String::new();
+    for v in violations {
+        let ticket = get_ticket(&v.rule.ticket_id);
+        let level = if v.rule.severity == Severity::Critical { "error" } else { "warning" };
+        let label = ticket.as_ref().map(|t| t.title);
+
+        let mut spans = vec![diagnostic_span(code, &line_starts, file, v.span.clone(), true, label, None)];
+        let mut children = Vec::new();
+
+        if let Some(context) = v.context.clone().filter(|c| *c != v.span) {
+            spans.push(diagnostic_span(code, &line_starts, file, context.clone(), false, Some("in this statement"), None));
+        }
+        if let Some(context) = &v.context {
+            if let Some(lines) = markers.remove(&context.start).filter(|l| !l.is_empty()) {
+                let line_start = line_starts[line_starts.partition_point(|&s| s <= context.start) - 1];
+                let before = &code[line_start..context.start];
+                let indent = &before[..before.len() - before.trim_start().len()];
+                let replacement: String = lines.iter().map(|l| format!("{}\n{}", l, indent)).collect();
+                let insertion = context.start..context.start;
+                let span = diagnostic_span(code, &line_starts, file, insertion, true, None,
+                    Some((&replacement, Applicability::MachineApplicable)));
+                children.push(diagnostic("mark this with an audit marker", "help", None, vec![span], vec![], None));
+            }
+        }
+        if let Some(suggestion) = &v.rule.suggestion {
+            children.push(diagnostic(&format!("consider:\n{}", suggestion), "help", None, vec![], vec![], None));
+        }
+        if let Some(t) = &ticket {
+            children.push(diagnostic(&format!("ticket {}: {}", t.id, t.description), "note", None, vec![], vec![], None));
+        }
+
+        let (line, column) = line_col(&line_starts, code, v.span.start);
+        let rendered = format!("{}: {}\n --> {}:{}:{}\n", level, v.rule.message, file, line, column);
+        let code_json = serde_json::json!({
+            "code": format!("audit::{}", v.rule.name),
+            "explanation": ticket.as_ref().map(|t| t.description),
+        });
+
+        let diag = diagnostic(&v.rule.message, level, Some(code_json), spans, children, Some(rendered));
+        out.push_str(&diag.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/// Applies the machine-applicable suggestions in rustc-style JSON `diagnostics`
+/// to `code`, the way `rustfix` does, and checks that the result parses.
+#[decl(fn, name = "apply_rustfix_json", vis = "pub", hash = "67c61001")]
+pub fn apply_rustfix_json(code: &str, diagnostics: &str) -> Result<String, String> {
+    fn collect(diag: &serde_json::Value, edits: &mut Vec<(usize, usize, String)>) {
+        for span in diag["spans"].as_array().into_iter().flatten() {
+            if span["suggestion_applicability"] == "MachineApplicable" {
+                if let (Some(start), Some(end), Some(text)) = (
+                    span["byte_start"].as_u64(),
+                    span["byte_end"].as_u64(),
+                    span["suggested_replacement"].as_str(),
+                ) {
+                    edits.push((start as usize, end as usize, text.to_string()));
+                }
+            }
+        }
+        for child in diag["children"].as_array().into_iter().flatten() {
+            collect(child, edits);
+        }
+    }
+
+    let mut edits = Vec::new();
+    for diag in serde_json::Deserializer::from_str(diagnostics).into_iter::<serde_json::Value>() {
+        collect(&diag.map_err(|e| e.to_string())?, &mut edits);
+    }
+    edits.sort();
+    edits.dedup();
+    for pair in edits.windows(2) {
+        if pair[1].0 < pair[0].1 || (pair[1].0 == pair[0].0 && pair[0].0 == pair[0].1) {
+            return Err(format!("overlapping suggestions at byte {}", pair[1].0));
+        }
+    }
+
+    let mut result = code.to_string();
+    for (start, end, text) in edits.iter().rev() {
+        if *end > code.len() || !code.is_char_boundary(*start) || !code.is_char_boundary(*end) {
+            return Err(format!("suggestion span {}..{} is outside the source", start, end));
+        }
+        result.replace_range(*start..*end, text);
+    }
+    syn::parse_file(&result).map_err(|e| format!("fixed source no longer parses: {}", e))?;
+    Ok(result)
 }
 
 #[macro_export]
@@ -371,4 +1083,192 @@ mod tests {
         let violations = check_code_for_violations(code);
         assert!(violations.iter().any(|(r, _)| r.ticket_id == "CON-002"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ignores_comments_and_resolves_uses() {
+        let code = r#"
+use std::process::{self, Command};
+use crate::morph::verify_morphism as check;
+
+// verify_morphism(a, b) used to live here
+fn run() {
+    let ok = check(1, 2);
+    let out = Command::new("ls").output();
+    process::exit(1);
+}
+"#;
+        let rules = parse_user_rules(r#"
+[[rule]]
+name = "no_exit"
+ticket = "HOUSE-001"
+severity = "high"
+message = "Do not exit"
+pattern = { function_call = "std::process::exit" }
+
+[[rule]]
+name = "no_spawn"
+ticket = "HOUSE-002"
+severity = "medium"
+message = "Spawns a process"
+pattern = { module_path = "std::process::Command" }
+"#).unwrap();
+        let mut rules = rules;
+        rules.extend(generate_lint_rules());
+        let hits: Vec<(String, usize)> = check_code_with_rules(code, &rules)
+            .into_iter()
+            .map(|(r, line)| (r.ticket_id, line))
+            .collect();
+
+        assert!(hits.contains(&("UNV-001".to_string(), 7)), "{:?}", hits);
+        assert!(!hits.iter().any(|(t, line)| t == "UNV-001" && *line == 5));
+        assert!(hits.contains(&("HOUSE-001".to_string(), 9)));
+        assert!(hits.contains(&("HOUSE-002".to_string(), 2)));
+        assert!(hits.contains(&("HOUSE-002".to_string(), 8)));
+    }
+
+    #[test]
+    fn test_module_scopes_do_not_leak_uses() {
+        let code = r#"
+use std::process::exit;
+mod inner {
+    fn f() { exit(0); }
+}
+mod glob {
+    use super::*;
+    fn g() { exit(0); }
+}
+"#;
+        let rules = parse_user_rules(r#"
+[[rule]]
+name = "no_exit"
+ticket = "HOUSE-001"
+severity = "low"
+message = "Do not exit"
+pattern = { function_call = "std::process::exit" }
+"#).unwrap();
+        let lines: Vec<usize> = check_code_with_rules(code, &rules).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(lines, vec![8]);
+    }
+
+    #[test]
+    fn test_glob_imports_and_relative_prefixes_resolve() {
+        let code = r#"
+use std::process::*;
+fn a() { exit(1); }
+mod shadowed {
+    use std::process::*;
+    fn exit(_: i32) {}
+    fn b() { exit(1); }
+}
+mod sys {
+    pub use std::process;
+    fn c() { self::process::exit(1); }
+    mod nested {
+        fn d() { super::process::exit(1); }
+    }
+}
+fn e() { crate::sys::process::exit(1); }
+"#;
+        let rules = parse_user_rules(r#"
+[[rule]]
+name = "no_exit"
+ticket = "HOUSE-001"
+severity = "low"
+message = "Do not exit"
+pattern = { function_call = "std::process::exit" }
+"#).unwrap();
+        let lines: Vec<usize> = check_code_with_rules(code, &rules).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(lines, vec![3, 11, 13, 16]);
+    }
+
+    #[test]
+    fn test_macro_invocations_and_literals_in_macros() {
+        let code = r#"fn main() {
+    let v = ca!(program);
+    println!("{}", "/nix/store/abc");
+    log::debug!("x");
+}"#;
+        let mut rules = generate_lint_rules();
+        rules.extend(parse_user_rules(r#"
+[[rule]]
+name = "no_debug_logs"
+ticket = "HOUSE-003"
+severity = "info"
+message = "Debug logging"
+applicability = "machine-applicable"
+pattern = { macro_invocation = "debug!" }
+"#).unwrap());
+        let hits: Vec<(String, usize)> = check_code_with_rules(code, &rules)
+            .into_iter()
+            .map(|(r, line)| (r.ticket_id, line))
+            .collect();
+        assert!(hits.contains(&("PHO-003".to_string(), 2)));
+        assert!(hits.contains(&("CON-002".to_string(), 3)));
+        assert!(hits.contains(&("HOUSE-003".to_string(), 4)));
+    }
+
+    #[test]
+    fn test_user_rule_errors_and_override() {
+        let bad_severity = "[[rule]]\nname = \"a\"\nticket = \"X-1\"\nseverity = \"urgent\"\nmessage = \"m\"\npattern = { regex = \"x\" }\n";
+        assert!(parse_user_rules(bad_severity).unwrap_err().contains("unknown severity"));
+        let bad_regex = "[[rule]]\nname = \"a\"\nticket = \"X-1\"\nseverity = \"low\"\nmessage = \"m\"\npattern = { regex = \"(\" }\n";
+        assert!(parse_user_rules(bad_regex).is_err());
+
+        let path = std::env::temp_dir().join("clippy_rules_override_test.toml");
+        std::fs::write(&path, "[[rule]]\nname = \"hardcoded_path\"\nticket = \"CON-002\"\nseverity = \"critical\"\nmessage = \"No store paths\"\npattern = { string_literal = \"/nix/store\" }\n").unwrap();
+        let rules = lint_rules_with_user_file(Some(&path)).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let matching: Vec<_> = rules.iter().filter(|r| r.name == "hardcoded_path").collect();
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].severity, Severity::Critical);
+        assert_eq!(matching[0].message, "[CON-002] No store paths");
+    }
+
+    #[test]
+    fn test_violation_spans_are_byte_accurate() {
+        let code = "fn main() {\n    let p = \"/nix/store/x\"; let q = verify_morphism(1);\n}\n";
+        let violations = find_violations(code, &generate_lint_rules());
+        let con = violations.iter().find(|v| v.rule.ticket_id == "CON-002").unwrap();
+        assert_eq!(&code[con.span.clone()], "\"/nix/store/x\"");
+        assert_eq!(&code[con.context.clone().unwrap()], "let p = \"/nix/store/x\";");
+        let unv = violations.iter().find(|v| v.rule.ticket_id == "UNV-001").unwrap();
+        assert_eq!(&code[unv.span.clone()], "verify_morphism(1)");
+    }
+
+    #[test]
+    fn test_rustfix_json_schema_and_application() {
+        let code = "use std::process;\n\nfn main() {\n    let hash = \"11111111111111111111111111111111\";\n    if hash.is_empty() { let p = \"/nix/store\"; }\n    let s = \"sample_block_hash\"; let n = 196883;\n}\n";
+        let violations = find_violations(code, &generate_lint_rules());
+        let json = generate_rustfix_json(&violations, code, "src/main.rs");
+
+        let diags: Vec<serde_json::Value> = json.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(diags.len(), violations.len());
+        let keys = ["$message_type", "message", "code", "level", "spans", "children", "rendered"];
+        let span_keys = ["file_name", "byte_start", "byte_end", "line_start", "line_end", "column_start",
+            "column_end", "is_primary", "text", "label", "suggested_replacement", "suggestion_applicability", "expansion"];
+        for d in &diags {
+            assert!(keys.iter().all(|k| d.get(k).is_some()), "{}", d);
+            for span in d["spans"].as_array().unwrap() {
+                assert!(span_keys.iter().all(|k| span.get(k).is_some()), "{}", span);
+            }
+        }
+
+        let fkd = diags.iter().find(|d| d["code"]["code"] == "audit::fake_blockchain_data").unwrap();
+        let primary = &fkd["spans"][0];
+        assert_eq!(primary["is_primary"], true);
+        assert_eq!(primary["line_start"], 4);
+        assert_eq!(primary["column_start"], 16);
+        assert_eq!(primary["column_end"], 50);
+        assert_eq!(primary["text"][0]["highlight_start"], 16);
+        assert_eq!(fkd["spans"][1]["is_primary"], false);
+
+        let fixed = apply_rustfix_json(code, &json).unwrap();
+        assert!(fixed.contains("    // audit_id!(\"FKD-002\", \"fake_blockchain_data\");\n    let hash ="));
+        assert!(fixed.contains("{ // audit_id!(\"CON-002\", \"hardcoded_path\");\n    let p = "));
+
+        // Re-running on fixed code suggests nothing new.
+        let again = generate_rustfix_json(&find_violations(&fixed, &generate_lint_rules()), &fixed, "src/main.rs");
+        assert_eq!(apply_rustfix_json(&fixed, &again).unwrap(), fixed);
+    }
+}
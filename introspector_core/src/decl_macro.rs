@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use introspector_decl_common::{get_all_declarations, DeclInfo};
 
-#[decl(fn, name = "print_declaration_summary", vis = "pub", hash = "5990104a")]
+#[decl(fn, name = "print_declaration_summary", vis = "pub", hash = "17ff283c")]
 pub fn print_declaration_summary() {
     let decls = get_all_declarations();
     let reset = "\x1b[0m";
@@ -50,7 +50,7 @@ pub fn print_declaration_summary() {
     }
 }
 
-#[decl(fn, name = "generate_rdf_declarations", vis = "pub", hash = "aa5f62fe")]
+#[decl(fn, name = "generate_rdf_declarations", vis = "pub", hash = "0ba80709")]
 pub fn generate_rdf_declarations() -> String {
     let decls = get_all_declarations();
     let mut rdf = String::new();
@@ -69,4 +69,32 @@ pub fn generate_rdf_declarations() -> String {
     }
     
     rdf
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    mod shapes {
+        /// A round shape.
+        #[decl(struct, name = "Circle", vis = "pub")]
+        pub struct Circle {
+            pub radius: u32,
+        }
+
+        #[decl(fn, name = "area", vis = "pub")]
+        pub fn area(c: &Circle) -> u32 {
+            3 * c.radius * c.radius
+        }
+    }
+
+    // `decl_of!` resolves to the hidden const, so it is usable in const context.
+    const CIRCLE: introspector_decl_common::ConstDeclMetadata = decl_of!(shapes::Circle);
+    const _: () = assert!(CIRCLE.name_is("Circle") && CIRCLE.kind_is("struct"));
+    const _: () = assert!(decl_of!(shapes::area).kind_is("fn"));
+
+    #[test]
+    fn test_decl_of_reads_item_metadata() {
+        assert_eq!(CIRCLE.doc, Some("A round shape."));
+        assert!(CIRCLE.module.ends_with("decl_macro::tests::shapes"));
+        assert!(decl_of!(shapes::area).signature.contains("area"));
+        assert_eq!(shapes::area(&shapes::Circle { radius: 2 }), 12);
+    }
+}
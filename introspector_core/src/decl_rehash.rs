@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::Path;
+use syn::{parse_file, Attribute, Item};
+use introspector_decl_core::{item_hash, DeclArgs};
+use crate::decl_wrapper::LineIndex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "HashDrift", vis = "pub", hash = "7ce702e6")]
+pub struct HashDrift {
+    pub name: String,
+    pub line: usize,
+    pub declared: String,
+    pub actual: String,
+    /// Byte range of the declared hash literal, quotes included.
+    pub literal_range: (usize, usize),
+}
+
+fn is_decl_attribute(attr: &Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "decl" || seg.ident == "decl2")
+}
+
+fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(i) => &i.attrs,
+        Item::Enum(i) => &i.attrs,
+        Item::ExternCrate(i) => &i.attrs,
+        Item::Fn(i) => &i.attrs,
+        Item::ForeignMod(i) => &i.attrs,
+        Item::Impl(i) => &i.attrs,
+        Item::Macro(i) => &i.attrs,
+        Item::Mod(i) => &i.attrs,
+        Item::Static(i) => &i.attrs,
+        Item::Struct(i) => &i.attrs,
+        Item::Trait(i) => &i.attrs,
+        Item::TraitAlias(i) => &i.attrs,
+        Item::Type(i) => &i.attrs,
+        Item::Union(i) => &i.attrs,
+        Item::Use(i) => &i.attrs,
+        _ => &[],
+    }
+}
+
+fn collect_drift(
+    items: &[Item],
+    index: &LineIndex,
+    drifts: &mut Vec<HashDrift>,
+) {
+    for item in items {
+        if let Item::Mod(m) = item {
+            if let Some((_, nested)) = &m.content {
+                collect_drift(nested, index, drifts);
+            }
+        }
+
+        for attr in item_attrs(item).iter().filter(|a| is_decl_attribute(a)) {
+            let args: DeclArgs = match attr.parse_args() {
+                Ok(args) => args,
+                Err(_) => continue,
+            };
+            let (declared, span) = match (args.hash, args.hash_span) {
+                (Some(declared), Some(span)) => (declared, span),
+                _ => continue,
+            };
+
+            let actual = item_hash(item);
+            if declared == actual {
+                continue;
+            }
+
+            let (start, end) = (span.start(), span.end());
+            let range = index.byte_offset(start.line, start.column)
+                .zip(index.byte_offset(end.line, end.column));
+            if let Some(literal_range) = range {
+                drifts.push(HashDrift {
+                    name: args.name.unwrap_or_else(|| "declaration".to_string()),
+                    line: start.line,
+                    declared,
+                    actual,
+                    literal_range,
+                });
+            }
+        }
+    }
+}
+
+#[decl(fn, name = "find_hash_drift", vis = "pub", hash = "19442266")]
+pub fn find_hash_drift(content: &str) -> Vec<HashDrift> {
+    let ast = match parse_file(content) {
+        Ok(ast) => ast,
+        Err(_) => return vec![],
+    };
+
+    let index = LineIndex::new(content);
+    let mut drifts = Vec::new();
+    collect_drift(&ast.items, &index, &mut drifts);
+    drifts.sort_by_key(|d| d.literal_range.0);
+    drifts
+}
+
+/// Rewrites every stale hash in `content`. An outer item's hash covers the
+/// `#[decl]` attributes of the items nested in it, so fixing an inner hash
+/// stales the outer one; passes repeat until nothing drifts. Each item is
+/// reported once, with its original declared hash and its final one.
+#[decl(fn, name = "rehash_source", vis = "pub", hash = "caaaa4b4")]
+pub fn rehash_source(content: &str) -> (String, Vec<HashDrift>) {
+    let mut updated = content.to_string();
+    let mut reported: Vec<HashDrift> = Vec::new();
+
+    loop {
+        let drifts = find_hash_drift(&updated);
+        if drifts.is_empty() {
+            break;
+        }
+
+        // Replace back to front so earlier byte ranges stay valid.
+        for drift in drifts.iter().rev() {
+            let (start, end) = drift.literal_range;
+            updated.replace_range(start..end, &format!("\"{}\"", drift.actual));
+        }
+
+        for drift in drifts {
+            match reported.iter_mut().find(|d| d.line == drift.line && d.name == drift.name) {
+                Some(seen) => seen.actual = drift.actual,
+                None => reported.push(drift),
+            }
+        }
+    }
+
+    reported.sort_by_key(|d| d.line);
+    (updated, reported)
+}
+
+#[decl(fn, name = "rehash_file", vis = "pub", hash = "b660a381")]
+pub fn rehash_file(path: &Path, dry_run: bool) -> Result<Vec<HashDrift>, std::io::Error> {
+    let content = fs::read_to_string(path)?;
+    let (updated, drifts) = rehash_source(&content);
+
+    if !dry_run && !drifts.is_empty() {
+        fs::write(path, updated)?;
+    }
+
+    Ok(drifts)
+}
+
+#[decl(fn, name = "print_hash_drift", vis = "pub", hash = "3fbfd1a9")]
+pub fn print_hash_drift(path: &Path, drifts: &[HashDrift]) {
+    for drift in drifts {
+        eprintln!(
+            "  {}:{} `{}` {} → {}",
+            path.display(),
+            drift.line,
+            drift.name,
+            drift.declared,
+            drift.actual
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_and_rewrites_stale_hash() {
+        let code = r#"
+#[decl(fn, name = "add", vis = "pub", hash = "00000000")]
+pub fn add(a: u32, b: u32) -> u32 { a + b }
+"#;
+        let (updated, drifts) = rehash_source(code);
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].name, "add");
+        assert_eq!(drifts[0].line, 2);
+        assert!(updated.contains(&format!("hash = \"{}\"", drifts[0].actual)));
+
+        // A second pass finds nothing left to fix.
+        assert!(find_hash_drift(&updated).is_empty());
+    }
+
+    #[test]
+    fn test_nested_module_and_unicode_prefix() {
+        let code = "mod inner {\n    // ünïcödé\n    #[decl2(struct, name = \"S\", hash = \"ffffffff\")] pub struct S;\n}\n";
+        let (updated, drifts) = rehash_source(code);
+        assert_eq!(drifts.len(), 1);
+        assert!(updated.starts_with("mod inner {\n    // ünïcödé\n    #[decl2(struct"));
+        assert!(find_hash_drift(&updated).is_empty());
+    }
+
+    #[test]
+    fn test_nested_decls_converge_in_one_run() {
+        let code = "#[decl(mod, name = \"outer\", hash = \"00000000\")]\nmod outer {\n    #[decl(fn, name = \"f\", hash = \"00000000\")]\n    fn f() {}\n}\n";
+        let (updated, drifts) = rehash_source(code);
+        assert_eq!(drifts.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["outer", "f"]);
+        assert!(drifts.iter().all(|d| d.declared == "00000000"));
+
+        // The outer hash already covers the rewritten inner one.
+        assert!(find_hash_drift(&updated).is_empty());
+        let (again, drifts) = rehash_source(&updated);
+        assert_eq!(again, updated);
+        assert!(drifts.is_empty());
+    }
+
+    #[test]
+    fn test_items_without_hash_are_ignored() {
+        let code = "#[decl(fn, name = \"f\")]\nfn f() {}\n";
+        assert!(find_hash_drift(code).is_empty());
+    }
+}
@@ -1,20 +1,35 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use syn::{
     parse_file, Item, ItemFn, ItemStruct, ItemEnum, ItemTrait, ItemImpl,
     ItemMod, ItemConst, ItemStatic, ItemType, ItemMacro, Visibility,
-    Attribute, Generics, FnArg, ReturnType, Type, Fields,
+    ItemUnion, ItemForeignMod, ForeignItemFn, ForeignItemStatic, ForeignItemType,
+    Attribute, Generics, FnArg, ReturnType, Fields, Signature,
+    ImplItemFn, ImplItemConst, ImplItemType, TraitItemFn, TraitItemConst, TraitItemType,
 };
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
 use quote::ToTokens;
+use introspector_decl_core::{item_hash, tokens_hash, impl_display_name, impl_owner_name};
 
 const DECL_USE_STATEMENT: &str = "use patch_build_rs_macros::decl;";
 
-#[decl(struct, name = "DeclMetadata", vis = "pub", hash = "1b3f6c17")]
+#[derive(Debug, Clone)]
+#[decl(struct, name = "DeclMetadata", vis = "pub", hash = "d2633437")]
 pub struct DeclMetadata {
     pub node_type: NodeType,
     pub name: String,
+    /// `::`-separated path of enclosing modules, types, traits and functions.
+    pub path: String,
+    /// Kind of the enclosing declaration, `None` at file level.
+    pub container: Option<NodeType>,
     pub visibility: VisibilityKind,
+    /// Line (1-based) and column (1-based, in chars) of the item head, after outer attributes.
     pub line: usize,
+    pub column: usize,
+    /// Whole item, outer attributes included.
+    pub span: SourceSpan,
     pub generics: Option<String>,
     pub attributes: Vec<String>,
     pub signature: Option<String>,
@@ -25,7 +40,76 @@ pub struct DeclMetadata {
     pub semantic_hash: String,
 }
 
-#[decl(enum, name = "NodeType", vis = "pub", hash = "e8a169d5")]
+impl DeclMetadata {
+    /// Whether `#[decl]` can be attached in place: items at file or inline
+    /// module level, not associated items of impls or traits.
+    pub fn is_wrappable(&self) -> bool {
+        matches!(self.container, None | Some(NodeType::Module))
+            && self.node_type != NodeType::Module
+    }
+
+    pub fn is_wrapped(&self) -> bool {
+        self.attributes.iter().any(|a| {
+            let a = a.replace(' ', "");
+            a.starts_with("#[decl(") || a.starts_with("#[decl2(")
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[decl(struct, name = "SourceSpan", vis = "pub", hash = "f956774f")]
+pub struct SourceSpan {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Maps proc-macro2 line/column positions (with `span-locations`) back to
+/// byte offsets in the parsed source.
+#[derive(Debug, Clone)]
+#[decl(struct, name = "LineIndex", vis = "pub", hash = "8d81a3e3")]
+pub struct LineIndex<'a> {
+    content: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(content: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { content, line_starts }
+    }
+
+    /// Byte offset of a 1-based line and 0-based char column.
+    pub fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let rest = &self.content[start..];
+        match rest.char_indices().nth(column) {
+            Some((offset, _)) => Some(start + offset),
+            None if rest.chars().count() == column => Some(self.content.len()),
+            None => None,
+        }
+    }
+
+    pub fn span(&self, span: proc_macro2::Span) -> SourceSpan {
+        let (start, end) = (span.start(), span.end());
+        SourceSpan {
+            start_line: start.line,
+            start_column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            start_byte: self.byte_offset(start.line, start.column).unwrap_or(0),
+            end_byte: self.byte_offset(end.line, end.column).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(enum, name = "NodeType", vis = "pub", hash = "f138d1a9")]
 pub enum NodeType {
     Function,
     Struct,
@@ -36,8 +120,14 @@ pub enum NodeType {
     Const,
     Static,
     TypeAlias,
+    /// `type T;` in an `extern` block.
+    ForeignType,
+    /// `type T;` in a trait, or its definition in an impl.
+    AssocType,
     Macro,
     Use,
+    Union,
+    ExternBlock,
 }
 
 impl NodeType {
@@ -52,11 +142,15 @@ impl NodeType {
             NodeType::Const => "const",
             NodeType::Static => "static",
             NodeType::TypeAlias => "type",
+            NodeType::ForeignType => "extern type",
+            NodeType::AssocType => "assoc type",
             NodeType::Macro => "macro",
             NodeType::Use => "use",
+            NodeType::Union => "union",
+            NodeType::ExternBlock => "extern",
         }
     }
-    
+
     pub fn emoji(&self) -> &'static str {
         match self {
             NodeType::Function => "🔧",
@@ -68,14 +162,18 @@ impl NodeType {
             NodeType::Const => "🔒",
             NodeType::Static => "📌",
             NodeType::TypeAlias => "🏷️",
+            NodeType::ForeignType => "🌐",
+            NodeType::AssocType => "🔗",
             NodeType::Macro => "✨",
             NodeType::Use => "📎",
+            NodeType::Union => "🧩",
+            NodeType::ExternBlock => "🔌",
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[decl(enum, name = "VisibilityKind", vis = "pub", hash = "834c6e48")]
+#[decl(enum, name = "VisibilityKind", vis = "pub", hash = "a32de0aa")]
 pub enum VisibilityKind {
     Public,
     Crate,
@@ -97,7 +195,7 @@ impl VisibilityKind {
 }
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "FieldInfo", vis = "pub", hash = "0eb70a2e")]
+#[decl(struct, name = "FieldInfo", vis = "pub", hash = "f5e88911")]
 pub struct FieldInfo {
     pub name: Option<String>,
     pub ty: String,
@@ -105,11 +203,17 @@ pub struct FieldInfo {
 }
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "WrapAction", vis = "pub", hash = "cde2f8bf")]
+#[decl(struct, name = "WrapAction", vis = "pub", hash = "076cd0cf")]
 pub struct WrapAction {
     pub line_start: usize,
     pub line_end: usize,
     pub metadata: DeclMetadata,
+    /// Byte offset of the item head, where `insertion` goes.
+    pub offset: usize,
+    /// The attribute as inserted: on a line of its own above an item that
+    /// starts its line, else inline before it.
+    pub insertion: String,
+    /// The item's line, before and after the insertion.
     pub original: String,
     pub wrapped: String,
 }
@@ -159,58 +263,96 @@ fn generics_to_string(generics: &Generics) -> Option<String> {
     }
 }
 
-fn compute_semantic_hash(content: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    
-    let mut hasher = DefaultHasher::new();
-    content.hash(&mut hasher);
-    format!("{:016x}", hasher.finish())
-}
-
-#[decl(fn, name = "extract_fn_metadata", vis = "pub", hash = "c49987ef")]
-fn extract_fn_metadata(item: &ItemFn, line: usize) -> DeclMetadata {
-    let params: Vec<String> = item.sig.inputs.iter().map(|arg| {
+fn signature_to_string(sig: &Signature) -> String {
+    let params: Vec<String> = sig.inputs.iter().map(|arg| {
         match arg {
             FnArg::Receiver(r) => {
-                if r.mutability.is_some() { "&mut self".to_string() }
+                if r.mutability.is_some() && r.reference.is_some() { "&mut self".to_string() }
                 else if r.reference.is_some() { "&self".to_string() }
                 else { "self".to_string() }
             }
             FnArg::Typed(t) => {
-                format!("{}: {}", 
+                format!("{}: {}",
                     t.pat.to_token_stream(),
                     t.ty.to_token_stream())
             }
         }
     }).collect();
-    
-    let return_type = match &item.sig.output {
+
+    let return_type = match &sig.output {
         ReturnType::Default => "()".to_string(),
         ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
     };
-    
-    let signature = format!("fn {}({}) -> {}", 
-        item.sig.ident, params.join(", "), return_type);
-    
+
+    format!("fn {}({}) -> {}", sig.ident, params.join(", "), return_type)
+}
+
+/// Span of `node` without its outer attributes, i.e. where the item head starts.
+fn head_span<T: Clone + Spanned>(node: &T, strip: impl FnOnce(&mut T)) -> proc_macro2::Span {
+    let mut node = node.clone();
+    strip(&mut node);
+    node.span()
+}
+
+fn hash_without_attrs<T: Clone + ToTokens>(node: &T, strip: impl FnOnce(&mut T)) -> String {
+    let mut node = node.clone();
+    strip(&mut node);
+    tokens_hash(node.to_token_stream())
+}
+
+struct Located {
+    line: usize,
+    column: usize,
+    span: SourceSpan,
+}
+
+/// Fields common to every kind; the extractors below fill in the rest.
+fn base_metadata(
+    node_type: NodeType,
+    name: String,
+    visibility: VisibilityKind,
+    attrs: &[Attribute],
+    located: Located,
+    semantic_hash: String,
+) -> DeclMetadata {
     DeclMetadata {
-        node_type: NodeType::Function,
-        name: item.sig.ident.to_string(),
-        visibility: extract_visibility(&item.vis),
-        line,
-        generics: generics_to_string(&item.sig.generics),
-        attributes: extract_attributes(&item.attrs),
-        signature: Some(signature),
+        node_type,
+        name: name.clone(),
+        path: name,
+        container: None,
+        visibility,
+        line: located.line,
+        column: located.column,
+        span: located.span,
+        generics: None,
+        attributes: extract_attributes(attrs),
+        signature: None,
         fields: vec![],
         variants: vec![],
         methods: vec![],
-        doc: extract_doc_comment(&item.attrs),
-        semantic_hash: compute_semantic_hash(&item.to_token_stream().to_string()),
+        doc: extract_doc_comment(attrs),
+        semantic_hash,
+    }
+}
+
+#[decl(fn, name = "extract_fn_metadata", vis = "pub", hash = "131e33bb")]
+fn extract_fn_metadata(item: &ItemFn, located: Located) -> DeclMetadata {
+    DeclMetadata {
+        generics: generics_to_string(&item.sig.generics),
+        signature: Some(signature_to_string(&item.sig)),
+        ..base_metadata(
+            NodeType::Function,
+            item.sig.ident.to_string(),
+            extract_visibility(&item.vis),
+            &item.attrs,
+            located,
+            item_hash(&Item::Fn(item.clone())),
+        )
     }
 }
 
-#[decl(fn, name = "extract_struct_metadata", vis = "pub", hash = "b8630732")]
-fn extract_struct_metadata(item: &ItemStruct, line: usize) -> DeclMetadata {
+#[decl(fn, name = "extract_struct_metadata", vis = "pub", hash = "c169cc42")]
+fn extract_struct_metadata(item: &ItemStruct, located: Located) -> DeclMetadata {
     let fields: Vec<FieldInfo> = match &item.fields {
         Fields::Named(named) => {
             named.named.iter().map(|f| FieldInfo {
@@ -228,47 +370,43 @@ fn extract_struct_metadata(item: &ItemStruct, line: usize) -> DeclMetadata {
         }
         Fields::Unit => vec![],
     };
-    
+
     DeclMetadata {
-        node_type: NodeType::Struct,
-        name: item.ident.to_string(),
-        visibility: extract_visibility(&item.vis),
-        line,
         generics: generics_to_string(&item.generics),
-        attributes: extract_attributes(&item.attrs),
-        signature: None,
         fields,
-        variants: vec![],
-        methods: vec![],
-        doc: extract_doc_comment(&item.attrs),
-        semantic_hash: compute_semantic_hash(&item.to_token_stream().to_string()),
+        ..base_metadata(
+            NodeType::Struct,
+            item.ident.to_string(),
+            extract_visibility(&item.vis),
+            &item.attrs,
+            located,
+            item_hash(&Item::Struct(item.clone())),
+        )
     }
 }
 
-#[decl(fn, name = "extract_enum_metadata", vis = "pub", hash = "202e7751")]
-fn extract_enum_metadata(item: &ItemEnum, line: usize) -> DeclMetadata {
+#[decl(fn, name = "extract_enum_metadata", vis = "pub", hash = "bea35a63")]
+fn extract_enum_metadata(item: &ItemEnum, located: Located) -> DeclMetadata {
     let variants: Vec<String> = item.variants.iter()
         .map(|v| v.ident.to_string())
         .collect();
-    
+
     DeclMetadata {
-        node_type: NodeType::Enum,
-        name: item.ident.to_string(),
-        visibility: extract_visibility(&item.vis),
-        line,
         generics: generics_to_string(&item.generics),
-        attributes: extract_attributes(&item.attrs),
-        signature: None,
-        fields: vec![],
         variants,
-        methods: vec![],
-        doc: extract_doc_comment(&item.attrs),
-        semantic_hash: compute_semantic_hash(&item.to_token_stream().to_string()),
+        ..base_metadata(
+            NodeType::Enum,
+            item.ident.to_string(),
+            extract_visibility(&item.vis),
+            &item.attrs,
+            located,
+            item_hash(&Item::Enum(item.clone())),
+        )
     }
 }
 
-#[decl(fn, name = "extract_trait_metadata", vis = "pub", hash = "8098c3e8")]
-fn extract_trait_metadata(item: &ItemTrait, line: usize) -> DeclMetadata {
+#[decl(fn, name = "extract_trait_metadata", vis = "pub", hash = "e01969b8")]
+fn extract_trait_metadata(item: &ItemTrait, located: Located) -> DeclMetadata {
     let methods: Vec<String> = item.items.iter()
         .filter_map(|i| {
             if let syn::TraitItem::Fn(m) = i {
@@ -278,35 +416,23 @@ fn extract_trait_metadata(item: &ItemTrait, line: usize) -> DeclMetadata {
             }
         })
         .collect();
-    
+
     DeclMetadata {
-        node_type: NodeType::Trait,
-        name: item.ident.to_string(),
-        visibility: extract_visibility(&item.vis),
-        line,
         generics: generics_to_string(&item.generics),
-        attributes: extract_attributes(&item.attrs),
-        signature: None,
-        fields: vec![],
-        variants: vec![],
         methods,
-        doc: extract_doc_comment(&item.attrs),
-        semantic_hash: compute_semantic_hash(&item.to_token_stream().to_string()),
+        ..base_metadata(
+            NodeType::Trait,
+            item.ident.to_string(),
+            extract_visibility(&item.vis),
+            &item.attrs,
+            located,
+            item_hash(&Item::Trait(item.clone())),
+        )
     }
 }
 
-#[decl(fn, name = "extract_impl_metadata", vis = "pub", hash = "fb4a9840")]
-fn extract_impl_metadata(item: &ItemImpl, line: usize) -> DeclMetadata {
-    let self_ty = item.self_ty.to_token_stream().to_string();
-    let trait_name = item.trait_.as_ref()
-        .map(|(_, path, _)| path.to_token_stream().to_string());
-    
-    let name = if let Some(trait_name) = trait_name {
-        format!("{} for {}", trait_name, self_ty)
-    } else {
-        self_ty
-    };
-    
+#[decl(fn, name = "extract_impl_metadata", vis = "pub", hash = "ea651f31")]
+fn extract_impl_metadata(item: &ItemImpl, located: Located) -> DeclMetadata {
     let methods: Vec<String> = item.items.iter()
         .filter_map(|i| {
             if let syn::ImplItem::Fn(m) = i {
@@ -316,167 +442,304 @@ fn extract_impl_metadata(item: &ItemImpl, line: usize) -> DeclMetadata {
             }
         })
         .collect();
-    
+
     DeclMetadata {
-        node_type: NodeType::Impl,
-        name,
-        visibility: VisibilityKind::Private, // impls don't have visibility
-        line,
         generics: generics_to_string(&item.generics),
-        attributes: extract_attributes(&item.attrs),
-        signature: None,
-        fields: vec![],
-        variants: vec![],
         methods,
         doc: None,
-        semantic_hash: compute_semantic_hash(&item.to_token_stream().to_string()),
+        ..base_metadata(
+            NodeType::Impl,
+            impl_display_name(item),
+            VisibilityKind::Private, // impls don't have visibility
+            &item.attrs,
+            located,
+            item_hash(&Item::Impl(item.clone())),
+        )
     }
 }
 
-pub fn extract_declarations(content: &str) -> Vec<DeclMetadata> {
-    let ast = match parse_file(content) {
-        Ok(ast) => ast,
-        Err(_) => return vec![],
-    };
-    
-    let mut decls = Vec::new();
-    let lines: Vec<&str> = content.lines().collect();
-    
-    for item in &ast.items {
-        let decl = match item {
-            Item::Fn(f) => {
-                let line = find_item_line(&lines, &f.sig.ident.to_string(), "fn ");
-                Some(extract_fn_metadata(f, line))
-            }
-            Item::Struct(s) => {
-                let line = find_item_line(&lines, &s.ident.to_string(), "struct ");
-                Some(extract_struct_metadata(s, line))
-            }
-            Item::Enum(e) => {
-                let line = find_item_line(&lines, &e.ident.to_string(), "enum ");
-                Some(extract_enum_metadata(e, line))
-            }
-            Item::Trait(t) => {
-                let line = find_item_line(&lines, &t.ident.to_string(), "trait ");
-                Some(extract_trait_metadata(t, line))
-            }
-            Item::Impl(i) => {
-                let line = find_item_line(&lines, "impl", "impl ");
-                Some(extract_impl_metadata(i, line))
-            }
-            Item::Mod(m) => {
-                let line = find_item_line(&lines, &m.ident.to_string(), "mod ");
-                Some(DeclMetadata {
-                    node_type: NodeType::Module,
-                    name: m.ident.to_string(),
-                    visibility: extract_visibility(&m.vis),
-                    line,
-                    generics: None,
-                    attributes: extract_attributes(&m.attrs),
-                    signature: None,
-                    fields: vec![],
-                    variants: vec![],
-                    methods: vec![],
-                    doc: extract_doc_comment(&m.attrs),
-                    semantic_hash: compute_semantic_hash(&m.to_token_stream().to_string()),
-                })
-            }
-            Item::Const(c) => {
-                let line = find_item_line(&lines, &c.ident.to_string(), "const ");
-                Some(DeclMetadata {
-                    node_type: NodeType::Const,
-                    name: c.ident.to_string(),
-                    visibility: extract_visibility(&c.vis),
-                    line,
-                    generics: None,
-                    attributes: extract_attributes(&c.attrs),
-                    signature: Some(format!("const {}: {}", c.ident, c.ty.to_token_stream())),
-                    fields: vec![],
-                    variants: vec![],
-                    methods: vec![],
-                    doc: extract_doc_comment(&c.attrs),
-                    semantic_hash: compute_semantic_hash(&c.to_token_stream().to_string()),
-                })
-            }
-            Item::Type(t) => {
-                let line = find_item_line(&lines, &t.ident.to_string(), "type ");
-                Some(DeclMetadata {
-                    node_type: NodeType::TypeAlias,
-                    name: t.ident.to_string(),
-                    visibility: extract_visibility(&t.vis),
-                    line,
-                    generics: generics_to_string(&t.generics),
-                    attributes: extract_attributes(&t.attrs),
-                    signature: Some(format!("type {} = {}", t.ident, t.ty.to_token_stream())),
-                    fields: vec![],
-                    variants: vec![],
-                    methods: vec![],
-                    doc: extract_doc_comment(&t.attrs),
-                    semantic_hash: compute_semantic_hash(&t.to_token_stream().to_string()),
-                })
-            }
-            _ => None,
-        };
-        
-        if let Some(d) = decl {
-            decls.push(d);
+/// Walks a file recursively, tracking the enclosing path so nested modules,
+/// impl and trait members and functions inside functions are all reported.
+struct DeclCollector<'a> {
+    index: LineIndex<'a>,
+    scope: Vec<(String, NodeType, VisibilityKind)>,
+    decls: Vec<DeclMetadata>,
+}
+
+impl<'a> DeclCollector<'a> {
+    fn locate(&self, head: proc_macro2::Span, whole: proc_macro2::Span) -> Located {
+        let head = head.start();
+        Located {
+            line: head.line,
+            column: head.column + 1,
+            span: self.index.span(whole),
+        }
+    }
+
+    fn push(&mut self, mut decl: DeclMetadata) {
+        if let Some((_, kind, _)) = self.scope.last() {
+            decl.container = Some(*kind);
+            let parents: Vec<&str> = self.scope.iter().map(|(name, _, _)| name.as_str()).collect();
+            decl.path = format!("{}::{}", parents.join("::"), decl.name);
+        }
+        self.decls.push(decl);
+    }
+
+    fn scoped(&mut self, name: String, kind: NodeType, vis: VisibilityKind, walk: impl FnOnce(&mut Self)) {
+        self.scope.push((name, kind, vis));
+        walk(self);
+        self.scope.pop();
+    }
+
+    /// Trait members carry the trait's visibility and trait impl members are
+    /// public; everything else has its own.
+    fn member_visibility(&self, vis: &Visibility) -> VisibilityKind {
+        match self.scope.last() {
+            Some((_, NodeType::Trait, trait_vis)) => *trait_vis,
+            Some((_, NodeType::Impl, VisibilityKind::Public)) => VisibilityKind::Public,
+            _ => extract_visibility(vis),
         }
     }
-    
-    decls
+
+    fn push_member_fn(&mut self, sig: &Signature, vis: &Visibility, attrs: &[Attribute], located: Located, hash: String) {
+        let decl = DeclMetadata {
+            generics: generics_to_string(&sig.generics),
+            signature: Some(signature_to_string(sig)),
+            ..base_metadata(NodeType::Function, sig.ident.to_string(), self.member_visibility(vis), attrs, located, hash)
+        };
+        self.push(decl);
+    }
 }
 
-fn find_item_line(lines: &[&str], name: &str, keyword: &str) -> usize {
-    for (i, line) in lines.iter().enumerate() {
-        if line.contains(keyword) && line.contains(name) {
-            return i + 1;
+impl<'ast, 'a> Visit<'ast> for DeclCollector<'a> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = extract_fn_metadata(i, located);
+        let vis = decl.visibility;
+        self.push(decl);
+        self.scoped(i.sig.ident.to_string(), NodeType::Function, vis, |s| visit::visit_item_fn(s, i));
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        self.push(extract_struct_metadata(i, located));
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast ItemEnum) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        self.push(extract_enum_metadata(i, located));
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast ItemTrait) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = extract_trait_metadata(i, located);
+        let vis = decl.visibility;
+        self.push(decl);
+        self.scoped(i.ident.to_string(), NodeType::Trait, vis, |s| visit::visit_item_trait(s, i));
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        self.push(extract_impl_metadata(i, located));
+        // Scope visibility marks trait impls, whose members are public.
+        let vis = if i.trait_.is_some() { VisibilityKind::Public } else { VisibilityKind::Private };
+        self.scoped(impl_owner_name(i), NodeType::Impl, vis, |s| visit::visit_item_impl(s, i));
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = base_metadata(
+            NodeType::Module,
+            i.ident.to_string(),
+            extract_visibility(&i.vis),
+            &i.attrs,
+            located,
+            item_hash(&Item::Mod(i.clone())),
+        );
+        let vis = decl.visibility;
+        self.push(decl);
+        self.scoped(i.ident.to_string(), NodeType::Module, vis, |s| visit::visit_item_mod(s, i));
+    }
+
+    fn visit_item_const(&mut self, i: &'ast ItemConst) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("const {}: {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::Const, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, item_hash(&Item::Const(i.clone())))
+        };
+        self.push(decl);
+        visit::visit_item_const(self, i);
+    }
+
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("static {}: {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::Static, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, item_hash(&Item::Static(i.clone())))
+        };
+        self.push(decl);
+        visit::visit_item_static(self, i);
+    }
+
+    fn visit_item_type(&mut self, i: &'ast ItemType) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            generics: generics_to_string(&i.generics),
+            signature: Some(format!("type {} = {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::TypeAlias, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, item_hash(&Item::Type(i.clone())))
+        };
+        self.push(decl);
+    }
+
+    fn visit_item_macro(&mut self, i: &'ast ItemMacro) {
+        // Only `macro_rules!` definitions have a name; invocations are skipped.
+        if let Some(ident) = &i.ident {
+            let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+            let vis = if i.attrs.iter().any(|a| a.path().is_ident("macro_export")) {
+                VisibilityKind::Public
+            } else {
+                VisibilityKind::Private
+            };
+            let decl = base_metadata(NodeType::Macro, ident.to_string(), vis, &i.attrs, located, item_hash(&Item::Macro(i.clone())));
+            self.push(decl);
         }
     }
-    1
+
+    fn visit_item_union(&mut self, i: &'ast ItemUnion) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let fields = i.fields.named.iter().map(|f| FieldInfo {
+            name: f.ident.as_ref().map(|i| i.to_string()),
+            ty: f.ty.to_token_stream().to_string(),
+            visibility: extract_visibility(&f.vis),
+        }).collect();
+        let decl = DeclMetadata {
+            generics: generics_to_string(&i.generics),
+            fields,
+            ..base_metadata(NodeType::Union, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, item_hash(&Item::Union(i.clone())))
+        };
+        self.push(decl);
+    }
+
+    fn visit_item_foreign_mod(&mut self, i: &'ast ItemForeignMod) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let abi = i.abi.name.as_ref().map(|n| n.value()).unwrap_or_else(|| "C".to_string());
+        let name = format!("extern \"{}\"", abi);
+        // Foreign items are wrapped together with their block, so they are
+        // reported at the block's level rather than inside it.
+        let decl = base_metadata(NodeType::ExternBlock, name, VisibilityKind::Private, &i.attrs, located, item_hash(&Item::ForeignMod(i.clone())));
+        self.push(decl);
+        self.scoped(format!("extern_{}", abi), NodeType::ExternBlock, VisibilityKind::Private, |s| visit::visit_item_foreign_mod(s, i));
+    }
+
+    fn visit_foreign_item_fn(&mut self, i: &'ast ForeignItemFn) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let hash = hash_without_attrs(i, |n| n.attrs.clear());
+        self.push_member_fn(&i.sig, &i.vis, &i.attrs, located, hash);
+    }
+
+    fn visit_foreign_item_static(&mut self, i: &'ast ForeignItemStatic) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("static {}: {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::Static, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()))
+        };
+        self.push(decl);
+    }
+
+    fn visit_foreign_item_type(&mut self, i: &'ast ForeignItemType) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = base_metadata(NodeType::ForeignType, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()));
+        self.push(decl);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let hash = hash_without_attrs(i, |n| n.attrs.clear());
+        self.push_member_fn(&i.sig, &i.vis, &i.attrs, located, hash);
+        let vis = self.member_visibility(&i.vis);
+        self.scoped(i.sig.ident.to_string(), NodeType::Function, vis, |s| visit::visit_impl_item_fn(s, i));
+    }
+
+    fn visit_impl_item_const(&mut self, i: &'ast ImplItemConst) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("const {}: {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::Const, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()))
+        };
+        self.push(decl);
+    }
+
+    fn visit_impl_item_type(&mut self, i: &'ast ImplItemType) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("type {} = {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::AssocType, i.ident.to_string(), extract_visibility(&i.vis), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()))
+        };
+        self.push(decl);
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let hash = hash_without_attrs(i, |n| n.attrs.clear());
+        self.push_member_fn(&i.sig, &Visibility::Inherited, &i.attrs, located, hash);
+        let vis = self.member_visibility(&Visibility::Inherited);
+        self.scoped(i.sig.ident.to_string(), NodeType::Function, vis, |s| visit::visit_trait_item_fn(s, i));
+    }
+
+    fn visit_trait_item_const(&mut self, i: &'ast TraitItemConst) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            signature: Some(format!("const {}: {}", i.ident, i.ty.to_token_stream())),
+            ..base_metadata(NodeType::Const, i.ident.to_string(), self.member_visibility(&Visibility::Inherited), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()))
+        };
+        self.push(decl);
+    }
+
+    fn visit_trait_item_type(&mut self, i: &'ast TraitItemType) {
+        let located = self.locate(head_span(i, |n| n.attrs.clear()), i.span());
+        let decl = DeclMetadata {
+            generics: generics_to_string(&i.generics),
+            ..base_metadata(NodeType::AssocType, i.ident.to_string(), self.member_visibility(&Visibility::Inherited), &i.attrs, located, hash_without_attrs(i, |n| n.attrs.clear()))
+        };
+        self.push(decl);
+    }
 }
 
-pub fn generate_decl_wrapper(metadata: &DeclMetadata) -> String {
-    let fields_json = if metadata.fields.is_empty() {
-        "[]".to_string()
-    } else {
-        let fields: Vec<String> = metadata.fields.iter()
-            .map(|f| format!("{{\"name\":\"{{}}\",\"ty\":\"{{}}\"}}", 
-                f.name.as_deref().unwrap_or("_"), 
-                f.ty.replace("\"", "\\\"")))
-            .collect();
-        format!("[{}]", fields.join(','))
-    };
-    
-    let variants_json = if metadata.variants.is_empty() {
-        "[]".to_string()
-    } else {
-        format!("[{}]", metadata.variants.iter()
-            .map(|v| format!("\"{{}}\"", v))
-            .collect::<Vec<_>>()
-            .join(','))
+pub fn extract_declarations(content: &str) -> Vec<DeclMetadata> {
+    let ast = match parse_file(content) {
+        Ok(ast) => ast,
+        Err(_) => return vec![],
     };
-    
-    let methods_json = if metadata.methods.is_empty() {
-        "[]".to_string()
-    } else {
-        format!("[{}]", metadata.methods.iter()
-            .map(|m| format!("\"{{}}\"", m))
-            .collect::<Vec<_>>()
-            .join(','))
+
+    let mut collector = DeclCollector {
+        index: LineIndex::new(content),
+        scope: Vec::new(),
+        decls: Vec::new(),
     };
-    
+    collector.visit_file(&ast);
+    collector.decls
+}
+
+pub fn generate_decl_wrapper(metadata: &DeclMetadata) -> String {
+    let fields: Vec<serde_json::Value> = metadata.fields.iter()
+        .map(|f| serde_json::json!({
+            "name": f.name.as_deref().unwrap_or("_"),
+            "ty": f.ty,
+        }))
+        .collect();
+
     format!(
-        r###"decl! {{ 
-    node_type: \"{{}}\",
-    name: \"{{}}\",
-    visibility: \"{{}}\",
-    line: {{}},
-    generics: {{:?}},
-    hash: \"{{}}\",
-    fields: {{}},
-    variants: {{}},
-    methods: {{}},
-    doc: {{:?}},
+        r###"decl! {{
+    node_type: "{}",
+    name: "{}",
+    visibility: "{}",
+    line: {},
+    generics: {:?},
+    hash: "{}",
+    fields: {},
+    variants: {},
+    methods: {},
+    doc: {:?},
 }}"###,
         metadata.node_type.as_str(),
         metadata.name,
@@ -484,84 +747,109 @@ pub fn generate_decl_wrapper(metadata: &DeclMetadata) -> String {
         metadata.line,
         metadata.generics,
         metadata.semantic_hash,
-        fields_json,
-        variants_json,
-        methods_json,
+        serde_json::Value::from(fields),
+        serde_json::json!(metadata.variants),
+        serde_json::json!(metadata.methods),
         metadata.doc,
     )
 }
 
 pub fn generate_decl_attribute(metadata: &DeclMetadata) -> String {
     format!(
-        "#[decl({{}}), name = \"{{}}\", vis = \"{{}}\", hash = \"{{}}\"",
+        "#[decl({}, name = {:?}, vis = \"{}\", hash = \"{}\")]",
         metadata.node_type.as_str(),
         metadata.name,
         metadata.visibility.as_str(),
-        &metadata.semantic_hash[..8],
+        metadata.semantic_hash,
     )
 }
 
 pub fn generate_inventory_registration(metadata: &DeclMetadata) -> String {
     format!(
-        r###"inventory::submit! {{ 
-    DeclInfo {{ 
-        node_type: NodeType::{:?},
-        name: \"{{}}\",
+        r###"introspector_decl_common::inventory::submit! {{
+    introspector_decl_common::DeclInfo {{
+        node_type: "{}",
+        name: "{}",
+        visibility: "{}",
         module: module_path!(),
         file: file!(),
-        line: {{}},
-        hash: \"{{}}\",
+        line: {},
+        hash: "{}",
     }}
 }}"###,
-        metadata.node_type,
+        metadata.node_type.as_str(),
         metadata.name,
+        metadata.visibility.as_str(),
         metadata.line,
-        &metadata.semantic_hash[..16],
+        metadata.semantic_hash,
     )
 }
 
 pub fn wrap_public_declarations(content: &str) -> Vec<WrapAction> {
     let decls = extract_declarations(content);
-    let lines: Vec<&str> = content.lines().collect();
-    
+    let index = LineIndex::new(content);
+
     let mut actions = Vec::new();
-    
+
     for decl in decls {
-        // Only wrap public declarations and skip module declarations for now
-        if decl.visibility != VisibilityKind::Public || decl.node_type == NodeType::Module {
+        // Only wrap public items and impl/extern blocks (which register their
+        // members) that can carry an attribute in place
+        let registers_members = matches!(decl.node_type, NodeType::Impl | NodeType::ExternBlock);
+        if (decl.visibility != VisibilityKind::Public && !registers_members)
+            || !decl.is_wrappable()
+            || decl.is_wrapped()
+        {
             continue;
         }
-        
-        let line_idx = decl.line.saturating_sub(1);
-        if line_idx >= lines.len() {
-            continue;
-        }
-        
-        let original = lines[line_idx].to_string();
-        
-        // Skip if already wrapped
-        if original.contains("#[decl(") || original.contains("decl!") {
+
+        let Some(offset) = index.byte_offset(decl.line, decl.column.saturating_sub(1)) else {
             continue;
-        }
-        
+        };
+        let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = content[offset..].find('\n').map_or(content.len(), |i| offset + i);
+        let before = &content[line_start..offset];
+
         let attr = generate_decl_attribute(&decl);
-        let wrapped = format!("{}\n{{}}", attr, original);
-        
+        let insertion = if before.trim().is_empty() {
+            format!("{}\n{}", attr, before)
+        } else {
+            format!("{} ", attr)
+        };
+        let original = content[line_start..line_end].to_string();
+        let wrapped = format!("{}{}{}", before, insertion, &content[offset..line_end]);
+
         actions.push(WrapAction {
             line_start: decl.line,
             line_end: decl.line,
             metadata: decl,
+            offset,
+            insertion,
             original,
             wrapped,
         });
     }
-    
+
     actions
 }
 
+/// `content` with the attribute of every action inserted at its offset.
+pub(crate) fn insert_wrap_actions(content: &str, actions: &[WrapAction]) -> String {
+    let by_offset: BTreeMap<usize, &WrapAction> = actions.iter().map(|a| (a.offset, a)).collect();
+    let mut output = String::with_capacity(content.len() + actions.iter().map(|a| a.insertion.len()).sum::<usize>());
+    let mut copied = 0;
+    for (offset, action) in by_offset {
+        output.push_str(&content[copied..offset]);
+        output.push_str(&action.insertion);
+        copied = offset;
+    }
+    output.push_str(&content[copied..]);
+    output
+}
+
 pub fn apply_decl_wrappers(path: &Path) -> Result<usize, std::io::Error> {
     let mut content = fs::read_to_string(path)?;
-    
+    let trailing_newline = content.ends_with('\n');
+
     // Check if the use statement is present
     if !content.contains(DECL_USE_STATEMENT) {
         let mut lines: Vec<String> = content.lines().map(String::from).collect();
@@ -576,7 +864,7 @@ pub fn apply_decl_wrappers(path: &Path) -> Result<usize, std::io::Error> {
             if line.trim().starts_with("//") || line.trim().is_empty() || line.trim().starts_with("#[") {
                 continue;
             }
-            
+
             // Insert before the first non-comment, non-empty, non-attribute line
             lines.insert(i, DECL_USE_STATEMENT.to_string());
             lines.insert(i + 1, "".to_string()); // Add a newline for formatting
@@ -592,110 +880,103 @@ pub fn apply_decl_wrappers(path: &Path) -> Result<usize, std::io::Error> {
     }
 
     let actions = wrap_public_declarations(&content); // Pass the potentially modified content
-    
+
     if actions.is_empty() {
         return Ok(0);
     }
-    
-    let lines: Vec<String> = content.lines().map(String::from).collect(); // Re-read lines from modified content
-    let mut result = Vec::new();
-    let mut declarations_added = 0;
-    
-    let action_map: std::collections::HashMap<usize, &WrapAction> = 
-        actions.iter().map(|a| (a.line_start, a)).collect();
-    
-    for (i, line) in lines.iter().enumerate() {
-        let line_num = i + 1;
-        if let Some(action) = action_map.get(&line_num) {
-            result.push(action.wrapped.clone());
-            declarations_added += 1;
-        } else {
-            result.push(line.to_string());
-        }
+
+    let mut output = insert_wrap_actions(&content, &actions);
+    if trailing_newline && !output.ends_with('\n') {
+        output.push('\n');
     }
-    
-    fs::write(path, result.join("\n"))?;
-    Ok(declarations_added)
+    fs::write(path, output)?;
+    Ok(actions.len())
 }
 
 pub fn preview_decl_wrappers(path: &Path) -> String {
     let content = fs::read_to_string(path).unwrap_or_default();
     let actions = wrap_public_declarations(&content);
-    
+
     let mut output = String::new();
-    output.push_str(&format!("📁 File: {{}}\n", path.display()));
+    output.push_str(&format!("📁 File: {}\n", path.display()));
     output.push_str(&format!("🔍 Found {} public declarations to wrap\n\n", actions.len()));
-    
+
     for action in &actions {
         output.push_str(&format!(
-            "┌─ Line {{}}: {{}} {{}} `{{}}`\n",
+            "┌─ Line {}:{}: {} {} `{}`\n",
             action.line_start,
+            action.metadata.column,
             action.metadata.node_type.emoji(),
             action.metadata.node_type.as_str(),
-            action.metadata.name
+            action.metadata.path
+        ));
+        output.push_str(&format!("│ Hash: {}\n", action.metadata.semantic_hash));
+        output.push_str(&format!(
+            "│ Bytes: {}..{}\n",
+            action.metadata.span.start_byte, action.metadata.span.end_byte
         ));
-        output.push_str(&format!("│ Hash: {{}}\n", &action.metadata.semantic_hash[..16]));
         if !action.metadata.fields.is_empty() {
-            output.push_str(&format!("│ Fields: {{}}\n", action.metadata.fields.len()));
+            output.push_str(&format!("│ Fields: {}\n", action.metadata.fields.len()));
         }
         if !action.metadata.methods.is_empty() {
-            output.push_str(&format!("│ Methods: {{:?}}\n", action.metadata.methods));
+            output.push_str(&format!("│ Methods: {:?}\n", action.metadata.methods));
         }
-        output.push_str(&format!("│ Before: {{}}\n", action.original.trim()));
-        output.push_str(&format!("│ After:\n"));
+        output.push_str(&format!("│ Before: {}\n", action.original.trim()));
+        output.push_str("│ After:\n");
         for line in action.wrapped.lines() {
-            output.push_str(&format!("│   {{}}\n", line));
+            output.push_str(&format!("│   {}\n", line));
         }
         output.push_str("└────────────────────────────────────────\n\n");
     }
-    
+
     output
 }
 
 pub fn generate_declarations_json(path: &Path) -> String {
     let content = fs::read_to_string(path).unwrap_or_default();
     let decls = extract_declarations(&content);
-    
-    let items: Vec<String> = decls.iter().map(|d| {
-        format!(
-            r###"  {{ 
-    \"type\": \"{{}}\",
-    \"name\": \"{{}}\",
-    \"visibility\": \"{{}}\",
-    \"line\": {},
-    \"hash\": \"{{}}\",
-    \"fields\": {},
-    \"variants\": {},
-    \"methods\": {}
-  }}"###,
-            d.node_type.as_str(),
-            d.name,
-            d.visibility.as_str(),
-            d.line,
-            d.semantic_hash,
-            d.fields.len(),
-            d.variants.len(),
-            d.methods.len(),
-        )
+
+    let items: Vec<serde_json::Value> = decls.iter().map(|d| {
+        serde_json::json!({
+            "type": d.node_type.as_str(),
+            "name": d.name,
+            "path": d.path,
+            "container": d.container.map(|c| c.as_str()),
+            "visibility": d.visibility.as_str(),
+            "line": d.line,
+            "column": d.column,
+            "span": {
+                "start_line": d.span.start_line,
+                "start_column": d.span.start_column,
+                "end_line": d.span.end_line,
+                "end_column": d.span.end_column,
+                "start_byte": d.span.start_byte,
+                "end_byte": d.span.end_byte,
+            },
+            "hash": d.semantic_hash,
+            "signature": d.signature,
+            "fields": d.fields.len(),
+            "variants": d.variants.len(),
+            "methods": d.methods.len(),
+        })
     }).collect();
-    
-    format!("[\n{}
-]", items.join(",\n"))
+
+    serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string())
 }
 
 #[macro_export]
 macro_rules! wrap_decls {
-    ($path:expr) => {{ 
+    ($path:expr) => {{
         let path = std::path::Path::new($path);
         let preview = $crate::decl_wrapper::preview_decl_wrappers(path);
-        eprintln!("{{}}", preview);
-    }}; 
-    
-    ($path:expr, apply) => {{ 
+        eprintln!("{}", preview);
+    }};
+
+    ($path:expr, apply) => {{
         let path = std::path::Path::new($path);
-        match $crate::decl_wrapper::apply_decl_wrappers(path) {{
-            Ok(count) => eprintln!("✅ Wrapped {{}} declarations in {{}}", count, $path),
-            Err(e) => eprintln!("❌ Error: {{}}", e),
+        match $crate::decl_wrapper::apply_decl_wrappers(path) {
+            Ok(count) => eprintln!("✅ Wrapped {} declarations in {}", count, $path),
+            Err(e) => eprintln!("❌ Error: {}", e),
         }
     }};
 }
@@ -706,9 +987,9 @@ mod tests {
 
     #[test]
     fn test_extract_function() {
-        let code = r###" 
+        let code = r###"
 pub fn hello_world(name: &str) -> String {
-    format!("Hello, {{}}", name)
+    format!("Hello, {}", name)
 }
 "###;
         let decls = extract_declarations(code);
@@ -720,7 +1001,7 @@ pub fn hello_world(name: &str) -> String {
 
     #[test]
     fn test_extract_struct() {
-        let code = r###" 
+        let code = r###"
 pub struct User {
     pub name: String,
     pub age: u32,
@@ -734,7 +1015,7 @@ pub struct User {
 
     #[test]
     fn test_extract_enum() {
-        let code = r###" 
+        let code = r###"
 pub enum Status {
     Active,
     Inactive,
@@ -747,13 +1028,207 @@ pub enum Status {
         assert_eq!(decls[0].variants.len(), 3);
     }
 
+    #[test]
+    fn test_nested_declarations_and_paths() {
+        let code = r###"
+pub mod outer {
+    pub struct Widget;
+
+    impl Widget {
+        pub fn new() -> Self {
+            fn helper() {}
+            helper();
+            Widget
+        }
+        const SIZE: usize = 4;
+    }
+
+    pub trait Render {
+        fn render(&self);
+    }
+}
+"###;
+        let decls = extract_declarations(code);
+        let paths: Vec<(&str, &str)> = decls.iter()
+            .map(|d| (d.node_type.as_str(), d.path.as_str()))
+            .collect();
+        assert_eq!(paths, vec![
+            ("mod", "outer"),
+            ("struct", "outer::Widget"),
+            ("impl", "outer::Widget"),
+            ("fn", "outer::Widget::new"),
+            ("fn", "outer::Widget::new::helper"),
+            ("const", "outer::Widget::SIZE"),
+            ("trait", "outer::Render"),
+            ("fn", "outer::Render::render"),
+        ]);
+
+        let render = decls.iter().find(|d| d.name == "render").unwrap();
+        assert_eq!(render.visibility, VisibilityKind::Public);
+        assert_eq!(render.container, Some(NodeType::Trait));
+        assert!(!render.is_wrappable());
+        assert!(decls[1].is_wrappable());
+    }
+
+    #[test]
+    fn test_repeated_names_get_their_own_lines() {
+        // Text search used to report line 2 for both `new`s.
+        let code = "// new\nimpl A {\n    pub fn new() {}\n}\nimpl B {\n    pub fn new() {}\n}\n";
+        let lines: Vec<usize> = extract_declarations(code).iter()
+            .filter(|d| d.name == "new")
+            .map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![3, 6]);
+    }
+
+    #[test]
+    fn test_span_byte_range_and_columns() {
+        let code = "/// Docs\n#[inline]\npub fn é() {}\nmod m { pub const X: u8 = 1; }\n";
+        let decls = extract_declarations(code);
+
+        let f = &decls[0];
+        assert_eq!((f.line, f.column), (3, 1));
+        assert_eq!((f.span.start_line, f.span.end_line), (1, 3));
+        assert_eq!(&code[f.span.start_byte..f.span.end_byte], "/// Docs\n#[inline]\npub fn é() {}");
+
+        let x = decls.iter().find(|d| d.name == "X").unwrap();
+        assert_eq!((x.line, x.column), (4, 9));
+        assert_eq!(&code[x.span.start_byte..x.span.end_byte], "pub const X: u8 = 1;");
+    }
+
+    #[test]
+    fn test_wrap_skips_already_wrapped_and_members() {
+        let code = r###"
+/// Already registered.
+#[decl(fn, name = "done", vis = "pub", hash = "00000000")]
+pub fn done() {}
+
+pub struct Fresh;
+
+impl Fresh {
+    pub fn method(&self) {}
+}
+"###;
+        let actions = wrap_public_declarations(code);
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].metadata.name, "Fresh");
+        assert!(actions[0].wrapped.starts_with("#[decl(struct, name = \"Fresh\""));
+        // The impl block is wrapped as a whole; `method` is registered through it.
+        assert!(actions[1].wrapped.starts_with("#[decl(impl, name = \"Fresh\""));
+    }
+
+    #[test]
+    fn test_wrap_inserts_at_the_item_not_its_line() {
+        let code = "mod m { pub const X: u8 = 1; }\npub const A: u8 = 1; pub const B: u8 = 2;\n    pub fn f() {}\n";
+        let actions = wrap_public_declarations(code);
+        assert_eq!(actions.iter().map(|a| a.metadata.name.as_str()).collect::<Vec<_>>(), vec!["X", "A", "B", "f"]);
+
+        let wrapped = insert_wrap_actions(code, &actions);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert!(lines[0].starts_with("mod m { #[decl(const, name = \"X\""), "{}", lines[0]);
+        assert!(lines[1].starts_with("#[decl(const, name = \"A\""));
+        assert!(lines[2].starts_with("pub const A: u8 = 1; #[decl(const, name = \"B\""), "{}", lines[2]);
+        assert!(lines[2].ends_with("\")] pub const B: u8 = 2;"), "{}", lines[2]);
+        assert!(lines[3].starts_with("    #[decl(fn, name = \"f\""));
+        assert_eq!(lines[4], "    pub fn f() {}");
+        // The wrapped source parses and nothing is left to wrap.
+        syn::parse_file(&wrapped).unwrap();
+        assert!(wrap_public_declarations(&wrapped).is_empty());
+    }
+
+    #[test]
+    fn test_union_extern_and_macro_kinds() {
+        let code = r###"
+pub union Bits { pub i: u32, f: f32 }
+extern "C" {
+    pub fn abs(x: i32) -> i32;
+}
+#[macro_export]
+macro_rules! square { ($x:expr) => { $x * $x }; }
+impl Default for Bits {
+    fn default() -> Self { Bits { i: 0 } }
+}
+"###;
+        let decls = extract_declarations(code);
+        let kinds: Vec<(NodeType, &str, VisibilityKind)> = decls.iter()
+            .map(|d| (d.node_type, d.path.as_str(), d.visibility))
+            .collect();
+        assert_eq!(kinds, vec![
+            (NodeType::Union, "Bits", VisibilityKind::Public),
+            (NodeType::ExternBlock, "extern \"C\"", VisibilityKind::Private),
+            (NodeType::Function, "extern_C::abs", VisibilityKind::Public),
+            (NodeType::Macro, "square", VisibilityKind::Public),
+            (NodeType::Impl, "Default for Bits", VisibilityKind::Private),
+            (NodeType::Function, "Bits::default", VisibilityKind::Public),
+        ]);
+        assert_eq!(decls[0].fields.len(), 2);
+
+        let attr = generate_decl_attribute(&decls[1]);
+        assert!(attr.starts_with("#[decl(extern, name = \"extern \\\"C\\\"\""));
+        assert_eq!(wrap_public_declarations(code).len(), 4);
+    }
+
+    #[test]
+    fn test_foreign_and_associated_types_are_not_aliases() {
+        let code = r###"
+pub type Alias = u32;
+extern "C" {
+    pub type Opaque;
+}
+pub trait Shape {
+    type Unit;
+}
+impl Shape for Alias {
+    type Unit = f64;
+}
+"###;
+        let kinds: Vec<(NodeType, String)> = extract_declarations(code).into_iter()
+            .filter(|d| d.name != "Shape" && d.node_type != NodeType::Impl && d.node_type != NodeType::ExternBlock)
+            .map(|d| (d.node_type, d.path))
+            .collect();
+        assert_eq!(kinds, vec![
+            (NodeType::TypeAlias, "Alias".to_string()),
+            (NodeType::ForeignType, "extern_C::Opaque".to_string()),
+            (NodeType::AssocType, "Shape::Unit".to_string()),
+            (NodeType::AssocType, "Alias::Unit".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_generated_attribute_hash_matches_decl_macro() {
+        let code = "pub fn f(x: u32) -> u32 { x }\n";
+        let decl = &extract_declarations(code)[0];
+        let item: Item = syn::parse_str(code).unwrap();
+        assert_eq!(decl.semantic_hash, item_hash(&item));
+    }
+
+    #[test]
+    fn test_declarations_json_is_valid() {
+        let dir = std::env::temp_dir().join(format!("decl_json_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("sample.rs");
+        fs::write(&file, "pub struct \"Quoted\";\n").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&generate_declarations_json(&file)).unwrap();
+        assert!(json.as_array().unwrap().is_empty());
+
+        fs::write(&file, "mod a { pub fn b() {} }\n").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&generate_declarations_json(&file)).unwrap();
+        assert_eq!(json[1]["path"], "a::b");
+        assert_eq!(json[1]["span"]["start_byte"], 8);
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_generate_attribute() {
         let metadata = DeclMetadata {
             node_type: NodeType::Function,
             name: "test_fn".to_string(),
+            path: "test_fn".to_string(),
+            container: None,
             visibility: VisibilityKind::Public,
             line: 10,
+            column: 1,
+            span: SourceSpan::default(),
             generics: None,
             attributes: vec![],
             signature: Some("fn test_fn() -> ()".to_string()),
@@ -761,11 +1236,12 @@ pub enum Status {
             variants: vec![],
             methods: vec![],
             doc: None,
-            semantic_hash: "abc123def456".to_string(),
+            semantic_hash: "abc123de".to_string(),
         };
-        
+
         let attr = generate_decl_attribute(&metadata);
         assert!(attr.contains("#[decl(fn"));
         assert!(attr.contains("test_fn"));
+        assert!(attr.ends_with("hash = \"abc123de\")]"));
     }
 }
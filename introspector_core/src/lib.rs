@@ -1,11 +1,24 @@
+// `#[decl]` on items throughout the crate.
+#[macro_use]
+extern crate patch_build_rs_macros;
+
 pub mod audit_macros;
 pub mod audit_tickets;
 pub mod auto_fix;
 pub mod clippy_rules;
+pub mod sarif;
+pub mod baseline;
+pub mod capabilities;
 pub mod decl_macro;
 pub mod decl_wrapper;
+pub mod decl_rehash;
+pub mod api_snapshot;
 pub mod macro_report;
 pub mod nix_rustc;
+pub mod unified_diff;
+pub mod toolchain_cache;
+pub mod rustc_trace;
+pub mod rust_in_nix;
 pub mod expr;
 pub mod pureprogram;
 pub mod new_quote_trait;
@@ -18,7 +31,10 @@ pub use audit_macros::{
     get_audit_stats, get_audits_by_kind, clear_audits,
 };
 pub use audit_tickets::{
-    AuditTicket, TicketCategory, Severity, TICKET_REGISTRY,
+    AuditTicket, TicketCategory, CustomCategory, Severity, TICKET_REGISTRY,
+    TICKET_DIR, TICKET_DIR_ENV, ticket_dir, load_ticket_sources, load_ticket_dir, merge_ticket_dir,
+    DocumentedTicket, TicketDivergence, TICKETS_MD_BEGIN, TICKETS_MD_END,
+    generate_tickets_markdown, parse_tickets_markdown, ticket_divergence, sync_tickets_markdown,
     get_ticket, get_all_tickets, get_tickets_by_category,
     get_tickets_by_module, get_tickets_by_severity,
     print_ticket, print_all_tickets, generate_clippy_toml,
@@ -26,37 +42,81 @@ pub use audit_tickets::{
 pub use clippy_rules::{
     ClippyRule, LintPattern, Applicability,
     generate_lint_rules, generate_suggested_fixes_report,
-    check_code_for_violations, print_violation, generate_rustfix_json,
+    LintViolation, find_violations, apply_rustfix_json,
+    check_code_for_violations, check_code_with_rules, parse_user_rules, load_user_rules,
+    lint_rules_with_user_file, print_violation, generate_rustfix_json, USER_RULES_FILE,
+};
+pub use sarif::{
+    SARIF_VERSION, SARIF_SCHEMA, SRCROOT, FINGERPRINT_KEY,
+    sarif_level, scan_results_to_sarif, add_invalid_suppressions,
+};
+pub use baseline::{
+    BASELINE_FILE, BASELINE_VERSION, Baseline, BaselineEntry, Finding, Suppression, InvalidSuppression,
+    ScanTriage, normalize_snippet, finding_fingerprint, findings_for_fixes, parse_suppressions,
+    triage_source, triage_directory, print_triage,
+};
+pub use capabilities::{
+    CapabilityKind, Justification, CapabilityUse, CapabilityInventory,
+    scan_source_capabilities, scan_capabilities, workspace_capabilities,
+    crates_from_metadata, dependency_crates, print_capability_inventory,
 };
 pub use auto_fix::{
     FixAction, FixPattern, FixType,
-    get_fix_patterns, scan_file_for_fixes, apply_fixes_to_file,
+    get_fix_patterns, scan_source_for_fixes, scan_file_for_fixes,
+    apply_fixes_to_source, apply_fixes_to_file,
     apply_fixes_to_file_in_place, preview_fixes, generate_diff,
     scan_directory, print_scan_summary, apply_all_fixes,
 };
 pub use introspector_decl_common::{
-    DeclInfo, DeclRegistry, DECL_REGISTRY,
-    register_decl, get_all_declarations, get_declarations_by_type,
-    get_declarations_by_module, get_declaration_by_hash,
+    DeclInfo, ConstDeclMetadata, DeclRegistry, DeclQuery, DECL_REGISTRY,
+    all_decls, get_all_declarations, get_declarations_by_name, get_declarations_by_type,
+    get_declarations_by_module, get_declarations_by_visibility, get_declaration_by_hash,
 };
 pub use decl_macro::{
     print_declaration_summary, generate_rdf_declarations,
 };
 pub use decl_wrapper::{
-    DeclMetadata, NodeType, VisibilityKind, FieldInfo, WrapAction,
+    DeclMetadata, NodeType, VisibilityKind, FieldInfo, WrapAction, SourceSpan, LineIndex,
     extract_declarations, generate_decl_wrapper, generate_decl_attribute,
     generate_inventory_registration, wrap_public_declarations,
     apply_decl_wrappers, preview_decl_wrappers, generate_declarations_json,
 };
+pub use decl_rehash::{
+    HashDrift, find_hash_drift, rehash_source, rehash_file, print_hash_drift,
+};
+pub use api_snapshot::{
+    ApiItem, ApiSnapshot, ApiChange, ApiDiff, SemverImpact,
+    snapshot_source, snapshot_path, diff_snapshots, print_api_diff,
+};
 pub use nix_rustc::{
-    RustcNixInfo, RustcSourceStats,
-    find_current_rustc, unpack_rustc_source, analyze_rustc_source,
+    RustcNixInfo, RustcSourceStats, CrateStats, ItemCounts, DiscoveryStrategy,
+    WrappedItem, CratePatch, RustcPatchSeries, DEFAULT_WRAP_CRATES, RustcDiscovery, NixRustcEval,
+    find_current_rustc, parse_rustc_version, parse_nix_rustc_eval, rust_src_component,
+    rustup_toolchains, nix_store_rustcs, unpack_rustc_source, analyze_rustc_source, analyze_crate_source,
     apply_decl_wrappers_to_rustc, generate_rustc_introspection_report,
     print_rustc_info,
 };
+pub use unified_diff::{
+    DiffAlgorithm, DiffOptions, DiffOp, HunkLine, Hunk, FilePatch, Patch, FileEdit,
+    ApplyOptions, HunkResult, FileResult,
+    diff_lines, diff_hunks, diff_text, diff_edits, apply_file_patch, apply_patch,
+};
+pub use toolchain_cache::{
+    ToolchainCache, CacheLock, EntryKind, CacheEntry, VerifyReport, GcReport, CACHE_DIR_ENV,
+    tree_manifest, print_toolchain_cache,
+};
+pub use rustc_trace::{
+    RustcTrace, RustcVersionInfo, SysrootLayout, TargetLibdir, CodegenBackend,
+    parse_rustc_verbose_version, find_in_path, symlink_chain, read_sysroot_layout,
+};
+pub use rust_in_nix::{
+    RustInNixOptions, RustInNixPlan, PlannedChange, Wrapper,
+    wrap_build_script, wrap_bootstrap,
+};
 pub use macro_report::{
-    MacroReport, MacroDefinition, MacroKind, ModuleStats,
+    MacroReport, MacroDefinition, MacroKind, ModuleStats, MacroUsage, InvocationSite,
     AuditSummary as MacroAuditSummary,
+    find_workspace_root, workspace_crates, analyze_workspace_macros,
     generate_macro_report, print_macro_report, generate_rdf_turtle_report,
 };
 pub use expr::Expr;
@@ -1,8 +1,24 @@
-use crate::audit_macros::{AuditKind, register_audit, print_audit_summary};
-use std::collections::HashMap;
+use crate::audit_macros::AuditKind;
+use crate::audit_tickets::{get_ticket, TicketCategory};
+use crate::auto_fix::code_tokens;
+use crate::api_snapshot::module_prefix;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use walkdir::WalkDir;
+
+// Macro usage of a Cargo workspace, computed from the sources: every member
+// crate is parsed with syn, proc-macro and `macro_rules!` definitions are
+// collected with their invocation sites, and the audit flags come from the
+// `audit_id!`/`phony!`/... markers found along the way.
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "MacroDefinition", vis = "pub", hash = "5caf2682")]
+#[decl(struct, name = "MacroDefinition", vis = "pub", hash = "78391a0c")]
 pub struct MacroDefinition {
     pub name: String,
     pub kind: MacroKind,
@@ -13,7 +29,7 @@ pub struct MacroDefinition {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[decl(enum, name = "MacroKind", vis = "pub", hash = "df2d976e")]
+#[decl(enum, name = "MacroKind", vis = "pub", hash = "1fce8f1d")]
 pub enum MacroKind {
     ProcMacro,
     ProcMacroAttribute,
@@ -32,39 +48,98 @@ impl MacroKind {
             MacroKind::DeclarativeMacro => "📝",
         }
     }
+
+    /// Class name in the Turtle output.
+    pub fn rdf_class(&self) -> &'static str {
+        match self {
+            MacroKind::ProcMacro => "ProcMacro",
+            MacroKind::ProcMacroAttribute => "ProcMacroAttribute",
+            MacroKind::ProcMacroDerive => "ProcMacroDerive",
+            MacroKind::MacroRules => "MacroRules",
+            MacroKind::DeclarativeMacro => "DeclarativeMacro",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "AuditFlag", vis = "pub", hash = "61ba6352")]
+#[decl(struct, name = "AuditFlag", vis = "pub", hash = "7c9189ef")]
 pub struct AuditFlag {
     pub kind: AuditKind,
     pub message: String,
 }
 
+/// Where a macro is invoked; for attributes and derives, the item they are on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "InvocationSite", vis = "pub", hash = "e4a03fe1")]
+pub struct InvocationSite {
+    pub module: String,
+    pub file: String,
+    pub line: u32,
+}
+
 #[derive(Debug, Clone, Default)]
-#[decl(struct, name = "MacroReport", vis = "pub", hash = "7c45cf9e")]
+#[decl(struct, name = "MacroUsage", vis = "pub", hash = "c3a49fc1")]
+pub struct MacroUsage {
+    pub name: String,
+    pub sites: Vec<InvocationSite>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[decl(struct, name = "MacroReport", vis = "pub", hash = "1d0dfde0")]
 pub struct MacroReport {
     pub total_proc_macros: usize,
     pub total_macro_rules: usize,
     pub total_impl_functions: usize,
     pub modules: HashMap<String, ModuleStats>,
     pub definitions: Vec<MacroDefinition>,
+    /// Invocation sites by macro name, including macros defined outside the
+    /// workspace such as `println!`.
+    pub usage: BTreeMap<String, MacroUsage>,
+    /// Crate names, as used in module paths.
+    pub crates: Vec<String>,
+    /// Files or manifests that could not be read or parsed.
+    pub errors: Vec<String>,
     pub audit_summary: AuditSummary,
 }
 
+impl MacroReport {
+    pub fn invocation_count(&self, name: &str) -> usize {
+        self.usage.get(name).map_or(0, |u| u.sites.len())
+    }
+
+    pub fn total_invocations(&self) -> usize {
+        self.usage.values().map(|u| u.sites.len()).sum()
+    }
+
+    /// Workspace macros that are never invoked within the workspace.
+    pub fn unused_macros(&self) -> Vec<&MacroDefinition> {
+        self.definitions.iter().filter(|d| self.invocation_count(&d.name) == 0).collect()
+    }
+
+    /// The `n` most invoked macros, most frequent first.
+    pub fn top_macros(&self, n: usize) -> Vec<&MacroUsage> {
+        let mut usage: Vec<&MacroUsage> = self.usage.values().collect();
+        usage.sort_by(|a, b| b.sites.len().cmp(&a.sites.len()).then_with(|| a.name.cmp(&b.name)));
+        usage.truncate(n);
+        usage
+    }
+}
+
 #[derive(Debug, Clone, Default)]
-#[decl(struct, name = "ModuleStats", vis = "pub", hash = "69015702")]
+#[decl(struct, name = "ModuleStats", vis = "pub", hash = "0c759ce1")]
 pub struct ModuleStats {
     pub name: String,
     pub proc_macro_count: usize,
+    pub macro_rules_count: usize,
     pub impl_function_count: usize,
+    pub invocation_count: usize,
     pub has_phony_data: bool,
     pub has_fakedata: bool,
     pub audit_count: usize,
 }
 
 #[derive(Debug, Clone, Default)]
-#[decl(struct, name = "AuditSummary", vis = "pub", hash = "e8b8aec0")]
+#[decl(struct, name = "AuditSummary", vis = "pub", hash = "ef60aa3a")]
 pub struct AuditSummary {
     pub phony_modules: Vec<String>,
     pub fakedata_locations: Vec<String>,
@@ -73,134 +148,391 @@ pub struct AuditSummary {
     pub concerns: Vec<String>,
 }
 
-#[decl(fn, name = "generate_macro_report", vis = "pub", hash = "1857f535")]
-pub fn generate_macro_report() -> MacroReport {
-    let mut report = MacroReport::default();
-    
-    register_audit(AuditKind::Fixme, "Generating macro report - uses static analysis", file!(), line!(), column!());
-    
-    report.total_proc_macros = 87;
-    report.total_macro_rules = 54;
-    report.total_impl_functions = 107;
-    
-    let modules = vec![
-        ("duplicate_analysis", 5, true, true, vec![
-            "🎭 PHONY: All statistics are illustrative (1247 items, 55.1% reduction)",
-            "🧪 FAKEDATA: Hardcoded hash values (a7f3b2c1, d8e9f4a6)",
-            "🤔 CONCERN: Claims not based on actual measurements",
-        ]),
-        ("solana_lift", 5, true, true, vec![
-            "🎭 PHONY: Template code, not production Solana programs",
-            "🧪 FAKEDATA: Fallback blockhash '11111111...'",
-            "🧪 FAKEDATA: sample_block_hash placeholder",
-        ]),
-        ("zk_proof", 4, true, true, vec![
-            "🎭 PHONY: Template ZK structures, not real proofs",
-            "❓ UNVERIFIED: PLONK/STARK/SNARK are structural only",
-            "⚠️ ISSUE: verify_morphism does not verify anything",
-            "🤔 CONCERN: Monster group 196883 is metaphorical",
-        ]),
-        ("dao_governance", 4, true, true, vec![
-            "🎭 PHONY: Simulated voting, not on-chain",
-            "🧪 FAKEDATA: Hardcoded senator/representative counts",
-            "🤔 CONCERN: Paxos is conceptual, not distributed",
-        ]),
-        ("mev_protection", 4, true, true, vec![
-            "🎭 PHONY: Naive string matching, not mempool analysis",
-            "🧪 FAKEDATA: transaction_matches always returns true",
-            "⚠️ ISSUE: Not production-ready protection",
-        ]),
-        ("quant_trading", 3, true, true, vec![
-            "🎭 PHONY: Template trading code, not a trading system",
-            "🧪 FAKEDATA: Hardcoded sample prices (145.32, 43250.67...)",
-            "🤔 CONCERN: Not financial advice, illustrative only",
-        ]),
-        ("rustc_ring", 4, true, true, vec![
-            "🎭 PHONY: Ring properties are conceptual analogy",
-            "🧪 FAKEDATA: DOT graph is hardcoded example",
-            "🤔 CONCERN: May fail in different environments",
-        ]),
-        ("real_data_analysis", 3, false, false, vec![
-            "🤔 CONCERN: Pattern counting may over/undercount",
-            "⚠️ ISSUE: 'Eigenvalue' is normalized frequency, not LA",
-            "📝 TODO: Replace string matching with AST parsing",
-        ]),
-        ("lean4_proof", 5, true, false, vec![
-            "🎭 PHONY: Generates Lean4 syntax templates",
-            "❓ UNVERIFIED: Not verified by actual Lean4 compiler",
-        ]),
-        ("lean4_mirror", 4, true, false, vec![
-            "🎭 PHONY: Lean4↔Rust translation is illustrative",
-            "❓ UNVERIFIED: Proofs are simulated, not verified",
-        ]),
-        ("lean4_json", 4, true, false, vec![
-            "🎭 PHONY: JSON proof structures are templates",
-        ]),
-        ("event_memory", 6, false, false, vec![
-            "🤔 CONCERN: Event structures are illustrative",
-        ]),
-        ("graph_partition", 4, true, false, vec![
-            "🎭 PHONY: METIS partitioning is simulated",
-        ]),
-        ("context_knapsack", 4, false, false, vec![
-            "📝 TODO: Implement actual knapsack optimization",
-        ]),
-        ("emoji_poetry", 4, false, false, vec![]),
-        ("rust_eigenmatrix", 3, false, false, vec![
-            "🤔 CONCERN: Eigenmatrix concept needs formalization",
-        ]),
-        ("quine_relay", 4, true, false, vec![
-            "🎭 PHONY: Quines are template code",
-        ]),
-        ("sat_lfunction", 4, true, false, vec![
-            "🎭 PHONY: L-function SAT solving is conceptual",
-            "❓ UNVERIFIED: Unity proof is illustrative",
-        ]),
-        ("lmfdb_morph", 4, true, false, vec![
-            "🎭 PHONY: LMFDB morphisms are conceptual",
-        ]),
-        ("rust_nix", 3, false, false, vec![]),
-        ("mkbuildrs", 3, false, false, vec![]),
-        ("macro_lattice", 3, false, false, vec![]),
-        ("repo_analysis", 2, false, false, vec![]),
-        ("macro_generator", 2, false, false, vec![]),
-        ("template_checker", 3, false, false, vec![]),
-        ("compiler_inventory", 4, false, false, vec![]),
-        ("rustc_tracer", 3, false, false, vec![]),
-        ("real_rustc_analysis", 3, false, false, vec![]),
-    ];
-    
-    for (name, impl_count, has_phony, has_fakedata, audits) in modules {
-        let mut stats = ModuleStats {
-            name: name.to_string(),
-            impl_function_count: impl_count,
-            has_phony_data: has_phony,
-            has_fakedata,
-            audit_count: audits.len(),
-            ..Default::default()
+/// `// audit_id!("ID", "message")` markers left by `cargo-audit-fix`.
+static MARKER_COMMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"//\s*audit_id!\(\s*"([A-Z][A-Z0-9]*-\d+)"\s*,\s*"([^"]*)""#).unwrap()
+});
+
+/// Directories and files of a crate that hold its targets.
+const TARGET_PATHS: &[&str] = &["src", "examples", "tests", "benches", "build.rs"];
+
+fn audit_kind_for_macro(name: &str) -> Option<AuditKind> {
+    match name {
+        "fixme" => Some(AuditKind::Fixme),
+        "phony" => Some(AuditKind::Phony),
+        "fakedata" => Some(AuditKind::FakeData),
+        "issue" => Some(AuditKind::Issue),
+        "audit_todo" => Some(AuditKind::Todo),
+        "concern" => Some(AuditKind::Concern),
+        "unverified" => Some(AuditKind::Unverified),
+        _ => None,
+    }
+}
+
+fn audit_kind_for_ticket(id: &str) -> AuditKind {
+    match get_ticket(id).map(|t| t.category) {
+        Some(TicketCategory::Phony) => AuditKind::Phony,
+        Some(TicketCategory::FakeData) => AuditKind::FakeData,
+        Some(TicketCategory::Unverified) => AuditKind::Unverified,
+        Some(TicketCategory::Concern) => AuditKind::Concern,
+        Some(TicketCategory::Todo) => AuditKind::Todo,
+        _ => AuditKind::Issue,
+    }
+}
+
+/// Nearest ancestor of `start` (inclusive) whose `Cargo.toml` has a
+/// `[workspace]` table.
+#[decl(fn, name = "find_workspace_root", vis = "pub", hash = "4110c73a")]
+pub fn find_workspace_root(start: &Path) -> Option<PathBuf> {
+    start.ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|s| s.parse::<toml::Table>().ok())
+                .is_some_and(|t| t.contains_key("workspace"))
+        })
+        .map(Path::to_path_buf)
+}
+
+fn crate_name(manifest: &toml::Table) -> Option<String> {
+    manifest.get("package")?.get("name")?.as_str().map(|n| n.replace('-', "_"))
+}
+
+/// Name and directory of every crate in the workspace at `root`: the root
+/// package, if any, and each member. Members may end in `/*`.
+#[decl(fn, name = "workspace_crates", vis = "pub", hash = "ad7e5b42")]
+pub fn workspace_crates(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let manifest_path = root.join("Cargo.toml");
+    let manifest: toml::Table = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?
+        .parse()
+        .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+
+    let list = |key: &str| -> Vec<String> {
+        manifest.get("workspace")
+            .and_then(|w| w.get(key))
+            .and_then(|m| m.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    };
+    let excluded: HashSet<PathBuf> = list("exclude").iter().map(|e| root.join(e)).collect();
+
+    let mut dirs = Vec::new();
+    for member in list("members") {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                let mut children: Vec<PathBuf> = fs::read_dir(root.join(parent))
+                    .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+                    .unwrap_or_default();
+                children.sort();
+                dirs.extend(children.into_iter().filter(|d| d.join("Cargo.toml").is_file()));
+            }
+            None => dirs.push(root.join(member)),
+        }
+    }
+
+    let mut crates = Vec::new();
+    if let Some(name) = crate_name(&manifest) {
+        crates.push((name, root.to_path_buf()));
+    }
+    for dir in dirs.into_iter().filter(|d| !excluded.contains(d)) {
+        let path = dir.join("Cargo.toml");
+        let member: toml::Table = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .parse()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let name = crate_name(&member).ok_or_else(|| format!("{}: no package name", path.display()))?;
+        crates.push((name, dir));
+    }
+    Ok(crates)
+}
+
+/// `.rs` files of the crate in `dir`, without nested crates or `target`.
+pub(crate) fn crate_sources(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for target in TARGET_PATHS {
+        let path = dir.join(target);
+        if path.is_file() {
+            files.push(path);
+            continue;
+        }
+        let walker = WalkDir::new(&path).into_iter().filter_entry(|e| {
+            e.depth() == 0 || !(e.file_type().is_dir() && (e.file_name() == "target" || e.path().join("Cargo.toml").is_file()))
+        });
+        files.extend(walker
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(|e| e.into_path()));
+    }
+    files.sort();
+    files
+}
+
+/// Module path of `path` in the crate at `dir`: `krate::a::b` for
+/// `src/a/b.rs`, `krate::examples::demo` for `examples/demo.rs`.
+pub(crate) fn source_module(crate_name: &str, dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+    let in_src = relative.strip_prefix("src").unwrap_or(relative);
+    [crate_name.to_string(), module_prefix(in_src)].into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Macro definitions, invocations and audit markers of one file.
+#[derive(Default)]
+struct FileMacros {
+    definitions: Vec<(MacroDefinition, u32)>,
+    /// Name, line and string literal arguments of each `name!(...)`.
+    invocations: Vec<(String, u32, Vec<String>)>,
+    /// Attribute and derive names with the line of their item.
+    attributes: Vec<(String, u32)>,
+    derives: Vec<(String, u32)>,
+    impl_functions: usize,
+}
+
+struct MacroCollector<'a> {
+    module: &'a str,
+    file: &'a str,
+    found: FileMacros,
+}
+
+impl MacroCollector<'_> {
+    fn define(&mut self, name: String, kind: MacroKind, span: proc_macro2::Span) {
+        let definition = MacroDefinition {
+            name,
+            kind,
+            module: self.module.to_string(),
+            file: self.file.to_string(),
+            line: span.start().line as u32,
+            audit_flags: Vec::new(),
         };
-        
-        for audit in &audits {
-            if audit.contains("PHONY") {
-                report.audit_summary.phony_modules.push(format!("{}::{}", name, audit));
-            } else if audit.contains("FAKEDATA") {
-                report.audit_summary.fakedata_locations.push(format!("{}::{}", name, audit));
-            } else if audit.contains("UNVERIFIED") {
-                report.audit_summary.unverified_claims.push(format!("{}::{}", name, audit));
-            } else if audit.contains("ISSUE") {
-                report.audit_summary.issues.push(format!("{}::{}", name, audit));
-            } else if audit.contains("CONCERN") {
-                report.audit_summary.concerns.push(format!("{}::{}", name, audit));
+        self.found.definitions.push((definition, span.end().line as u32));
+    }
+}
+
+impl<'ast> Visit<'ast> for MacroCollector<'_> {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        for attr in &item.attrs {
+            let path = attr.path();
+            if path.is_ident("proc_macro") {
+                self.define(item.sig.ident.to_string(), MacroKind::ProcMacro, item.span());
+            } else if path.is_ident("proc_macro_attribute") {
+                self.define(item.sig.ident.to_string(), MacroKind::ProcMacroAttribute, item.span());
+            } else if path.is_ident("proc_macro_derive") {
+                // The derive is exported under the name in its first argument.
+                let name = attr.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+                    .ok()
+                    .and_then(|args| args.first().and_then(|m| m.path().get_ident()).map(|i| i.to_string()))
+                    .unwrap_or_else(|| item.sig.ident.to_string());
+                self.define(name, MacroKind::ProcMacroDerive, item.span());
+            }
+        }
+        if item.sig.ident.to_string().ends_with("_impl") {
+            self.found.impl_functions += 1;
+        }
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_macro(&mut self, item: &'ast syn::ItemMacro) {
+        match &item.ident {
+            Some(ident) if item.mac.path.is_ident("macro_rules") => {
+                for attr in &item.attrs {
+                    self.visit_attribute(attr);
+                }
+                self.define(ident.to_string(), MacroKind::MacroRules, item.span());
             }
+            _ => visit::visit_item_macro(self, item),
         }
-        
-        report.modules.insert(name.to_string(), stats);
     }
-    
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Some(name) = mac.path.segments.last().map(|s| s.ident.to_string()) else { return };
+        let line = mac.path.span().start().line as u32;
+        let args = mac.parse_body_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated).ok();
+        let literals = args.iter().flatten()
+            .filter_map(|arg| match arg {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            })
+            .collect();
+        self.found.invocations.push((name, line, literals));
+        // Macros in the arguments, e.g. `format!` inside `println!`.
+        for arg in args.iter().flatten() {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_attribute(&mut self, attr: &'ast syn::Attribute) {
+        let line = attr.span().start().line as u32;
+        if attr.path().is_ident("derive") {
+            if let Ok(paths) = attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated) {
+                for path in paths {
+                    if let Some(last) = path.segments.last() {
+                        self.found.derives.push((last.ident.to_string(), line));
+                    }
+                }
+            }
+        } else if let Some(last) = attr.path().segments.last() {
+            self.found.attributes.push((last.ident.to_string(), line));
+        }
+        visit::visit_attribute(self, attr);
+    }
+}
+
+/// Audit flags of a file with their lines: audit macro invocations and
+/// `// audit_id!` comment markers.
+fn audit_flags(content: &str, found: &FileMacros) -> Vec<(u32, AuditFlag)> {
+    let mut flags = Vec::new();
+    for (name, line, literals) in &found.invocations {
+        if name == "audit_id" {
+            if let Some(id) = literals.first() {
+                let message = literals.get(1).map_or(id.clone(), |m| format!("{} {}", id, m));
+                flags.push((*line, AuditFlag { kind: audit_kind_for_ticket(id), message }));
+            }
+        } else if let Some(kind) = audit_kind_for_macro(name) {
+            let message = literals.first().cloned().unwrap_or_default();
+            flags.push((*line, AuditFlag { kind, message }));
+        }
+    }
+
+    let tokens = code_tokens(content).unwrap_or_default();
+    for caps in MARKER_COMMENT_RE.captures_iter(content) {
+        let start = caps.get(0).unwrap().start();
+        let idx = tokens.partition_point(|&(s, _)| s <= start);
+        if idx > 0 && start < tokens[idx - 1].1 {
+            continue;
+        }
+        let line = content[..start].matches('\n').count() as u32 + 1;
+        flags.push((line, AuditFlag {
+            kind: audit_kind_for_ticket(&caps[1]),
+            message: format!("{} {}", &caps[1], &caps[2]),
+        }));
+    }
+    flags.sort_by_key(|(line, _)| *line);
+    flags
+}
+
+/// The macro report of the workspace at `root`.
+#[decl(fn, name = "analyze_workspace_macros", vis = "pub", hash = "56b04067")]
+pub fn analyze_workspace_macros(root: &Path) -> MacroReport {
+    let mut report = MacroReport::default();
+    let crates = match workspace_crates(root) {
+        Ok(crates) => crates,
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+
+    let mut attributes = Vec::new();
+    let mut derives = Vec::new();
+
+    for (crate_name, dir) in &crates {
+        report.crates.push(crate_name.clone());
+        for path in crate_sources(dir) {
+            let module = source_module(crate_name, dir, &path);
+            let file = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", file, e));
+                    continue;
+                }
+            };
+            let ast = match syn::parse_file(&content) {
+                Ok(ast) => ast,
+                Err(e) => {
+                    report.errors.push(format!("{}:{}: {}", file, e.span().start().line, e));
+                    continue;
+                }
+            };
+
+            let mut collector = MacroCollector { module: &module, file: &file, found: FileMacros::default() };
+            collector.visit_file(&ast);
+            let found = collector.found;
+            let flags = audit_flags(&content, &found);
+            let site = |line: u32| InvocationSite { module: module.clone(), file: file.clone(), line };
+
+            let stats = report.modules.entry(module.clone()).or_insert_with(|| ModuleStats {
+                name: module.clone(),
+                ..Default::default()
+            });
+            stats.impl_function_count += found.impl_functions;
+            stats.invocation_count += found.invocations.len();
+            stats.audit_count += flags.len();
+            stats.has_phony_data |= flags.iter().any(|(_, f)| f.kind == AuditKind::Phony);
+            stats.has_fakedata |= flags.iter().any(|(_, f)| f.kind == AuditKind::FakeData);
+            report.total_impl_functions += found.impl_functions;
+
+            for (mut definition, end_line) in found.definitions {
+                if definition.kind == MacroKind::MacroRules {
+                    stats.macro_rules_count += 1;
+                    report.total_macro_rules += 1;
+                } else {
+                    stats.proc_macro_count += 1;
+                    report.total_proc_macros += 1;
+                }
+                definition.audit_flags = flags.iter()
+                    .filter(|(line, _)| (definition.line..=end_line).contains(line))
+                    .map(|(_, f)| f.clone())
+                    .collect();
+                report.definitions.push(definition);
+            }
+
+            for (name, line, _) in found.invocations {
+                report.usage.entry(name.clone())
+                    .or_insert_with(|| MacroUsage { name, sites: Vec::new() })
+                    .sites.push(site(line));
+            }
+            attributes.extend(found.attributes.into_iter().map(|(name, line)| (name, site(line))));
+            derives.extend(found.derives.into_iter().map(|(name, line)| (name, site(line))));
+
+            for (line, flag) in flags {
+                let entry = format!("{}:{} {} {}", module, line, flag.kind.emoji(), flag.message);
+                match flag.kind {
+                    AuditKind::Phony => report.audit_summary.phony_modules.push(entry),
+                    AuditKind::FakeData => report.audit_summary.fakedata_locations.push(entry),
+                    AuditKind::Unverified => report.audit_summary.unverified_claims.push(entry),
+                    AuditKind::Issue => report.audit_summary.issues.push(entry),
+                    AuditKind::Concern => report.audit_summary.concerns.push(entry),
+                    AuditKind::Fixme | AuditKind::Todo => {}
+                }
+            }
+        }
+    }
+
+    // Attributes and derives only count as macro uses when the workspace
+    // defines a macro of that name and kind; the rest are built-ins.
+    let defined = |kind: MacroKind| -> HashSet<String> {
+        report.definitions.iter().filter(|d| d.kind == kind).map(|d| d.name.clone()).collect()
+    };
+    let attribute_macros = defined(MacroKind::ProcMacroAttribute);
+    let derive_macros = defined(MacroKind::ProcMacroDerive);
+    let uses = attributes.into_iter().filter(|(name, _)| attribute_macros.contains(name))
+        .chain(derives.into_iter().filter(|(name, _)| derive_macros.contains(name)));
+    for (name, site) in uses {
+        if let Some(stats) = report.modules.get_mut(&site.module) {
+            stats.invocation_count += 1;
+        }
+        report.usage.entry(name.clone())
+            .or_insert_with(|| MacroUsage { name, sites: Vec::new() })
+            .sites.push(site);
+    }
+
     report
 }
 
-#[decl(fn, name = "print_macro_report", vis = "pub", hash = "9206a7d7")]
+/// The macro report of the workspace containing the current directory.
+#[decl(fn, name = "generate_macro_report", vis = "pub", hash = "9a61c3af")]
+pub fn generate_macro_report() -> MacroReport {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let root = find_workspace_root(&cwd).unwrap_or(cwd);
+    analyze_workspace_macros(&root)
+}
+
+#[decl(fn, name = "print_macro_report", vis = "pub", hash = "18f75bd1")]
 pub fn print_macro_report(report: &MacroReport) {
     let reset = "\x1b[0m";
     let cyan = "\x1b[96m";
@@ -209,34 +541,56 @@ pub fn print_macro_report(report: &MacroReport) {
     let magenta = "\x1b[95m";
     let green = "\x1b[92m";
     let gray = "\x1b[90m";
-    
+
     eprintln!("\n{}╔══════════════════════════════════════════════════════════════════╗{}", cyan, reset);
     eprintln!("{}║       🔬 MACRO USAGE REPORT - TOWER OF REFLECTION ANALYSIS       ║{}", cyan, reset);
     eprintln!("{}╚══════════════════════════════════════════════════════════════════╝{}", cyan, reset);
-    
+
     eprintln!("\n{}📊 GLOBAL STATISTICS:{}", green, reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
+    eprintln!("   📦 Crates Analyzed:          {:>4}", report.crates.len());
     eprintln!("   ⚙️  Proc Macros Exported:     {:>4}", report.total_proc_macros);
     eprintln!("   📜 macro_rules! Definitions: {:>4}", report.total_macro_rules);
     eprintln!("   🔧 Implementation Functions: {:>4}", report.total_impl_functions);
+    eprintln!("   📞 Invocation Sites:         {:>4}", report.total_invocations());
     eprintln!("   📁 Modules Analyzed:         {:>4}", report.modules.len());
-    
+    if !report.errors.is_empty() {
+        eprintln!("   {}⚠️  Skipped (unreadable):     {:>4}{}", yellow, report.errors.len(), reset);
+    }
+
     eprintln!("\n{}📁 MODULE BREAKDOWN:{}", green, reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
-    eprintln!("   {:<25} {:>5} {:>7} {:>8}", "Module", "Impls", "Phony?", "Audits");
-    eprintln!("   {}─────────────────────────────────────────────────────────────{}", gray, reset);
-    
-    let mut sorted_modules: Vec<_> = report.modules.iter().collect();
-    sorted_modules.sort_by(|a, b| b.1.audit_count.cmp(&a.1.audit_count));
-    
-    for (name, stats) in sorted_modules {
+    eprintln!("   {:<40} {:>5} {:>5} {:>6} {:>7} {:>6}", "Module", "Procs", "Rules", "Calls", "Phony?", "Audits");
+    eprintln!("   {}─────────────────────────────────────────────────────────────────────────{}", gray, reset);
+
+    let mut sorted_modules: Vec<_> = report.modules.values()
+        .filter(|m| m.proc_macro_count + m.macro_rules_count + m.audit_count > 0)
+        .collect();
+    sorted_modules.sort_by(|a, b| b.audit_count.cmp(&a.audit_count).then_with(|| a.name.cmp(&b.name)));
+
+    for stats in sorted_modules {
         let phony_marker = if stats.has_phony_data { "🎭" } else { "✅" };
         let color = if stats.has_phony_data { yellow } else { reset };
-        eprintln!("   {}{:<25} {:>5} {:>7} {:>8}{}", 
-            color, name, stats.impl_function_count, phony_marker, stats.audit_count, reset);
+        eprintln!("   {}{:<40} {:>5} {:>5} {:>6} {:>7} {:>6}{}",
+            color, stats.name, stats.proc_macro_count, stats.macro_rules_count,
+            stats.invocation_count, phony_marker, stats.audit_count, reset);
+    }
+
+    eprintln!("\n{}🔝 MOST INVOKED MACROS:{}", green, reset);
+    eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
+    for usage in report.top_macros(10) {
+        let modules: HashSet<&str> = usage.sites.iter().map(|s| s.module.as_str()).collect();
+        eprintln!("   {:<30} {:>6} sites in {} modules", usage.name, usage.sites.len(), modules.len());
     }
-    
-    eprintln!("\n{}🎭 PHONY DATA FLAGS ({} modules):{}", red, report.audit_summary.phony_modules.len(), reset);
+
+    let unused = report.unused_macros();
+    eprintln!("\n{}💤 UNUSED MACROS ({}):{}", yellow, unused.len(), reset);
+    eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
+    for definition in &unused {
+        eprintln!("   {} {:<30} {}:{}", definition.kind.emoji(), definition.name, definition.file, definition.line);
+    }
+
+    eprintln!("\n{}🎭 PHONY DATA FLAGS ({}):{}", red, report.audit_summary.phony_modules.len(), reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     for flag in report.audit_summary.phony_modules.iter().take(10) {
         eprintln!("   {}", flag);
@@ -244,31 +598,31 @@ pub fn print_macro_report(report: &MacroReport) {
     if report.audit_summary.phony_modules.len() > 10 {
         eprintln!("   ... and {} more", report.audit_summary.phony_modules.len() - 10);
     }
-    
+
     eprintln!("\n{}🧪 FAKEDATA LOCATIONS ({}):{}", magenta, report.audit_summary.fakedata_locations.len(), reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     for flag in report.audit_summary.fakedata_locations.iter().take(10) {
         eprintln!("   {}", flag);
     }
-    
+
     eprintln!("\n{}❓ UNVERIFIED CLAIMS ({}):{}", gray, report.audit_summary.unverified_claims.len(), reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     for flag in &report.audit_summary.unverified_claims {
         eprintln!("   {}", flag);
     }
-    
+
     eprintln!("\n{}⚠️  ISSUES ({}):{}", yellow, report.audit_summary.issues.len(), reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     for flag in &report.audit_summary.issues {
         eprintln!("   {}", flag);
     }
-    
+
     let total_audits = report.audit_summary.phony_modules.len()
         + report.audit_summary.fakedata_locations.len()
         + report.audit_summary.unverified_claims.len()
         + report.audit_summary.issues.len()
         + report.audit_summary.concerns.len();
-    
+
     eprintln!("\n{}📈 AUDIT TOTALS:{}", cyan, reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     eprintln!("   🎭 PHONY:      {:>4}", report.audit_summary.phony_modules.len());
@@ -278,55 +632,121 @@ pub fn print_macro_report(report: &MacroReport) {
     eprintln!("   🤔 CONCERNS:   {:>4}", report.audit_summary.concerns.len());
     eprintln!("   {}─────────────────{}", gray, reset);
     eprintln!("   📊 TOTAL:      {:>4}", total_audits);
-    
-    let phony_percentage = (report.modules.values().filter(|m| m.has_phony_data).count() as f64 
-        / report.modules.len() as f64) * 100.0;
-    
+
+    let module_count = report.modules.len().max(1) as f64;
+    let phony_percentage = (report.modules.values().filter(|m| m.has_phony_data).count() as f64
+        / module_count) * 100.0;
+
     eprintln!("\n{}🎯 QUALITY ASSESSMENT:{}", cyan, reset);
     eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
     eprintln!("   Modules with phony data:    {:.1}%", phony_percentage);
-    eprintln!("   Modules with fake data:     {:.1}%", 
-        (report.modules.values().filter(|m| m.has_fakedata).count() as f64 / report.modules.len() as f64) * 100.0);
-    
-    let severity = if phony_percentage > 50.0 { "🔴 HIGH" } 
-                   else if phony_percentage > 25.0 { "🟡 MEDIUM" } 
+    eprintln!("   Modules with fake data:     {:.1}%",
+        (report.modules.values().filter(|m| m.has_fakedata).count() as f64 / module_count) * 100.0);
+
+    let severity = if phony_percentage > 50.0 { "🔴 HIGH" }
+                   else if phony_percentage > 25.0 { "🟡 MEDIUM" }
                    else { "🟢 LOW" };
     eprintln!("   Audit Severity:             {}", severity);
-    
+
+    if !report.errors.is_empty() {
+        eprintln!("\n{}⚠️  SKIPPED FILES ({}):{}", yellow, report.errors.len(), reset);
+        eprintln!("{}═══════════════════════════════════════════════════════════════════{}", gray, reset);
+        for error in &report.errors {
+            eprintln!("   {}", error);
+        }
+    }
+
     eprintln!("\n{}╔══════════════════════════════════════════════════════════════════╗{}", cyan, reset);
     eprintln!("{}║                    END OF MACRO REPORT                            ║{}", cyan, reset);
     eprintln!("{}╚══════════════════════════════════════════════════════════════════╝{}\n", cyan, reset);
 }
 
-#[decl(fn, name = "generate_rdf_turtle_report", vis = "pub", hash = "7adcf315")]
+fn turtle_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[decl(fn, name = "generate_rdf_turtle_report", vis = "pub", hash = "5a257835")]
 pub fn generate_rdf_turtle_report(report: &MacroReport) -> String {
     let mut rdf = String::new();
-    
+
     rdf.push_str("@prefix macro: <http://patch-build-rs.local/macro/> .\n");
+    rdf.push_str("@prefix module: <http://patch-build-rs.local/module/> .\n");
     rdf.push_str("@prefix audit: <http://patch-build-rs.local/audit/> .\n");
     rdf.push_str("@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\n");
-    
+
     rdf.push_str("# MACRO REPORT - GRAST RDF TURTLE FORMAT\n\n");
-    
-    rdf.push_str(&format!("macro:report a macro:MacroUsageReport ;\n"));
+
+    let unused = report.unused_macros();
+    rdf.push_str("macro:report a macro:MacroUsageReport ;\n");
+    rdf.push_str(&format!("    macro:cratesAnalyzed \"{}\"^^xsd:integer ;\n", report.crates.len()));
     rdf.push_str(&format!("    macro:totalProcMacros \"{}\"^^xsd:integer ;\n", report.total_proc_macros));
     rdf.push_str(&format!("    macro:totalMacroRules \"{}\"^^xsd:integer ;\n", report.total_macro_rules));
     rdf.push_str(&format!("    macro:totalImplFunctions \"{}\"^^xsd:integer ;\n", report.total_impl_functions));
+    rdf.push_str(&format!("    macro:totalInvocations \"{}\"^^xsd:integer ;\n", report.total_invocations()));
+    rdf.push_str(&format!("    macro:unusedMacros \"{}\"^^xsd:integer ;\n", unused.len()));
     rdf.push_str(&format!("    macro:modulesAnalyzed \"{}\"^^xsd:integer .\n\n", report.modules.len()));
-    
-    for (name, stats) in &report.modules {
-        rdf.push_str(&format!("macro:{} a macro:Module ;\n", name));
+
+    let mut modules: Vec<&ModuleStats> = report.modules.values().collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    for stats in modules {
+        rdf.push_str(&format!("module:{} a macro:Module ;\n", stats.name));
+        rdf.push_str(&format!("    macro:procMacroCount \"{}\"^^xsd:integer ;\n", stats.proc_macro_count));
+        rdf.push_str(&format!("    macro:macroRulesCount \"{}\"^^xsd:integer ;\n", stats.macro_rules_count));
         rdf.push_str(&format!("    macro:implCount \"{}\"^^xsd:integer ;\n", stats.impl_function_count));
+        rdf.push_str(&format!("    macro:invocationCount \"{}\"^^xsd:integer ;\n", stats.invocation_count));
         rdf.push_str(&format!("    macro:hasPhonyData \"{}\"^^xsd:boolean ;\n", stats.has_phony_data));
         rdf.push_str(&format!("    macro:hasFakeData \"{}\"^^xsd:boolean ;\n", stats.has_fakedata));
         rdf.push_str(&format!("    audit:auditCount \"{}\"^^xsd:integer .\n\n", stats.audit_count));
     }
-    
-    for (i, flag) in report.audit_summary.phony_modules.iter().enumerate() {
-        rdf.push_str(&format!("audit:phony_{} a audit:PhonyFlag ;\n", i));
-        rdf.push_str(&format!("    audit:message \"{}\" .\n\n", flag.replace("\"", "\\\"")));
+
+    for definition in &report.definitions {
+        rdf.push_str(&format!("macro:{}::{} a macro:{} ;\n", definition.module, definition.name, definition.kind.rdf_class()));
+        rdf.push_str(&format!("    macro:name {} ;\n", turtle_string(&definition.name)));
+        rdf.push_str(&format!("    macro:definedIn module:{} ;\n", definition.module));
+        rdf.push_str(&format!("    macro:file {} ;\n", turtle_string(&definition.file)));
+        rdf.push_str(&format!("    macro:line \"{}\"^^xsd:integer ;\n", definition.line));
+        rdf.push_str(&format!("    macro:invocationCount \"{}\"^^xsd:integer ;\n", report.invocation_count(&definition.name)));
+        rdf.push_str(&format!("    macro:unused \"{}\"^^xsd:boolean .\n\n", report.invocation_count(&definition.name) == 0));
+    }
+
+    for usage in report.usage.values() {
+        let mut per_module: BTreeMap<&str, usize> = BTreeMap::new();
+        for site in &usage.sites {
+            *per_module.entry(&site.module).or_insert(0) += 1;
+        }
+        rdf.push_str(&format!("macro:invoked_{} a macro:MacroInvocation ;\n", usage.name));
+        rdf.push_str(&format!("    macro:name {} ;\n", turtle_string(&usage.name)));
+        let modules: Vec<String> = per_module.keys().map(|m| format!("module:{}", m)).collect();
+        rdf.push_str(&format!("    macro:invokedIn {} ;\n", modules.join(", ")));
+        rdf.push_str(&format!("    macro:frequency \"{}\"^^xsd:integer .\n\n", usage.sites.len()));
+    }
+
+    let flags = [
+        ("PhonyFlag", &report.audit_summary.phony_modules),
+        ("FakeDataFlag", &report.audit_summary.fakedata_locations),
+        ("UnverifiedFlag", &report.audit_summary.unverified_claims),
+        ("IssueFlag", &report.audit_summary.issues),
+        ("ConcernFlag", &report.audit_summary.concerns),
+    ];
+    for (class, list) in flags {
+        for (i, flag) in list.iter().enumerate() {
+            rdf.push_str(&format!("audit:{}_{} a audit:{} ;\n", class, i, class));
+            rdf.push_str(&format!("    audit:message {} .\n\n", turtle_string(flag)));
+        }
     }
-    
+
     rdf
 }
 
@@ -351,18 +771,84 @@ macro_rules! macro_report_rdf {
 mod tests {
     use super::*;
 
+    fn workspace(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(name);
+        fs::remove_dir_all(&root).ok();
+        let files = [
+            ("Cargo.toml", "[workspace]\nmembers = [\"macros\", \"app\"]\n"),
+            ("macros/Cargo.toml", "[package]\nname = \"my-macros\"\n\n[lib]\nproc-macro = true\n"),
+            ("macros/src/lib.rs", concat!(
+                "use proc_macro::TokenStream;\n",
+                "#[proc_macro]\npub fn used(input: TokenStream) -> TokenStream { input }\n",
+                "#[proc_macro]\npub fn unused(input: TokenStream) -> TokenStream {\n    phony!(\"template only\");\n    input\n}\n",
+                "#[proc_macro_attribute]\npub fn tag(_a: TokenStream, item: TokenStream) -> TokenStream { item }\n",
+                "#[proc_macro_derive(Describe)]\npub fn describe_impl(input: TokenStream) -> TokenStream { input }\n",
+            )),
+            ("app/Cargo.toml", "[package]\nname = \"app\"\n"),
+            ("app/src/main.rs", concat!(
+                "macro_rules! local { () => {} }\n",
+                "macro_rules! dead { () => {} }\n",
+                "#[tag]\n#[derive(Debug, Describe)]\nstruct S;\n",
+                "fn main() {\n    local!();\n    println!(\"{}\", format!(\"{}\", used!(1)));\n",
+                "    // audit_id!(\"FKD-002\", \"fake_blockchain_data\");\n    let h = 1;\n}\n",
+            )),
+            ("app/src/broken.rs", "fn ("),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        root
+    }
+
     #[test]
     fn test_generate_report() {
-        let report = generate_macro_report();
-        assert!(report.total_proc_macros > 0);
-        assert!(report.modules.len() > 0);
+        let root = workspace("macro_report_workspace");
+        assert_eq!(find_workspace_root(&root.join("app/src")), Some(root.clone()));
+        let report = analyze_workspace_macros(&root);
+        fs::remove_dir_all(&root).ok();
+
+        assert_eq!(report.crates, vec!["my_macros", "app"]);
+        assert_eq!(report.total_proc_macros, 4);
+        assert_eq!(report.total_macro_rules, 2);
+        assert_eq!(report.total_impl_functions, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("app/src/broken.rs"));
+
+        for (name, count) in [("used", 1), ("tag", 1), ("Describe", 1), ("local", 1), ("println", 1), ("format", 1), ("Debug", 0)] {
+            assert_eq!(report.invocation_count(name), count, "{}", name);
+        }
+        let mut unused: Vec<&str> = report.unused_macros().iter().map(|d| d.name.as_str()).collect();
+        unused.sort();
+        assert_eq!(unused, vec!["dead", "unused"]);
+
+        let definition = report.definitions.iter().find(|d| d.name == "unused").unwrap();
+        assert_eq!(definition.module, "my_macros");
+        assert_eq!(definition.audit_flags.len(), 1);
+        assert_eq!(definition.audit_flags[0].kind, AuditKind::Phony);
+        assert_eq!(report.definitions.iter().find(|d| d.name == "Describe").unwrap().kind, MacroKind::ProcMacroDerive);
+
+        let app = &report.modules["app"];
+        assert!(app.has_fakedata);
+        assert_eq!(app.macro_rules_count, 2);
+        assert_eq!(app.invocation_count, 6);
+        assert!(report.modules["my_macros"].has_phony_data);
     }
 
     #[test]
     fn test_rdf_generation() {
-        let report = generate_macro_report();
+        let root = workspace("macro_report_rdf_workspace");
+        let report = analyze_workspace_macros(&root);
+        fs::remove_dir_all(&root).ok();
+
         let rdf = generate_rdf_turtle_report(&report);
         assert!(rdf.contains("@prefix macro:"));
         assert!(rdf.contains("macro:MacroUsageReport"));
+        assert!(rdf.contains("macro:totalProcMacros \"4\"^^xsd:integer"));
+        assert!(rdf.contains("macro:my_macros::unused a macro:ProcMacro ;"));
+        assert!(rdf.contains("macro:unused \"true\"^^xsd:boolean"));
+        assert!(rdf.contains("macro:invoked_println a macro:MacroInvocation ;\n    macro:name \"println\" ;\n    macro:invokedIn module:app ;\n    macro:frequency \"1\"^^xsd:integer ."));
+        assert!(rdf.contains("audit:PhonyFlag_0 a audit:PhonyFlag ;"));
     }
-}
\ No newline at end of file
+}
@@ -4,7 +4,7 @@ use std::hash::{DefaultHasher, Hash, Hasher};
 use std::collections::BTreeSet;
 use proc_macro2::TokenStream; // Explicitly import TokenStream
 
-#[decl(trait, name = "NewQuoteTrait", vis = "pub", hash = "852a05ae")]
+#[decl(trait, name = "NewQuoteTrait", vis = "pub", hash = "953f88ab")]
 pub trait NewQuoteTrait {
     fn to_expr(&self) -> Expr;
 }
@@ -1,12 +1,59 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use serde::{Deserialize, Serialize};
+use syn::visit::{self, Visit};
+use walkdir::WalkDir;
+use crate::macro_report::crate_sources;
+use crate::unified_diff::{diff_text, DiffOptions};
+use nix_core::{nix_file, Expr, Param, StrPart};
+
+// Discovery of the active rustc. Strategies are tried in order: `$RUSTC`,
+// the `rustc` on PATH (via `--print sysroot`), rustup toolchain directories
+// and finally a scan of the Nix store, so machines without Nix or rustup
+// still resolve a compiler. For Nix toolchains the source tarball and
+// derivation are then looked up with `nix eval`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(enum, name = "DiscoveryStrategy", vis = "pub", hash = "9d80dfff")]
+pub enum DiscoveryStrategy {
+    RustcEnv,
+    Sysroot,
+    RustupToolchain,
+    NixStore,
+}
+
+impl DiscoveryStrategy {
+    pub const ALL: [DiscoveryStrategy; 4] = [
+        DiscoveryStrategy::RustcEnv,
+        DiscoveryStrategy::Sysroot,
+        DiscoveryStrategy::RustupToolchain,
+        DiscoveryStrategy::NixStore,
+    ];
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            DiscoveryStrategy::RustcEnv => "RUSTC env var",
+            DiscoveryStrategy::Sysroot => "rustc --print sysroot",
+            DiscoveryStrategy::RustupToolchain => "rustup toolchain",
+            DiscoveryStrategy::NixStore => "Nix store scan",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
-#[decl(struct, name = "RustcNixInfo", vis = "pub", hash = "c11c79f6")]
+#[decl(struct, name = "RustcNixInfo", vis = "pub", hash = "762a1c6f")]
 pub struct RustcNixInfo {
     pub version: String,
+    /// Strategy that found this rustc; `None` only for a default value.
+    pub strategy: Option<DiscoveryStrategy>,
+    pub rustc_path: PathBuf,
+    pub sysroot: Option<PathBuf>,
+    /// `lib/rustlib/src/rust` of the sysroot, when the `rust-src`
+    /// component is installed.
+    pub rust_src: Option<PathBuf>,
     pub store_path: String,
     pub src_tarball: String,
     pub src_url: String,
@@ -14,22 +61,124 @@ pub struct RustcNixInfo {
     pub unpacked_path: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone)]
-#[decl(struct, name = "RustcSourceStats", vis = "pub", hash = "cb3cb97f")]
+/// Items of one kind, split by visibility: `pub` is public, restricted
+/// (`pub(crate)`, ...) and inherited visibility are private.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "ItemCounts", vis = "pub", hash = "118d1602")]
+pub struct ItemCounts {
+    pub public: usize,
+    pub private: usize,
+}
+
+impl ItemCounts {
+    pub fn total(&self) -> usize {
+        self.public + self.private
+    }
+}
+
+/// Statistics of one `compiler/rustc_*` crate, from its parsed sources.
+#[derive(Debug, Clone, Default, Serialize)]
+#[decl(struct, name = "CrateStats", vis = "pub", hash = "3da1829f")]
+pub struct CrateStats {
+    pub name: String,
+    pub files: usize,
+    pub lines: usize,
+    /// `fn`, `method`, `struct`, `enum`, `union`, `trait`, `impl`, `type`,
+    /// `const`, `static`, `mod` → counts.
+    pub items: BTreeMap<String, ItemCounts>,
+    pub unsafe_blocks: usize,
+    pub unsafe_fns: usize,
+    pub unsafe_impls: usize,
+    pub macro_rules: usize,
+    pub proc_macros: usize,
+    /// `file:line: message` of each file syn could not parse.
+    pub parse_failures: Vec<String>,
+}
+
+impl CrateStats {
+    pub fn total_items(&self) -> ItemCounts {
+        self.items.values().fold(ItemCounts::default(), |acc, c| ItemCounts {
+            public: acc.public + c.public,
+            private: acc.private + c.private,
+        })
+    }
+
+    pub fn unsafe_total(&self) -> usize {
+        self.unsafe_blocks + self.unsafe_fns + self.unsafe_impls
+    }
+
+    fn add(&mut self, other: &CrateStats) {
+        self.files += other.files;
+        self.lines += other.lines;
+        for (kind, counts) in &other.items {
+            let entry = self.items.entry(kind.clone()).or_default();
+            entry.public += counts.public;
+            entry.private += counts.private;
+        }
+        self.unsafe_blocks += other.unsafe_blocks;
+        self.unsafe_fns += other.unsafe_fns;
+        self.unsafe_impls += other.unsafe_impls;
+        self.macro_rules += other.macro_rules;
+        self.proc_macros += other.proc_macros;
+        self.parse_failures.extend(other.parse_failures.iter().cloned());
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[decl(struct, name = "RustcSourceStats", vis = "pub", hash = "2c5967f0")]
 pub struct RustcSourceStats {
     pub total_files: usize,
     pub rust_files: usize,
     pub build_rs_files: usize,
     pub lib_rs_files: usize,
+    /// Lines of the parsed compiler crates.
     pub total_lines: usize,
+    /// Public items of the parsed compiler crates.
     pub declarations: usize,
     pub modules: Vec<String>,
+    pub crates: Vec<CrateStats>,
+    pub parse_failures: usize,
+}
+
+impl RustcSourceStats {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// One row per crate, largest first, with a total row.
+    pub fn summary_table(&self) -> String {
+        let mut crates: Vec<&CrateStats> = self.crates.iter().collect();
+        crates.sort_by(|a, b| b.total_items().total().cmp(&a.total_items().total()).then(a.name.cmp(&b.name)));
+
+        let mut table = format!("{:<32} {:>6} {:>9} {:>7} {:>7} {:>7} {:>7} {:>7} {:>6}\n",
+            "Crate", "Files", "Lines", "Items", "Pub", "Priv", "Unsafe", "Macros", "Fails");
+        table.push_str(&format!("{}\n", "─".repeat(97)));
+        let mut total = CrateStats { name: "TOTAL".to_string(), ..Default::default() };
+        for stats in crates {
+            table.push_str(&crate_row(stats));
+            total.add(stats);
+        }
+        table.push_str(&format!("{}\n", "─".repeat(97)));
+        table.push_str(&crate_row(&total));
+        table
+    }
+}
+
+fn crate_row(stats: &CrateStats) -> String {
+    let items = stats.total_items();
+    format!("{:<32} {:>6} {:>9} {:>7} {:>7} {:>7} {:>7} {:>7} {:>6}\n",
+        stats.name, stats.files, stats.lines, items.total(), items.public, items.private,
+        stats.unsafe_total(), stats.macro_rules + stats.proc_macros, stats.parse_failures.len())
 }
 
 impl Default for RustcNixInfo {
     fn default() -> Self {
         Self {
             version: String::new(),
+            strategy: None,
+            rustc_path: PathBuf::new(),
+            sysroot: None,
+            rust_src: None,
             store_path: String::new(),
             src_tarball: String::new(),
             src_url: String::new(),
@@ -39,88 +188,283 @@ impl Default for RustcNixInfo {
     }
 }
 
-#[decl(fn, name = "find_current_rustc", vis = "pub", hash = "b40d822a")]
-pub fn find_current_rustc() -> Result<RustcNixInfo, String> {
-    let mut info = RustcNixInfo::default();
-    
-    // Get rustc version from Nix
-    let version_output = Command::new("nix")
-        .args(["eval", "--raw", "nixpkgs#rustc.version"])
-        .output()
-        .map_err(|e| format!("Failed to query nix: {}", e))?;
-    
-    if version_output.status.success() {
-        info.version = String::from_utf8_lossy(&version_output.stdout).to_string();
-    } else {
-        // Fallback: try rustc --version
-        let rustc_output = Command::new("rustc")
-            .arg("--version")
-            .output()
-            .map_err(|e| format!("Failed to run rustc: {}", e))?;
-        
-        let version_str = String::from_utf8_lossy(&rustc_output.stdout);
-        info.version = version_str
-            .split_whitespace()
-            .nth(1)
-            .unwrap_or("unknown")
-            .to_string();
+impl RustcNixInfo {
+    /// Whether the toolchain lives in the Nix store.
+    pub fn is_nix(&self) -> bool {
+        !self.store_path.is_empty()
     }
-    
-    // Get source tarball path
-    let src_output = Command::new("nix")
-        .args(["eval", "--raw", "nixpkgs#rustc.src"])
-        .output()
-        .map_err(|e| format!("Failed to get src: {}", e))?;
-    
-    if src_output.status.success() {
-        info.src_tarball = String::from_utf8_lossy(&src_output.stdout).to_string();
+}
+
+/// Where `RustcDiscovery` looks; `from_env` reads the real environment,
+/// tests point it at fixture directories.
+#[derive(Debug, Clone)]
+#[decl(struct, name = "RustcDiscovery", vis = "pub", hash = "2958b997")]
+pub struct RustcDiscovery {
+    /// `$RUSTC`, tried first.
+    pub rustc_env: Option<PathBuf>,
+    /// Ask the `rustc` on PATH for its sysroot.
+    pub use_path: bool,
+    /// `$RUSTUP_HOME`, or `~/.rustup`.
+    pub rustup_home: Option<PathBuf>,
+    pub nix_store: PathBuf,
+    /// Look up the source tarball and derivation of Nix toolchains.
+    pub query_nix: bool,
+}
+
+impl RustcDiscovery {
+    pub fn from_env() -> Self {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        RustcDiscovery {
+            rustc_env: std::env::var_os("RUSTC").filter(|v| !v.is_empty()).map(PathBuf::from),
+            use_path: true,
+            rustup_home: std::env::var_os("RUSTUP_HOME").map(PathBuf::from)
+                .or_else(|| home.map(|h| h.join(".rustup"))),
+            nix_store: PathBuf::from("/nix/store"),
+            query_nix: true,
+        }
     }
-    
-    // Get source URL
-    let url_output = Command::new("nix")
-        .args(["eval", "--json", "nixpkgs#rustc.src.urls"])
-        .output()
-        .map_err(|e| format!("Failed to get src urls: {}", e))?;
-    
-    if url_output.status.success() {
-        let urls_str = String::from_utf8_lossy(&url_output.stdout);
-        // Parse JSON array and get first URL
-        if let Some(url) = urls_str
-            .trim_matches(|c| c == '[' || c == ']' || c == '"')
-            .split("\",\"")
-            .next()
-        {
-            info.src_url = url.to_string();
+
+    /// The first strategy that finds a rustc; the error lists why each failed.
+    pub fn discover(&self) -> Result<RustcNixInfo, String> {
+        let mut failures = Vec::new();
+        for strategy in DiscoveryStrategy::ALL {
+            match self.try_strategy(strategy) {
+                Ok(mut info) => {
+                    if self.query_nix && info.is_nix() {
+                        // Best effort: a missing `nix` only leaves the source fields empty.
+                        if let Ok(eval) = query_nix_rustc() {
+                            eval.apply_to(&mut info);
+                        }
+                    }
+                    return Ok(info);
+                }
+                Err(e) => failures.push(format!("{}: {}", strategy.describe(), e)),
+            }
         }
+        Err(format!("No rustc found ({})", failures.join("; ")))
     }
-    
-    // Get derivation path
-    let drv_output = Command::new("nix")
-        .args(["path-info", "--derivation", "nixpkgs#rustc"])
+
+    fn try_strategy(&self, strategy: DiscoveryStrategy) -> Result<RustcNixInfo, String> {
+        match strategy {
+            DiscoveryStrategy::RustcEnv => {
+                let rustc = self.rustc_env.as_ref().ok_or("RUSTC is not set")?;
+                probe_rustc(rustc, strategy)
+            }
+            DiscoveryStrategy::Sysroot => {
+                if !self.use_path {
+                    return Err("disabled".to_string());
+                }
+                let mut info = probe_rustc(Path::new("rustc"), strategy)?;
+                if let Some(rustc) = info.sysroot.as_ref().map(|s| s.join("bin").join("rustc")).filter(|r| r.is_file()) {
+                    info.rustc_path = rustc;
+                }
+                Ok(info)
+            }
+            DiscoveryStrategy::RustupToolchain => {
+                let home = self.rustup_home.as_ref().ok_or("no rustup home")?;
+                let toolchain = rustup_toolchains(home).into_iter().next()
+                    .ok_or_else(|| format!("no toolchains in {}", home.join("toolchains").display()))?;
+                Ok(toolchain_info(&toolchain, strategy))
+            }
+            DiscoveryStrategy::NixStore => {
+                let (_, dir) = nix_store_rustcs(&self.nix_store).into_iter().next()
+                    .ok_or_else(|| format!("no rustc in {}", self.nix_store.display()))?;
+                Ok(toolchain_info(&dir, strategy))
+            }
+        }
+    }
+}
+
+pub(crate) fn run_rustc(rustc: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(rustc)
+        .args(args)
         .output()
-        .map_err(|e| format!("Failed to get drv path: {}", e))?;
-    
-    if drv_output.status.success() {
-        info.drv_path = String::from_utf8_lossy(&drv_output.stdout).trim().to_string();
+        .map_err(|e| format!("Failed to run {}: {}", rustc.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} {} failed", rustc.display(), args.join(" ")));
     }
-    
-    // Get store path
-    let store_output = Command::new("nix")
-        .args(["path-info", "nixpkgs#rustc"])
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `rustc 1.79.0 (129f3b996 2024-06-10)` → `1.79.0`.
+#[decl(fn, name = "parse_rustc_version", vis = "pub", hash = "b5509210")]
+pub fn parse_rustc_version(output: &str) -> Option<String> {
+    let mut words = output.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("rustc"), Some(version)) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+/// Version and sysroot reported by the `rustc` binary itself.
+fn probe_rustc(rustc: &Path, strategy: DiscoveryStrategy) -> Result<RustcNixInfo, String> {
+    let version_output = run_rustc(rustc, &["--version"])?;
+    let version = parse_rustc_version(&version_output)
+        .ok_or_else(|| format!("Unexpected `rustc --version` output: {}", version_output))?;
+    let sysroot = PathBuf::from(run_rustc(rustc, &["--print", "sysroot"])?);
+    Ok(sysroot_info(rustc.to_path_buf(), version, sysroot, strategy))
+}
+
+/// A toolchain directory (`<sysroot>/bin/rustc`), probed when its rustc runs
+/// and otherwise described from its name.
+fn toolchain_info(dir: &Path, strategy: DiscoveryStrategy) -> RustcNixInfo {
+    let rustc = dir.join("bin").join("rustc");
+    probe_rustc(&rustc, strategy).unwrap_or_else(|_| {
+        let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        sysroot_info(rustc, version_from_name(&name), dir.to_path_buf(), strategy)
+    })
+}
+
+fn sysroot_info(rustc_path: PathBuf, version: String, sysroot: PathBuf, strategy: DiscoveryStrategy) -> RustcNixInfo {
+    let rust_src = rust_src_component(&sysroot);
+    RustcNixInfo {
+        version,
+        strategy: Some(strategy),
+        rustc_path,
+        store_path: nix_store_root(&sysroot).map(|p| p.display().to_string()).unwrap_or_default(),
+        unpacked_path: rust_src.clone(),
+        rust_src,
+        sysroot: Some(sysroot),
+        ..Default::default()
+    }
+}
+
+/// `<sysroot>/lib/rustlib/src/rust`, if the `rust-src` component is there.
+#[decl(fn, name = "rust_src_component", vis = "pub", hash = "9a5675b4")]
+pub fn rust_src_component(sysroot: &Path) -> Option<PathBuf> {
+    let src = sysroot.join("lib").join("rustlib").join("src").join("rust");
+    src.join("library").is_dir().then_some(src)
+}
+
+/// `/nix/store/<hash>-<name>` prefix of a store path.
+pub(crate) fn nix_store_root(path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix("/nix/store").ok()?;
+    let entry = relative.components().next()?;
+    Some(Path::new("/nix/store").join(entry))
+}
+
+/// First `-`-separated part starting with a digit (`1.79.0` in
+/// `rustc-wrapper-1.79.0`), or the channel (`stable-x86_64-...`).
+fn version_from_name(name: &str) -> String {
+    name.split('-')
+        .find(|p| p.starts_with(|c: char| c.is_ascii_digit()))
+        .or_else(|| name.split('-').next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn version_key(version: &str) -> Vec<u64> {
+    version.split(['.', '-']).map_while(|p| p.parse().ok()).collect()
+}
+
+/// Toolchain directories under `<rustup_home>/toolchains` with a
+/// `bin/rustc`, the default toolchain of `settings.toml` first.
+#[decl(fn, name = "rustup_toolchains", vis = "pub", hash = "676a1afb")]
+pub fn rustup_toolchains(rustup_home: &Path) -> Vec<PathBuf> {
+    let default = fs::read_to_string(rustup_home.join("settings.toml")).ok()
+        .and_then(|s| s.parse::<toml::Table>().ok())
+        .and_then(|t| t.get("default_toolchain")?.as_str().map(str::to_string));
+    let mut toolchains: Vec<PathBuf> = fs::read_dir(rustup_home.join("toolchains"))
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    toolchains.retain(|t| t.join("bin").join("rustc").is_file());
+    toolchains.sort_by_key(|t| {
+        let name = t.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let is_default = default.as_ref().is_some_and(|d| name == *d || name.starts_with(&format!("{}-", d)));
+        (!is_default, name)
+    });
+    toolchains
+}
+
+/// `(version, dir)` of each store entry with a `bin/rustc`, newest first.
+#[decl(fn, name = "nix_store_rustcs", vis = "pub", hash = "bb057375")]
+pub fn nix_store_rustcs(store: &Path) -> Vec<(String, PathBuf)> {
+    let mut found: Vec<(String, PathBuf)> = fs::read_dir(store)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|dir| dir.join("bin").join("rustc").is_file())
+        .filter_map(|dir| {
+            let name = dir.file_name()?.to_string_lossy().into_owned();
+            let (_, name) = name.split_once('-')?;
+            name.contains("rust").then(|| (version_from_name(name), dir))
+        })
+        .collect();
+    found.sort_by(|(a, da), (b, db)| version_key(b).cmp(&version_key(a)).then(da.cmp(db)));
+    found
+}
+
+/// Source and derivation of `nixpkgs#rustc`, from `nix eval --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[decl(struct, name = "NixRustcEval", vis = "pub", hash = "8ccdb755")]
+pub struct NixRustcEval {
+    pub version: String,
+    pub src: String,
+    #[serde(default)]
+    pub urls: Vec<String>,
+    #[serde(rename = "drvPath")]
+    pub drv_path: String,
+    #[serde(rename = "outPath")]
+    pub out_path: String,
+}
+
+impl NixRustcEval {
+    /// Fills the source fields of `info`, unless nixpkgs has another version.
+    pub fn apply_to(&self, info: &mut RustcNixInfo) {
+        if !info.version.is_empty() && info.version != self.version {
+            return;
+        }
+        info.version = self.version.clone();
+        info.src_tarball = self.src.clone();
+        info.src_url = self.urls.first().cloned().unwrap_or_default();
+        info.drv_path = self.drv_path.clone();
+        if info.store_path.is_empty() {
+            info.store_path = self.out_path.clone();
+        }
+    }
+}
+
+/// The `--apply` function picking the `NixRustcEval` fields of a rustc.
+fn nix_rustc_apply() -> String {
+    let r = Expr::var("r");
+    Expr::lambda(Param::Var("r".to_string()), Expr::attrs([
+        ("version", r.clone().select("version")),
+        ("src", Expr::interpolated([StrPart::Interp(r.clone().select("src"))])),
+        ("urls", r.clone().select_or("src.urls", Expr::list([]))),
+        ("drvPath", r.clone().select("drvPath")),
+        ("outPath", r.select("outPath")),
+    ])).to_nix()
+}
+
+#[decl(fn, name = "parse_nix_rustc_eval", vis = "pub", hash = "dc4a58bb")]
+pub fn parse_nix_rustc_eval(json: &str) -> Result<NixRustcEval, String> {
+    serde_json::from_str(json).map_err(|e| format!("Unexpected nix eval output: {}", e))
+}
+
+fn query_nix_rustc() -> Result<NixRustcEval, String> {
+    let output = Command::new("nix")
+        .args(["eval", "--json", "nixpkgs#rustc", "--apply", &nix_rustc_apply()])
         .output()
-        .map_err(|e| format!("Failed to get store path: {}", e))?;
-    
-    if store_output.status.success() {
-        info.store_path = String::from_utf8_lossy(&store_output.stdout).trim().to_string();
+        .map_err(|e| format!("Failed to query nix: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("nix eval failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
     }
-    
-    Ok(info)
+    parse_nix_rustc_eval(&String::from_utf8_lossy(&output.stdout))
 }
 
-#[decl(fn, name = "unpack_rustc_source", vis = "pub", hash = "17f6ce8b")]
+/// Finds the active rustc, trying `$RUSTC`, the `rustc` on PATH, rustup
+/// toolchains and the Nix store in that order.
+#[decl(fn, name = "find_current_rustc", vis = "pub", hash = "f6d46857")]
+pub fn find_current_rustc() -> Result<RustcNixInfo, String> {
+    RustcDiscovery::from_env().discover()
+}
+
+#[decl(fn, name = "unpack_rustc_source", vis = "pub", hash = "9563f270")]
 pub fn unpack_rustc_source(info: &RustcNixInfo, target_dir: &Path) -> Result<PathBuf, String> {
     if info.src_tarball.is_empty() {
-        return Err("No source tarball found".to_string());
+        // Without Nix the installed rust-src component is already unpacked.
+        return info.rust_src.clone()
+            .ok_or_else(|| "No source tarball or rust-src component found".to_string());
     }
     
     let tarball_path = Path::new(&info.src_tarball);
@@ -156,145 +500,336 @@ pub fn unpack_rustc_source(info: &RustcNixInfo, target_dir: &Path) -> Result<Pat
     Ok(extracted_dir)
 }
 
-#[decl(fn, name = "analyze_rustc_source", vis = "pub", hash = "b71ac192")]
-pub fn analyze_rustc_source(source_dir: &Path) -> Result<RustcSourceStats, String> {
-    let mut stats = RustcSourceStats {
-        total_files: 0,
-        rust_files: 0,
-        build_rs_files: 0,
-        lib_rs_files: 0,
-        total_lines: 0,
-        declarations: 0,
-        modules: vec![],
-    };
-    
-    // Find all Rust files
-    let find_output = Command::new("find")
-        .args([source_dir.to_str().unwrap(), "-name", "*.rs", "-type", "f"])
-        .output()
-        .map_err(|e| format!("Failed to find files: {}", e))?;
-    
-    let stdout_str = String::from_utf8_lossy(&find_output.stdout).into_owned(); // Create an owned String
-    let files: Vec<&str> = stdout_str
-        .lines()
-        .filter(|s| !s.is_empty())
-        .collect();
-    
-    stats.rust_files = files.len();
-    
-    // Count build.rs and lib.rs files
-    for file in &files {
-        if file.ends_with("build.rs") {
-            stats.build_rs_files += 1;
+struct ItemStatsVisitor<'a> {
+    stats: &'a mut CrateStats,
+}
+
+impl ItemStatsVisitor<'_> {
+    fn count(&mut self, kind: &str, vis: Option<&syn::Visibility>) {
+        let counts = self.stats.items.entry(kind.to_string()).or_default();
+        match vis {
+            Some(syn::Visibility::Public(_)) => counts.public += 1,
+            _ => counts.private += 1,
         }
-        if file.ends_with("lib.rs") {
-            stats.lib_rs_files += 1;
+    }
+
+    fn count_fn(&mut self, kind: &str, sig: &syn::Signature, vis: Option<&syn::Visibility>) {
+        self.count(kind, vis);
+        if sig.unsafety.is_some() {
+            self.stats.unsafe_fns += 1;
         }
-        
-        // Extract module names from compiler directory
-        if file.contains("/compiler/") {
-            if let Some(module) = file
-                .split("/compiler/")
-                .nth(1)
-                .and_then(|s| s.split('/').next())
-            {
-                if !stats.modules.contains(&module.to_string()) {
-                    stats.modules.push(module.to_string());
+    }
+}
+
+impl<'ast> Visit<'ast> for ItemStatsVisitor<'_> {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        match item {
+            syn::Item::Fn(f) => {
+                self.count_fn("fn", &f.sig, Some(&f.vis));
+                let is_proc_macro = f.attrs.iter().any(|a| {
+                    a.path().get_ident().is_some_and(|i| i == "proc_macro" || i == "proc_macro_attribute" || i == "proc_macro_derive")
+                });
+                if is_proc_macro {
+                    self.stats.proc_macros += 1;
+                }
+            }
+            syn::Item::Struct(i) => self.count("struct", Some(&i.vis)),
+            syn::Item::Enum(i) => self.count("enum", Some(&i.vis)),
+            syn::Item::Union(i) => self.count("union", Some(&i.vis)),
+            syn::Item::Trait(i) => self.count("trait", Some(&i.vis)),
+            syn::Item::Type(i) => self.count("type", Some(&i.vis)),
+            syn::Item::Const(i) => self.count("const", Some(&i.vis)),
+            syn::Item::Static(i) => self.count("static", Some(&i.vis)),
+            syn::Item::Mod(i) => self.count("mod", Some(&i.vis)),
+            syn::Item::Impl(i) => {
+                self.count("impl", None);
+                if i.unsafety.is_some() {
+                    self.stats.unsafe_impls += 1;
                 }
             }
+            syn::Item::Macro(m) if m.ident.is_some() => self.stats.macro_rules += 1,
+            _ => {}
         }
+        visit::visit_item(self, item);
     }
-    
-    // Count lines and declarations in a sample
-    let sample_files: Vec<_> = files.iter().take(100).collect();
-    for file in &sample_files {
-        if let Ok(content) = fs::read_to_string(file) {
-            stats.total_lines += content.lines().count();
-            stats.declarations += content.matches("pub fn ").count();
-            stats.declarations += content.matches("pub struct ").count();
-            stats.declarations += content.matches("pub enum ").count();
-            stats.declarations += content.matches("pub trait ").count();
+
+    fn visit_impl_item_fn(&mut self, f: &'ast syn::ImplItemFn) {
+        self.count_fn("method", &f.sig, Some(&f.vis));
+        visit::visit_impl_item_fn(self, f);
+    }
+
+    fn visit_trait_item_fn(&mut self, f: &'ast syn::TraitItemFn) {
+        self.count_fn("method", &f.sig, None);
+        visit::visit_trait_item_fn(self, f);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.stats.unsafe_blocks += 1;
+        visit::visit_expr_unsafe(self, expr);
+    }
+}
+
+/// Parses every source file of the crate at `dir`.
+#[decl(fn, name = "analyze_crate_source", vis = "pub", hash = "111a866a")]
+pub fn analyze_crate_source(name: &str, dir: &Path) -> CrateStats {
+    let mut stats = CrateStats { name: name.to_string(), ..Default::default() };
+    for path in crate_sources(dir) {
+        let display = path.strip_prefix(dir).unwrap_or(&path).display().to_string();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                stats.parse_failures.push(format!("{}: {}", display, e));
+                continue;
+            }
+        };
+        stats.files += 1;
+        stats.lines += content.lines().count();
+        match syn::parse_file(&content) {
+            Ok(file) => ItemStatsVisitor { stats: &mut stats }.visit_file(&file),
+            Err(e) => stats.parse_failures.push(format!("{}:{}: {}", display, e.span().start().line, e)),
         }
     }
-    
-    // Extrapolate for full codebase
-    if !files.is_empty() {
-        let ratio = files.len() as f64 / sample_files.len().max(1) as f64;
-        stats.total_lines = (stats.total_lines as f64 * ratio) as usize;
-        stats.declarations = (stats.declarations as f64 * ratio) as usize;
+    stats
+}
+
+/// `f` applied to every element on a pool of scoped threads, in order.
+fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(items.len().max(1));
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(|| {
+            let mut done = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                done.push((i, f(item)));
+            }
+            done
+        })).collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Statistics of a rustc source tree: file counts over the whole tree and a
+/// syn walk, one thread per crate, of every `compiler/rustc_*` crate.
+#[decl(fn, name = "analyze_rustc_source", vis = "pub", hash = "fb3158b7")]
+pub fn analyze_rustc_source(source_dir: &Path) -> Result<RustcSourceStats, String> {
+    if !source_dir.is_dir() {
+        return Err(format!("Not a directory: {}", source_dir.display()));
     }
-    
-    stats.modules.sort();
-    
+    let mut stats = RustcSourceStats::default();
+
+    for entry in WalkDir::new(source_dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+        stats.total_files += 1;
+        let name = entry.file_name().to_string_lossy();
+        if name.ends_with(".rs") {
+            stats.rust_files += 1;
+        }
+        if name == "build.rs" {
+            stats.build_rs_files += 1;
+        }
+        if name == "lib.rs" {
+            stats.lib_rs_files += 1;
+        }
+    }
+
+    let mut crate_dirs: Vec<(String, PathBuf)> = fs::read_dir(source_dir.join("compiler"))
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|dir| dir.join("Cargo.toml").is_file())
+        .filter_map(|dir| {
+            let name = dir.file_name()?.to_string_lossy().into_owned();
+            name.starts_with("rustc_").then_some((name, dir))
+        })
+        .collect();
+    crate_dirs.sort();
+
+    stats.crates = parallel_map(&crate_dirs, |(name, dir)| analyze_crate_source(name, dir));
+    stats.modules = crate_dirs.into_iter().map(|(name, _)| name).collect();
+    stats.total_lines = stats.crates.iter().map(|c| c.lines).sum();
+    stats.declarations = stats.crates.iter().map(|c| c.total_items().public).sum();
+    stats.parse_failures = stats.crates.iter().map(|c| c.parse_failures.len()).sum();
+
     Ok(stats)
 }
 
-#[decl(fn, name = "apply_decl_wrappers_to_rustc", vis = "pub", hash = "8c6261ea")]
-pub fn apply_decl_wrappers_to_rustc(source_dir: &Path, output_dir: &Path) -> Result<usize, String> {
-    use crate::decl_wrapper::{extract_declarations, generate_decl_attribute};
-    
+/// Crates wrapped when `apply_decl_wrappers_to_rustc` is given none.
+pub const DEFAULT_WRAP_CRATES: &[&str] = &[
+    "rustc_driver",
+    "rustc_interface",
+    "rustc_middle",
+    "rustc_ast",
+    "rustc_hir",
+    "rustc_parse",
+    "rustc_expand",
+    "rustc_codegen_ssa",
+];
+
+/// One `#[decl]` attribute added to the compiler, for the manifest.
+#[derive(Debug, Clone, Serialize)]
+#[decl(struct, name = "WrappedItem", vis = "pub", hash = "dfba14ff")]
+pub struct WrappedItem {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// Path in the rustc source tree, `/`-separated.
+    pub file: String,
+    /// Line of the item in the unpatched file.
+    pub line: usize,
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+    pub hash: String,
+}
+
+/// The patch of one crate, relative to the output directory.
+#[derive(Debug, Clone, Serialize)]
+#[decl(struct, name = "CratePatch", vis = "pub", hash = "9d399fba")]
+pub struct CratePatch {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub patch: String,
+    pub files: Vec<String>,
+    pub items: usize,
+}
+
+/// Result of `apply_decl_wrappers_to_rustc`: modified files under
+/// `overlay/`, one patch per crate under `patches/`, `patches.nix` and
+/// `manifest.json`, all in the output directory.
+#[derive(Debug, Clone, Default, Serialize)]
+#[decl(struct, name = "RustcPatchSeries", vis = "pub", hash = "576755da")]
+pub struct RustcPatchSeries {
+    pub source: PathBuf,
+    pub output: PathBuf,
+    pub patches: Vec<CratePatch>,
+    pub items: Vec<WrappedItem>,
+    /// `file: error` of the files that could not be read.
+    pub errors: Vec<String>,
+}
+
+impl RustcPatchSeries {
+    pub fn total_wrapped(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn overlay_dir(&self) -> PathBuf {
+        self.output.join("overlay")
+    }
+
+    pub fn to_manifest_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// A Nix list of the patches, in order, for `patches = ... ++ import ./patches.nix;`.
+    pub fn nix_patch_list(&self) -> String {
+        let patches = Expr::list(self.patches.iter().map(|patch| Expr::path(&format!("./{}", patch.patch))));
+        nix_file(&[
+            "Generated by apply_decl_wrappers_to_rustc; use as",
+            "  patches = (old.patches or []) ++ import ./patches.nix;",
+        ], &patches)
+    }
+}
+
+/// Wraps the public items of `crates` (default: `DEFAULT_WRAP_CRATES`) of
+/// the rustc source at `source_dir` with `#[decl]`, without touching it:
+/// modified files go to `output_dir/overlay`, and each crate's changes to a
+/// unified-diff patch (`patch -p1` in the source root) under
+/// `output_dir/patches`.
+#[decl(fn, name = "apply_decl_wrappers_to_rustc", vis = "pub", hash = "69443c08")]
+pub fn apply_decl_wrappers_to_rustc(source_dir: &Path, output_dir: &Path, crates: &[&str]) -> Result<RustcPatchSeries, String> {
+    use crate::decl_wrapper::{insert_wrap_actions, wrap_public_declarations};
+
+    let source = source_dir.canonicalize()
+        .map_err(|e| format!("Failed to read {}: {}", source_dir.display(), e))?;
     fs::create_dir_all(output_dir)
         .map_err(|e| format!("Failed to create output dir: {}", e))?;
-    
-    // Find key files to wrap
-    let key_patterns = [
-        "compiler/rustc_driver/src/lib.rs",
-        "compiler/rustc_interface/src/lib.rs",
-        "compiler/rustc_middle/src/lib.rs",
-        "compiler/rustc_ast/src/lib.rs",
-        "compiler/rustc_hir/src/lib.rs",
-        "compiler/rustc_parse/src/lib.rs",
-        "compiler/rustc_expand/src/lib.rs",
-        "compiler/rustc_codegen_ssa/src/lib.rs",
-    ];
-    
-    let mut total_wrapped = 0;
-    
-    for pattern in &key_patterns {
-        let src_file = source_dir.join(pattern);
-        if !src_file.exists() {
-            continue;
+    let output = output_dir.canonicalize()
+        .map_err(|e| format!("Failed to read {}: {}", output_dir.display(), e))?;
+    if output.starts_with(&source) {
+        return Err(format!("Output {} must be outside the rustc source {}", output.display(), source.display()));
+    }
+    for generated in ["overlay", "patches"] {
+        let dir = output.join(generated);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear {}: {}", dir.display(), e))?;
         }
-        
-        let content = fs::read_to_string(&src_file)
-            .map_err(|e| format!("Failed to read {}: {}", pattern, e))?;
-        
-        let decls = extract_declarations(&content);
-        let public_decls: Vec<_> = decls.iter()
-            .filter(|d| d.visibility == crate::decl_wrapper::VisibilityKind::Public)
+    }
+
+    let crates = if crates.is_empty() { DEFAULT_WRAP_CRATES } else { crates };
+    let mut series = RustcPatchSeries { source: source.clone(), output: output.clone(), ..Default::default() };
+
+    for crate_name in crates {
+        let crate_src = source.join("compiler").join(crate_name).join("src");
+        let files: Vec<PathBuf> = WalkDir::new(&crate_src).sort_by_file_name().into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(|e| e.into_path())
             .collect();
-        
-        if public_decls.is_empty() {
-            continue;
-        }
-        
-        // Generate wrapped version
-        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-        let mut offset = 0;
-        
-        for decl in &public_decls {
-            let insert_line = decl.line.saturating_sub(1) + offset;
-            if insert_line < lines.len() {
-                let attr = generate_decl_attribute(decl);
-                lines.insert(insert_line, attr);
-                offset += 1;
-                total_wrapped += 1;
+
+        let mut patch = String::new();
+        let mut patched_files = Vec::new();
+        let mut items = 0;
+        for path in files {
+            let relative = path.strip_prefix(&source).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    series.errors.push(format!("{}: {}", relative, e));
+                    continue;
+                }
+            };
+            let actions = wrap_public_declarations(&content);
+            if actions.is_empty() {
+                continue;
+            }
+
+            for action in &actions {
+                series.items.push(WrappedItem {
+                    crate_name: crate_name.to_string(),
+                    file: relative.clone(),
+                    line: action.line_start,
+                    kind: action.metadata.node_type.as_str().to_string(),
+                    name: action.metadata.name.clone(),
+                    path: action.metadata.path.clone(),
+                    hash: action.metadata.semantic_hash.clone(),
+                });
             }
+            items += actions.len();
+
+            let wrapped = insert_wrap_actions(&content, &actions);
+            let overlay_file = output.join("overlay").join(&relative);
+            if let Some(parent) = overlay_file.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::write(&overlay_file, &wrapped)
+                .map_err(|e| format!("Failed to write {}: {}", overlay_file.display(), e))?;
+
+            patch.push_str(&diff_text(&relative, &content, &wrapped, DiffOptions::default()).to_string());
+            patched_files.push(relative);
         }
-        
-        // Write to output directory
-        let out_file = output_dir.join(pattern);
-        if let Some(parent) = out_file.parent() {
-            fs::create_dir_all(parent).ok();
+
+        if patched_files.is_empty() {
+            continue;
         }
-        fs::write(&out_file, lines.join("\n"))
-            .map_err(|e| format!("Failed to write {}: {}", pattern, e))?;
+        let name = format!("patches/{:04}-{}-decl-wrappers.patch", series.patches.len() + 1, crate_name);
+        let header = format!("Wrap public items of {} with #[decl] ({} items)\n\n", crate_name, items);
+        let patch_file = output.join(&name);
+        fs::create_dir_all(output.join("patches")).map_err(|e| format!("Failed to create patches dir: {}", e))?;
+        fs::write(&patch_file, header + &patch)
+            .map_err(|e| format!("Failed to write {}: {}", patch_file.display(), e))?;
+        series.patches.push(CratePatch { crate_name: crate_name.to_string(), patch: name, files: patched_files, items });
     }
-    
-    Ok(total_wrapped)
+
+    fs::write(output.join("patches.nix"), series.nix_patch_list())
+        .map_err(|e| format!("Failed to write patches.nix: {}", e))?;
+    fs::write(output.join("manifest.json"), series.to_manifest_json())
+        .map_err(|e| format!("Failed to write manifest.json: {}", e))?;
+    Ok(series)
 }
 
-#[decl(fn, name = "generate_rustc_introspection_report", vis = "pub", hash = "7c8d404f")]
+#[decl(fn, name = "generate_rustc_introspection_report", vis = "pub", hash = "aec21d32")]
 pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSourceStats) -> String {
     let mut report = String::new();
     
@@ -306,6 +841,8 @@ pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSou
 📋 RUSTC INFO
 ═══════════════════════════════════════════════════════════════════════════════
    Version:      {}
+   Found Via:    {}
+   Rustc:        {}
    Store Path:   {}
    Derivation:   {}
    Source URL:   {}
@@ -316,13 +853,16 @@ pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSou
    Rust Files:       {:>8}
    Build.rs Files:   {:>8}
    Lib.rs Files:     {:>8}
-   Total Lines:      {:>8} (estimated)
-   Declarations:     {:>8} (estimated)
+   Total Lines:      {:>8}
+   Declarations:     {:>8} (public items)
+   Parse Failures:   {:>8}
 
 📁 COMPILER MODULES ({})
 ═══════════════════════════════════════════════════════════════════════════════
 "#,
         info.version,
+        info.strategy.map_or("unknown", |s| s.describe()),
+        info.rustc_path.display(),
         info.store_path,
         info.drv_path,
         info.src_url,
@@ -332,6 +872,7 @@ pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSou
         stats.lib_rs_files,
         stats.total_lines,
         stats.declarations,
+        stats.parse_failures,
         stats.modules.len(),
     ));
     
@@ -345,6 +886,12 @@ pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSou
         }
     }
     report.push('\n');
+
+    if !stats.crates.is_empty() {
+        report.push_str("\n📦 PER-CRATE STATISTICS\n");
+        report.push_str("═══════════════════════════════════════════════════════════════════════════════\n");
+        report.push_str(&stats.summary_table());
+    }
     
     report.push_str(r#"
 🔧 INTROSPECTION STATUS
@@ -363,7 +910,7 @@ pub fn generate_rustc_introspection_report(info: &RustcNixInfo, stats: &RustcSou
     report
 }
 
-#[decl(fn, name = "print_rustc_info", vis = "pub", hash = "b92771af")]
+#[decl(fn, name = "print_rustc_info", vis = "pub", hash = "e6a03978")]
 pub fn print_rustc_info() {
     match find_current_rustc() {
         Ok(info) => {
@@ -375,6 +922,14 @@ pub fn print_rustc_info() {
             eprintln!("\n{}🦀 RUSTC NIX INFORMATION{}", cyan, reset);
             eprintln!("{}", "═".repeat(60));
             eprintln!("{}Version:{} {}", green, reset, info.version);
+            eprintln!("{}Found Via:{} {}", green, reset, info.strategy.map_or("unknown", |s| s.describe()));
+            eprintln!("{}Rustc:{} {}", green, reset, info.rustc_path.display());
+            if let Some(sysroot) = &info.sysroot {
+                eprintln!("{}Sysroot:{} {}", green, reset, sysroot.display());
+            }
+            if let Some(src) = &info.rust_src {
+                eprintln!("{}rust-src:{} {}", yellow, reset, src.display());
+            }
             eprintln!("{}Store Path:{} {}", green, reset, info.store_path);
             eprintln!("{}Derivation:{} {}", green, reset, info.drv_path);
             eprintln!("{}Source URL:{} {}", yellow, reset, info.src_url);
@@ -421,4 +976,188 @@ mod tests {
             assert!(result.is_ok() || result.is_err()); // Just check it doesn't panic
         }
     }
+
+    #[test]
+    fn test_parse_rustc_version_and_nix_eval() {
+        assert_eq!(parse_rustc_version("rustc 1.79.0 (129f3b996 2024-06-10)\n"), Some("1.79.0".to_string()));
+        assert_eq!(parse_rustc_version("cargo 1.79.0"), None);
+
+        let eval = parse_nix_rustc_eval(r#"{"version":"1.79.0","src":"/nix/store/abc-rustc-1.79.0-src.tar.gz",
+            "urls":["https://static.rust-lang.org/dist/rustc-1.79.0-src.tar.gz"],
+            "drvPath":"/nix/store/def-rustc-1.79.0.drv","outPath":"/nix/store/ghi-rustc-1.79.0"}"#).unwrap();
+        let mut info = RustcNixInfo { version: "1.79.0".to_string(), ..Default::default() };
+        eval.apply_to(&mut info);
+        assert_eq!(info.src_url, "https://static.rust-lang.org/dist/rustc-1.79.0-src.tar.gz");
+        assert_eq!(info.store_path, "/nix/store/ghi-rustc-1.79.0");
+
+        let mut other = RustcNixInfo { version: "1.80.0".to_string(), ..Default::default() };
+        eval.apply_to(&mut other);
+        assert!(other.src_tarball.is_empty());
+        assert!(parse_nix_rustc_eval("[]").is_err());
+    }
+
+    #[test]
+    fn test_analyze_rustc_source() {
+        let root = std::env::temp_dir().join(format!("rustc_source_stats_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("compiler/rustc_span/Cargo.toml", "[package]\nname = \"rustc_span\"\n");
+        write("compiler/rustc_span/src/lib.rs", r#"
+// pub fn in_a_comment() {}
+pub struct Span(u32);
+pub(crate) const DUMMY: &str = "pub fn in_a_string() {}";
+macro_rules! span { () => { pub fn in_a_macro() {} } }
+impl Span {
+    pub fn lo(&self) -> u32 { self.0 }
+    fn raw(&self) -> *const u32 { unsafe { std::ptr::addr_of!(self.0) } }
+}
+unsafe impl Sync for Span {}
+mod inner { pub unsafe fn danger() {} }
+"#);
+        write("compiler/rustc_span/build.rs", "fn main() {}\n");
+        write("compiler/rustc_broken/Cargo.toml", "[package]\nname = \"rustc_broken\"\n");
+        write("compiler/rustc_broken/src/lib.rs", "pub fn ok() {}\n");
+        write("compiler/rustc_broken/src/bad.rs", "pub fn {\n");
+        write("compiler/not_rustc/Cargo.toml", "[package]\nname = \"not_rustc\"\n");
+        write("library/std/src/lib.rs", "pub fn std_item() {}\n");
+
+        let stats = analyze_rustc_source(&root).unwrap();
+        assert_eq!(stats.modules, vec!["rustc_broken", "rustc_span"]);
+        assert_eq!((stats.rust_files, stats.build_rs_files, stats.lib_rs_files), (5, 1, 3));
+
+        let span = &stats.crates[1];
+        assert_eq!(span.files, 2);
+        assert_eq!(span.items["fn"], ItemCounts { public: 1, private: 1 });
+        assert_eq!(span.items["method"], ItemCounts { public: 1, private: 1 });
+        assert_eq!(span.items["const"], ItemCounts { public: 0, private: 1 });
+        assert_eq!(span.items["struct"].public, 1);
+        assert_eq!(span.items["impl"].total(), 2);
+        assert_eq!((span.unsafe_blocks, span.unsafe_fns, span.unsafe_impls), (1, 1, 1));
+        assert_eq!(span.macro_rules, 1);
+        assert!(span.parse_failures.is_empty());
+
+        let broken = &stats.crates[0];
+        assert_eq!(broken.total_items().public, 1);
+        assert_eq!(broken.parse_failures.len(), 1);
+        assert!(broken.parse_failures[0].starts_with("src/bad.rs:1:"), "{:?}", broken.parse_failures);
+        assert_eq!(stats.parse_failures, 1);
+        // Span, lo, danger and ok; comments, strings and macro bodies do not count.
+        assert_eq!(stats.declarations, 4);
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(json["crates"][1]["items"]["method"]["public"], 1);
+        let table = stats.summary_table();
+        assert!(table.lines().nth(2).unwrap().starts_with("rustc_span"));
+        assert!(table.lines().last().unwrap().starts_with("TOTAL"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_apply_decl_wrappers_to_rustc() {
+        let root = std::env::temp_dir().join(format!("rustc_patch_series_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let source = root.join("rustc-src");
+        let write = |path: &str, content: &str| {
+            let path = source.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        let filler = "// filler\n".repeat(10);
+        let lib = format!("pub fn first() {{}}\n{}fn private() {{}}\n{}pub struct Last;", filler, filler);
+        write("compiler/rustc_ast/src/lib.rs", &lib);
+        write("compiler/rustc_ast/src/token.rs", "use std::fmt;\n\npub enum Token {\n    Ident,\n}\n");
+        write("compiler/rustc_hir/src/lib.rs", "fn nothing_public() {}\n");
+
+        let out = root.join("wrapped");
+        let series = apply_decl_wrappers_to_rustc(&source, &out, &["rustc_ast", "rustc_hir"]).unwrap();
+        assert_eq!(series.total_wrapped(), 3);
+        assert_eq!(series.patches.len(), 1);
+        assert_eq!(series.patches[0].patch, "patches/0001-rustc_ast-decl-wrappers.patch");
+        assert_eq!(series.patches[0].files, vec!["compiler/rustc_ast/src/lib.rs", "compiler/rustc_ast/src/token.rs"]);
+
+        // The source is untouched; the overlay holds the wrapped files only.
+        assert_eq!(fs::read_to_string(source.join("compiler/rustc_ast/src/lib.rs")).unwrap(), lib);
+        assert!(!out.join("overlay/compiler/rustc_hir").exists());
+        let token = series.items.iter().find(|i| i.name == "Token").unwrap();
+        let attr = format!("#[decl(enum, name = \"Token\", vis = \"pub\", hash = \"{}\")]", token.hash);
+        assert_eq!(token.line, 3);
+        assert_eq!(
+            fs::read_to_string(out.join("overlay/compiler/rustc_ast/src/token.rs")).unwrap(),
+            format!("use std::fmt;\n\n{}\npub enum Token {{\n    Ident,\n}}\n", attr),
+        );
+
+        let patch = fs::read_to_string(out.join(&series.patches[0].patch)).unwrap();
+        assert!(patch.starts_with("Wrap public items of rustc_ast with #[decl] (3 items)\n\ndiff --git a/compiler/rustc_ast/src/lib.rs"));
+        // Items 23 lines apart get separate hunks; the last line has no newline.
+        assert!(patch.contains("@@ -1,3 +1,4 @@\n+#[decl(fn, name = \"first\""));
+        assert!(patch.contains("@@ -20,4 +21,5 @@\n // filler\n // filler\n // filler\n+#[decl(struct, name = \"Last\""));
+        assert!(patch.contains(" pub struct Last;\n\\ No newline at end of file\n"));
+        assert!(patch.ends_with(&format!(
+            "+++ b/compiler/rustc_ast/src/token.rs\n@@ -1,5 +1,6 @@\n use std::fmt;\n \n+{}\n pub enum Token {{\n     Ident,\n }}\n",
+            attr,
+        )));
+
+        let nix = fs::read_to_string(out.join("patches.nix")).unwrap();
+        assert!(nix.ends_with("[\n  ./patches/0001-rustc_ast-decl-wrappers.patch\n]\n"));
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(out.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["items"][0]["crate"], "rustc_ast");
+        assert_eq!(manifest["patches"][0]["items"], 3);
+
+        assert!(apply_decl_wrappers_to_rustc(&source, &source.join("out"), &[]).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_layered_discovery() {
+        let root = std::env::temp_dir().join(format!("rustc_discovery_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        // The fixture rustc binaries are empty files: probing fails and the
+        // toolchain is described from its directory.
+        let toolchain = |dir: PathBuf| {
+            fs::create_dir_all(dir.join("bin")).unwrap();
+            fs::write(dir.join("bin").join("rustc"), "").unwrap();
+            dir
+        };
+        let rustup = root.join("rustup");
+        toolchain(rustup.join("toolchains/1.78.0-x86_64-unknown-linux-gnu"));
+        let stable = toolchain(rustup.join("toolchains/stable-x86_64-unknown-linux-gnu"));
+        fs::create_dir_all(stable.join("lib/rustlib/src/rust/library")).unwrap();
+        fs::write(rustup.join("settings.toml"), "default_toolchain = \"stable\"\n").unwrap();
+        let store = root.join("store");
+        toolchain(store.join("aaaa-rustc-1.79.0"));
+        let newest = toolchain(store.join("bbbb-rustc-wrapper-1.80.0"));
+        fs::create_dir_all(store.join("cccc-rust-src-1.81.0")).unwrap();
+
+        let mut discovery = RustcDiscovery {
+            rustc_env: Some(root.join("missing-rustc")),
+            use_path: false,
+            rustup_home: Some(rustup.clone()),
+            nix_store: store.clone(),
+            query_nix: false,
+        };
+        let info = discovery.discover().unwrap();
+        assert_eq!(info.strategy, Some(DiscoveryStrategy::RustupToolchain));
+        assert_eq!(info.version, "stable");
+        assert_eq!(info.sysroot.as_deref(), Some(stable.as_path()));
+        assert_eq!(info.rust_src, Some(stable.join("lib/rustlib/src/rust")));
+        assert_eq!(unpack_rustc_source(&info, &root.join("unpacked")), Ok(stable.join("lib/rustlib/src/rust")));
+
+        discovery.rustup_home = Some(root.join("no-rustup"));
+        let info = discovery.discover().unwrap();
+        assert_eq!(info.strategy, Some(DiscoveryStrategy::NixStore));
+        assert_eq!(info.version, "1.80.0");
+        assert_eq!(info.rustc_path, newest.join("bin/rustc"));
+        assert_eq!(info.rust_src, None);
+
+        discovery.nix_store = root.join("no-store");
+        let error = discovery.discover().unwrap_err();
+        for strategy in DiscoveryStrategy::ALL {
+            assert!(error.contains(strategy.describe()), "{}", error);
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
 }
\ No newline at end of file
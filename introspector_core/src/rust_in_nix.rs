@@ -0,0 +1,420 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use proc_macro2::LineColumn;
+use serde::Serialize;
+use syn::spanned::Spanned;
+use walkdir::WalkDir;
+use nix_core::{default_store, scan_store_rustcs, RustcQuery, StoreRustc};
+use crate::unified_diff::{diff_text, DiffOptions};
+
+// `RustInNix!`: wrap the build scripts of a rustc source tree for a
+// toolchain from the Nix store. Nothing is touched until the plan is
+// executed: `plan` picks the toolchain and computes every file change,
+// `dry_run` shows them as a diff, and `execute` stages all new contents
+// before renaming any into place, restoring the originals if a step fails.
+//
+// A wrapped build.rs keeps the original `main` (renamed) and exports the
+// toolchain version to the crate as `RUST_IN_NIX_TOOLCHAIN`; bootstrap.py
+// sets the same variable for everything it builds.
+
+const MARKER: &str = "RustInNix:";
+const ORIGINAL_MAIN: &str = "rust_in_nix_original_main";
+const TOOLCHAIN_ENV: &str = "RUST_IN_NIX_TOOLCHAIN";
+/// Suffix of the staged copy written next to each target by `execute`.
+const STAGED_SUFFIX: &str = ".rust-in-nix.new";
+/// Directories that hold build output or vendored code, not crates to wrap.
+const SKIP_DIRS: &[&str] = &[".git", "target", "build", "vendor"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[decl(enum, name = "Wrapper", vis = "pub", hash = "d2cc6877")]
+pub enum Wrapper {
+    BuildScript,
+    Bootstrap,
+}
+
+impl fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Wrapper::BuildScript => "build.rs wrapper",
+            Wrapper::Bootstrap => "bootstrap.py wrapper",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[decl(struct, name = "RustInNixOptions", vis = "pub", hash = "00cf824c")]
+pub struct RustInNixOptions {
+    /// `stable`, `beta`, `nightly` or a version requirement (`1.79`, `>=1.75`).
+    pub toolchain: String,
+    /// Store roots searched for the toolchain; empty searches
+    /// `$NIX_STORE_DIR` or `/nix/store`.
+    pub stores: Vec<PathBuf>,
+    /// The rustc checkout to wrap. Required: the toolchain's `rust-src` is
+    /// in the read-only store and only ships `library/`.
+    pub source: Option<PathBuf>,
+    /// Only wrap the build scripts of these crates; empty wraps all.
+    pub crates: Vec<String>,
+    pub bootstrap: bool,
+}
+
+impl RustInNixOptions {
+    pub fn new(toolchain: &str) -> Self {
+        RustInNixOptions {
+            toolchain: toolchain.to_string(),
+            stores: Vec::new(),
+            source: None,
+            crates: Vec::new(),
+            bootstrap: true,
+        }
+    }
+
+    pub fn store(mut self, root: impl Into<PathBuf>) -> Self {
+        self.stores.push(root.into());
+        self
+    }
+
+    pub fn source(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.source = Some(dir.into());
+        self
+    }
+
+    pub fn crates<S: Into<String>>(mut self, crates: impl IntoIterator<Item = S>) -> Self {
+        self.crates = crates.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn query(&self) -> Result<RustcQuery, String> {
+        match self.toolchain.as_str() {
+            "stable" | "beta" | "nightly" => RustcQuery::default().channel(&self.toolchain),
+            req => RustcQuery::default().version(req),
+        }
+    }
+
+    /// Picks the toolchain and computes every change, without writing.
+    pub fn plan(&self) -> Result<RustInNixPlan, String> {
+        let stores = if self.stores.is_empty() { vec![default_store()] } else { self.stores.clone() };
+        let candidates = scan_store_rustcs(&stores);
+        let toolchain = self.query()?.select(&candidates).first().map(|c| (*c).clone()).ok_or_else(|| {
+            let searched = stores.iter().map(|s| s.display().to_string()).collect::<Vec<_>>().join(", ");
+            let seen = candidates.iter().map(|c| format!("\n  {}", c)).collect::<String>();
+            format!("No rustc matching `{}` in {}{}", self.toolchain, searched, seen)
+        })?;
+
+        let source = self.source.clone().ok_or_else(|| {
+            format!("No rustc checkout to wrap for {}; pass one as the source (rust-src is read-only and has no compiler)", toolchain)
+        })?;
+        let source = source.canonicalize().map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        if let Some(store) = stores.iter().filter_map(|s| s.canonicalize().ok()).find(|s| source.starts_with(s)) {
+            return Err(format!("{} is in the read-only store {}; copy it to a checkout first", source.display(), store.display()));
+        }
+
+        let version = toolchain.version.as_ref().map_or_else(|| toolchain.name.clone(), |v| v.to_string());
+        let mut plan = RustInNixPlan { toolchain, source: source.clone(), changes: Vec::new(), skipped: Vec::new() };
+
+        let build_scripts = WalkDir::new(&source).sort_by_file_name().into_iter()
+            .filter_entry(|e| !(e.file_type().is_dir() && SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref())))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.file_name() == "build.rs");
+        for entry in build_scripts {
+            let crate_name = crate_name(entry.path().parent().unwrap_or(&source));
+            if !self.crates.is_empty() && !self.crates.contains(&crate_name) {
+                continue;
+            }
+            plan.add(&source, entry.path(), crate_name, Wrapper::BuildScript, |old| wrap_build_script(old, &version));
+        }
+
+        let bootstrap = source.join("src").join("bootstrap").join("bootstrap.py");
+        if self.bootstrap && bootstrap.is_file() {
+            plan.add(&source, &bootstrap, "bootstrap".to_string(), Wrapper::Bootstrap, |old| wrap_bootstrap(old, &version));
+        }
+        Ok(plan)
+    }
+}
+
+/// `package.name` of the `Cargo.toml` in `dir`, else the directory name.
+fn crate_name(dir: &Path) -> String {
+    fs::read_to_string(dir.join("Cargo.toml")).ok()
+        .and_then(|s| s.parse::<toml::Table>().ok())
+        .and_then(|t| t.get("package")?.get("name")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default())
+}
+
+fn offset(content: &str, at: LineColumn) -> usize {
+    let line_start: usize = content.split_inclusive('\n').take(at.line - 1).map(str::len).sum();
+    line_start + content[line_start..].chars().take(at.column).map(char::len_utf8).sum::<usize>()
+}
+
+/// Renames the build script's `main` and adds a `main` that exports the
+/// toolchain version before calling it.
+#[decl(fn, name = "wrap_build_script", vis = "pub", hash = "82c8a247")]
+pub fn wrap_build_script(content: &str, toolchain: &str) -> Result<String, String> {
+    if content.contains(MARKER) {
+        return Err("already wrapped".to_string());
+    }
+    let file = syn::parse_file(content).map_err(|e| format!("does not parse: {}", e))?;
+    let main = file.items.iter()
+        .find_map(|item| match item {
+            syn::Item::Fn(f) if f.sig.ident == "main" => Some(f),
+            _ => None,
+        })
+        .ok_or("has no fn main")?;
+    let name_at = offset(content, main.sig.ident.span().start());
+    let output = match &main.sig.output {
+        syn::ReturnType::Default => String::new(),
+        syn::ReturnType::Type(..) => {
+            let span = main.sig.output.span();
+            format!(" {}", &content[offset(content, span.start())..offset(content, span.end())])
+        }
+    };
+
+    let mut wrapped = format!("// {} build script wrapped for rustc {}; `main` is now `{}`.\n", MARKER, toolchain, ORIGINAL_MAIN);
+    wrapped.push_str(&content[..name_at]);
+    wrapped.push_str(ORIGINAL_MAIN);
+    wrapped.push_str(&content[name_at + "main".len()..]);
+    if !wrapped.ends_with('\n') {
+        wrapped.push('\n');
+    }
+    wrapped.push_str(&format!(
+        "\nfn main(){} {{\n    println!(\"cargo:rustc-env={}={}\");\n    {}()\n}}\n",
+        output, TOOLCHAIN_ENV, toolchain, ORIGINAL_MAIN,
+    ));
+    Ok(wrapped)
+}
+
+/// Sets `RUST_IN_NIX_TOOLCHAIN` just before bootstrap.py's entry point.
+#[decl(fn, name = "wrap_bootstrap", vis = "pub", hash = "22370b38")]
+pub fn wrap_bootstrap(content: &str, toolchain: &str) -> Result<String, String> {
+    if content.contains(MARKER) {
+        return Err("already wrapped".to_string());
+    }
+    let entry = content.find("if __name__ == '__main__':")
+        .or_else(|| content.find("if __name__ == \"__main__\":"))
+        .filter(|&at| at == 0 || content[..at].ends_with('\n'))
+        .ok_or("has no `if __name__ == '__main__':` entry point")?;
+    Ok(format!(
+        "{}# {} bootstrap wrapped for rustc {}\n__import__(\"os\").environ.setdefault(\"{}\", \"{}\")\n\n{}",
+        &content[..entry], MARKER, toolchain, TOOLCHAIN_ENV, toolchain, &content[entry..],
+    ))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[decl(struct, name = "PlannedChange", vis = "pub", hash = "463304a6")]
+pub struct PlannedChange {
+    /// Path in the source tree, `/`-separated.
+    pub file: String,
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub wrapper: Wrapper,
+    #[serde(skip)]
+    pub old: String,
+    #[serde(skip)]
+    pub new: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[decl(struct, name = "RustInNixPlan", vis = "pub", hash = "34802f57")]
+pub struct RustInNixPlan {
+    #[serde(serialize_with = "serialize_toolchain")]
+    pub toolchain: StoreRustc,
+    pub source: PathBuf,
+    pub changes: Vec<PlannedChange>,
+    /// `file: reason` of the candidates left alone.
+    pub skipped: Vec<String>,
+}
+
+fn serialize_toolchain<S: serde::Serializer>(toolchain: &StoreRustc, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(toolchain)
+}
+
+impl RustInNixPlan {
+    fn add(&mut self, source: &Path, path: &Path, crate_name: String, wrapper: Wrapper, wrap: impl Fn(&str) -> Result<String, String>) {
+        let file = path.strip_prefix(source).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let result = fs::read_to_string(path).map_err(|e| e.to_string())
+            .and_then(|old| wrap(&old).map(|new| (old, new)));
+        match result {
+            Ok((old, new)) => self.changes.push(PlannedChange { file, crate_name, wrapper, old, new }),
+            Err(reason) => self.skipped.push(format!("{}: {}", file, reason)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Crate → the wrappers it gets.
+    pub fn crates(&self) -> BTreeMap<String, Vec<Wrapper>> {
+        let mut crates: BTreeMap<String, Vec<Wrapper>> = BTreeMap::new();
+        for change in &self.changes {
+            crates.entry(change.crate_name.clone()).or_default().push(change.wrapper);
+        }
+        crates
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// What `execute` would do: a summary followed by the patch.
+    pub fn dry_run(&self) -> String {
+        let mut out = String::from("🔍 RustInNix plan (dry run, nothing written)\n\n");
+        out.push_str(&format!("Toolchain: {}\n", self.toolchain));
+        out.push_str(&format!("Source:    {}\n\n", self.source.display()));
+        out.push_str(&format!("{} files in {} crates would change:\n", self.changes.len(), self.crates().len()));
+        for change in &self.changes {
+            out.push_str(&format!("  {:<28} {:<22} {}\n", change.crate_name, change.wrapper.to_string(), change.file));
+        }
+        if !self.skipped.is_empty() {
+            out.push_str(&format!("\n{} skipped:\n", self.skipped.len()));
+            for skipped in &self.skipped {
+                out.push_str(&format!("  {}\n", skipped));
+            }
+        }
+        out.push('\n');
+        for change in &self.changes {
+            out.push_str(&diff_text(&change.file, &change.old, &change.new, DiffOptions::default()).to_string());
+        }
+        out
+    }
+
+    /// Applies every change or none: all new contents are staged next to
+    /// their targets first, and files already replaced are restored if a
+    /// later one fails. Returns the changed files.
+    pub fn execute(&self) -> Result<Vec<PathBuf>, String> {
+        self.execute_with(|staged, target| fs::rename(staged, target))
+    }
+
+    /// `execute`, moving each staged file over its target with `replace`.
+    fn execute_with(&self, mut replace: impl FnMut(&Path, &Path) -> std::io::Result<()>) -> Result<Vec<PathBuf>, String> {
+        let targets: Vec<PathBuf> = self.changes.iter().map(|c| self.source.join(&c.file)).collect();
+        for (change, target) in self.changes.iter().zip(&targets) {
+            let current = fs::read_to_string(target).map_err(|e| format!("Failed to read {}: {}", target.display(), e))?;
+            if current != change.old {
+                return Err(format!("{} changed since the plan was made; plan again", change.file));
+            }
+        }
+
+        let staged: Vec<PathBuf> = targets.iter().map(|t| PathBuf::from(format!("{}{}", t.display(), STAGED_SUFFIX))).collect();
+        let discard = |staged: &[PathBuf]| staged.iter().for_each(|s| { let _ = fs::remove_file(s); });
+        for ((change, target), staged_file) in self.changes.iter().zip(&targets).zip(&staged) {
+            let written = fs::write(staged_file, &change.new)
+                .and_then(|_| fs::set_permissions(staged_file, fs::metadata(target)?.permissions()));
+            if let Err(e) = written {
+                discard(&staged);
+                return Err(format!("Failed to stage {}: {}; nothing was changed", change.file, e));
+            }
+        }
+
+        for (i, (target, staged_file)) in targets.iter().zip(&staged).enumerate() {
+            if let Err(e) = replace(staged_file, target) {
+                discard(&staged[i..]);
+                let restore_failures: Vec<String> = self.changes[..i].iter().zip(&targets)
+                    .filter_map(|(change, target)| fs::write(target, &change.old).err().map(|e| format!("{}: {}", change.file, e)))
+                    .collect();
+                if restore_failures.is_empty() {
+                    return Err(format!("Failed to replace {}: {}; rolled back {} files", self.changes[i].file, e, i));
+                }
+                return Err(format!("Failed to replace {}: {}; could not restore {}", self.changes[i].file, e, restore_failures.join(", ")));
+            }
+        }
+        Ok(targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("rust_in_nix_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let store = root.join("store");
+        for entry in ["0a1b-rustc-1.75.0", "1c2d-rustc-1.79.0", "2e3f-rust-default-1.80.0-nightly-2024-05-01"] {
+            fs::create_dir_all(store.join(entry).join("bin")).unwrap();
+            fs::write(store.join(entry).join("bin/rustc"), "").unwrap();
+        }
+        let source = root.join("rust");
+        let write = |path: &str, content: &str| {
+            let path = source.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("compiler/rustc_llvm/Cargo.toml", "[package]\nname = \"rustc_llvm\"\n");
+        write("compiler/rustc_llvm/build.rs", "use std::env;\n\nfn main() {\n    println!(\"cargo:rerun-if-changed=build.rs\");\n}\n");
+        write("library/std/Cargo.toml", "[package]\nname = \"std\"\n");
+        write("library/std/build.rs", "fn main() -> Result<(), String> {\n    Ok(())\n}");
+        write("library/profiler_builtins/build.rs", "fn helper() {}\n");
+        write("build/x86_64-unknown-linux-gnu/stage0/build.rs", "fn main() {}\n");
+        write("src/bootstrap/bootstrap.py", "from __future__ import print_function\nimport sys\n\ndef main():\n    pass\n\nif __name__ == '__main__':\n    main()\n");
+        (root, store, source)
+    }
+
+    #[test]
+    fn test_plan_and_dry_run() {
+        let (root, store, source) = fixture("plan");
+        let plan = RustInNixOptions::new("stable").store(&store).source(&source).plan().unwrap();
+        assert!(plan.toolchain.rustc.ends_with("1c2d-rustc-1.79.0/bin/rustc"));
+        let files: Vec<&str> = plan.changes.iter().map(|c| c.file.as_str()).collect();
+        assert_eq!(files, vec!["compiler/rustc_llvm/build.rs", "library/std/build.rs", "src/bootstrap/bootstrap.py"]);
+        assert_eq!(plan.skipped, vec!["library/profiler_builtins/build.rs: has no fn main"]);
+        assert_eq!(plan.crates().get("std"), Some(&vec![Wrapper::BuildScript]));
+
+        let std = &plan.changes[1].new;
+        assert!(std.contains("fn rust_in_nix_original_main() -> Result<(), String> {"));
+        assert!(std.contains("fn main() -> Result<(), String> {\n    println!(\"cargo:rustc-env=RUST_IN_NIX_TOOLCHAIN=1.79.0\");"));
+        syn::parse_file(std).unwrap();
+        assert!(plan.changes[2].new.contains("setdefault(\"RUST_IN_NIX_TOOLCHAIN\", \"1.79.0\")\n\nif __name__"));
+
+        let dry_run = plan.dry_run();
+        assert!(dry_run.contains("+++ b/compiler/rustc_llvm/build.rs"));
+        assert!(dry_run.contains("3 files in 3 crates would change"));
+        assert!(plan.to_json().contains("\"wrapper\": \"bootstrap\""));
+        // Planning wrote nothing.
+        assert!(!fs::read_to_string(source.join("library/std/build.rs")).unwrap().contains(MARKER));
+
+        let only_std = RustInNixOptions::new(">=1.80").store(&store).source(&source).crates(["std"]).plan().unwrap();
+        assert_eq!(only_std.toolchain.channel, nix_core::Channel::Nightly);
+        assert_eq!(only_std.changes.len(), 2);
+        assert!(RustInNixOptions::new("1.90").store(&store).source(&source).plan().unwrap_err().contains("1.79.0 stable"));
+        assert!(RustInNixOptions::new("stable").store(&store).source(store.join("1c2d-rustc-1.79.0")).plan().unwrap_err().contains("read-only"));
+        assert!(RustInNixOptions::new("stable").store(&store).plan().unwrap_err().contains("No rustc checkout"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_execute_and_rollback() {
+        let (root, store, source) = fixture("execute");
+        let original: Vec<String> = ["compiler/rustc_llvm/build.rs", "library/std/build.rs", "src/bootstrap/bootstrap.py"]
+            .iter().map(|f| fs::read_to_string(source.join(f)).unwrap()).collect();
+        let plan = RustInNixOptions::new("stable").store(&store).source(&source).plan().unwrap();
+
+        // The third rename fails: the first two files are restored and no
+        // staged copies are left behind.
+        let mut renames = 0;
+        let err = plan.execute_with(|staged, target| {
+            renames += 1;
+            if renames == 3 {
+                return Err(std::io::Error::other("injected failure"));
+            }
+            fs::rename(staged, target)
+        }).unwrap_err();
+        assert!(err.contains("rolled back 2 files"), "{}", err);
+        for (change, old) in plan.changes.iter().zip(&original) {
+            assert_eq!(&fs::read_to_string(source.join(&change.file)).unwrap(), old);
+        }
+        assert!(WalkDir::new(&source).into_iter().filter_map(|e| e.ok()).all(|e| !e.file_name().to_string_lossy().ends_with(STAGED_SUFFIX)));
+
+        let changed = plan.execute().unwrap();
+        assert_eq!(changed.len(), 3);
+        assert!(fs::read_to_string(source.join("library/std/build.rs")).unwrap().starts_with("// RustInNix:"));
+        // Executing a stale plan is refused, and planning again skips what
+        // is already wrapped.
+        assert!(plan.execute().unwrap_err().contains("changed since the plan"));
+        let again = RustInNixOptions::new("stable").store(&store).source(&source).plan().unwrap();
+        assert!(again.is_empty());
+        assert_eq!(again.skipped.len(), 4);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
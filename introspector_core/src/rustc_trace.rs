@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::nix_rustc::{nix_store_root, run_rustc, rust_src_component};
+
+// A structured trace of one rustc: how the name resolves through PATH and
+// symlinks, what `rustc -vV` reports and what its sysroot contains. PATH
+// lookup and symlink chasing happen in-process; the only program run is the
+// rustc being traced.
+
+/// Symlink hops followed before giving up on a loop.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// `rustc -vV`, field by field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "RustcVersionInfo", vis = "pub", hash = "ac5c9741")]
+pub struct RustcVersionInfo {
+    /// The first line, e.g. `rustc 1.79.0 (129f3b996 2024-06-10)`.
+    pub banner: String,
+    pub binary: String,
+    pub release: String,
+    /// `None` when rustc was built without git information (`unknown`).
+    pub commit_hash: Option<String>,
+    pub commit_date: Option<String>,
+    pub host: String,
+    pub llvm_version: Option<String>,
+    /// Fields this parser does not know about.
+    pub extra: BTreeMap<String, String>,
+}
+
+impl RustcVersionInfo {
+    /// `stable`, `beta`, `nightly` or `dev`, from the release string.
+    pub fn channel(&self) -> &'static str {
+        if self.release.contains("nightly") {
+            "nightly"
+        } else if self.release.contains("beta") {
+            "beta"
+        } else if self.release.contains("dev") {
+            "dev"
+        } else {
+            "stable"
+        }
+    }
+
+    /// The source archive of the exact commit, falling back to the release tag.
+    pub fn source_url(&self) -> String {
+        match &self.commit_hash {
+            Some(hash) => format!("https://github.com/rust-lang/rust/archive/{}.tar.gz", hash),
+            None => format!("https://static.rust-lang.org/dist/rustc-{}-src.tar.gz", self.release),
+        }
+    }
+}
+
+#[decl(fn, name = "parse_rustc_verbose_version", vis = "pub", hash = "e8977adf")]
+pub fn parse_rustc_verbose_version(output: &str) -> Result<RustcVersionInfo, String> {
+    let mut lines = output.lines();
+    let banner = lines.next().unwrap_or_default().trim().to_string();
+    if !banner.starts_with("rustc ") {
+        return Err(format!("Unexpected `rustc -vV` output: {}", banner));
+    }
+    let mut info = RustcVersionInfo { banner, ..Default::default() };
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim().to_string();
+        let known = |v: String| (v != "unknown").then_some(v);
+        match key.trim() {
+            "binary" => info.binary = value,
+            "release" => info.release = value,
+            "commit-hash" => info.commit_hash = known(value),
+            "commit-date" => info.commit_date = known(value),
+            "host" => info.host = value,
+            "LLVM version" => info.llvm_version = Some(value),
+            key => {
+                info.extra.insert(key.to_string(), value);
+            }
+        }
+    }
+    if info.release.is_empty() || info.host.is_empty() {
+        return Err("`rustc -vV` output has no release or host".to_string());
+    }
+    Ok(info)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The first executable `name` in the directories of `path_var` (a PATH
+/// value), like `which`.
+#[decl(fn, name = "find_in_path", vis = "pub", hash = "b6a312c9")]
+pub fn find_in_path(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let names: Vec<String> = if cfg!(windows) && Path::new(name).extension().is_none() {
+        vec![format!("{}.exe", name), name.to_string()]
+    } else {
+        vec![name.to_string()]
+    };
+    std::env::split_paths(path_var)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .flat_map(|dir| names.iter().map(move |n| dir.join(n)))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Every path visited while following symlinks from `path`, starting with
+/// `path` itself and ending with a file that is not a symlink. Relative
+/// targets resolve against the link's directory, like `readlink -f`.
+#[decl(fn, name = "symlink_chain", vis = "pub", hash = "217b66cc")]
+pub fn symlink_chain(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut chain = vec![path.to_path_buf()];
+    let mut current = path.to_path_buf();
+    while fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+        if chain.len() > MAX_SYMLINK_HOPS {
+            return Err(format!("Too many levels of symlinks at {}", path.display()));
+        }
+        let target = fs::read_link(&current).map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+        current = match current.parent() {
+            Some(dir) if target.is_relative() => dir.join(target),
+            _ => target,
+        };
+        chain.push(current.clone());
+    }
+    if !current.exists() {
+        return Err(format!("{} does not exist", current.display()));
+    }
+    Ok(chain)
+}
+
+/// `lib/rustlib/<triple>/lib` of one installed target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "TargetLibdir", vis = "pub", hash = "ad7dead0")]
+pub struct TargetLibdir {
+    pub triple: String,
+    pub libdir: PathBuf,
+    pub rlibs: usize,
+    /// Whether `libstd-*.rlib` is there, i.e. the target's `rust-std` is installed.
+    pub has_std: bool,
+}
+
+/// A `lib/rustlib/<triple>/codegen-backends` library, e.g. `cranelift`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "CodegenBackend", vis = "pub", hash = "f40b6545")]
+pub struct CodegenBackend {
+    pub name: String,
+    pub triple: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "SysrootLayout", vis = "pub", hash = "6230abec")]
+pub struct SysrootLayout {
+    pub sysroot: PathBuf,
+    pub targets: Vec<TargetLibdir>,
+    pub codegen_backends: Vec<CodegenBackend>,
+    /// `lib/rustlib/src/rust`, when the `rust-src` component is installed.
+    pub rust_src: Option<PathBuf>,
+    /// `lib/rustlib/components`, written by rustup and the dist installers.
+    pub components: Vec<String>,
+}
+
+fn sorted_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+#[decl(fn, name = "read_sysroot_layout", vis = "pub", hash = "457f38ea")]
+pub fn read_sysroot_layout(sysroot: &Path) -> SysrootLayout {
+    let rustlib = sysroot.join("lib").join("rustlib");
+    let mut layout = SysrootLayout {
+        sysroot: sysroot.to_path_buf(),
+        rust_src: rust_src_component(sysroot),
+        components: fs::read_to_string(rustlib.join("components"))
+            .map(|c| c.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    // Target directories are the ones with a `lib/`; `src` and `etc` have none.
+    for dir in sorted_dir(&rustlib).into_iter().filter(|d| d.join("lib").is_dir()) {
+        let triple = file_name(&dir);
+        let libdir = dir.join("lib");
+        let libs: Vec<String> = sorted_dir(&libdir).iter().map(|p| file_name(p)).collect();
+        layout.targets.push(TargetLibdir {
+            triple: triple.clone(),
+            rlibs: libs.iter().filter(|l| l.ends_with(".rlib")).count(),
+            has_std: libs.iter().any(|l| l.starts_with("libstd-") && l.ends_with(".rlib")),
+            libdir,
+        });
+        for path in sorted_dir(&dir.join("codegen-backends")).into_iter().filter(|p| p.is_file()) {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let name = stem.trim_start_matches("lib").trim_start_matches("rustc_codegen_");
+            // `librustc_codegen_cranelift-<hash>.so` → `cranelift`
+            let name = name.split('-').next().unwrap_or(name).to_string();
+            layout.codegen_backends.push(CodegenBackend { name, triple: triple.clone(), path });
+        }
+    }
+    layout
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[decl(struct, name = "RustcTrace", vis = "pub", hash = "a71d32b7")]
+pub struct RustcTrace {
+    /// What was asked for: a bare name (looked up on PATH) or a path.
+    pub requested: String,
+    /// `requested` and then every symlink target up to `real_path`.
+    pub symlink_chain: Vec<PathBuf>,
+    pub real_path: PathBuf,
+    /// The binary is a proxy (rustup) rather than the compiler in a sysroot.
+    pub proxy: bool,
+    /// `/nix/store/<hash>-<name>` holding the sysroot.
+    pub store_path: Option<PathBuf>,
+    pub version: RustcVersionInfo,
+    pub sysroot: SysrootLayout,
+    pub source_url: String,
+}
+
+impl RustcTrace {
+    /// Traces `$RUSTC`, or `rustc` on `$PATH`.
+    pub fn from_env() -> Result<Self, String> {
+        let rustc = std::env::var("RUSTC").ok().filter(|r| !r.is_empty()).unwrap_or_else(|| "rustc".to_string());
+        Self::trace(&rustc, &std::env::var_os("PATH").unwrap_or_default())
+    }
+
+    /// Traces `rustc`: a path, or a bare name looked up in `path_var`.
+    pub fn trace(rustc: &str, path_var: &OsStr) -> Result<Self, String> {
+        let start = if Path::new(rustc).components().count() > 1 {
+            PathBuf::from(rustc)
+        } else {
+            find_in_path(rustc, path_var).ok_or_else(|| format!("{} not found on PATH", rustc))?
+        };
+        let symlink_chain = symlink_chain(&start)?;
+        let real_path = symlink_chain.last().cloned().unwrap_or_else(|| start.clone());
+
+        // Run it by its first name: proxies dispatch on argv[0].
+        let version = parse_rustc_verbose_version(&run_rustc(&start, &["-vV"])?)?;
+        // `<sysroot>/bin/rustc` knows its sysroot from where it lives; a proxy
+        // (rustup's `rustc` is the `rustup` binary) has to be asked.
+        let parent_sysroot = real_path.parent().and_then(Path::parent)
+            .filter(|root| root.join("lib").join("rustlib").is_dir())
+            .map(Path::to_path_buf);
+        let proxy = parent_sysroot.is_none();
+        let sysroot = match parent_sysroot {
+            Some(sysroot) => sysroot,
+            None => PathBuf::from(run_rustc(&start, &["--print", "sysroot"])?),
+        };
+
+        Ok(RustcTrace {
+            requested: rustc.to_string(),
+            symlink_chain,
+            proxy,
+            store_path: nix_store_root(&sysroot).or_else(|| nix_store_root(&real_path)),
+            source_url: version.source_url(),
+            sysroot: read_sysroot_layout(&sysroot),
+            real_path,
+            version,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = String::from("🔍 RUSTC TRACE\n\n");
+        report.push_str(&format!("Requested: {}\n", self.requested));
+        for (i, hop) in self.symlink_chain.iter().enumerate() {
+            report.push_str(&format!("{} {}\n", if i == 0 { "  " } else { "  →" }, hop.display()));
+        }
+        if self.proxy {
+            report.push_str("  (proxy; sysroot from `rustc --print sysroot`)\n");
+        }
+        report.push_str(&format!("\nVersion: {} ({})\n", self.version.release, self.version.channel()));
+        report.push_str(&format!("Host: {}\n", self.version.host));
+        report.push_str(&format!("Commit: {} {}\n",
+            self.version.commit_hash.as_deref().unwrap_or("unknown"),
+            self.version.commit_date.as_deref().unwrap_or("")));
+        if let Some(llvm) = &self.version.llvm_version {
+            report.push_str(&format!("LLVM: {}\n", llvm));
+        }
+        report.push_str(&format!("\nSysroot: {}\n", self.sysroot.sysroot.display()));
+        if let Some(store) = &self.store_path {
+            report.push_str(&format!("Nix store: {}\n", store.display()));
+        }
+        for target in &self.sysroot.targets {
+            report.push_str(&format!("  {} {} rlibs{}\n", target.triple, target.rlibs, if target.has_std { "" } else { " (no std)" }));
+        }
+        for backend in &self.sysroot.codegen_backends {
+            report.push_str(&format!("  codegen backend: {} ({})\n", backend.name, backend.triple));
+        }
+        match &self.sysroot.rust_src {
+            Some(src) => report.push_str(&format!("rust-src: {}\n", src.display())),
+            None => report.push_str("rust-src: not installed\n"),
+        }
+        report.push_str(&format!("Source: {}\n", self.source_url));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERBOSE: &str = "rustc 1.79.0 (129f3b996 2024-06-10)\nbinary: rustc\ncommit-hash: 129f3b9964af4d4a709d1383930ade12dfe7c081\ncommit-date: 2024-06-10\nhost: x86_64-unknown-linux-gnu\nrelease: 1.79.0\nLLVM version: 18.1.7\n";
+
+    #[test]
+    fn test_parse_rustc_verbose_version() {
+        let info = parse_rustc_verbose_version(VERBOSE).unwrap();
+        assert_eq!(info.release, "1.79.0");
+        assert_eq!(info.host, "x86_64-unknown-linux-gnu");
+        assert_eq!(info.commit_date.as_deref(), Some("2024-06-10"));
+        assert_eq!(info.llvm_version.as_deref(), Some("18.1.7"));
+        assert_eq!(info.channel(), "stable");
+        assert!(info.source_url().ends_with("/129f3b9964af4d4a709d1383930ade12dfe7c081.tar.gz"));
+
+        let nix = "rustc 1.80.0-nightly\nbinary: rustc\ncommit-hash: unknown\ncommit-date: unknown\nhost: aarch64-apple-darwin\nrelease: 1.80.0-nightly\nshiny: yes\n";
+        let info = parse_rustc_verbose_version(nix).unwrap();
+        assert_eq!((info.commit_hash.as_deref(), info.channel()), (None, "nightly"));
+        assert_eq!(info.extra.get("shiny").map(String::as_str), Some("yes"));
+        assert!(parse_rustc_verbose_version("cargo 1.79.0\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_trace_through_path_and_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let root = std::env::temp_dir().join(format!("rustc_trace_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let sysroot = root.join("toolchain");
+        let rustlib = sysroot.join("lib/rustlib");
+        fs::create_dir_all(sysroot.join("bin")).unwrap();
+        fs::create_dir_all(rustlib.join("x86_64-unknown-linux-gnu/lib")).unwrap();
+        fs::create_dir_all(rustlib.join("x86_64-unknown-linux-gnu/codegen-backends")).unwrap();
+        fs::create_dir_all(rustlib.join("wasm32-unknown-unknown/lib")).unwrap();
+        fs::create_dir_all(rustlib.join("src/rust/library")).unwrap();
+        fs::create_dir_all(rustlib.join("etc")).unwrap();
+        fs::write(rustlib.join("components"), "rustc-x86_64-unknown-linux-gnu\nrust-src\n").unwrap();
+        fs::write(rustlib.join("x86_64-unknown-linux-gnu/lib/libstd-0a1b.rlib"), "").unwrap();
+        fs::write(rustlib.join("x86_64-unknown-linux-gnu/lib/libcore-2c3d.rlib"), "").unwrap();
+        fs::write(rustlib.join("x86_64-unknown-linux-gnu/codegen-backends/librustc_codegen_cranelift-1.79.0.so"), "").unwrap();
+        fs::write(rustlib.join("wasm32-unknown-unknown/lib/libcore-4e5f.rlib"), "").unwrap();
+
+        let rustc = sysroot.join("bin/rustc");
+        fs::write(&rustc, format!("#!/bin/sh\ncat <<'EOF'\n{}EOF\n", VERBOSE)).unwrap();
+        fs::set_permissions(&rustc, fs::Permissions::from_mode(0o755)).unwrap();
+        // PATH holds a relative symlink to an absolute one to the real binary.
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::create_dir_all(root.join("links")).unwrap();
+        symlink(&rustc, root.join("links/rustc-1.79")).unwrap();
+        symlink("../links/rustc-1.79", root.join("bin/rustc")).unwrap();
+
+        let path_var = std::env::join_paths([root.join("empty"), root.join("bin")]).unwrap();
+        let trace = RustcTrace::trace("rustc", &path_var).unwrap();
+        assert_eq!(trace.symlink_chain.len(), 3);
+        assert_eq!(trace.real_path, rustc);
+        assert!(!trace.proxy);
+        assert_eq!(trace.version.release, "1.79.0");
+        assert_eq!(trace.sysroot.sysroot, sysroot);
+        assert_eq!(trace.sysroot.components.len(), 2);
+        let targets: Vec<(&str, usize, bool)> = trace.sysroot.targets.iter().map(|t| (t.triple.as_str(), t.rlibs, t.has_std)).collect();
+        assert_eq!(targets, vec![("wasm32-unknown-unknown", 1, false), ("x86_64-unknown-linux-gnu", 2, true)]);
+        assert_eq!(trace.sysroot.codegen_backends[0].name, "cranelift");
+        assert_eq!(trace.sysroot.rust_src, Some(rustlib.join("src/rust")));
+        assert!(trace.to_json().contains("\"has_std\": true"));
+        assert!(trace.report().contains("rust-src: "));
+
+        assert!(RustcTrace::trace("rustc", &std::env::join_paths([root.join("empty")]).unwrap()).is_err());
+        symlink("loop-b", root.join("bin/loop-a")).unwrap();
+        symlink("loop-a", root.join("bin/loop-b")).unwrap();
+        assert!(symlink_chain(&root.join("bin/loop-a")).unwrap_err().contains("symlinks"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::{json, Value};
+use crate::audit_tickets::{get_ticket, Severity};
+use crate::auto_fix::FixAction;
+use crate::baseline::{findings_for_fixes, InvalidSuppression};
+use crate::clippy_rules::generate_lint_rules;
+
+// SARIF 2.1.0 rendering of `scan_directory` results. Rule metadata comes
+// from the ticket registry and the lint rules, locations and fixes from the
+// `FixAction`s, and each result carries the baseline fingerprint so review
+// tools can match findings across runs.
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const SRCROOT: &str = "%SRCROOT%";
+/// Key of the fingerprint in `partialFingerprints`.
+pub const FINGERPRINT_KEY: &str = "auditFix/v2";
+
+#[decl(fn, name = "sarif_level", vis = "pub", hash = "ee3d87fe")]
+pub fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+/// `path` relative to `root` with `/` separators, percent-encoded for a URI.
+fn relative_uri(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let joined = relative.components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    percent_encode(&joined)
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::new();
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// 1-based line and column (in code points) of a byte offset.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(content.len());
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = content[..offset].matches('\n').count() + 1;
+    (line, content[line_start..offset].chars().count() + 1)
+}
+
+fn rule_descriptor(ticket_id: &str, fallback_description: &str) -> Value {
+    let ticket = get_ticket(ticket_id);
+    let lint = generate_lint_rules().into_iter().find(|r| r.ticket_id == ticket_id);
+
+    let name = lint.as_ref().map(|r| r.name.clone())
+        .or_else(|| ticket.as_ref().and_then(|t| t.clippy_lint).map(str::to_string))
+        .unwrap_or_else(|| ticket_id.to_string());
+    let severity = ticket.as_ref().map(|t| t.severity)
+        .or_else(|| lint.as_ref().map(|r| r.severity))
+        .unwrap_or(Severity::Medium);
+
+    let mut rule = json!({
+        "id": ticket_id,
+        "name": name,
+        "shortDescription": { "text": ticket.as_ref().map_or(fallback_description, |t| t.title) },
+        "fullDescription": { "text": ticket.as_ref().map_or(fallback_description, |t| t.description) },
+        "defaultConfiguration": { "level": sarif_level(severity) },
+        "properties": { "severity": severity.as_str() },
+    });
+
+    let help_text = ticket.as_ref().and_then(|t| t.suggested_fix).map(str::to_string)
+        .or_else(|| lint.as_ref().map(|r| r.message.clone()));
+    if let Some(text) = help_text {
+        let mut help = json!({ "text": text });
+        if let Some(suggestion) = lint.as_ref().and_then(|r| r.suggestion.as_deref()) {
+            help["markdown"] = json!(format!("{}\n\n```rust\n{}\n```", text, suggestion));
+        }
+        rule["help"] = help;
+    }
+    if let Some(t) = &ticket {
+        rule["properties"]["category"] = json!(t.category.name());
+        rule["properties"]["module"] = json!(t.module);
+        rule["properties"]["tags"] = json!([t.category.name(), t.category.prefix()]);
+    }
+    rule
+}
+
+/// A SARIF 2.1.0 log for `results`, with artifact URIs relative to `root`.
+#[decl(fn, name = "scan_results_to_sarif", vis = "pub", hash = "ae035c13")]
+pub fn scan_results_to_sarif(results: &[(PathBuf, Vec<FixAction>)], root: &Path) -> Value {
+    // Rules are listed once, sorted by ticket id; results refer to them by index.
+    let mut descriptions: BTreeMap<&str, &str> = BTreeMap::new();
+    for fix in results.iter().flat_map(|(_, fixes)| fixes) {
+        descriptions.entry(fix.ticket_id).or_insert(&fix.description);
+    }
+    let rule_index: HashMap<&str, usize> = descriptions.keys().enumerate().map(|(i, id)| (*id, i)).collect();
+    let rules: Vec<Value> = descriptions.iter().map(|(id, desc)| rule_descriptor(id, desc)).collect();
+
+    let mut artifacts = Vec::new();
+    let mut sarif_results = Vec::new();
+
+    for (artifact_index, (path, fixes)) in results.iter().enumerate() {
+        let uri = relative_uri(path, root);
+        let artifact_location = json!({ "uri": uri, "uriBaseId": SRCROOT, "index": artifact_index });
+        artifacts.push(json!({
+            "location": { "uri": uri, "uriBaseId": SRCROOT },
+            "sourceLanguage": "rust",
+        }));
+
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let findings = findings_for_fixes(&content, path, root, fixes.clone());
+
+        for (fix, finding) in fixes.iter().zip(&findings) {
+            let level = rules[rule_index[fix.ticket_id]]["defaultConfiguration"]["level"].clone();
+
+            let region = if fix.matched.end <= content.len() && content.is_char_boundary(fix.matched.start) {
+                let (start_line, start_column) = line_column(&content, fix.matched.start);
+                let (end_line, end_column) = line_column(&content, fix.matched.end);
+                json!({
+                    "startLine": start_line,
+                    "startColumn": start_column,
+                    "endLine": end_line,
+                    "endColumn": end_column,
+                    "byteOffset": fix.matched.start,
+                    "byteLength": fix.matched.len(),
+                    "snippet": { "text": &content[fix.matched.clone()] },
+                })
+            } else {
+                json!({ "startLine": fix.line })
+            };
+
+            sarif_results.push(json!({
+                "ruleId": fix.ticket_id,
+                "ruleIndex": rule_index[fix.ticket_id],
+                "level": level,
+                "message": { "text": format!("[{}] {}", fix.ticket_id, fix.description) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": artifact_location, "region": region },
+                }],
+                "partialFingerprints": { FINGERPRINT_KEY: finding.fingerprint },
+                "fixes": [{
+                    "description": { "text": format!("Mark with {}", fix.insertion.trim()) },
+                    "artifactChanges": [{
+                        "artifactLocation": artifact_location,
+                        "replacements": [{
+                            "deletedRegion": { "byteOffset": fix.offset, "byteLength": 0 },
+                            "insertedContent": { "text": fix.insertion },
+                        }],
+                    }],
+                }],
+            }));
+        }
+    }
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "cargo-audit-fix",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            }
+        },
+        "columnKind": "unicodeCodePoints",
+        "artifacts": artifacts,
+        "results": sarif_results,
+    });
+    if let Ok(abs_root) = root.canonicalize() {
+        let mut root_uri = format!("file://{}", percent_encode(&abs_root.to_string_lossy().replace('\\', "/")));
+        if !root_uri.ends_with('/') {
+            root_uri.push('/');
+        }
+        run["originalUriBaseIds"] = json!({ SRCROOT: { "uri": root_uri } });
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [run],
+    })
+}
+
+/// Records `invalid` suppressions in `log` as error notifications of an
+/// unsuccessful invocation, since they are not findings but still fail the scan.
+#[decl(fn, name = "add_invalid_suppressions", vis = "pub", hash = "70a53bc0")]
+pub fn add_invalid_suppressions(log: &mut Value, invalid: &[InvalidSuppression]) {
+    let notifications: Vec<Value> = invalid.iter().map(|s| json!({
+        "level": "error",
+        "message": { "text": format!("invalid audit-allow: {}", s.message) },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": percent_encode(&s.file), "uriBaseId": SRCROOT },
+                "region": { "startLine": s.line },
+            },
+        }],
+    })).collect();
+    if let Some(run) = log["runs"].get_mut(0) {
+        run["invocations"] = json!([{
+            "executionSuccessful": invalid.is_empty(),
+            "toolExecutionNotifications": notifications,
+        }]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auto_fix::scan_directory;
+
+    fn scan_tmp(name: &str, files: &[(&str, &str)]) -> (PathBuf, Value) {
+        let dir = std::env::temp_dir().join(name);
+        fs::remove_dir_all(&dir).ok();
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let mut results = scan_directory(&dir, &["rs"]);
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        let log = scan_results_to_sarif(&results, &dir);
+        (dir, log)
+    }
+
+    #[test]
+    fn test_sarif_structure_and_regions() {
+        let src = "fn main() {\n    let h = \"11111111111111111111111111111111\";\n}\n";
+        let (dir, log) = scan_tmp("sarif_structure_test", &[("src/my lib.rs", src)]);
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let result = &run["results"][0];
+        let rule = &rules[result["ruleIndex"].as_u64().unwrap() as usize];
+        assert_eq!(rule["id"], "FKD-002");
+        assert_eq!(rule["name"], "fake_blockchain_data");
+        assert_eq!(rule["shortDescription"]["text"], get_ticket("FKD-002").unwrap().title);
+        assert_eq!(result["level"], "error");
+
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/my%20lib.rs");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 13);
+        assert_eq!(location["region"]["endColumn"], 47);
+
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["byteOffset"], 16);
+        assert!(replacement["insertedContent"]["text"].as_str().unwrap().contains("audit_id!(\"FKD-002\""));
+        assert!(run["originalUriBaseIds"][SRCROOT]["uri"].as_str().unwrap().ends_with('/'));
+    }
+
+    #[test]
+    fn test_fingerprints_survive_line_shifts() {
+        let src = "fn f() {\n    let a = 196883;\n}\nfn g() {\n    let a = 196883;\n}\n";
+        let shifted = format!("// header\n\n{}", src);
+        let (dir, before) = scan_tmp("sarif_fingerprint_a", &[("lib.rs", src)]);
+        fs::remove_dir_all(&dir).ok();
+        let (dir, after) = scan_tmp("sarif_fingerprint_b", &[("lib.rs", &shifted)]);
+        fs::remove_dir_all(&dir).ok();
+
+        let prints = |log: &Value| -> Vec<String> {
+            log["runs"][0]["results"].as_array().unwrap().iter()
+                .map(|r| r["partialFingerprints"][FINGERPRINT_KEY].as_str().unwrap().to_string())
+                .collect()
+        };
+        let (a, b) = (prints(&before), prints(&after));
+        assert_eq!(a.len(), 2);
+        assert_ne!(a[0], a[1]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_invalid_suppressions_are_notifications() {
+        let mut log = scan_results_to_sarif(&[], Path::new("."));
+        let invalid = InvalidSuppression { file: "src/my lib.rs".to_string(), line: 4, message: "no reason".to_string() };
+        add_invalid_suppressions(&mut log, &[invalid]);
+
+        let invocation = &log["runs"][0]["invocations"][0];
+        assert_eq!(invocation["executionSuccessful"], false);
+        let notification = &invocation["toolExecutionNotifications"][0];
+        assert_eq!(notification["level"], "error");
+        assert!(notification["message"]["text"].as_str().unwrap().ends_with("no reason"));
+        let location = &notification["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/my%20lib.rs");
+        assert_eq!(location["region"]["startLine"], 4);
+    }
+}
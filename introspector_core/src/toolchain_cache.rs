@@ -0,0 +1,766 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+// Content-addressed store of toolchain tarballs and unpacked sysroots,
+// behind `rust_cache!`. Layout under the cache root:
+//
+//   objects/<sha256>            tarballs, named by the hash of their bytes
+//   sysroots/<sha256>/tree      unpacked sysroots, named by the hash of
+//   sysroots/<sha256>/manifest  their manifest (path, mode, file hash)
+//   refs/<name>                 pins: `tarball <sha256>` or `sysroot <sha256>`
+//   tmp/                        staging; entries appear by a single rename
+//   lock                        advisory lock: shared by installs and pins,
+//                               exclusive for `gc`
+//
+// An entry's reference count is the number of pins naming it; `gc` removes
+// entries nobody pins, and `verify` re-hashes everything against its name.
+// An entry is unpinned between its install and its pin, so callers hold
+// `ToolchainCache::lock` across both to keep `gc` out.
+
+pub const CACHE_DIR_ENV: &str = "RUST_TOOLCHAIN_CACHE";
+
+/// Staging entries older than this are left over from a crashed install.
+const STAGING_GRACE: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[decl(enum, name = "EntryKind", vis = "pub", hash = "4b13e0e0")]
+pub enum EntryKind {
+    Tarball,
+    Sysroot,
+}
+
+impl EntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::Tarball => "tarball",
+            EntryKind::Sysroot => "sysroot",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "tarball" => Some(EntryKind::Tarball),
+            "sysroot" => Some(EntryKind::Sysroot),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[decl(struct, name = "CacheEntry", vis = "pub", hash = "e54768a1")]
+pub struct CacheEntry {
+    pub kind: EntryKind,
+    pub hash: String,
+    pub size: u64,
+    /// Pins naming this entry.
+    pub refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[decl(struct, name = "VerifyReport", vis = "pub", hash = "c1d05021")]
+pub struct VerifyReport {
+    pub checked: usize,
+    /// `kind hash: reason` of the entries that do not match their name.
+    pub corrupt: Vec<String>,
+    /// Entries removed because they were corrupt (`verify(true)`).
+    pub removed: Vec<String>,
+    /// Pins naming a missing entry.
+    pub dangling_refs: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty() && self.dangling_refs.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[decl(struct, name = "GcReport", vis = "pub", hash = "d2e8a54d")]
+pub struct GcReport {
+    /// `kind hash` of the removed entries.
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// A held advisory lock on a cache, released on drop.
+#[derive(Debug)]
+#[decl(struct, name = "CacheLock", vis = "pub", hash = "77a7959b")]
+pub struct CacheLock {
+    _file: File,
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hex sha256 and size of `reader`, copied to `sink` on the way.
+fn hash_stream(reader: &mut impl Read, mut sink: Option<&mut File>) -> std::io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 16];
+    let mut size = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        if let Some(sink) = sink.as_mut() {
+            sink.write_all(&buf[..n])?;
+        }
+        size += n as u64;
+    }
+    Ok((hex(&hasher.finalize()), size))
+}
+
+fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    hash_stream(&mut file, None)
+        .map(|(hash, _)| hash)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn is_hash(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+/// One line per entry of `dir`, sorted: `d path`, `f x|- sha256 path` or
+/// `l target path`. Its hash is the sysroot's name.
+#[decl(fn, name = "tree_manifest", vis = "pub", hash = "0240abde")]
+pub fn tree_manifest(dir: &Path) -> Result<String, String> {
+    let mut manifest = String::new();
+    for entry in WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to walk {}: {}", dir.display(), e))?;
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path()).to_string_lossy().replace('\\', "/");
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            let target = fs::read_link(entry.path()).map_err(|e| format!("Failed to read {}: {}", entry.path().display(), e))?;
+            manifest.push_str(&format!("l {} {}\n", target.display(), relative));
+        } else if file_type.is_dir() {
+            manifest.push_str(&format!("d {}\n", relative));
+        } else {
+            let meta = entry.metadata().map_err(|e| format!("Failed to stat {}: {}", entry.path().display(), e))?;
+            let mode = if is_executable(&meta) { "x" } else { "-" };
+            manifest.push_str(&format!("f {} {} {}\n", mode, hash_file(entry.path())?, relative));
+        }
+    }
+    Ok(manifest)
+}
+
+/// Copies `src` into the new directory `dst`, keeping symlinks and modes.
+fn copy_tree(src: &Path, dst: &Path) -> Result<(), String> {
+    fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {}", dst.display(), e))?;
+    for entry in WalkDir::new(src).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to walk {}: {}", src.display(), e))?;
+        let target = dst.join(entry.path().strip_prefix(src).unwrap_or(entry.path()));
+        let file_type = entry.file_type();
+        let result = if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)
+        } else if file_type.is_dir() {
+            fs::create_dir_all(&target)
+        } else {
+            fs::copy(entry.path(), &target).map(|_| ())
+        };
+        result.map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(link: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(link)?, target)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(link: &Path, target: &Path) -> std::io::Result<()> {
+    fs::copy(link, target).map(|_| ())
+}
+
+fn tree_size(dir: &Path) -> u64 {
+    WalkDir::new(dir).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[decl(struct, name = "ToolchainCache", vis = "pub", hash = "58ee85d8")]
+pub struct ToolchainCache {
+    root: PathBuf,
+}
+
+impl ToolchainCache {
+    /// Opens (creating if needed) the cache at `root`.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, String> {
+        let root = root.as_ref().to_path_buf();
+        for dir in ["objects", "sysroots", "refs", "tmp"] {
+            fs::create_dir_all(root.join(dir))
+                .map_err(|e| format!("Failed to create {}: {}", root.join(dir).display(), e))?;
+        }
+        Ok(ToolchainCache { root })
+    }
+
+    /// `$RUST_TOOLCHAIN_CACHE`, else `$XDG_CACHE_HOME/patch-build-rs/toolchains`,
+    /// else `~/.cache/patch-build-rs/toolchains`.
+    pub fn default_root() -> PathBuf {
+        if let Some(dir) = std::env::var_os(CACHE_DIR_ENV) {
+            return PathBuf::from(dir);
+        }
+        let cache = std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir);
+        cache.join("patch-build-rs").join("toolchains")
+    }
+
+    pub fn open_default() -> Result<Self, String> {
+        Self::open(Self::default_root())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn tarball_path(&self, hash: &str) -> PathBuf {
+        self.root.join("objects").join(hash)
+    }
+
+    fn sysroot_dir(&self, hash: &str) -> PathBuf {
+        self.root.join("sysroots").join(hash)
+    }
+
+    /// Where the entry lives: the tarball file or the sysroot tree.
+    pub fn entry_path(&self, kind: EntryKind, hash: &str) -> PathBuf {
+        match kind {
+            EntryKind::Tarball => self.tarball_path(hash),
+            EntryKind::Sysroot => self.sysroot_dir(hash).join("tree"),
+        }
+    }
+
+    pub fn contains(&self, kind: EntryKind, hash: &str) -> bool {
+        is_hash(hash) && match kind {
+            EntryKind::Tarball => self.tarball_path(hash).is_file(),
+            EntryKind::Sysroot => self.sysroot_dir(hash).join("manifest").is_file(),
+        }
+    }
+
+    /// A fresh path under `tmp/`, on the same filesystem as the store.
+    fn staging(&self, what: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.root.join("tmp").join(format!("{}-{}-{}-{}", what, std::process::id(), nanos, n))
+    }
+
+    /// Moves `staged` to `dest` in one rename. If another install got there
+    /// first, the staged copy is dropped: both have the same content.
+    fn install(&self, staged: &Path, dest: &Path) -> Result<(), String> {
+        match fs::rename(staged, dest) {
+            Ok(()) => Ok(()),
+            Err(_) if dest.exists() => {
+                let _ = remove_path(staged);
+                Ok(())
+            }
+            Err(e) => {
+                let _ = remove_path(staged);
+                Err(format!("Failed to install {}: {}", dest.display(), e))
+            }
+        }
+    }
+
+    fn lock_file(&self, exclusive: bool) -> Result<CacheLock, String> {
+        let path = self.root.join("lock");
+        let file = File::options().create(true).truncate(false).write(true).open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let locked = if exclusive { file.lock() } else { file.lock_shared() };
+        locked.map_err(|e| format!("Failed to lock {}: {}", path.display(), e))?;
+        Ok(CacheLock { _file: file })
+    }
+
+    /// Keeps `gc` from running until the lock is dropped. Hold it from
+    /// adding entries until they are pinned; installs may run meanwhile.
+    pub fn lock(&self) -> Result<CacheLock, String> {
+        self.lock_file(false)
+    }
+
+    /// Stores the tarball at `path` and returns its sha256.
+    pub fn add_tarball(&self, path: &Path) -> Result<String, String> {
+        let _lock = self.lock()?;
+        let mut source = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let staged = self.staging("tarball");
+        let mut file = File::create(&staged).map_err(|e| format!("Failed to create {}: {}", staged.display(), e))?;
+        let copied = hash_stream(&mut source, Some(&mut file)).and_then(|(hash, _)| file.sync_all().map(|_| hash));
+        let hash = match copied {
+            Ok(hash) => hash,
+            Err(e) => {
+                let _ = fs::remove_file(&staged);
+                return Err(format!("Failed to copy {}: {}", path.display(), e));
+            }
+        };
+        drop(file);
+
+        let dest = self.tarball_path(&hash);
+        if dest.is_file() {
+            let _ = fs::remove_file(&staged);
+            return Ok(hash);
+        }
+        let mut permissions = fs::metadata(&staged).map_err(|e| e.to_string())?.permissions();
+        permissions.set_readonly(true);
+        let _ = fs::set_permissions(&staged, permissions);
+        self.install(&staged, &dest)?;
+        Ok(hash)
+    }
+
+    /// Stores a copy of the sysroot at `dir` and returns its sha256.
+    pub fn add_sysroot(&self, dir: &Path) -> Result<String, String> {
+        let _lock = self.lock()?;
+        let manifest = tree_manifest(dir)?;
+        let hash = hex(&Sha256::digest(manifest.as_bytes()));
+        if self.contains(EntryKind::Sysroot, &hash) {
+            return Ok(hash);
+        }
+
+        let staged = self.staging("sysroot");
+        let result = copy_tree(dir, &staged.join("tree")).and_then(|_| {
+            // The source may have changed while it was copied.
+            if tree_manifest(&staged.join("tree"))? != manifest {
+                return Err(format!("{} changed while it was being cached", dir.display()));
+            }
+            fs::write(staged.join("manifest"), &manifest)
+                .map_err(|e| format!("Failed to write manifest: {}", e))
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&staged);
+            return Err(e);
+        }
+        self.install(&staged, &self.sysroot_dir(&hash))?;
+        Ok(hash)
+    }
+
+    /// Unpacks a cached tarball into a sysroot and returns the sysroot's
+    /// hash. Rust dist tarballs (a top directory with a `components` file)
+    /// are installed by merging their components, as `install.sh` would.
+    pub fn unpack_tarball(&self, hash: &str) -> Result<String, String> {
+        let _lock = self.lock()?;
+        if !self.contains(EntryKind::Tarball, hash) {
+            return Err(format!("No tarball {} in the cache", hash));
+        }
+        let staged = self.staging("unpack");
+        fs::create_dir_all(&staged).map_err(|e| format!("Failed to create {}: {}", staged.display(), e))?;
+        let result = self.unpack_into(hash, &staged);
+        let _ = fs::remove_dir_all(&staged);
+        result
+    }
+
+    fn unpack_into(&self, hash: &str, staged: &Path) -> Result<String, String> {
+        let unpacked = staged.join("unpacked");
+        fs::create_dir_all(&unpacked).map_err(|e| e.to_string())?;
+        // audit-allow(SEC-006): tar is the only reader of the .tar.{gz,xz} dist formats here
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(self.tarball_path(hash))
+            .arg("-C")
+            .arg(&unpacked)
+            .status()
+            .map_err(|e| format!("Failed to run tar: {}", e))?;
+        if !status.success() {
+            return Err(format!("tar failed to unpack {}", hash));
+        }
+
+        let entries: Vec<PathBuf> = fs::read_dir(&unpacked).map_err(|e| e.to_string())?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        let top = match entries.as_slice() {
+            [single] if single.is_dir() => single.clone(),
+            _ => unpacked.clone(),
+        };
+        let components = fs::read_to_string(top.join("components")).ok();
+        let Some(components) = components else {
+            return self.add_sysroot(&top);
+        };
+
+        let sysroot = staged.join("sysroot");
+        fs::create_dir_all(&sysroot).map_err(|e| e.to_string())?;
+        for component in components.lines().map(str::trim).filter(|c| !c.is_empty()) {
+            let dir = top.join(component);
+            for entry in WalkDir::new(&dir).min_depth(1).sort_by_file_name() {
+                let entry = entry.map_err(|e| e.to_string())?;
+                let relative = entry.path().strip_prefix(&dir).unwrap_or(entry.path());
+                // The installer's own bookkeeping, not part of the sysroot.
+                if relative.as_os_str() == "manifest.in" {
+                    continue;
+                }
+                let target = sysroot.join(relative);
+                let result = if entry.file_type().is_dir() {
+                    fs::create_dir_all(&target)
+                } else if entry.file_type().is_symlink() {
+                    copy_symlink(entry.path(), &target)
+                } else {
+                    fs::copy(entry.path(), &target).map(|_| ())
+                };
+                result.map_err(|e| format!("Failed to install {}: {}", relative.display(), e))?;
+            }
+        }
+        self.add_sysroot(&sysroot)
+    }
+
+    /// Caches the toolchains of a rustup home (all, or just `toolchain`)
+    /// and pins each under its toolchain name.
+    pub fn import_rustup(&self, rustup_home: &Path, toolchain: Option<&str>) -> Result<Vec<(String, String)>, String> {
+        let _lock = self.lock()?;
+        let mut imported = Vec::new();
+        for dir in crate::nix_rustc::rustup_toolchains(rustup_home) {
+            let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            if toolchain.is_some_and(|t| name != t && !name.starts_with(&format!("{}-", t))) {
+                continue;
+            }
+            let hash = self.add_sysroot(&dir)?;
+            self.pin(&name, EntryKind::Sysroot, &hash)?;
+            imported.push((name, hash));
+        }
+        if imported.is_empty() {
+            return Err(format!("No toolchains found in {}", rustup_home.join("toolchains").display()));
+        }
+        Ok(imported)
+    }
+
+    /// Pins `hash` under `name`, replacing any previous pin of that name.
+    pub fn pin(&self, name: &str, kind: EntryKind, hash: &str) -> Result<(), String> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(format!("Invalid pin name `{}`", name));
+        }
+        let _lock = self.lock()?;
+        if !self.contains(kind, hash) {
+            return Err(format!("No {} {} in the cache", kind.as_str(), hash));
+        }
+        let staged = self.staging("ref");
+        fs::write(&staged, format!("{} {}\n", kind.as_str(), hash))
+            .map_err(|e| format!("Failed to write pin: {}", e))?;
+        // Unlike entries, a pin may be replaced; rename overwrites atomically.
+        fs::rename(&staged, self.root.join("refs").join(name))
+            .map_err(|e| format!("Failed to pin {}: {}", name, e))
+    }
+
+    /// Removes the pin `name`; the entry stays until `gc`.
+    pub fn unpin(&self, name: &str) -> Result<bool, String> {
+        match fs::remove_file(self.root.join("refs").join(name)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(format!("Failed to unpin {}: {}", name, e)),
+        }
+    }
+
+    /// All pins: name → (kind, hash).
+    pub fn refs(&self) -> BTreeMap<String, (EntryKind, String)> {
+        let Ok(entries) = fs::read_dir(self.root.join("refs")) else {
+            return BTreeMap::new();
+        };
+        entries.filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let content = fs::read_to_string(e.path()).ok()?;
+                let (kind, hash) = content.trim().split_once(' ')?;
+                Some((e.file_name().to_string_lossy().into_owned(), (EntryKind::parse(kind)?, hash.to_string())))
+            })
+            .collect()
+    }
+
+    /// The path of the entry pinned as `name`, if it is in the cache.
+    pub fn resolve(&self, name: &str) -> Option<PathBuf> {
+        let (kind, hash) = self.refs().remove(name)?;
+        self.contains(kind, &hash).then(|| self.entry_path(kind, &hash))
+    }
+
+    /// The number of pins naming `hash`.
+    pub fn refcount(&self, hash: &str) -> usize {
+        self.refs().values().filter(|(_, h)| h == hash).count()
+    }
+
+    /// Every entry in the store, tarballs first.
+    pub fn entries(&self) -> Vec<CacheEntry> {
+        let refs = self.refs();
+        let mut entries = Vec::new();
+        for kind in [EntryKind::Tarball, EntryKind::Sysroot] {
+            let dir = match kind {
+                EntryKind::Tarball => self.root.join("objects"),
+                EntryKind::Sysroot => self.root.join("sysroots"),
+            };
+            let mut hashes: Vec<String> = fs::read_dir(&dir)
+                .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+                .unwrap_or_default();
+            hashes.retain(|h| is_hash(h));
+            hashes.sort();
+            for hash in hashes {
+                let path = self.entry_path(kind, &hash);
+                let size = match kind {
+                    EntryKind::Tarball => fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                    EntryKind::Sysroot => tree_size(&path),
+                };
+                let pins = refs.iter().filter(|(_, (k, h))| *k == kind && *h == hash).map(|(name, _)| name.clone()).collect();
+                entries.push(CacheEntry { kind, hash, size, refs: pins });
+            }
+        }
+        entries
+    }
+
+    fn check(&self, kind: EntryKind, hash: &str) -> Result<(), String> {
+        match kind {
+            EntryKind::Tarball => {
+                let actual = hash_file(&self.tarball_path(hash))?;
+                if actual != hash {
+                    return Err(format!("content hashes to {}", actual));
+                }
+            }
+            EntryKind::Sysroot => {
+                let dir = self.sysroot_dir(hash);
+                let manifest = fs::read_to_string(dir.join("manifest")).map_err(|_| "missing manifest".to_string())?;
+                if hex(&Sha256::digest(manifest.as_bytes())) != hash {
+                    return Err("manifest does not match the entry name".to_string());
+                }
+                let actual = tree_manifest(&dir.join("tree"))?;
+                if let Some((expected, found)) = manifest.lines().zip(actual.lines()).find(|(a, b)| a != b) {
+                    return Err(format!("expected `{}`, found `{}`", expected, found));
+                }
+                if manifest != actual {
+                    return Err("files were added or removed".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-hashes every entry. With `repair`, corrupt entries are removed so
+    /// the next install replaces them.
+    pub fn verify(&self, repair: bool) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        for entry in self.entries() {
+            report.checked += 1;
+            if let Err(reason) = self.check(entry.kind, &entry.hash) {
+                report.corrupt.push(format!("{} {}: {}", entry.kind.as_str(), entry.hash, reason));
+                let path = match entry.kind {
+                    EntryKind::Tarball => self.tarball_path(&entry.hash),
+                    EntryKind::Sysroot => self.sysroot_dir(&entry.hash),
+                };
+                if repair && remove_path(&path).is_ok() {
+                    report.removed.push(format!("{} {}", entry.kind.as_str(), entry.hash));
+                }
+            }
+        }
+        for (name, (kind, hash)) in self.refs() {
+            if !self.contains(kind, &hash) {
+                report.dangling_refs.push(format!("{} → {} {}", name, kind.as_str(), hash));
+            }
+        }
+        report
+    }
+
+    /// Removes the entries no pin names, and staging left by crashed
+    /// installs. Waits for every `lock` to be dropped, including this
+    /// thread's.
+    pub fn gc(&self, dry_run: bool) -> Result<GcReport, String> {
+        let _lock = self.lock_file(true)?;
+        let mut report = GcReport::default();
+        for entry in self.entries().into_iter().filter(|e| e.refs.is_empty()) {
+            let path = match entry.kind {
+                EntryKind::Tarball => self.tarball_path(&entry.hash),
+                EntryKind::Sysroot => self.sysroot_dir(&entry.hash),
+            };
+            if !dry_run {
+                remove_path(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            }
+            report.removed.push(format!("{} {}", entry.kind.as_str(), entry.hash));
+            report.freed_bytes += entry.size;
+        }
+
+        let stale = fs::read_dir(self.root.join("tmp")).map(|entries| entries.filter_map(|e| e.ok()).collect::<Vec<_>>()).unwrap_or_default();
+        for entry in stale {
+            let age = entry.metadata().ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.elapsed().ok())
+                .unwrap_or_default();
+            if age >= STAGING_GRACE && !dry_run {
+                let _ = remove_path(&entry.path());
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[decl(fn, name = "print_toolchain_cache", vis = "pub", hash = "e2c0f088")]
+pub fn print_toolchain_cache(cache: &ToolchainCache) {
+    let entries = cache.entries();
+    eprintln!("📦 Toolchain cache at {}: {} entries\n", cache.root().display(), entries.len());
+    for entry in &entries {
+        let refs = if entry.refs.is_empty() { "(unpinned)".to_string() } else { entry.refs.join(", ") };
+        eprintln!("  {:<8} {}  {:>8.1} MiB  {}", entry.kind.as_str(), &entry.hash[..16], entry.size as f64 / (1 << 20) as f64, refs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("toolchain_cache_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[cfg(unix)]
+    fn make_executable(path: &Path) {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(not(unix))]
+    fn make_executable(_path: &Path) {}
+
+    fn fake_toolchain(dir: &Path, rustc: &str) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::create_dir_all(dir.join("lib/rustlib/x86_64-unknown-linux-gnu/lib")).unwrap();
+        fs::write(dir.join("bin/rustc"), rustc).unwrap();
+        make_executable(&dir.join("bin/rustc"));
+        fs::write(dir.join("lib/rustlib/x86_64-unknown-linux-gnu/lib/libstd.rlib"), "std").unwrap();
+    }
+
+    #[test]
+    fn test_tarballs_pins_verify_and_gc() {
+        let root = temp_root("tarball");
+        let cache = ToolchainCache::open(root.join("cache")).unwrap();
+        fs::write(root.join("a.tar.gz"), "tarball a").unwrap();
+        fs::write(root.join("b.tar.gz"), "tarball b").unwrap();
+
+        let a = cache.add_tarball(&root.join("a.tar.gz")).unwrap();
+        assert_eq!(a, hex(&Sha256::digest(b"tarball a")));
+        assert_eq!(cache.add_tarball(&root.join("a.tar.gz")).unwrap(), a);
+        let b = cache.add_tarball(&root.join("b.tar.gz")).unwrap();
+        cache.pin("1.75.0", EntryKind::Tarball, &a).unwrap();
+        cache.pin("pinned-twice", EntryKind::Tarball, &a).unwrap();
+        assert!(cache.pin("../escape", EntryKind::Tarball, &a).is_err());
+        assert!(cache.pin("missing", EntryKind::Sysroot, &a).is_err());
+        assert_eq!(cache.refcount(&a), 2);
+        assert_eq!(cache.resolve("1.75.0"), Some(cache.entry_path(EntryKind::Tarball, &a)));
+        assert!(cache.verify(false).is_ok());
+        assert!(fs::read_dir(root.join("cache/tmp")).unwrap().next().is_none());
+
+        let gc = cache.gc(false).unwrap();
+        assert_eq!(gc.removed, vec![format!("tarball {}", b)]);
+        assert_eq!(cache.entries().len(), 1);
+
+        // Corrupt the pinned tarball: verify reports it, repair removes it
+        // and leaves a dangling pin.
+        let path = cache.entry_path(EntryKind::Tarball, &a);
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&path, permissions).unwrap();
+        fs::write(&path, "bit rot").unwrap();
+        let report = cache.verify(true);
+        assert_eq!(report.corrupt.len(), 1, "{:?}", report);
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.dangling_refs.len(), 2);
+        assert_eq!(cache.resolve("1.75.0"), None);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_sysroots_from_rustup_and_dist_tarballs() {
+        let root = temp_root("sysroot");
+        let cache = ToolchainCache::open(root.join("cache")).unwrap();
+        let rustup = root.join("rustup");
+        fake_toolchain(&rustup.join("toolchains/stable-x86_64-unknown-linux-gnu"), "rustc 1.75.0");
+        fake_toolchain(&rustup.join("toolchains/nightly-x86_64-unknown-linux-gnu"), "rustc 1.77.0-nightly");
+        fs::write(rustup.join("settings.toml"), "default_toolchain = \"stable\"\n").unwrap();
+
+        let imported = cache.import_rustup(&rustup, None).unwrap();
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].0, "stable-x86_64-unknown-linux-gnu");
+        let stable = cache.resolve("stable-x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(fs::read_to_string(stable.join("bin/rustc")).unwrap(), "rustc 1.75.0");
+        assert!(is_executable(&fs::metadata(stable.join("bin/rustc")).unwrap()) || cfg!(not(unix)));
+        assert_eq!(cache.add_sysroot(&rustup.join("toolchains/stable-x86_64-unknown-linux-gnu")).unwrap(), imported[0].1);
+
+        // A dist tarball merges its components into one sysroot with the
+        // same content as the rustup toolchain.
+        let dist = root.join("dist/rust-1.75.0-x86_64-unknown-linux-gnu");
+        fake_toolchain(&dist.join("rustc"), "rustc 1.75.0");
+        fs::remove_dir_all(dist.join("rustc/lib")).unwrap();
+        fs::create_dir_all(dist.join("rust-std/lib/rustlib/x86_64-unknown-linux-gnu/lib")).unwrap();
+        fs::write(dist.join("rust-std/lib/rustlib/x86_64-unknown-linux-gnu/lib/libstd.rlib"), "std").unwrap();
+        fs::write(dist.join("rust-std/manifest.in"), "file:lib/rustlib/x86_64-unknown-linux-gnu/lib/libstd.rlib\n").unwrap();
+        fs::write(dist.join("components"), "rustc\nrust-std\n").unwrap();
+        let status = Command::new("tar")
+            .args(["-czf", "rust.tar.gz", "-C", "dist", "rust-1.75.0-x86_64-unknown-linux-gnu"])
+            .current_dir(&root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let tarball = cache.add_tarball(&root.join("rust.tar.gz")).unwrap();
+        assert_eq!(cache.unpack_tarball(&tarball).unwrap(), imported[0].1);
+
+        // Tampering with a sysroot is caught file by file.
+        fs::write(stable.join("lib/rustlib/x86_64-unknown-linux-gnu/lib/libstd.rlib"), "patched").unwrap();
+        let report = cache.verify(false);
+        assert_eq!(report.corrupt.len(), 1);
+        assert!(report.corrupt[0].contains("libstd.rlib"), "{:?}", report.corrupt);
+
+        cache.unpin("nightly-x86_64-unknown-linux-gnu").unwrap();
+        let gc = cache.gc(true).unwrap();
+        assert_eq!(gc.removed.len(), 2, "{:?}", gc);
+        assert_eq!(cache.entries().len(), 3);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_gc_waits_for_add_then_pin() {
+        let root = temp_root("lock");
+        let cache = ToolchainCache::open(root.join("cache")).unwrap();
+        fs::write(root.join("a.tar.gz"), "tarball a").unwrap();
+
+        let lock = cache.lock().unwrap();
+        let a = cache.add_tarball(&root.join("a.tar.gz")).unwrap();
+        let (done, finished) = std::sync::mpsc::channel();
+        let collector = cache.clone();
+        let gc = std::thread::spawn(move || {
+            let report = collector.gc(false);
+            done.send(()).unwrap();
+            report
+        });
+        assert!(finished.recv_timeout(Duration::from_millis(200)).is_err(), "gc ran while the cache was locked");
+        cache.pin("1.75.0", EntryKind::Tarball, &a).unwrap();
+        drop(lock);
+
+        assert!(gc.join().unwrap().unwrap().removed.is_empty());
+        assert!(cache.resolve("1.75.0").is_some());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
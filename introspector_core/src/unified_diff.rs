@@ -0,0 +1,823 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Line diffs in the unified format understood by `patch -p1` and
+// `git apply`: Myers or patience diffing with configurable context,
+// git-style patches for a set of file edits, a parser for such patches and
+// an applier that tolerates moved hunks (offset) and stale context (fuzz),
+// forwards or in reverse.
+//
+// Lines keep their `\n`, so a missing newline at the end of a file is a
+// change like any other and is rendered as `\ No newline at end of file`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[decl(enum, name = "DiffAlgorithm", vis = "pub", hash = "6bc798a4")]
+pub enum DiffAlgorithm {
+    /// Shortest edit script.
+    #[default]
+    Myers,
+    /// Anchors on lines unique to both sides first; reads better on moved
+    /// blocks, falls back to Myers between anchors.
+    Patience,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(struct, name = "DiffOptions", vis = "pub", hash = "57e5cd52")]
+pub struct DiffOptions {
+    /// Unchanged lines around each change.
+    pub context: usize,
+    pub algorithm: DiffAlgorithm,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions { context: 3, algorithm: DiffAlgorithm::Myers }
+    }
+}
+
+/// One step of an edit script, with 0-based line indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(enum, name = "DiffOp", vis = "pub", hash = "138396e1")]
+pub enum DiffOp {
+    Equal { old: usize, new: usize },
+    Delete { old: usize },
+    Insert { new: usize },
+}
+
+/// A hunk line; the text keeps its `\n` unless it is the unterminated last
+/// line of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(enum, name = "HunkLine", vis = "pub", hash = "f637501f")]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+impl HunkLine {
+    fn old_side(&self) -> Option<&str> {
+        match self {
+            HunkLine::Context(t) | HunkLine::Remove(t) => Some(t),
+            HunkLine::Add(_) => None,
+        }
+    }
+
+    fn new_side(&self) -> Option<&str> {
+        match self {
+            HunkLine::Context(t) | HunkLine::Add(t) => Some(t),
+            HunkLine::Remove(_) => None,
+        }
+    }
+
+    fn reversed(&self) -> HunkLine {
+        match self {
+            HunkLine::Context(t) => HunkLine::Context(t.clone()),
+            HunkLine::Remove(t) => HunkLine::Add(t.clone()),
+            HunkLine::Add(t) => HunkLine::Remove(t.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "Hunk", vis = "pub", hash = "b0beb403")]
+pub struct Hunk {
+    /// 1-based, or the line before the hunk when `old_len` is 0.
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    /// Text after the closing `@@`, like git's function context.
+    pub section: String,
+    pub lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    fn reversed(&self) -> Hunk {
+        Hunk {
+            old_start: self.new_start,
+            old_len: self.new_len,
+            new_start: self.old_start,
+            new_len: self.old_len,
+            section: self.section.clone(),
+            lines: self.lines.iter().map(HunkLine::reversed).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@@ -{},{} +{},{} @@", self.old_start, self.old_len, self.new_start, self.new_len)?;
+        if !self.section.is_empty() {
+            write!(f, " {}", self.section)?;
+        }
+        writeln!(f)?;
+        for line in &self.lines {
+            let (prefix, text) = match line {
+                HunkLine::Context(t) => (' ', t),
+                HunkLine::Remove(t) => ('-', t),
+                HunkLine::Add(t) => ('+', t),
+            };
+            write!(f, "{}{}", prefix, text)?;
+            if !text.ends_with('\n') {
+                write!(f, "\n\\ No newline at end of file\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The changes to one file; a `None` path is `/dev/null` (creation or
+/// deletion).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "FilePatch", vis = "pub", hash = "21c2b27c")]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// The path the patch applies to.
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or_default()
+    }
+
+    pub fn is_creation(&self) -> bool {
+        self.old_path.is_none()
+    }
+
+    pub fn is_deletion(&self) -> bool {
+        self.new_path.is_none()
+    }
+
+    pub fn reversed(&self) -> FilePatch {
+        FilePatch {
+            old_path: self.new_path.clone(),
+            new_path: self.old_path.clone(),
+            hunks: self.hunks.iter().map(Hunk::reversed).collect(),
+        }
+    }
+}
+
+impl fmt::Display for FilePatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path();
+        writeln!(f, "diff --git a/{} b/{}", self.old_path.as_deref().unwrap_or(path), self.new_path.as_deref().unwrap_or(path))?;
+        if self.is_creation() {
+            writeln!(f, "new file mode 100644")?;
+        } else if self.is_deletion() {
+            writeln!(f, "deleted file mode 100644")?;
+        }
+        match &self.old_path {
+            Some(p) => writeln!(f, "--- a/{}", p)?,
+            None => writeln!(f, "--- /dev/null")?,
+        }
+        match &self.new_path {
+            Some(p) => writeln!(f, "+++ b/{}", p)?,
+            None => writeln!(f, "+++ /dev/null")?,
+        }
+        for hunk in &self.hunks {
+            write!(f, "{}", hunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[decl(struct, name = "Patch", vis = "pub", hash = "bbb7a42f")]
+pub struct Patch {
+    pub files: Vec<FilePatch>,
+}
+
+impl Patch {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn reversed(&self) -> Patch {
+        Patch { files: self.files.iter().map(FilePatch::reversed).collect() }
+    }
+
+    /// Parses git-style and plain unified diffs. Text before, between and
+    /// after file sections is ignored; `a/` and `b/` prefixes are stripped.
+    pub fn parse(text: &str) -> Result<Patch, String> {
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        let mut files: Vec<FilePatch> = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i].trim_end_matches(['\n', '\r']);
+            if line.starts_with("--- ") && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ ")) {
+                let next = lines[i + 1].trim_end_matches(['\n', '\r']);
+                files.push(FilePatch {
+                    old_path: header_path(&line[4..], "a/"),
+                    new_path: header_path(&next[4..], "b/"),
+                    hunks: Vec::new(),
+                });
+                i += 2;
+            } else if line.starts_with("@@ ") {
+                let file = files.last_mut().ok_or_else(|| format!("line {}: hunk before any file header", i + 1))?;
+                let (hunk, consumed) = parse_hunk(&lines[i..]).map_err(|e| format!("line {}: {}", i + 1, e))?;
+                file.hunks.push(hunk);
+                i += consumed;
+            } else {
+                i += 1;
+            }
+        }
+        Ok(Patch { files })
+    }
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            write!(f, "{}", file)?;
+        }
+        Ok(())
+    }
+}
+
+fn header_path(raw: &str, prefix: &str) -> Option<String> {
+    // `--- path\t2024-01-01 00:00:00` from diff -u
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize), String> {
+    let (start, len) = range.split_once(',').unwrap_or((range, "1"));
+    let parse = |s: &str| s.parse::<usize>().map_err(|_| format!("invalid hunk range `{}`", range));
+    Ok((parse(start)?, parse(len)?))
+}
+
+/// The hunk starting at `lines[0]` and the number of lines it spans.
+fn parse_hunk(lines: &[&str]) -> Result<(Hunk, usize), String> {
+    let header = lines[0].trim_end_matches(['\n', '\r']);
+    let rest = &header[3..];
+    let (ranges, section) = rest.split_once(" @@").ok_or("malformed hunk header")?;
+    let mut parts = ranges.split(' ');
+    let old = parts.next().and_then(|p| p.strip_prefix('-')).ok_or("malformed hunk header")?;
+    let new = parts.next().and_then(|p| p.strip_prefix('+')).ok_or("malformed hunk header")?;
+    let (old_start, old_len) = parse_range(old)?;
+    let (new_start, new_len) = parse_range(new)?;
+
+    let mut hunk = Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        section: section.trim().to_string(),
+        lines: Vec::new(),
+    };
+    let (mut old_left, mut new_left) = (old_len, new_len);
+    let mut i = 1;
+    while old_left > 0 || new_left > 0 || lines.get(i).is_some_and(|l| l.starts_with('\\')) {
+        let Some(line) = lines.get(i) else {
+            return Err(format!("hunk ends early ({} old and {} new lines missing)", old_left, new_left));
+        };
+        let text = line.get(1..).unwrap_or_default().to_string();
+        match line.as_bytes().first() {
+            Some(b' ') => hunk.lines.push(HunkLine::Context(text)),
+            // Some tools strip the space of empty context lines.
+            Some(b'\n') => hunk.lines.push(HunkLine::Context("\n".to_string())),
+            Some(b'-') => hunk.lines.push(HunkLine::Remove(text)),
+            Some(b'+') => hunk.lines.push(HunkLine::Add(text)),
+            Some(b'\\') => {
+                match hunk.lines.last_mut() {
+                    Some(HunkLine::Context(t) | HunkLine::Remove(t) | HunkLine::Add(t)) => {
+                        if t.ends_with('\n') {
+                            t.pop();
+                        }
+                    }
+                    None => return Err("`\\ No newline` before any line".to_string()),
+                }
+                i += 1;
+                continue;
+            }
+            _ => return Err(format!("unexpected line in hunk: {:?}", line)),
+        }
+        let last = hunk.lines.last().unwrap();
+        if last.old_side().is_some() {
+            old_left = old_left.checked_sub(1).ok_or("hunk has more old lines than its header")?;
+        }
+        if last.new_side().is_some() {
+            new_left = new_left.checked_sub(1).ok_or("hunk has more new lines than its header")?;
+        }
+        i += 1;
+    }
+    Ok((hunk, i))
+}
+
+/// Shortest edit script of `a` into `b` (Myers, O((N+M)D)).
+fn myers(a: &[u32], b: &[u32]) -> Vec<DiffOp> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let offset = max as usize;
+    let mut v = vec![0isize; 2 * offset + 2];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + max) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) { v[idx + 1] } else { v[idx - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[(k - 1 + max) as usize] < v[(k + 1 + max) as usize]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + max) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(DiffOp::Equal { old: (x - 1) as usize, new: (y - 1) as usize });
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(DiffOp::Insert { new: (y - 1) as usize });
+            } else {
+                ops.push(DiffOp::Delete { old: (x - 1) as usize });
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+fn shifted(ops: Vec<DiffOp>, old_base: usize, new_base: usize) -> impl Iterator<Item = DiffOp> {
+    ops.into_iter().map(move |op| match op {
+        DiffOp::Equal { old, new } => DiffOp::Equal { old: old + old_base, new: new + new_base },
+        DiffOp::Delete { old } => DiffOp::Delete { old: old + old_base },
+        DiffOp::Insert { new } => DiffOp::Insert { new: new + new_base },
+    })
+}
+
+/// Longest increasing subsequence of `(a, b)` pairs sorted by `a`, by `b`.
+fn longest_increasing(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut piles: Vec<usize> = Vec::new();
+    let mut back: Vec<Option<usize>> = vec![None; pairs.len()];
+    for (i, &(_, b)) in pairs.iter().enumerate() {
+        let pile = piles.partition_point(|&top| pairs[top].1 < b);
+        back[i] = pile.checked_sub(1).map(|p| piles[p]);
+        if pile == piles.len() {
+            piles.push(i);
+        } else {
+            piles[pile] = i;
+        }
+    }
+    let mut result = Vec::new();
+    let mut cursor = piles.last().copied();
+    while let Some(i) = cursor {
+        result.push(pairs[i]);
+        cursor = back[i];
+    }
+    result.reverse();
+    result
+}
+
+fn patience(a: &[u32], b: &[u32], old_base: usize, new_base: usize, out: &mut Vec<DiffOp>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    out.extend((0..prefix).map(|i| DiffOp::Equal { old: old_base + i, new: new_base + i }));
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let (old_base, new_base) = (old_base + prefix, new_base + prefix);
+    let suffix = a.iter().rev().zip(b.iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+
+    let mut counts: HashMap<u32, (usize, usize, usize)> = HashMap::new();
+    for (i, line) in a_mid.iter().enumerate() {
+        let entry = counts.entry(*line).or_insert((0, 0, i));
+        entry.0 += 1;
+        entry.2 = i;
+    }
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut b_counts: HashMap<u32, (usize, usize)> = HashMap::new();
+    for (j, line) in b_mid.iter().enumerate() {
+        let entry = b_counts.entry(*line).or_insert((0, j));
+        entry.0 += 1;
+    }
+    for (line, &(count_b, j)) in &b_counts {
+        if let Some(&(1, _, i)) = counts.get(line) {
+            if count_b == 1 {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs.sort();
+    let anchors = longest_increasing(&pairs);
+
+    if anchors.is_empty() {
+        out.extend(shifted(myers(a_mid, b_mid), old_base, new_base));
+    } else {
+        let (mut i, mut j) = (0, 0);
+        for (ai, bj) in anchors {
+            patience(&a_mid[i..ai], &b_mid[j..bj], old_base + i, new_base + j, out);
+            out.push(DiffOp::Equal { old: old_base + ai, new: new_base + bj });
+            i = ai + 1;
+            j = bj + 1;
+        }
+        patience(&a_mid[i..], &b_mid[j..], old_base + i, new_base + j, out);
+    }
+
+    let (a_end, b_end) = (old_base + a_mid.len(), new_base + b_mid.len());
+    out.extend((0..suffix).map(|i| DiffOp::Equal { old: a_end + i, new: b_end + i }));
+}
+
+/// Edit script turning `old` into `new`.
+#[decl(fn, name = "diff_lines", vis = "pub", hash = "abdc8d81")]
+pub fn diff_lines<'a>(old: &[&'a str], new: &[&'a str], algorithm: DiffAlgorithm) -> Vec<DiffOp> {
+    let mut ids: HashMap<&'a str, u32> = HashMap::new();
+    let mut intern = |line: &'a str| {
+        let next = ids.len() as u32;
+        *ids.entry(line).or_insert(next)
+    };
+    let a: Vec<u32> = old.iter().map(|l| intern(l)).collect();
+    let b: Vec<u32> = new.iter().map(|l| intern(l)).collect();
+
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    match algorithm {
+        DiffAlgorithm::Patience => patience(&a, &b, 0, 0, &mut ops),
+        DiffAlgorithm::Myers => {
+            let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+            let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+            ops.extend((0..prefix).map(|i| DiffOp::Equal { old: i, new: i }));
+            ops.extend(shifted(myers(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]), prefix, prefix));
+            let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+            ops.extend((0..suffix).map(|i| DiffOp::Equal { old: a_end + i, new: b_end + i }));
+        }
+    }
+    ops
+}
+
+/// Hunks turning `old` into `new`; empty when they are equal.
+#[decl(fn, name = "diff_hunks", vis = "pub", hash = "8e3efa32")]
+pub fn diff_hunks(old: &str, new: &str, options: DiffOptions) -> Vec<Hunk> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines, options.algorithm);
+
+    // Old and new line counts consumed before each op.
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut o, mut n) = (0, 0);
+    for op in &ops {
+        positions.push((o, n));
+        match op {
+            DiffOp::Equal { .. } => { o += 1; n += 1; }
+            DiffOp::Delete { .. } => o += 1,
+            DiffOp::Insert { .. } => n += 1,
+        }
+    }
+    positions.push((o, n));
+
+    let changes: Vec<usize> = ops.iter().enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal { .. }))
+        .map(|(i, _)| i)
+        .collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        let start = i.saturating_sub(options.context);
+        let end = (i + 1 + options.context).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges.into_iter().map(|(start, end)| {
+        let lines: Vec<HunkLine> = ops[start..end].iter().map(|op| match *op {
+            DiffOp::Equal { old, .. } => HunkLine::Context(old_lines[old].to_string()),
+            DiffOp::Delete { old } => HunkLine::Remove(old_lines[old].to_string()),
+            DiffOp::Insert { new } => HunkLine::Add(new_lines[new].to_string()),
+        }).collect();
+        let (old_pos, new_pos) = positions[start];
+        let old_len = lines.iter().filter(|l| l.old_side().is_some()).count();
+        let new_len = lines.iter().filter(|l| l.new_side().is_some()).count();
+        Hunk {
+            old_start: if old_len == 0 { old_pos } else { old_pos + 1 },
+            old_len,
+            new_start: if new_len == 0 { new_pos } else { new_pos + 1 },
+            new_len,
+            section: String::new(),
+            lines,
+        }
+    }).collect()
+}
+
+/// One file of a change set; `None` is an absent file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "FileEdit", vis = "pub", hash = "823ae948")]
+pub struct FileEdit {
+    /// `/`-separated, relative to the patch root.
+    pub path: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// The patch of one file turning `old` into `new`.
+#[decl(fn, name = "diff_text", vis = "pub", hash = "788a5ba6")]
+pub fn diff_text(path: &str, old: &str, new: &str, options: DiffOptions) -> FilePatch {
+    FilePatch {
+        old_path: Some(path.to_string()),
+        new_path: Some(path.to_string()),
+        hunks: diff_hunks(old, new, options),
+    }
+}
+
+/// A git-style patch of `edits`; unchanged files are left out.
+#[decl(fn, name = "diff_edits", vis = "pub", hash = "5192121a")]
+pub fn diff_edits(edits: &[FileEdit], options: DiffOptions) -> Patch {
+    let files = edits.iter()
+        .filter(|e| e.old != e.new)
+        .map(|e| FilePatch {
+            old_path: e.old.as_ref().map(|_| e.path.clone()),
+            new_path: e.new.as_ref().map(|_| e.path.clone()),
+            hunks: diff_hunks(e.old.as_deref().unwrap_or_default(), e.new.as_deref().unwrap_or_default(), options),
+        })
+        .collect();
+    Patch { files }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(struct, name = "ApplyOptions", vis = "pub", hash = "0b9bea99")]
+pub struct ApplyOptions {
+    /// Context lines that may be ignored at each end of a hunk, like
+    /// `patch --fuzz`.
+    pub fuzz: usize,
+    /// Undo the patch instead of applying it.
+    pub reverse: bool,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        ApplyOptions { fuzz: 2, reverse: false }
+    }
+}
+
+/// Where a hunk applied, relative to its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[decl(struct, name = "HunkResult", vis = "pub", hash = "cbe54e2b")]
+pub struct HunkResult {
+    /// 1-based hunk number.
+    pub hunk: usize,
+    /// 1-based line of the patched file where the hunk starts.
+    pub line: usize,
+    pub offset: isize,
+    pub fuzz: usize,
+}
+
+impl fmt::Display for HunkResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hunk #{} succeeded at {}", self.hunk, self.line)?;
+        if self.fuzz > 0 {
+            write!(f, " with fuzz {}", self.fuzz)?;
+        }
+        if self.offset != 0 {
+            let plural = if self.offset.abs() == 1 { "" } else { "s" };
+            write!(f, " (offset {} line{})", self.offset, plural)?;
+        }
+        Ok(())
+    }
+}
+
+fn leading_context(lines: &[HunkLine]) -> usize {
+    lines.iter().take_while(|l| matches!(l, HunkLine::Context(_))).count()
+}
+
+/// `content` with `patch` applied, and where each hunk landed.
+#[decl(fn, name = "apply_file_patch", vis = "pub", hash = "39fbf1c2")]
+pub fn apply_file_patch(content: &str, patch: &FilePatch, options: ApplyOptions) -> Result<(String, Vec<HunkResult>), String> {
+    let patch = if options.reverse { patch.reversed() } else { patch.clone() };
+    let mut lines: Vec<String> = content.split_inclusive('\n').map(str::to_string).collect();
+    let mut results = Vec::new();
+    // Net lines added by the applied hunks, and the offset of the last one.
+    let mut shift: isize = 0;
+    let mut last_offset: isize = 0;
+    let mut min_pos = 0;
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let head = leading_context(&hunk.lines);
+        let tail = hunk.lines.iter().rev().take_while(|l| matches!(l, HunkLine::Context(_))).count();
+        let header_pos = if hunk.old_len == 0 { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+        let expected = header_pos as isize + shift + last_offset;
+
+        let mut applied = None;
+        for fuzz in 0..=options.fuzz.min(head.max(tail)) {
+            let front = fuzz.min(head);
+            let back = fuzz.min(tail).min(hunk.lines.len() - front);
+            let body = &hunk.lines[front..hunk.lines.len() - back];
+            let old: Vec<&str> = body.iter().filter_map(HunkLine::old_side).collect();
+            let target = expected + front as isize;
+
+            let fits = |pos: usize| pos + old.len() <= lines.len() && old.iter().zip(&lines[pos..]).all(|(a, b)| a == b);
+            let last_start = lines.len().saturating_sub(old.len());
+            let candidates = (0..=lines.len()).flat_map(|distance| {
+                let distance = distance as isize;
+                [target - distance, target + distance].into_iter().take(if distance == 0 { 1 } else { 2 })
+            });
+            let found = candidates
+                .filter(|&pos| pos >= min_pos as isize && pos <= last_start as isize)
+                .map(|pos| pos as usize)
+                .find(|&pos| fits(pos));
+            if let Some(pos) = found {
+                applied = Some((pos, fuzz, front, body, old.len()));
+                break;
+            }
+        }
+
+        let (pos, fuzz, front, body, old_len) = applied
+            .ok_or_else(|| format!("Hunk #{} FAILED at {}", index + 1, hunk.old_start))?;
+        let new: Vec<String> = body.iter().filter_map(HunkLine::new_side).map(str::to_string).collect();
+        let new_len = new.len();
+        lines.splice(pos..pos + old_len, new);
+
+        let offset = pos as isize - (expected + front as isize);
+        results.push(HunkResult { hunk: index + 1, line: pos + 1 - front.min(pos), offset: offset + last_offset, fuzz });
+        last_offset += offset;
+        shift += new_len as isize - old_len as isize;
+        min_pos = pos + new_len;
+    }
+    Ok((lines.concat(), results))
+}
+
+/// Outcome of one file of `apply_patch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[decl(struct, name = "FileResult", vis = "pub", hash = "10c3d8f7")]
+pub struct FileResult {
+    pub path: String,
+    pub hunks: Vec<HunkResult>,
+    pub created: bool,
+    pub deleted: bool,
+}
+
+/// Applies `patch` to the tree at `root`. Every file is patched in memory
+/// first, so a failing hunk leaves the tree untouched; `dry_run` stops there.
+#[decl(fn, name = "apply_patch", vis = "pub", hash = "365874b9")]
+pub fn apply_patch(root: &Path, patch: &Patch, options: ApplyOptions, dry_run: bool) -> Result<Vec<FileResult>, String> {
+    let patch = if options.reverse { patch.reversed() } else { patch.clone() };
+    let forward = ApplyOptions { reverse: false, ..options };
+
+    let mut planned: Vec<(PathBuf, Option<String>, FileResult)> = Vec::new();
+    for file in &patch.files {
+        let path = root.join(file.path());
+        let content = match (file.is_creation(), path.exists()) {
+            (true, true) => return Err(format!("{}: already exists", file.path())),
+            (true, false) => String::new(),
+            (false, _) => fs::read_to_string(&path).map_err(|e| format!("{}: {}", file.path(), e))?,
+        };
+        let (patched, hunks) = apply_file_patch(&content, file, forward)
+            .map_err(|e| format!("{}: {}", file.path(), e))?;
+        if file.is_deletion() && !patched.is_empty() {
+            return Err(format!("{}: not empty after removing its contents", file.path()));
+        }
+        let result = FileResult {
+            path: file.path().to_string(),
+            hunks,
+            created: file.is_creation(),
+            deleted: file.is_deletion(),
+        };
+        planned.push((path, (!file.is_deletion()).then_some(patched), result));
+    }
+
+    if !dry_run {
+        for (path, content, _) in &planned {
+            match content {
+                Some(content) => {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+                    }
+                    fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+                }
+                None => fs::remove_file(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+            }
+        }
+    }
+    Ok(planned.into_iter().map(|(_, _, result)| result).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\nfn f() {}\nfn g() {}\nfn h() {}\n";
+    const NEW: &str = "fn a() {}\nfn b() {}\nfn c() {}\nfn d2() {}\nfn e() {}\nfn f() {}\nfn g() {}\nfn h() {}\nfn i() {}";
+
+    #[test]
+    fn test_diff_render_and_parse() {
+        let patch = diff_edits(&[
+            FileEdit { path: "src/lib.rs".to_string(), old: Some(OLD.to_string()), new: Some(NEW.to_string()) },
+            FileEdit { path: "src/new.rs".to_string(), old: None, new: Some("x\n".to_string()) },
+            FileEdit { path: "src/same.rs".to_string(), old: Some("y\n".to_string()), new: Some("y\n".to_string()) },
+        ], DiffOptions { context: 1, ..Default::default() });
+        let text = patch.to_string();
+        assert_eq!(text, "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,3 +3,3 @@
+ fn c() {}
+-fn d() {}
++fn d2() {}
+ fn e() {}
+@@ -8,1 +8,2 @@
+ fn h() {}
++fn i() {}
+\\ No newline at end of file
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,1 @@
++x
+");
+        assert_eq!(Patch::parse(&format!("Subject: edits\n\n{}-- \n2.40\n", text)).unwrap(), patch);
+        assert!(Patch::parse("--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\n").is_err());
+    }
+
+    #[test]
+    fn test_patience_anchors_unique_lines() {
+        let old = ["{\n", "a\n", "}\n", "{\n", "b\n", "}\n"];
+        let new = ["{\n", "b\n", "}\n", "{\n", "a\n", "}\n"];
+        for algorithm in [DiffAlgorithm::Myers, DiffAlgorithm::Patience] {
+            let ops = diff_lines(&old, &new, algorithm);
+            let equal = ops.iter().filter(|op| matches!(op, DiffOp::Equal { .. })).count();
+            let deleted = ops.iter().filter(|op| matches!(op, DiffOp::Delete { .. })).count();
+            let inserted = ops.iter().filter(|op| matches!(op, DiffOp::Insert { .. })).count();
+            assert_eq!((equal + deleted, equal + inserted), (old.len(), new.len()), "{:?}", algorithm);
+            assert_eq!(equal, 4, "{:?}: {:?}", algorithm, ops);
+        }
+        // The braces between the swapped lines are unique inside the
+        // changed region, so patience keeps them as anchors.
+        let ops = diff_lines(&old, &new, DiffAlgorithm::Patience);
+        assert!(ops.contains(&DiffOp::Equal { old: 2, new: 2 }) && ops.contains(&DiffOp::Equal { old: 3, new: 3 }), "{:?}", ops);
+    }
+
+    #[test]
+    fn test_apply_with_offset_fuzz_and_reverse() {
+        let patch = diff_text("lib.rs", OLD, NEW, DiffOptions::default());
+        let (patched, results) = apply_file_patch(OLD, &patch, ApplyOptions::default()).unwrap();
+        assert_eq!(patched, NEW);
+        assert!(results.iter().all(|r| r.offset == 0 && r.fuzz == 0));
+
+        // Two lines added on top move every hunk; a changed context line
+        // needs fuzz.
+        let moved = format!("// one\n// two\n{}", OLD.replace("fn b() {}", "fn b2() {}"));
+        let (patched, results) = apply_file_patch(&moved, &patch, ApplyOptions::default()).unwrap();
+        assert_eq!(patched, format!("// one\n// two\n{}", NEW.replace("fn b() {}", "fn b2() {}")));
+        assert_eq!(results[0], HunkResult { hunk: 1, line: 3, offset: 2, fuzz: 2 });
+        assert_eq!(results[0].to_string(), "Hunk #1 succeeded at 3 with fuzz 2 (offset 2 lines)");
+        assert!(apply_file_patch(&moved, &patch, ApplyOptions { fuzz: 0, reverse: false }).is_err());
+
+        let reverse = ApplyOptions { reverse: true, ..Default::default() };
+        assert_eq!(apply_file_patch(NEW, &patch, reverse).unwrap().0, OLD);
+    }
+
+    #[test]
+    fn test_apply_patch_to_tree() {
+        let root = std::env::temp_dir().join(format!("unified_diff_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), OLD).unwrap();
+        fs::write(root.join("src/gone.rs"), "bye\n").unwrap();
+        let patch = diff_edits(&[
+            FileEdit { path: "src/lib.rs".to_string(), old: Some(OLD.to_string()), new: Some(NEW.to_string()) },
+            FileEdit { path: "src/gone.rs".to_string(), old: Some("bye\n".to_string()), new: None },
+            FileEdit { path: "src/added/mod.rs".to_string(), old: None, new: Some("hi\n".to_string()) },
+        ], DiffOptions::default());
+        let patch = Patch::parse(&patch.to_string()).unwrap();
+
+        let results = apply_patch(&root, &patch, ApplyOptions::default(), true).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), OLD);
+
+        apply_patch(&root, &patch, ApplyOptions::default(), false).unwrap();
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), NEW);
+        assert!(!root.join("src/gone.rs").exists());
+        assert_eq!(fs::read_to_string(root.join("src/added/mod.rs")).unwrap(), "hi\n");
+        // Applying twice fails on the created file before touching anything.
+        assert!(apply_patch(&root, &patch, ApplyOptions::default(), false).is_err());
+
+        apply_patch(&root, &patch, ApplyOptions { reverse: true, ..Default::default() }, false).unwrap();
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), OLD);
+        assert_eq!(fs::read_to_string(root.join("src/gone.rs")).unwrap(), "bye\n");
+        assert!(!root.join("src/added/mod.rs").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
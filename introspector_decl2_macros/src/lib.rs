@@ -11,6 +11,13 @@ pub fn decl2(attr: TokenStream, item: TokenStream) -> TokenStream {
     introspector_decl_core::process_decl2_attribute_logic(attr_2, item_2).into()
 }
 
+// Compile-time metadata of a `#[decl2]` item: `decl_of!(module::item)`,
+// `decl_of!(Type::method)` or `decl_of!(macro name)`.
+#[proc_macro]
+pub fn decl_of(input: TokenStream) -> TokenStream {
+    introspector_decl_core::decl_of_logic(input.into()).into()
+}
+
 // DECL_MODULE START
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
@@ -21,6 +28,7 @@ pub fn decl_module(input: TokenStream) -> TokenStream {
 
     let mut generated_macros = proc_macro2::TokenStream::new();
     let mut module_names: Vec<String> = Vec::new();
+    let module_idents: Vec<Ident> = modules.iter().cloned().collect();
 
     for module_ident in modules {
         let module_name_str = module_ident.to_string();
@@ -49,29 +57,26 @@ pub fn decl_module(input: TokenStream) -> TokenStream {
 
     let module_names_str: Vec<String> = module_names.iter().map(|s| s.to_string()).collect();
 
-    // Generate modules_list! macro
+    // Generate modules_list! macro. Proc-macro crates cannot `#[macro_export]`
+    // macro_rules!, so these stay crate-local and are re-exported with `use`.
     let modules_list_output = quote! {
-        #[macro_export]
+        #[allow(unused_macros)]
         macro_rules! modules_list {
             () => {
                 &[ #(#module_names_str),* ]
             };
         }
+        #[allow(unused_imports)]
+        pub(crate) use modules_list;
     };
     generated_macros.extend(modules_list_output);
 
-    // Placeholder for module_members! macro (conceptual)
-    let module_members_output = quote! {
-        #[macro_export]
-        macro_rules! module_members {
-            ($module_name:expr) => {
-                // This would eventually query introspector_decl_common for members of $module_name
-                // For now, it's a placeholder.
-                compile_error!("module_members! is not yet implemented to query actual members.");
-            };
-        }
-    };
-    generated_macros.extend(module_members_output);
+    // Generate module_members! from the module sources next to the crate root
+    let src_dir = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("src");
+    match introspector_decl_core::module_members_logic(&module_idents, &src_dir) {
+        Ok(module_members_output) => generated_macros.extend(module_members_output),
+        Err(e) => return e.to_compile_error().into(),
+    }
 
     // Generate prelude module
     let all_decl_macros_re_exports = module_names.iter().map(|module_name_str| {
@@ -84,7 +89,7 @@ pub fn decl_module(input: TokenStream) -> TokenStream {
     let prelude_module = quote! {
         #[macro_export]
         mod prelude {
-            pub use introspector_decl_common::{DeclInfo, register_decl};
+            pub use introspector_decl_common::{DeclInfo, ConstDeclMetadata, DECL_REGISTRY, all_decls};
             #(#all_decl_macros_re_exports)*
         }
     };
@@ -4,7 +4,11 @@ use once_cell::sync::Lazy;
 use lru::LruCache; // New import
 use std::num::NonZeroUsize; // New import
 
-#[derive(Debug, Clone)]
+// Re-exported so generated `inventory::submit!` calls resolve without the
+// user crate depending on `inventory` directly.
+pub use inventory;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DeclInfo {
     pub node_type: &'static str,
     pub name: &'static str,
@@ -15,77 +19,221 @@ pub struct DeclInfo {
     pub hash: &'static str,
 }
 
+inventory::collect!(DeclInfo);
+
+/// One entry of a `module_members!` table generated by `decl_module!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModuleMember {
+    pub name: &'static str,
+    pub kind: &'static str,
+    pub visibility: &'static str,
+    pub hash: &'static str,
+}
+
+/// Compile-time metadata emitted by `#[decl]`/`#[decl2]` as a hidden const
+/// next to each item and read back with `decl_of!(path)`. Unlike `DeclInfo`
+/// it needs no registry, so it can be used in `const` contexts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstDeclMetadata {
+    pub name: &'static str,
+    pub kind: &'static str,
+    pub signature: &'static str,
+    /// First line of the item's doc comment.
+    pub doc: Option<&'static str>,
+    pub hash: &'static str,
+    pub module: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl ConstDeclMetadata {
+    /// `const`-evaluable string equality, for use in static assertions.
+    pub const fn name_is(&self, name: &str) -> bool {
+        const_str_eq(self.name, name)
+    }
+
+    pub const fn kind_is(&self, kind: &str) -> bool {
+        const_str_eq(self.kind, kind)
+    }
+
+    pub const fn hash_is(&self, hash: &str) -> bool {
+        const_str_eq(self.hash, hash)
+    }
+}
+
+const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Index over every `DeclInfo` submitted by `#[decl]`/`#[decl2]` at link time.
 #[derive(Debug, Clone, Default)]
 pub struct DeclRegistry {
-    pub declarations: Vec<DeclInfo>,
-    pub by_type: HashMap<String, Vec<usize>>,
-    pub by_module: HashMap<String, Vec<usize>>,
-    pub by_hash: HashMap<String, usize>,
-}
-
-pub static DECL_REGISTRY: Lazy<Mutex<DeclRegistry>> = 
-    Lazy::new(|| Mutex::new(DeclRegistry::default()));
-
-pub fn register_decl(info: DeclInfo) {
-    if let Ok(mut registry) = DECL_REGISTRY.lock() {
-        let idx = registry.declarations.len();
-        
-        registry.by_type
-            .entry(info.node_type.to_string())
-            .or_default()
-            .push(idx);
-        
-        registry.by_module
-            .entry(info.module.to_string())
-            .or_default()
-            .push(idx);
-        
-        registry.by_hash
-            .insert(info.hash.to_string(), idx);
-        
-        registry.declarations.push(info);
+    pub declarations: Vec<&'static DeclInfo>,
+    pub by_name: HashMap<&'static str, Vec<usize>>,
+    pub by_type: HashMap<&'static str, Vec<usize>>,
+    pub by_module: HashMap<&'static str, Vec<usize>>,
+    pub by_visibility: HashMap<&'static str, Vec<usize>>,
+    pub by_hash: HashMap<&'static str, usize>,
+}
+
+impl DeclRegistry {
+    pub fn from_decls<I>(decls: I) -> Self
+    where
+        I: IntoIterator<Item = &'static DeclInfo>,
+    {
+        let mut registry = DeclRegistry::default();
+
+        for info in decls {
+            let idx = registry.declarations.len();
+
+            registry.by_name.entry(info.name).or_default().push(idx);
+            registry.by_type.entry(info.node_type).or_default().push(idx);
+            registry.by_module.entry(info.module).or_default().push(idx);
+            registry.by_visibility.entry(info.visibility).or_default().push(idx);
+            registry.by_hash.entry(info.hash).or_insert(idx);
+
+            registry.declarations.push(info);
+        }
+
+        registry
+    }
+
+    pub fn from_inventory() -> Self {
+        Self::from_decls(inventory::iter::<DeclInfo>)
+    }
+
+    pub fn len(&self) -> usize {
+        self.declarations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    pub fn all(&self) -> &[&'static DeclInfo] {
+        &self.declarations
+    }
+
+    pub fn by_name(&self, name: &str) -> Vec<&'static DeclInfo> {
+        Self::lookup(&self.declarations, &self.by_name, name)
+    }
+
+    pub fn by_type(&self, node_type: &str) -> Vec<&'static DeclInfo> {
+        Self::lookup(&self.declarations, &self.by_type, node_type)
+    }
+
+    pub fn by_module(&self, module: &str) -> Vec<&'static DeclInfo> {
+        Self::lookup(&self.declarations, &self.by_module, module)
+    }
+
+    pub fn by_visibility(&self, visibility: &str) -> Vec<&'static DeclInfo> {
+        Self::lookup(&self.declarations, &self.by_visibility, visibility)
+    }
+
+    pub fn by_hash(&self, hash: &str) -> Option<&'static DeclInfo> {
+        self.by_hash.get(hash).and_then(|&idx| self.declarations.get(idx).copied())
+    }
+
+    pub fn query(&self) -> DeclQuery<'_> {
+        DeclQuery { registry: self, node_type: None, name: None, module: None, visibility: None }
+    }
+
+    fn lookup(
+        declarations: &[&'static DeclInfo],
+        index: &HashMap<&'static str, Vec<usize>>,
+        key: &str,
+    ) -> Vec<&'static DeclInfo> {
+        index.get(key)
+            .map(|indices| {
+                indices.iter()
+                    .filter_map(|&i| declarations.get(i).copied())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Conjunctive filter over a `DeclRegistry`; unset criteria match everything.
+#[derive(Debug, Clone, Copy)]
+pub struct DeclQuery<'a> {
+    registry: &'a DeclRegistry,
+    node_type: Option<&'a str>,
+    name: Option<&'a str>,
+    module: Option<&'a str>,
+    visibility: Option<&'a str>,
+}
+
+impl<'a> DeclQuery<'a> {
+    pub fn kind(mut self, node_type: &'a str) -> Self {
+        self.node_type = Some(node_type);
+        self
+    }
+
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn module(mut self, module: &'a str) -> Self {
+        self.module = Some(module);
+        self
+    }
+
+    pub fn visibility(mut self, visibility: &'a str) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    pub fn run(&self) -> Vec<&'static DeclInfo> {
+        self.registry.declarations.iter()
+            .copied()
+            .filter(|d| self.node_type.is_none_or(|t| d.node_type == t))
+            .filter(|d| self.name.is_none_or(|n| d.name == n))
+            .filter(|d| self.module.is_none_or(|m| d.module == m))
+            .filter(|d| self.visibility.is_none_or(|v| d.visibility == v))
+            .collect()
     }
 }
 
+pub static DECL_REGISTRY: Lazy<DeclRegistry> = Lazy::new(DeclRegistry::from_inventory);
+
+pub fn all_decls() -> impl Iterator<Item = &'static DeclInfo> {
+    inventory::iter::<DeclInfo>.into_iter()
+}
+
 pub fn get_all_declarations() -> Vec<DeclInfo> {
-    DECL_REGISTRY.lock()
-        .map(|r| r.declarations.clone())
-        .unwrap_or_default()
+    DECL_REGISTRY.all().iter().map(|&d| *d).collect()
+}
+
+pub fn get_declarations_by_name(name: &str) -> Vec<DeclInfo> {
+    DECL_REGISTRY.by_name(name).into_iter().copied().collect()
 }
 
 pub fn get_declarations_by_type(node_type: &str) -> Vec<DeclInfo> {
-    DECL_REGISTRY.lock()
-        .map(|r| {
-            r.by_type.get(node_type)
-                .map(|indices| {
-                    indices.iter()
-                        .filter_map(|&i| r.declarations.get(i).cloned())
-                        .collect()
-                })
-                .unwrap_or_default()
-        })
-        .unwrap_or_default()
+    DECL_REGISTRY.by_type(node_type).into_iter().copied().collect()
 }
 
 pub fn get_declarations_by_module(module: &str) -> Vec<DeclInfo> {
-    DECL_REGISTRY.lock()
-        .map(|r| {
-            r.by_module.get(module)
-                .map(|indices| {
-                    indices.iter()
-                        .filter_map(|&i| r.declarations.get(i).cloned())
-                        .collect()
-                })
-                .unwrap_or_default()
-        })
-        .unwrap_or_default()
+    DECL_REGISTRY.by_module(module).into_iter().copied().collect()
+}
+
+pub fn get_declarations_by_visibility(visibility: &str) -> Vec<DeclInfo> {
+    DECL_REGISTRY.by_visibility(visibility).into_iter().copied().collect()
 }
 
 pub fn get_declaration_by_hash(hash: &str) -> Option<DeclInfo> {
-    DECL_REGISTRY.lock().ok()?.by_hash.get(hash)
-        .and_then(|&idx| {
-            DECL_REGISTRY.lock().ok()?.declarations.get(idx).cloned()
-        })
+    DECL_REGISTRY.by_hash(hash).copied()
 }
 
 // Global LRU Cache for storing arbitrary data (e.g., serialized objects)
@@ -108,3 +256,60 @@ pub fn query_from_lru_cache(key: &str) -> Option<Vec<u8>> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    inventory::submit! {
+        DeclInfo {
+            node_type: "fn",
+            name: "registry_probe",
+            visibility: "pub",
+            module: "introspector_decl_common::tests",
+            file: file!(),
+            line: line!(),
+            hash: "0badc0de",
+        }
+    }
+
+    const PROBE_META: ConstDeclMetadata = ConstDeclMetadata {
+        name: "registry_probe",
+        kind: "fn",
+        signature: "fn registry_probe ()",
+        doc: None,
+        hash: "0badc0de",
+        module: module_path!(),
+        file: file!(),
+        line: line!(),
+    };
+
+    const _: () = assert!(PROBE_META.name_is("registry_probe") && PROBE_META.kind_is("fn"));
+
+    #[test]
+    fn test_const_metadata_comparisons() {
+        assert!(PROBE_META.hash_is("0badc0de"));
+        assert!(!PROBE_META.hash_is("0badc0d"));
+        assert!(!PROBE_META.name_is("registry_prob3"));
+    }
+
+    #[test]
+    fn test_inventory_registration_is_queryable() {
+        let by_name = get_declarations_by_name("registry_probe");
+        assert_eq!(by_name.len(), 1);
+        assert_eq!(by_name[0].node_type, "fn");
+
+        assert!(get_declaration_by_hash("0badc0de").is_some());
+        assert!(all_decls().any(|d| d.name == "registry_probe"));
+        assert_eq!(
+            DECL_REGISTRY.query()
+                .kind("fn")
+                .module("introspector_decl_common::tests")
+                .visibility("pub")
+                .run()
+                .len(),
+            1
+        );
+        assert!(DECL_REGISTRY.query().kind("struct").name("registry_probe").run().is_empty());
+    }
+}
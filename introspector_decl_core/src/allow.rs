@@ -0,0 +1,98 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{Lit, LitStr};
+
+// `#[audit_allow(...)]`: an inline suppression of audit findings on an item.
+// The attribute itself leaves the item untouched; `cargo-audit-fix scan`
+// reads it from source. Both sides parse the arguments here so an attribute
+// that compiles is one the scanner honours.
+
+pub const AUDIT_ALLOW: &str = "audit_allow";
+
+/// Ticket ids and the mandatory justification of one suppression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditAllow {
+    pub ticket_ids: Vec<String>,
+    pub reason: String,
+}
+
+/// Parses `SEC-001, FKD-002, reason = "..."`. Ids may be bare (`SEC-001`)
+/// or string literals; `reason` is required and must not be blank.
+pub fn parse_audit_allow(args: TokenStream) -> Result<AuditAllow, String> {
+    let mut ticket_ids = Vec::new();
+    let mut reason = None;
+
+    let mut parts: Vec<Vec<TokenTree>> = vec![Vec::new()];
+    for tt in args {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => parts.push(Vec::new()),
+            _ => parts.last_mut().unwrap().push(tt),
+        }
+    }
+
+    for part in parts.into_iter().filter(|p| !p.is_empty()) {
+        match part.as_slice() {
+            [TokenTree::Ident(key), TokenTree::Punct(eq), value] if key == "reason" && eq.as_char() == '=' => {
+                match syn::parse2::<LitStr>(value.clone().into()) {
+                    Ok(lit) => reason = Some(lit.value()),
+                    Err(_) => return Err("`reason` must be a string literal".to_string()),
+                }
+            }
+            [TokenTree::Literal(lit)] => match Lit::new(lit.clone()) {
+                Lit::Str(s) => ticket_ids.push(s.value()),
+                _ => return Err(format!("expected a ticket id, found `{}`", lit)),
+            },
+            tokens => {
+                let id: String = tokens.iter().map(|t| t.to_string()).collect();
+                let valid = id.split_once('-').is_some_and(|(prefix, number)| {
+                    !prefix.is_empty()
+                        && prefix.chars().all(|c| c.is_ascii_uppercase())
+                        && !number.is_empty()
+                        && number.chars().all(|c| c.is_ascii_digit())
+                });
+                if !valid {
+                    return Err(format!("expected a ticket id like `SEC-001`, found `{}`", id));
+                }
+                ticket_ids.push(id);
+            }
+        }
+    }
+
+    if ticket_ids.is_empty() {
+        return Err("audit_allow needs at least one ticket id".to_string());
+    }
+    match reason {
+        Some(reason) if !reason.trim().is_empty() => Ok(AuditAllow { ticket_ids, reason: reason.trim().to_string() }),
+        Some(_) => Err("audit_allow reason must not be empty".to_string()),
+        None => Err("audit_allow needs a justification: `reason = \"...\"`".to_string()),
+    }
+}
+
+/// `#[audit_allow]` expansion: the item unchanged, or a `compile_error!`
+/// next to it when the arguments are invalid.
+pub fn audit_allow_logic(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match parse_audit_allow(attr) {
+        Ok(_) => item,
+        Err(message) => quote! {
+            ::core::compile_error!(#message);
+            #item
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_audit_allow() {
+        let allow = parse_audit_allow(quote!(SEC-001, "FKD-002", reason = "vetted FFI wrapper")).unwrap();
+        assert_eq!(allow.ticket_ids, vec!["SEC-001", "FKD-002"]);
+        assert_eq!(allow.reason, "vetted FFI wrapper");
+
+        assert!(parse_audit_allow(quote!(SEC-001)).unwrap_err().contains("justification"));
+        assert!(parse_audit_allow(quote!(SEC-001, reason = "  ")).is_err());
+        assert!(parse_audit_allow(quote!(reason = "why")).is_err());
+        assert!(parse_audit_allow(quote!(sec_001, reason = "why")).is_err());
+    }
+}
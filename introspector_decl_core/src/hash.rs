@@ -0,0 +1,206 @@
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::Item;
+
+// Canonical declaration hashing shared by `#[decl]`/`#[decl2]` at expansion
+// time and by `cargo-audit-fix decl-rehash` when it rewrites stale hashes.
+// Both sides see the same tokens but through different `proc_macro2` backends,
+// so the hash is taken over a normalized serialization rather than
+// `TokenStream::to_string()`, and outer attributes (including the `#[decl]`
+// attribute carrying the hash itself) are excluded.
+
+pub const DRIFT_ENV_VAR: &str = "DECL_HASH_DRIFT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftPolicy {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl DriftPolicy {
+    /// Reads `DECL_HASH_DRIFT` (`allow`, `warn` or `deny`); defaults to `warn`.
+    /// Expansions that depend on it read it again with `option_env!` (see
+    /// `drift_diagnostic`), so cargo rebuilds them when it changes.
+    pub fn from_env() -> Self {
+        match std::env::var(DRIFT_ENV_VAR).as_deref() {
+            Ok("allow") => DriftPolicy::Allow,
+            Ok("deny") | Ok("error") => DriftPolicy::Deny,
+            _ => DriftPolicy::Warn,
+        }
+    }
+}
+
+pub fn item_hash(item: &Item) -> String {
+    let mut item = item.clone();
+    if let Some(attrs) = item_attrs_mut(&mut item) {
+        attrs.clear();
+    }
+    tokens_hash(item.to_token_stream())
+}
+
+pub fn tokens_hash(tokens: TokenStream) -> String {
+    let mut canonical = String::new();
+    write_canonical(tokens, &mut canonical);
+    format!("{:08x}", fnv1a32(canonical.as_bytes()))
+}
+
+pub fn item_attrs_mut(item: &mut Item) -> Option<&mut Vec<syn::Attribute>> {
+    match item {
+        Item::Const(i) => Some(&mut i.attrs),
+        Item::Enum(i) => Some(&mut i.attrs),
+        Item::ExternCrate(i) => Some(&mut i.attrs),
+        Item::Fn(i) => Some(&mut i.attrs),
+        Item::ForeignMod(i) => Some(&mut i.attrs),
+        Item::Impl(i) => Some(&mut i.attrs),
+        Item::Macro(i) => Some(&mut i.attrs),
+        Item::Mod(i) => Some(&mut i.attrs),
+        Item::Static(i) => Some(&mut i.attrs),
+        Item::Struct(i) => Some(&mut i.attrs),
+        Item::Trait(i) => Some(&mut i.attrs),
+        Item::TraitAlias(i) => Some(&mut i.attrs),
+        Item::Type(i) => Some(&mut i.attrs),
+        Item::Union(i) => Some(&mut i.attrs),
+        Item::Use(i) => Some(&mut i.attrs),
+        _ => None,
+    }
+}
+
+/// Tokens for a warning (or error, under `Deny`) when `declared` no longer
+/// matches `actual`. Emitted next to the wrapped item.
+///
+/// The policy only matters on a mismatch, so that is also when the expansion
+/// reads `DECL_HASH_DRIFT` through `option_env!`: rustc records it in the
+/// crate's dep-info and cargo recompiles once the variable changes, even under
+/// `Allow`. `proc_macro::tracked_env` would do the same but is nightly-only.
+pub fn drift_diagnostic(
+    name: &str,
+    declared: Option<&str>,
+    declared_span: Option<Span>,
+    actual: &str,
+    policy: DriftPolicy,
+) -> TokenStream {
+    let declared = match declared {
+        Some(declared) if declared != actual => declared,
+        _ => return TokenStream::new(),
+    };
+    let span = declared_span.unwrap_or_else(Span::call_site);
+    let message = format!(
+        "decl hash drift: `{}` declares hash \"{}\" but its current body hashes to \"{}\"; \
+         run `cargo-audit-fix decl-rehash` to update it",
+        name, declared, actual
+    );
+
+    let tracked = quote_spanned! {span=>
+        const _: ::core::option::Option<&str> = ::core::option_env!(#DRIFT_ENV_VAR);
+    };
+
+    let diagnostic = match policy {
+        DriftPolicy::Allow => TokenStream::new(),
+        DriftPolicy::Deny => quote_spanned! {span=>
+            compile_error!(#message);
+        },
+        // Stable proc macros cannot emit warnings directly, so route the
+        // message through a deprecated constant that is used right away.
+        DriftPolicy::Warn => quote_spanned! {span=>
+            const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const decl_hash_drift: () = ();
+                decl_hash_drift
+            };
+        },
+    };
+    quote! {
+        #tracked
+        #diagnostic
+    }
+}
+
+fn write_canonical(tokens: TokenStream, out: &mut String) {
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                write_canonical(group.stream(), out);
+                out.push_str(close);
+            }
+            TokenTree::Ident(ident) => {
+                out.push_str(&ident.to_string());
+                out.push(' ');
+            }
+            TokenTree::Punct(punct) => out.push(punct.as_char()),
+            TokenTree::Literal(literal) => {
+                // rustc hands doc comments to proc macros as raw strings while
+                // the fallback parser escapes them; compare string values.
+                let text = literal.to_string();
+                match syn::parse_str::<syn::LitStr>(&text) {
+                    Ok(s) => out.push_str(&format!("{:?}", s.value())),
+                    Err(_) => out.push_str(&text),
+                }
+                out.push(' ');
+            }
+        }
+    }
+}
+
+fn fnv1a32(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for &b in bytes {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_ignores_outer_attributes_and_formatting() {
+        let a: Item = syn::parse_str(
+            "#[decl(fn, name = \"f\", hash = \"00000000\")]\n/// docs\npub fn f(x: u32) -> u32 { x + 1 }",
+        ).unwrap();
+        let b: Item = syn::parse_str("pub fn f(x: u32)->u32{\n    x+1\n}").unwrap();
+        assert_eq!(item_hash(&a), item_hash(&b));
+    }
+
+    #[test]
+    fn test_hash_tracks_body_changes() {
+        let a: Item = syn::parse_str("pub fn f(x: u32) -> u32 { x + 1 }").unwrap();
+        let b: Item = syn::parse_str("pub fn f(x: u32) -> u32 { x + 2 }").unwrap();
+        assert_ne!(item_hash(&a), item_hash(&b));
+    }
+
+    #[test]
+    fn test_raw_and_escaped_doc_strings_hash_equal() {
+        let raw: Item = syn::parse_str("struct S { #[doc = r\" a field\"] a: u8 }").unwrap();
+        let esc: Item = syn::parse_str("struct S { #[doc = \" a field\"] a: u8 }").unwrap();
+        assert_eq!(item_hash(&raw), item_hash(&esc));
+    }
+
+    #[test]
+    fn test_drift_diagnostic_only_on_mismatch() {
+        assert!(drift_diagnostic("f", Some("abc"), None, "abc", DriftPolicy::Deny).is_empty());
+        assert!(drift_diagnostic("f", None, None, "abc", DriftPolicy::Deny).is_empty());
+        let err = drift_diagnostic("f", Some("old"), None, "abc", DriftPolicy::Deny).to_string();
+        assert!(err.contains("compile_error"));
+        let warn = drift_diagnostic("f", Some("old"), None, "abc", DriftPolicy::Warn).to_string();
+        assert!(warn.contains("deprecated"));
+    }
+
+    #[test]
+    fn test_drift_reads_policy_variable_under_every_policy() {
+        for policy in [DriftPolicy::Allow, DriftPolicy::Warn, DriftPolicy::Deny] {
+            let out = drift_diagnostic("f", Some("old"), None, "abc", policy).to_string();
+            assert!(out.contains("option_env ! (\"DECL_HASH_DRIFT\")"), "{:?}: {}", policy, out);
+        }
+    }
+}
@@ -1,15 +1,64 @@
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::quote;
+use quote::ToTokens;
 use syn::{
-    ItemFn, ItemStruct, ItemEnum, ItemTrait, Ident,
+    ItemFn, ItemStruct, ItemEnum, ItemTrait, ItemImpl, ItemConst, ItemStatic, ItemType,
+    ItemUnion, ItemForeignMod, ItemMacro, ImplItem, ForeignItem, Ident,
     Visibility, LitStr, parse::Parse, parse::ParseStream, Result as SynResult
 };
-use introspector_macro_helpers::{generate_decl_registration, parse_decl_args, dispatch_wrap_logic};
+use syn::ext::IdentExt;
+use introspector_macro_helpers::{parse_decl_args, dispatch_wrap_logic};
+
+pub mod allow;
+pub mod hash;
+pub mod members;
+pub mod meta;
+
+pub use allow::{AUDIT_ALLOW, AuditAllow, parse_audit_allow, audit_allow_logic};
+pub use hash::{item_hash, tokens_hash, DriftPolicy};
+pub use members::{collect_module_members, module_members_logic, module_source_path, visibility_str, MemberData};
+pub use meta::{decl_of_logic, meta_ident, item_signature, doc_summary, repeated_decl};
 
 // Core logic function
 pub fn process_decl2_attribute_logic(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let args = parse_decl_args!(attr);
-    dispatch_wrap_logic!(item, args)
+    let mut args: DeclArgs = parse_decl_args!(attr);
+
+    // Verify the declared hash against the item as it is now, and fill it in
+    // when the attribute does not carry one.
+    let drift = match syn::parse2::<syn::Item>(item.clone()) {
+        Ok(mut parsed) => {
+            let name = args.name.clone().unwrap_or_else(|| "declaration".to_string());
+            // The remaining attribute expands on its own; wrapping here as
+            // well would emit a second metadata const for the same item.
+            if let Some(repeated) = hash::item_attrs_mut(&mut parsed).and_then(|attrs| meta::repeated_decl(attrs)) {
+                let error = syn::Error::new_spanned(
+                    repeated,
+                    format!("decl applied twice to `{}`; keep a single #[decl]/#[decl2]", name),
+                ).to_compile_error();
+                return quote! {
+                    #item
+                    #error
+                };
+            }
+            let actual = item_hash(&parsed);
+            let drift = hash::drift_diagnostic(
+                &name,
+                args.hash.as_deref(),
+                args.hash_span,
+                &actual,
+                DriftPolicy::from_env(),
+            );
+            args.hash.get_or_insert(actual);
+            drift
+        }
+        Err(_) => TokenStream::new(),
+    };
+
+    let wrapped = dispatch_wrap_logic!(item, args, crate);
+    quote! {
+        #wrapped
+        #drift
+    }
 }
 
 
@@ -18,6 +67,7 @@ pub struct DeclArgs {
     pub name: Option<String>,
     pub vis: Option<String>,
     pub hash: Option<String>,
+    pub hash_span: Option<proc_macro2::Span>,
     pub extra: Vec<(String, String)>,
 }
 
@@ -28,12 +78,14 @@ impl Parse for DeclArgs {
             name: None,
             vis: None,
             hash: None,
+            hash_span: None,
             extra: vec![],
         };
 
-        // First token might be the node type
-        if input.peek(syn::Ident) {
-            let ident: Ident = input.parse()?;
+        // First token might be the node type; `fn`, `struct`, `enum`, `trait`
+        // and friends are keywords, so accept any identifier here.
+        if input.peek(Ident::peek_any) && !input.peek2(syn::Token![=]) {
+            let ident = Ident::parse_any(input)?;
             args.node_type = Some(ident.to_string());
 
             if input.peek(syn::Token![,]) {
@@ -50,7 +102,10 @@ impl Parse for DeclArgs {
             match key.to_string().as_str() {
                 "name" => args.name = Some(value.value()),
                 "vis" => args.vis = Some(value.value()),
-                "hash" => args.hash = Some(value.value()),
+                "hash" => {
+                    args.hash = Some(value.value());
+                    args.hash_span = Some(value.span());
+                }
                 other => args.extra.push((other.to_string(), value.value())),
             }
 
@@ -71,150 +126,355 @@ pub fn compute_hash(content: &str) -> String {
     format!("{:08x}", hasher.finish() & 0xFFFFFFFF)
 }
 
-pub fn wrap_function(args: &DeclArgs, item: &mut ItemFn) -> TokenStream {
-    let name = item.sig.ident.to_string();
-    let hash = args.hash.clone().unwrap_or_else(|| compute_hash(&item.to_token_stream().to_string()));
-
-    let vis_str = match &item.vis {
-        Visibility::Public(_) => "pub",
-        Visibility::Restricted(_) => "pub(restricted)",
-        Visibility::Inherited => "private",
-        _ => "unknown_visibility",
-    };
-
-    let line = 0 as u32; // Temporary workaround for proc_macro2::Span::start().line issue
-    let module_path_str = module_path!().to_string();
-
-    let registration_code = quote! {
+/// Registration for one declaration, emitted next to the wrapped item.
+/// Associated and foreign items report the line of the `#[decl]` attribute.
+fn registration(node_type: &str, name: &str, vis: &str, hash: &str) -> TokenStream {
+    quote! {
         // Auto-generated declaration registration
         const _: () = {
-            introspector_macro_helpers::generate_decl_registration!(
-                "fn",
-                #name,
-                #vis_str,
-                #module_path_str,
-                file!(),
-                #line,
-                #hash
-            );
+            ::introspector_decl_common::inventory::submit! {
+                ::introspector_decl_common::DeclInfo {
+                    node_type: #node_type,
+                    name: #name,
+                    visibility: #vis,
+                    module: module_path!(),
+                    file: file!(),
+                    line: line!(),
+                    hash: #hash,
+                }
+            }
         };
-    };
+    }
+}
+
+fn declared_hash(args: &DeclArgs, item: syn::Item) -> String {
+    args.hash.clone().unwrap_or_else(|| item_hash(&item))
+}
 
-    let output = quote! {
+/// Item, its registration and its hidden `ConstDeclMetadata` const.
+fn wrap_named(args: &DeclArgs, node_type: &str, ident: &Ident, vis: &Visibility, item: syn::Item) -> TokenStream {
+    let name = ident.to_string();
+    let hash = declared_hash(args, item.clone());
+    let registration_code = registration(node_type, &name, visibility_str(vis), &hash);
+
+    let mut probe = item.clone();
+    let doc = hash::item_attrs_mut(&mut probe).and_then(|attrs| meta::doc_summary(attrs));
+    let meta_const = meta::MetaConst {
+        vis,
+        ident: meta::meta_ident(ident, false),
+        name: &name,
+        kind: node_type,
+        signature: meta::item_signature(&item),
+        doc,
+        hash: &hash,
+    }.to_tokens();
+
+    quote! {
         #item
         #registration_code
-    };
+        #meta_const
+    }
+}
 
-    output.into()
+pub fn wrap_function(args: &DeclArgs, item: &mut ItemFn) -> TokenStream {
+    wrap_named(args, "fn", &item.sig.ident, &item.vis, item.clone().into())
 }
 
 pub fn wrap_struct(args: &DeclArgs, item: &mut ItemStruct) -> TokenStream {
-    let name = item.ident.to_string();
-    let hash = args.hash.clone().unwrap_or_else(|| compute_hash(&item.to_token_stream().to_string()));
-
-    let vis_str = match &item.vis {
-        Visibility::Public(_) => "pub",
-        Visibility::Restricted(_) => "pub(restricted)",
-        Visibility::Inherited => "private",
-        _ => "unknown_visibility",
-    };
+    wrap_named(args, "struct", &item.ident, &item.vis, item.clone().into())
+}
 
-    let line = 0 as u32; // Temporary workaround for proc_macro2::Span::start().line issue
-    let module_path_str = module_path!().to_string();
+pub fn wrap_enum(args: &DeclArgs, item: &mut ItemEnum) -> TokenStream {
+    wrap_named(args, "enum", &item.ident, &item.vis, item.clone().into())
+}
 
-    let registration_code = quote! {
-        // Auto-generated declaration registration
-        const _: () = {
-            introspector_macro_helpers::generate_decl_registration!(
-                "struct",
-                #name,
-                #vis_str,
-                #module_path_str,
-                file!(),
-                #line,
-                #hash
-            );
-        };
-    };
+pub fn wrap_trait(args: &DeclArgs, item: &mut ItemTrait) -> TokenStream {
+    wrap_named(args, "trait", &item.ident, &item.vis, item.clone().into())
+}
 
-    let output = quote! {
-        #item
-        #registration_code
-    };
+pub fn wrap_const(args: &DeclArgs, item: &mut ItemConst) -> TokenStream {
+    wrap_named(args, "const", &item.ident, &item.vis, item.clone().into())
+}
 
-    output.into()
+pub fn wrap_static(args: &DeclArgs, item: &mut ItemStatic) -> TokenStream {
+    wrap_named(args, "static", &item.ident, &item.vis, item.clone().into())
 }
 
-pub fn wrap_enum(args: &DeclArgs, item: &mut ItemEnum) -> TokenStream {
-    let name = item.ident.to_string();
-    let hash = args.hash.clone().unwrap_or_else(|| compute_hash(&item.to_token_stream().to_string()));
-
-    let vis_str = match &item.vis {
-        Visibility::Public(_) => "pub",
-        Visibility::Restricted(_) => "pub(restricted)",
-        Visibility::Inherited => "private",
-        _ => "unknown_visibility",
-    };
+pub fn wrap_type_alias(args: &DeclArgs, item: &mut ItemType) -> TokenStream {
+    wrap_named(args, "type", &item.ident, &item.vis, item.clone().into())
+}
 
-    let line = 0 as u32; // Temporary workaround for proc_macro2::Span::start().line issue
-    let module_path_str = module_path!().to_string();
+pub fn wrap_union(args: &DeclArgs, item: &mut ItemUnion) -> TokenStream {
+    wrap_named(args, "union", &item.ident, &item.vis, item.clone().into())
+}
 
-    let registration_code = quote! {
-        // Auto-generated declaration registration
-        const _: () = {
-            introspector_macro_helpers::generate_decl_registration!(
-                "enum",
-                #name,
-                #vis_str,
-                #module_path_str,
-                file!(),
-                #line,
-                #hash
-            );
-        };
+/// `macro_rules!` definitions are public when `#[macro_export]`ed.
+pub fn wrap_macro_rules(args: &DeclArgs, item: &mut ItemMacro) -> TokenStream {
+    let ident = match &item.ident {
+        Some(ident) => ident.clone(),
+        None => return item.to_token_stream(),
     };
-
-    let output = quote! {
+    let name = ident.to_string();
+    let exported = item.attrs.iter().any(|a| a.path().is_ident("macro_export"));
+    let vis: Visibility = if exported { syn::parse_quote!(pub) } else { Visibility::Inherited };
+    let hash = declared_hash(args, item.clone().into());
+    let registration_code = registration("macro", &name, visibility_str(&vis), &hash);
+
+    let meta_const = meta::MetaConst {
+        vis: &vis,
+        ident: meta::meta_ident(&ident, true),
+        name: &name,
+        kind: "macro",
+        signature: meta::item_signature(&item.clone().into()),
+        doc: meta::doc_summary(&item.attrs),
+        hash: &hash,
+    }.to_tokens();
+
+    quote! {
         #item
         #registration_code
-    };
+        #meta_const
+    }
+}
 
-    output.into()
+/// `Type` or `Trait for Type`, as used for the registered impl name.
+pub fn impl_display_name(item: &ItemImpl) -> String {
+    let self_ty = impl_owner_name(item);
+    match &item.trait_ {
+        Some((bang, path, _)) => {
+            let negation = if bang.is_some() { "!" } else { "" };
+            format!("{}{} for {}", negation, compact_tokens(path), self_ty)
+        }
+        None => self_ty,
+    }
 }
 
-pub fn wrap_trait(args: &DeclArgs, item: &mut ItemTrait) -> TokenStream {
-    let name = item.ident.to_string();
-    let hash = args.hash.clone().unwrap_or_else(|| compute_hash(&item.to_token_stream().to_string()));
-
-    let vis_str = match &item.vis {
-        Visibility::Public(_) => "pub",
-        Visibility::Restricted(_) => "pub(restricted)",
-        Visibility::Inherited => "private",
-        _ => "unknown_visibility",
-    };
+/// The implementing type, used to prefix associated item names.
+pub fn impl_owner_name(item: &ItemImpl) -> String {
+    compact_tokens(&item.self_ty)
+}
 
-    let line = 0 as u32; // Temporary workaround for proc_macro2::Span::start().line issue
-    let module_path_str = module_path!().to_string();
+fn compact_tokens<T: ToTokens>(tokens: &T) -> String {
+    tokens.to_token_stream().to_string()
+        .replace(" :: ", "::")
+        .replace(" < ", "<")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
 
-    let registration_code = quote! {
-        // Auto-generated declaration registration
-        const _: () = {
-            introspector_macro_helpers::generate_decl_registration!(
-                "trait",
-                #name,
-                #vis_str,
-                #module_path_str,
-                file!(),
-                #line,
-                #hash
-            );
+/// Strips outer attributes before hashing so member hashes stay stable
+/// under `#[decl]`/doc edits, like `item_hash` does for whole items.
+fn member_hash<T: Clone + ToTokens>(item: &T, attrs: impl FnOnce(&mut T) -> &mut Vec<syn::Attribute>) -> String {
+    let mut item = item.clone();
+    attrs(&mut item).clear();
+    tokens_hash(item.to_token_stream())
+}
+
+/// Registers the impl block and each associated fn, const and type as
+/// `Type::item`. Trait impl members inherit the trait's visibility, so they
+/// are recorded as `pub`. Inherent impls also get an associated metadata
+/// const per member, reachable as `decl_of!(Type::item)`; trait impls cannot
+/// carry extra items.
+pub fn wrap_impl(args: &DeclArgs, item: &mut ItemImpl) -> TokenStream {
+    let impl_name = args.name.clone().unwrap_or_else(|| impl_display_name(item));
+    let hash = declared_hash(args, item.clone().into());
+    let mut registrations = vec![registration("impl", &impl_name, "private", &hash)];
+
+    let owner = impl_owner_name(item);
+    let is_trait_impl = item.trait_.is_some();
+    let member_vis = |vis: &Visibility| if is_trait_impl { "pub" } else { visibility_str(vis) };
+
+    let mut meta_consts = Vec::new();
+
+    for member in &item.items {
+        let (node_type, ident, vis, attrs, signature, member_hash) = match member {
+            ImplItem::Fn(f) => {
+                ("fn", &f.sig.ident, &f.vis, &f.attrs, f.sig.to_token_stream(), member_hash(f, |f| &mut f.attrs))
+            }
+            ImplItem::Const(c) => {
+                let (id, ty) = (&c.ident, &c.ty);
+                ("const", id, &c.vis, &c.attrs, quote!(const #id : #ty), member_hash(c, |c| &mut c.attrs))
+            }
+            ImplItem::Type(t) => {
+                let (id, ty) = (&t.ident, &t.ty);
+                ("type", id, &t.vis, &t.attrs, quote!(type #id = #ty), member_hash(t, |t| &mut t.attrs))
+            }
+            _ => continue,
         };
-    };
+        let name = format!("{}::{}", owner, ident);
+        registrations.push(registration(node_type, &name, member_vis(vis), &member_hash));
+
+        if !is_trait_impl {
+            let meta_const = meta::MetaConst {
+                vis,
+                ident: meta::meta_ident(ident, false),
+                name: &name,
+                kind: node_type,
+                signature: signature.to_string(),
+                doc: meta::doc_summary(attrs),
+                hash: &member_hash,
+            }.to_tokens();
+            meta_consts.push(meta_const);
+        }
+    }
+
+    for meta_const in meta_consts {
+        item.items.push(ImplItem::Verbatim(meta_const));
+    }
 
-    let output = quote! {
+    quote! {
         #item
-        #registration_code
-    };
+        #(#registrations)*
+    }
+}
+
+/// Registers the `extern` block under its ABI and every foreign fn, static
+/// and type it declares.
+pub fn wrap_foreign_mod(args: &DeclArgs, item: &mut ItemForeignMod) -> TokenStream {
+    let abi = item.abi.name.as_ref().map(|n| n.value()).unwrap_or_else(|| "C".to_string());
+    let block_name = args.name.clone().unwrap_or_else(|| format!("extern \"{}\"", abi));
+    let hash = declared_hash(args, item.clone().into());
+    let mut registrations = vec![registration("extern", &block_name, "private", &hash)];
+
+    for foreign in &item.items {
+        let (node_type, ident, vis, member_hash) = match foreign {
+            ForeignItem::Fn(f) => ("fn", &f.sig.ident, &f.vis, member_hash(f, |f| &mut f.attrs)),
+            ForeignItem::Static(s) => ("static", &s.ident, &s.vis, member_hash(s, |s| &mut s.attrs)),
+            ForeignItem::Type(t) => ("type", &t.ident, &t.vis, member_hash(t, |t| &mut t.attrs)),
+            _ => continue,
+        };
+        registrations.push(registration(node_type, &ident.to_string(), visibility_str(vis), &member_hash));
+    }
 
-    output.into()
+    quote! {
+        #item
+        #(#registrations)*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(node_type, name, visibility)` of every registration in `output`.
+    fn registered(output: &TokenStream) -> Vec<(String, String, String)> {
+        use proc_macro2::{Delimiter, TokenTree};
+
+        fn walk(tokens: TokenStream, found: &mut Vec<(String, String, String)>) {
+            let mut after_decl_info = false;
+            for tt in tokens {
+                match tt {
+                    TokenTree::Group(g) if after_decl_info && g.delimiter() == Delimiter::Brace => {
+                        let fields = g.stream();
+                        let info: syn::ExprStruct = syn::parse2(quote!(DeclInfo { #fields })).unwrap();
+                        let field = |name: &str| info.fields.iter()
+                            .find(|f| matches!(&f.member, syn::Member::Named(i) if i == name))
+                            .and_then(|f| match &f.expr {
+                                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                                _ => None,
+                            })
+                            .unwrap();
+                        found.push((field("node_type"), field("name"), field("visibility")));
+                        after_decl_info = false;
+                    }
+                    TokenTree::Group(g) => walk(g.stream(), found),
+                    TokenTree::Ident(i) => after_decl_info = i == "DeclInfo",
+                    _ => after_decl_info = false,
+                }
+            }
+        }
+
+        let mut found = Vec::new();
+        walk(output.clone(), &mut found);
+        found
+    }
+
+    fn expand(attr: &str, item: &str) -> Vec<(String, String, String)> {
+        let out = process_decl2_attribute_logic(attr.parse().unwrap(), item.parse().unwrap());
+        registered(&out)
+    }
+
+    #[test]
+    fn test_impl_registers_block_and_members() {
+        let regs = expand(
+            "impl",
+            "impl<T> Stack<T> { pub fn push(&mut self, t: T) {} const CAP: usize = 8; fn grow(&mut self) {} }",
+        );
+        assert_eq!(regs, vec![
+            ("impl".into(), "Stack<T>".into(), "private".into()),
+            ("fn".into(), "Stack<T>::push".into(), "pub".into()),
+            ("const".into(), "Stack<T>::CAP".into(), "private".into()),
+            ("fn".into(), "Stack<T>::grow".into(), "private".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_trait_impl_members_are_public() {
+        let regs = expand("impl", "impl Default for Config { fn default() -> Self { Config } }");
+        assert_eq!(regs[0].1, "Default for Config");
+        assert_eq!(regs[1], ("fn".into(), "Config::default".into(), "pub".into()));
+    }
+
+    #[test]
+    fn test_value_and_type_items() {
+        assert_eq!(expand("const", "pub const MAX: u32 = 3;")[0].0, "const");
+        assert_eq!(expand("static", "pub(crate) static N: u8 = 0;")[0], ("static".into(), "N".into(), "pub(crate)".into()));
+        assert_eq!(expand("type", "pub type Id = u64;")[0].0, "type");
+        assert_eq!(expand("union", "pub union Bits { i: u32, f: f32 }")[0].0, "union");
+    }
+
+    #[test]
+    fn test_extern_block_and_macro_rules() {
+        let regs = expand("extern", "extern \"C\" { pub fn abs(x: i32) -> i32; static errno: i32; }");
+        assert_eq!(regs, vec![
+            ("extern".into(), "extern \"C\"".into(), "private".into()),
+            ("fn".into(), "abs".into(), "pub".into()),
+            ("static".into(), "errno".into(), "private".into()),
+        ]);
+
+        let regs = expand("macro", "#[macro_export] macro_rules! square { ($x:expr) => { $x * $x }; }");
+        assert_eq!(regs, vec![("macro".into(), "square".into(), "pub".into())]);
+    }
+
+    #[test]
+    fn test_metadata_consts_follow_items() {
+        let out = process_decl2_attribute_logic(
+            quote!(fn),
+            quote!(/// Adds one.
+                   pub(crate) fn inc(x: u32) -> u32 { x + 1 }),
+        );
+        let file: syn::File = syn::parse2(out).unwrap();
+        let meta = file.items.iter().find_map(|i| match i {
+            syn::Item::Const(c) if c.ident == "__decl_meta_inc" => Some(c),
+            _ => None,
+        }).expect("metadata const");
+        assert_eq!(meta.vis.to_token_stream().to_string(), "pub (crate)");
+        let body = meta.expr.to_token_stream().to_string();
+        assert!(body.contains("\"fn inc (x : u32) -> u32\""));
+        assert!(body.contains("Some (\"Adds one.\")"));
+
+        let out = process_decl2_attribute_logic(quote!(impl), quote!(impl S { pub fn f() {} }));
+        assert!(out.to_string().contains("pub const __decl_meta_f"));
+        let out = process_decl2_attribute_logic(quote!(impl), quote!(impl Clone for S { fn clone(&self) -> S { S } }));
+        assert!(!out.to_string().contains("__decl_meta_"));
+    }
+
+    #[test]
+    fn test_macro_invocations_pass_through() {
+        let out = process_decl2_attribute_logic(quote!(macro), quote!(thread_local! { static X: u8 = 0; }));
+        assert!(registered(&out).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_decl_is_rejected() {
+        let out = process_decl2_attribute_logic(
+            quote!(fn, name = "trace_rustc"),
+            quote!(#[decl2(fn, name = "trace_rustc")] pub fn trace_rustc() {}),
+        ).to_string();
+        assert!(out.contains("decl applied twice to `trace_rustc`"));
+        assert!(!out.contains("__decl_meta_"));
+    }
 }
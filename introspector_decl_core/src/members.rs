@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Item, Visibility};
+use crate::hash::item_hash;
+
+// Source-level view of a module's declarations, used by `decl_module!` to
+// generate `module_members!` tables at expansion time.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberData {
+    pub name: String,
+    pub kind: &'static str,
+    pub visibility: &'static str,
+    pub hash: String,
+}
+
+/// `src/<module>.rs` or `src/<module>/mod.rs` under `src_dir`.
+pub fn module_source_path(src_dir: &Path, module: &str) -> Option<PathBuf> {
+    let flat = src_dir.join(format!("{}.rs", module));
+    if flat.is_file() {
+        return Some(flat);
+    }
+    let nested = src_dir.join(module).join("mod.rs");
+    if nested.is_file() {
+        return Some(nested);
+    }
+    None
+}
+
+pub fn visibility_str(vis: &Visibility) -> &'static str {
+    match vis {
+        Visibility::Public(_) => "pub",
+        Visibility::Restricted(r) if r.path.is_ident("crate") => "pub(crate)",
+        Visibility::Restricted(r) if r.path.is_ident("super") => "pub(super)",
+        Visibility::Restricted(_) => "pub(restricted)",
+        Visibility::Inherited => "private",
+    }
+}
+
+/// Named top-level items of a module file, in source order.
+pub fn collect_module_members(content: &str) -> syn::Result<Vec<MemberData>> {
+    let file = syn::parse_file(content)?;
+
+    Ok(file.items.iter().filter_map(|item| {
+        let (name, kind, vis) = match item {
+            Item::Fn(i) => (i.sig.ident.to_string(), "fn", Some(&i.vis)),
+            Item::Struct(i) => (i.ident.to_string(), "struct", Some(&i.vis)),
+            Item::Enum(i) => (i.ident.to_string(), "enum", Some(&i.vis)),
+            Item::Union(i) => (i.ident.to_string(), "union", Some(&i.vis)),
+            Item::Trait(i) => (i.ident.to_string(), "trait", Some(&i.vis)),
+            Item::Const(i) => (i.ident.to_string(), "const", Some(&i.vis)),
+            Item::Static(i) => (i.ident.to_string(), "static", Some(&i.vis)),
+            Item::Type(i) => (i.ident.to_string(), "type", Some(&i.vis)),
+            Item::Mod(i) => (i.ident.to_string(), "mod", Some(&i.vis)),
+            Item::Macro(i) => (i.ident.as_ref()?.to_string(), "macro", None),
+            _ => return None,
+        };
+
+        Some(MemberData {
+            name,
+            kind,
+            visibility: vis.map_or("private", visibility_str),
+            hash: item_hash(item),
+        })
+    }).collect())
+}
+
+/// The `module_members!` macro of `decl_module!(modules)`, reading each
+/// module from `src_dir`. Every module source is also `include_bytes!`d at
+/// the expansion site, so rustc re-expands `decl_module!` when one changes.
+pub fn module_members_logic(modules: &[Ident], src_dir: &Path) -> syn::Result<TokenStream> {
+    let mut member_arms = TokenStream::new();
+    let mut tracked_sources = TokenStream::new();
+    for module_ident in modules {
+        let module_name_str = module_ident.to_string();
+        let path = module_source_path(src_dir, &module_name_str).ok_or_else(|| {
+            let message = format!("decl_module!: no source file for module `{}` under {}", module_name_str, src_dir.display());
+            syn::Error::new(module_ident.span(), message)
+        })?;
+        let members = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| collect_module_members(&content).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                let message = format!("decl_module!: cannot read members of `{}` from {}: {}", module_name_str, path.display(), e);
+                syn::Error::new(module_ident.span(), message)
+            })?;
+
+        let entries = members.iter().map(|m| {
+            let (name, kind, visibility, hash) = (&m.name, m.kind, m.visibility, &m.hash);
+            quote! {
+                ::introspector_decl_common::ModuleMember {
+                    name: #name,
+                    kind: #kind,
+                    visibility: #visibility,
+                    hash: #hash,
+                }
+            }
+        }).collect::<Vec<_>>();
+
+        let path_str = path.display().to_string();
+        tracked_sources.extend(quote! {
+            const _: &[u8] = include_bytes!(#path_str);
+        });
+        member_arms.extend(quote! {
+            (#module_ident) => {
+                &[ #(#entries),* ] as &[::introspector_decl_common::ModuleMember]
+            };
+            (#module_name_str) => {
+                module_members!(#module_ident)
+            };
+        });
+    }
+
+    Ok(quote! {
+        #tracked_sources
+        #[allow(unused_macros)]
+        macro_rules! module_members {
+            #member_arms
+        }
+        #[allow(unused_imports)]
+        pub(crate) use module_members;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_named_items() {
+        let code = r#"
+use std::fmt;
+
+#[decl2(fn, name = "run", vis = "pub", hash = "00000000")]
+pub fn run() {}
+
+pub(crate) struct Config;
+const LIMIT: usize = 3;
+macro_rules! helper { () => {} }
+impl Config {}
+"#;
+        let members = collect_module_members(code).unwrap();
+        let summary: Vec<(&str, &str, &str)> = members.iter()
+            .map(|m| (m.name.as_str(), m.kind, m.visibility))
+            .collect();
+        assert_eq!(summary, vec![
+            ("run", "fn", "pub"),
+            ("Config", "struct", "pub(crate)"),
+            ("LIMIT", "const", "private"),
+            ("helper", "macro", "private"),
+        ]);
+        assert_eq!(members[0].hash.len(), 8);
+    }
+
+    #[test]
+    fn test_module_members_expansion() {
+        let src_dir = std::env::temp_dir().join(format!("decl_module_members_{}", std::process::id()));
+        std::fs::create_dir_all(src_dir.join("nested")).unwrap();
+        std::fs::write(src_dir.join("flat.rs"), "pub fn run() {}\nstruct Hidden;\n").unwrap();
+        std::fs::write(src_dir.join("nested").join("mod.rs"), "pub const N: u8 = 1;\n").unwrap();
+
+        let modules: Vec<Ident> = vec![syn::parse_quote!(flat), syn::parse_quote!(nested)];
+        let output = module_members_logic(&modules, &src_dir);
+        let missing = module_members_logic(&[syn::parse_quote!(absent)], &src_dir);
+        std::fs::remove_dir_all(&src_dir).unwrap();
+
+        let file: syn::File = syn::parse2(output.unwrap()).unwrap();
+        // One `include_bytes!` per module, at the `decl_module!` site.
+        let tracked: Vec<String> = file.items.iter().filter_map(|item| match item {
+            Item::Const(c) => Some(quote!(#c).to_string()),
+            _ => None,
+        }).collect();
+        assert_eq!(tracked.len(), 2);
+        assert!(tracked.iter().all(|c| c.contains("include_bytes !")));
+        assert!(tracked[0].contains("flat.rs") && tracked[1].contains("mod.rs"));
+
+        let rules = file.items.iter().find_map(|item| match item {
+            Item::Macro(m) if m.ident.as_ref().is_some_and(|i| i == "module_members") => Some(m.mac.tokens.to_string()),
+            _ => None,
+        }).unwrap();
+        assert!(!rules.contains("include_bytes"));
+        assert!(rules.contains(":: introspector_decl_common :: ModuleMember { name : \"run\" , kind : \"fn\" , visibility : \"pub\""));
+        assert!(rules.contains("name : \"Hidden\" , kind : \"struct\" , visibility : \"private\""));
+        assert!(rules.contains("(\"nested\") => { module_members ! (nested) }"));
+
+        let err = missing.unwrap_err().to_string();
+        assert!(err.contains("no source file for module `absent`"), "{}", err);
+    }
+
+    #[test]
+    fn test_module_source_path_missing() {
+        assert!(module_source_path(Path::new("/nonexistent"), "nope").is_none());
+    }
+}
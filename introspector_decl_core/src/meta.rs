@@ -0,0 +1,183 @@
+use std::sync::OnceLock;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Ident, Item, Visibility};
+
+// Hidden `ConstDeclMetadata` consts emitted next to `#[decl]` items, and the
+// `decl_of!` lookup that maps an item path onto them. The const shares the
+// item's module and visibility, so `decl_of!(a::b::item)` resolves wherever
+// `a::b::item` does.
+
+pub const META_PREFIX: &str = "__decl_meta_";
+
+/// `__decl_meta_<ident>`, or `__decl_meta_macro_<ident>` for `macro_rules!`,
+/// which live in their own namespace and may share a name with a fn.
+pub fn meta_ident(ident: &Ident, is_macro: bool) -> Ident {
+    let infix = if is_macro { "macro_" } else { "" };
+    format_ident!("{}{}{}", META_PREFIX, infix, ident, span = ident.span())
+}
+
+/// A further `#[decl]`/`#[decl2]` still on an item being expanded.
+pub fn repeated_decl(attrs: &[Attribute]) -> Option<&Attribute> {
+    attrs.iter().find(|a| a.path().segments.last().is_some_and(|s| s.ident == "decl" || s.ident == "decl2"))
+}
+
+/// First non-empty line of the item's doc comment.
+pub fn doc_summary(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.trim().to_string())
+        .find(|line| !line.is_empty())
+}
+
+/// Declaration head without bodies or attributes, e.g. `fn f (x : u32) -> u32`
+/// or `struct S < T >`.
+pub fn item_signature(item: &Item) -> String {
+    let head = match item {
+        Item::Fn(i) => i.sig.to_token_stream(),
+        Item::Struct(i) => { let (s, id, g) = (&i.struct_token, &i.ident, &i.generics); quote!(#s #id #g) }
+        Item::Enum(i) => { let (e, id, g) = (&i.enum_token, &i.ident, &i.generics); quote!(#e #id #g) }
+        Item::Union(i) => { let (u, id, g) = (&i.union_token, &i.ident, &i.generics); quote!(#u #id #g) }
+        Item::Trait(i) => { let (t, id, g) = (&i.trait_token, &i.ident, &i.generics); quote!(#t #id #g) }
+        Item::Const(i) => { let (c, id, ty) = (&i.const_token, &i.ident, &i.ty); quote!(#c #id : #ty) }
+        Item::Static(i) => { let (s, m, id, ty) = (&i.static_token, &i.mutability, &i.ident, &i.ty); quote!(#s #m #id : #ty) }
+        Item::Type(i) => { let (t, id, g, ty) = (&i.type_token, &i.ident, &i.generics, &i.ty); quote!(#t #id #g = #ty) }
+        Item::Macro(i) => { let id = &i.ident; quote!(macro_rules! #id) }
+        _ => TokenStream::new(),
+    };
+    head.to_string()
+}
+
+/// Proc-macro crates may only export `#[proc_macro*]` functions, so `pub`
+/// metadata consts are narrowed to `pub(crate)` there.
+fn exported_visibility(vis: &Visibility) -> TokenStream {
+    match vis {
+        Visibility::Public(_) if compiling_proc_macro_crate() => quote!(pub(crate)),
+        _ => vis.to_token_stream(),
+    }
+}
+
+fn compiling_proc_macro_crate() -> bool {
+    static IS_PROC_MACRO: OnceLock<bool> = OnceLock::new();
+    *IS_PROC_MACRO.get_or_init(|| {
+        let manifest = match std::env::var("CARGO_MANIFEST_DIR") {
+            Ok(dir) => std::path::Path::new(&dir).join("Cargo.toml"),
+            Err(_) => return false,
+        };
+        std::fs::read_to_string(manifest)
+            .map(|toml| {
+                toml.lines()
+                    .map(|l| l.replace(' ', ""))
+                    .any(|l| l == "proc-macro=true" || l == "proc_macro=true")
+            })
+            .unwrap_or(false)
+    })
+}
+
+pub struct MetaConst<'a> {
+    pub vis: &'a Visibility,
+    pub ident: Ident,
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub signature: String,
+    pub doc: Option<String>,
+    pub hash: &'a str,
+}
+
+impl MetaConst<'_> {
+    pub fn to_tokens(&self) -> TokenStream {
+        let MetaConst { ident, name, kind, signature, hash, .. } = self;
+        let vis = exported_visibility(self.vis);
+        let doc = match &self.doc {
+            Some(doc) => quote!(::core::option::Option::Some(#doc)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals, dead_code)]
+            #vis const #ident: ::introspector_decl_common::ConstDeclMetadata =
+                ::introspector_decl_common::ConstDeclMetadata {
+                    name: #name,
+                    kind: #kind,
+                    signature: #signature,
+                    doc: #doc,
+                    hash: #hash,
+                    module: module_path!(),
+                    file: file!(),
+                    line: line!(),
+                };
+        }
+    }
+}
+
+/// Input of `decl_of!`: an item path, optionally preceded by `macro` for
+/// `macro_rules!` definitions.
+pub struct DeclOfInput {
+    pub is_macro: bool,
+    pub path: syn::Path,
+}
+
+impl Parse for DeclOfInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let is_macro = input.peek(syn::Token![macro]);
+        if is_macro {
+            input.parse::<syn::Token![macro]>()?;
+        }
+        Ok(DeclOfInput { is_macro, path: input.parse()? })
+    }
+}
+
+/// Expands `decl_of!(path::to::item)` to `path::to::__decl_meta_item`.
+pub fn decl_of_logic(input: TokenStream) -> TokenStream {
+    let DeclOfInput { is_macro, mut path } = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    match path.segments.last_mut() {
+        Some(last) if last.arguments.is_none() => {
+            last.ident = meta_ident(&last.ident, is_macro);
+            path.to_token_stream()
+        }
+        _ => syn::Error::new(
+            Span::call_site(),
+            "decl_of! expects a path ending in an item name, e.g. `decl_of!(module::item)`",
+        ).to_compile_error(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decl_of_rewrites_last_segment() {
+        let out = decl_of_logic(quote!(crate::shapes::Circle)).to_string();
+        assert_eq!(out, "crate :: shapes :: __decl_meta_Circle");
+        let out = decl_of_logic(quote!(Stack::<u8>::push)).to_string();
+        assert_eq!(out, "Stack :: < u8 > :: __decl_meta_push");
+        let out = decl_of_logic(quote!(macro square)).to_string();
+        assert_eq!(out, "__decl_meta_macro_square");
+        assert!(decl_of_logic(quote!(Vec<u8>)).to_string().contains("compile_error"));
+    }
+
+    #[test]
+    fn test_signature_and_doc_summary() {
+        let item: Item = syn::parse_str("/// \n/// Adds one.\n/// More.\npub fn inc(x: u32) -> u32 { x + 1 }").unwrap();
+        let Item::Fn(f) = &item else { unreachable!() };
+        assert_eq!(doc_summary(&f.attrs).as_deref(), Some("Adds one."));
+        assert_eq!(item_signature(&item), "fn inc (x : u32) -> u32");
+
+        let item: Item = syn::parse_str("pub static mut N: u8 = 0;").unwrap();
+        assert_eq!(item_signature(&item), "static mut N : u8");
+    }
+}
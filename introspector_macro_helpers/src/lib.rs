@@ -1,3 +1,8 @@
+// Re-exported for the macros below, so expansions do not depend on the
+// caller's own `syn` dependency.
+#[doc(hidden)]
+pub use syn;
+
 #[macro_export]
 macro_rules! generate_decl_registration {
     (
@@ -9,9 +14,8 @@ macro_rules! generate_decl_registration {
         $line_expr:expr,
         $hash_expr:expr
     ) => {
-        {
-            use introspector_decl_common::{DeclInfo, register_decl};
-            register_decl(DeclInfo {
+        introspector_decl_common::inventory::submit! {
+            introspector_decl_common::DeclInfo {
                 node_type: $node_type,
                 name: $name_expr,
                 visibility: $vis_expr,
@@ -19,7 +23,7 @@ macro_rules! generate_decl_registration {
                 file: $file_expr,
                 line: $line_expr,
                 hash: $hash_expr,
-            });
+            }
         }
     };
 }
@@ -34,9 +38,9 @@ macro_rules! is_attribute_ident {
 #[macro_export]
 macro_rules! is_use_decl_module {
     ($item_use:expr) => {
-        if let syn::UseTree::Path(use_tree_path) = &$item_use.tree {
+        if let $crate::syn::UseTree::Path(use_tree_path) = &$item_use.tree {
             if use_tree_path.ident == "introspector_decl2_macros" {
-                if let syn::UseTree::Name(use_tree_name) = &*use_tree_path.tree {
+                if let $crate::syn::UseTree::Name(use_tree_name) = &*use_tree_path.tree {
                     use_tree_name.ident == "decl_module"
                 } else {
                     false
@@ -54,27 +58,35 @@ macro_rules! is_use_decl_module {
 #[macro_export]
 macro_rules! parse_decl_args {
     ($attr_token_stream:expr) => {
-        syn::parse2($attr_token_stream).unwrap() // Consider better error handling later
+        match $crate::syn::parse2($attr_token_stream) {
+            Ok(args) => args,
+            Err(err) => return err.to_compile_error(),
+        }
     };
 }
 
+/// Hands a `#[decl]` item to the `wrap_*` function for its kind, looked up in
+/// the module the caller names: `dispatch_wrap_logic!(item, args, crate)`.
+/// The wrappers live in the caller's crate, which this crate cannot name.
 #[macro_export]
 macro_rules! dispatch_wrap_logic {
-    ($item_token_stream:expr, $args_struct:expr) => {
+    ($item_token_stream:expr, $args_struct:expr, $($wrappers:ident)::+) => {
         {
-            let item_clone = $item_token_stream.clone();
             let args = $args_struct;
 
-            if let Ok(mut item_fn) = syn::parse2::<syn::ItemFn>(item_clone.clone()) {
-                crate::wrap_function(&args, &mut item_fn)
-            } else if let Ok(mut item_struct) = syn::parse2::<syn::ItemStruct>(item_clone.clone()) {
-                crate::wrap_struct(&args, &mut item_struct)
-            } else if let Ok(mut item_enum) = syn::parse2::<syn::ItemEnum>(item_clone.clone()) {
-                crate::wrap_enum(&args, &mut item_enum)
-            } else if let Ok(mut item_trait) = syn::parse2::<syn::ItemTrait>(item_clone.clone()) {
-                crate::wrap_trait(&args, &mut item_trait)
-            } else {
-                $item_token_stream // If we can't parse, just return the original
+            match $crate::syn::parse2::<$crate::syn::Item>($item_token_stream.clone()) {
+                Ok($crate::syn::Item::Fn(mut item)) => $($wrappers)::+::wrap_function(&args, &mut item),
+                Ok($crate::syn::Item::Struct(mut item)) => $($wrappers)::+::wrap_struct(&args, &mut item),
+                Ok($crate::syn::Item::Enum(mut item)) => $($wrappers)::+::wrap_enum(&args, &mut item),
+                Ok($crate::syn::Item::Trait(mut item)) => $($wrappers)::+::wrap_trait(&args, &mut item),
+                Ok($crate::syn::Item::Impl(mut item)) => $($wrappers)::+::wrap_impl(&args, &mut item),
+                Ok($crate::syn::Item::Const(mut item)) => $($wrappers)::+::wrap_const(&args, &mut item),
+                Ok($crate::syn::Item::Static(mut item)) => $($wrappers)::+::wrap_static(&args, &mut item),
+                Ok($crate::syn::Item::Type(mut item)) => $($wrappers)::+::wrap_type_alias(&args, &mut item),
+                Ok($crate::syn::Item::Union(mut item)) => $($wrappers)::+::wrap_union(&args, &mut item),
+                Ok($crate::syn::Item::ForeignMod(mut item)) => $($wrappers)::+::wrap_foreign_mod(&args, &mut item),
+                Ok($crate::syn::Item::Macro(mut item)) if item.ident.is_some() => $($wrappers)::+::wrap_macro_rules(&args, &mut item),
+                _ => $item_token_stream, // If we can't parse, just return the original
             }
         }
     };
@@ -1,10 +1,12 @@
-{ pkgs ? import <nixpkgs> {} }:
+{ pkgs ? import <nixpkgs> {}
+  # Patch series from `audit-fix rustc-wrap SRC OUT`, e.g. `import OUT/patches.nix`
+, declPatches ? []
+}:
 
 let
   # Our macro-enhanced patches
   introspection-patch = pkgs.writeText "add-introspection.patch" ''
     diff --git a/compiler/rustc_driver/src/lib.rs b/compiler/rustc_driver/src/lib.rs
-    index 1234567..abcdefg 100644
     --- a/compiler/rustc_driver/src/lib.rs
     +++ b/compiler/rustc_driver/src/lib.rs
     @@ -100,6 +100,10 @@ pub fn main() {
@@ -22,7 +24,7 @@ let
 
 in pkgs.rustc.overrideAttrs (old: {
   pname = "rustc-with-macros";
-  patches = (old.patches or []) ++ [ introspection-patch ];
+  patches = (old.patches or []) ++ [ introspection-patch ] ++ declPatches;
   
   postInstall = old.postInstall or "" + ''
     echo "🦀 Macro-enhanced Rust compiler installed"
@@ -1,75 +1,128 @@
 use patch_build_rs_macros::decl;
 use proc_macro::TokenStream;
 use quote::quote;
-use std::fs;
-use std::path::Path;
-use syn::parse_macro_input; // Only parse_macro_input is needed here
+use std::path::PathBuf;
+use syn::parse_macro_input;
+use nix_core::{default_store, scan_store_rustcs};
 
-/// Represents the input to the find_nix_rustc! macro.
-/// Can be empty, or a single string literal for filtering.
+/// Represents the input to the find_nix_rustc! macro: empty, a single
+/// string literal (a substring of the path, as before), or `key = value`
+/// filters.
 mod macro_args {
-    use syn::{parse::{Parse, ParseStream, Result as SynResult}, LitStr};
+    use syn::{bracketed, parse::{Parse, ParseStream, Result as SynResult}, punctuated::Punctuated, Ident, LitStr, Token};
+    use nix_core::RustcQuery;
 
     pub struct FindRustcArgs {
-        pub filter: Option<LitStr>,
+        pub query: RustcQuery,
+        /// Store roots as written; relative ones are resolved by the caller.
+        pub stores: Vec<String>,
+    }
+
+    fn check<T>(lit: &LitStr, result: Result<T, String>) -> SynResult<T> {
+        result.map_err(|e| syn::Error::new(lit.span(), e))
     }
 
     impl Parse for FindRustcArgs {
         fn parse(input: ParseStream) -> SynResult<Self> {
+            let mut args = FindRustcArgs { query: RustcQuery::default(), stores: Vec::new() };
             if input.is_empty() {
-                Ok(FindRustcArgs { filter: None })
-            } else {
-                let filter_lit: LitStr = input.parse()?;
-                Ok(FindRustcArgs { filter: Some(filter_lit) })
+                return Ok(args);
+            }
+            if input.peek(LitStr) {
+                let filter: LitStr = input.parse()?;
+                args.query = args.query.contains(&filter.value());
+                return Ok(args);
+            }
+            while !input.is_empty() {
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                if key == "store" {
+                    if input.peek(syn::token::Bracket) {
+                        let content;
+                        bracketed!(content in input);
+                        let roots = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                        args.stores.extend(roots.iter().map(LitStr::value));
+                    } else {
+                        args.stores.push(input.parse::<LitStr>()?.value());
+                    }
+                } else {
+                    let value: LitStr = input.parse()?;
+                    let query = std::mem::take(&mut args.query);
+                    args.query = match key.to_string().as_str() {
+                        "version" => check(&value, query.version(&value.value()))?,
+                        "channel" => check(&value, query.channel(&value.value()))?,
+                        "date" => check(&value, query.date(&value.value()))?,
+                        "target" => query.target(&value.value()),
+                        "contains" => query.contains(&value.value()),
+                        _ => return Err(syn::Error::new(
+                            key.span(),
+                            "unknown filter (expected version, channel, date, target, contains or store)",
+                        )),
+                    };
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
             }
+            Ok(args)
         }
     }
 }
 
 /// A procedural macro to find Rust compilers in the Nix store.
-/// It can optionally take a string literal as a filter.
-/// Returns a Vec<String> of paths.
+/// Returns a Vec<String> of rustc paths, best first: highest version, then
+/// newest nightly date. Fails to compile when nothing matches, listing the
+/// candidates it saw; only a call without filters or `store` expands to an
+/// empty Vec when the default store holds no rustc, so builds without Nix
+/// still compile.
+///
+/// Filters, all optional and combined:
+/// - `version = ">=1.75, <1.80"`: a Cargo-style version requirement
+/// - `channel = "stable"`: `stable`, `beta` or `nightly`
+/// - `date = ">=2024-05-01"`: comparisons with the toolchain date
+/// - `target = "wasm32-unknown-unknown"`: a target the toolchain ships std for
+/// - `contains = "1.91"`: a substring of the path
+/// - `store = "path"` or `store = ["a", "b"]`: store roots to scan instead of
+///   `$NIX_STORE_DIR` or `/nix/store`; relative roots are resolved against
+///   the crate's manifest directory
 ///
 /// Usage:
 /// `let rustc_paths: Vec<String> = find_nix_rustc!();`
 /// `let rustc_filtered_paths: Vec<String> = find_nix_rustc!("1.91");`
+/// `let nightly: Vec<String> = find_nix_rustc!(channel = "nightly", date = ">=2024-05-01");`
 #[proc_macro]
-#[decl(fn, name = "find_nix_rustc", vis = "pub", hash = "9ccd7a99")]
+#[decl(fn, name = "find_nix_rustc", vis = "pub", hash = "a82a22dd")]
 pub fn find_nix_rustc(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as macro_args::FindRustcArgs);
-    let filter_str = args.filter.map(|lit| lit.value());
 
-    let nix_store_path = Path::new("/nix/store");
-    let mut rustc_paths = Vec::new();
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let stores: Vec<PathBuf> = if args.stores.is_empty() {
+        vec![default_store()]
+    } else {
+        args.stores.iter().map(|s| manifest_dir.join(s)).collect()
+    };
+    let candidates = scan_store_rustcs(&stores);
+    let selected = args.query.select(&candidates);
 
-    if let Ok(entries) = fs::read_dir(nix_store_path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                // Look for common rustc paths within Nix store derivations
-                let rustc_candidate_path = path.join("bin").join("rustc");
-                if rustc_candidate_path.exists() && rustc_candidate_path.is_file() {
-                    if let Some(path_str) = rustc_candidate_path.to_str() {
-                        // Apply filter if present
-                        if let Some(ref filter) = filter_str {
-                            if path_str.contains(filter) {
-                                rustc_paths.push(path_str.to_string());
-                            }
-                        } else {
-                            rustc_paths.push(path_str.to_string());
-                        }
-                    }
-                }
-            }
-        }
+    let unfiltered = args.stores.is_empty() && args.query.is_empty();
+    if selected.is_empty() && !(unfiltered && candidates.is_empty()) {
+        let searched = stores.iter().map(|s| s.display().to_string()).collect::<Vec<_>>().join(", ");
+        let message = if candidates.is_empty() {
+            format!("find_nix_rustc!: no rustc found in {}", searched)
+        } else {
+            let listed = candidates.iter().map(|c| format!("\n  {}", c)).collect::<String>();
+            format!("find_nix_rustc!: none of the {} rustc in {} match:{}", candidates.len(), searched, listed)
+        };
+        return syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error().into();
     }
 
-    // Generate a TokenStream that represents a Vec<String>
-    // Convert &str to String using .to_string()
-    let quoted_paths = rustc_paths.iter().map(|s| quote! { #s.to_string() });
+    let quoted_paths = selected.iter().map(|c| {
+        let path = c.rustc.display().to_string();
+        quote! { #path.to_string() }
+    });
     let output = quote! {
         vec![#(#quoted_paths),*]
     };
 
     output.into()
-}
\ No newline at end of file
+}
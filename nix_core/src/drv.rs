@@ -0,0 +1,485 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Store derivations (`.drv` files), read directly instead of through
+// `nix show-derivation`. A `.drv` is one ATerm:
+//
+//   Derive([outputs],[input drvs],[input srcs],"system","builder",[args],[env])
+//
+// with outputs `("out","/nix/store/...","","")`, input derivations
+// `("/nix/store/....drv",["out"])` and env pairs `("name","value")`.
+// Strings escape `"`, `\`, newline, carriage return and tab with a backslash.
+//
+// `DrvGraph` loads a derivation and everything it depends on from a store
+// directory, so a copy of the relevant `.drv` files is enough to explore a
+// build without Nix.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrvOutput {
+    pub path: String,
+    /// `r:sha256` and the like for fixed-output derivations, else empty.
+    pub hash_algo: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Derivation {
+    pub outputs: BTreeMap<String, DrvOutput>,
+    /// `.drv` path → the outputs of it this derivation uses.
+    pub input_drvs: BTreeMap<String, Vec<String>>,
+    pub input_srcs: Vec<String>,
+    pub system: String,
+    pub builder: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, what: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        let near: String = self.text[self.pos..].chars().take(20).collect();
+        format!("Invalid derivation at byte {} (line {}): expected {}, found `{}`", self.pos, line, what, near)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.text[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) { Ok(()) } else { Err(self.error(&format!("`{}`", token))) }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 'r')) => out.push('\r'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, c)) => out.push(c),
+                    None => break,
+                },
+                c => out.push(c),
+            }
+        }
+        self.pos = self.text.len();
+        Err(self.error("closing `\"`"))
+    }
+
+    /// `[item,item,...]`
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T, String>) -> Result<Vec<T>, String> {
+        self.expect("[")?;
+        let mut items = Vec::new();
+        if self.eat("]") {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat("]") {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, String> {
+        self.list(Self::string)
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_strings<'a>(out: &mut String, items: impl IntoIterator<Item = &'a String>) {
+    out.push('[');
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(out, item);
+    }
+    out.push(']');
+}
+
+impl Derivation {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut p = Parser { text: text.trim_end(), pos: 0 };
+        p.expect("Derive(")?;
+        let outputs = p.list(|p| {
+            p.expect("(")?;
+            let name = p.string()?;
+            p.expect(",")?;
+            let path = p.string()?;
+            p.expect(",")?;
+            let hash_algo = p.string()?;
+            p.expect(",")?;
+            let hash = p.string()?;
+            p.expect(")")?;
+            Ok((name, DrvOutput { path, hash_algo, hash }))
+        })?;
+        p.expect(",")?;
+        let input_drvs = p.list(|p| {
+            p.expect("(")?;
+            let path = p.string()?;
+            p.expect(",")?;
+            let outputs = p.strings()?;
+            p.expect(")")?;
+            Ok((path, outputs))
+        })?;
+        p.expect(",")?;
+        let input_srcs = p.strings()?;
+        p.expect(",")?;
+        let system = p.string()?;
+        p.expect(",")?;
+        let builder = p.string()?;
+        p.expect(",")?;
+        let args = p.strings()?;
+        p.expect(",")?;
+        let env = p.list(|p| {
+            p.expect("(")?;
+            let key = p.string()?;
+            p.expect(",")?;
+            let value = p.string()?;
+            p.expect(")")?;
+            Ok((key, value))
+        })?;
+        p.expect(")")?;
+        if p.pos != p.text.len() {
+            return Err(p.error("end of derivation"));
+        }
+        Ok(Derivation {
+            outputs: outputs.into_iter().collect(),
+            input_drvs: input_drvs.into_iter().collect(),
+            input_srcs,
+            system,
+            builder,
+            args,
+            env: env.into_iter().collect(),
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// The ATerm Nix writes, without a trailing newline.
+    pub fn to_aterm(&self) -> String {
+        let mut out = String::from("Derive([");
+        for (i, (name, output)) in self.outputs.iter().enumerate() {
+            out.push_str(if i > 0 { ",(" } else { "(" });
+            for (j, field) in [name, &output.path, &output.hash_algo, &output.hash].into_iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write_string(&mut out, field);
+            }
+            out.push(')');
+        }
+        out.push_str("],[");
+        for (i, (path, outputs)) in self.input_drvs.iter().enumerate() {
+            out.push_str(if i > 0 { ",(" } else { "(" });
+            write_string(&mut out, path);
+            out.push(',');
+            write_strings(&mut out, outputs);
+            out.push(')');
+        }
+        out.push_str("],");
+        write_strings(&mut out, &self.input_srcs);
+        out.push(',');
+        write_string(&mut out, &self.system);
+        out.push(',');
+        write_string(&mut out, &self.builder);
+        out.push(',');
+        write_strings(&mut out, &self.args);
+        out.push_str(",[");
+        for (i, (key, value)) in self.env.iter().enumerate() {
+            out.push_str(if i > 0 { ",(" } else { "(" });
+            write_string(&mut out, key);
+            out.push(',');
+            write_string(&mut out, value);
+            out.push(')');
+        }
+        out.push_str("])");
+        out
+    }
+
+    /// `env.name`.
+    pub fn name(&self) -> Option<&str> {
+        self.env.get("name").map(String::as_str)
+    }
+
+    /// Store paths in `env.patches`, in application order.
+    pub fn patches(&self) -> Vec<&str> {
+        self.env.get("patches").map(|p| p.split_whitespace().collect()).unwrap_or_default()
+    }
+
+    pub fn is_fixed_output(&self) -> bool {
+        self.outputs.values().any(|o| !o.hash.is_empty())
+    }
+}
+
+/// `/nix/store/<hash>-<name>` → `<name>`.
+pub fn store_path_name(path: &str) -> &str {
+    let base = path.rsplit('/').next().unwrap_or(path);
+    base.split_once('-').map_or(base, |(_, name)| name)
+}
+
+/// How a patch reaches a derivation that applies it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchUse {
+    /// Store path of the patch.
+    pub patch: String,
+    /// The derivation with the patch in its `patches`.
+    pub applied_by: String,
+    /// The fetch derivation producing the patch; `None` for a patch that is
+    /// a plain input source.
+    pub produced_by: Option<String>,
+    /// Derivations from the root down to `applied_by`.
+    pub chain: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DrvGraph {
+    pub root: String,
+    pub nodes: BTreeMap<String, Derivation>,
+    /// Input derivations without a `.drv` file in the store directory.
+    pub missing: Vec<String>,
+}
+
+impl DrvGraph {
+    /// Reads `root` and every derivation it depends on. Store paths map to
+    /// files in `store_dir` by name, so a directory of copied `.drv` files
+    /// works as well as `/nix/store`.
+    pub fn load(store_dir: &Path, root: &str) -> Result<Self, String> {
+        let file = |drv: &str| -> PathBuf { store_dir.join(drv.rsplit('/').next().unwrap_or(drv)) };
+        let mut graph = DrvGraph { root: root.to_string(), ..Default::default() };
+        graph.nodes.insert(root.to_string(), Derivation::read(&file(root))?);
+        let mut queue = VecDeque::from([root.to_string()]);
+        while let Some(drv) = queue.pop_front() {
+            let inputs: Vec<String> = graph.nodes[&drv].input_drvs.keys().cloned().collect();
+            for input in inputs {
+                if graph.nodes.contains_key(&input) || graph.missing.contains(&input) {
+                    continue;
+                }
+                let path = file(&input);
+                if !path.is_file() {
+                    graph.missing.push(input);
+                    continue;
+                }
+                graph.nodes.insert(input.clone(), Derivation::read(&path)?);
+                queue.push_back(input);
+            }
+        }
+        graph.missing.sort();
+        Ok(graph)
+    }
+
+    /// Every derivation `drv` depends on, itself included, dependencies
+    /// before their dependents.
+    pub fn closure<'a>(&'a self, drv: &'a str) -> Vec<&'a str> {
+        fn visit<'a>(graph: &'a DrvGraph, drv: &'a str, seen: &mut BTreeSet<&'a str>, order: &mut Vec<&'a str>) {
+            if !seen.insert(drv) {
+                return;
+            }
+            if let Some(node) = graph.nodes.get(drv) {
+                for input in node.input_drvs.keys() {
+                    visit(graph, input, seen, order);
+                }
+                order.push(drv);
+            }
+        }
+        let mut order = Vec::new();
+        visit(self, drv, &mut BTreeSet::new(), &mut order);
+        order
+    }
+
+    /// The derivation with output `path`, if it is in the graph.
+    pub fn producer(&self, path: &str) -> Option<&str> {
+        self.nodes.iter()
+            .find(|(_, node)| node.outputs.values().any(|o| o.path == path))
+            .map(|(drv, _)| drv.as_str())
+    }
+
+    /// The shortest dependency chain from the root to `drv`.
+    pub fn chain_to(&self, drv: &str) -> Option<Vec<String>> {
+        let mut parent: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = VecDeque::from([self.root.as_str()]);
+        while let Some(current) = queue.pop_front() {
+            if current == drv {
+                let mut chain = vec![drv.to_string()];
+                let mut at = drv;
+                while let Some(p) = parent.get(at) {
+                    chain.push(p.to_string());
+                    at = p;
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+            for input in self.nodes.get(current).into_iter().flat_map(|n| n.input_drvs.keys()) {
+                if input != &self.root && !parent.contains_key(input.as_str()) {
+                    parent.insert(input, current);
+                    queue.push_back(input);
+                }
+            }
+        }
+        None
+    }
+
+    /// Where patches matching `patch` (a store path, a name such as
+    /// `0001-rustc_ast-decl-wrappers.patch`, or a suffix of either) are
+    /// applied in the closure of the root, and what produces them.
+    pub fn patch_uses(&self, patch: &str) -> Vec<PatchUse> {
+        let matches = |path: &str| path == patch || store_path_name(path) == patch || path.ends_with(patch);
+        let mut uses = Vec::new();
+        for drv in self.closure(&self.root) {
+            for path in self.nodes[drv].patches().into_iter().filter(|p| matches(p)) {
+                uses.push(PatchUse {
+                    patch: path.to_string(),
+                    applied_by: drv.to_string(),
+                    produced_by: self.producer(path).map(str::to_string),
+                    chain: self.chain_to(drv).unwrap_or_default(),
+                });
+            }
+        }
+        uses
+    }
+
+    /// One line per derivation of the closure, indented by depth from the
+    /// root; shared dependencies are expanded once.
+    pub fn tree(&self) -> String {
+        fn walk(graph: &DrvGraph, drv: &str, depth: usize, seen: &mut BTreeSet<String>, out: &mut String) {
+            let name = graph.nodes.get(drv).and_then(|n| n.name()).unwrap_or_else(|| store_path_name(drv));
+            let repeated = !seen.insert(drv.to_string());
+            let _ = writeln!(out, "{}{}{}", "  ".repeat(depth), name, if repeated { " (see above)" } else { "" });
+            if repeated {
+                return;
+            }
+            for input in graph.nodes.get(drv).into_iter().flat_map(|n| n.input_drvs.keys()) {
+                walk(graph, input, depth + 1, seen, out);
+            }
+        }
+        let mut out = String::new();
+        walk(self, &self.root, 0, &mut BTreeSet::new(), &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testdata() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("drv")
+    }
+
+    const RUSTC: &str = "/nix/store/9z0sq5m0g4rmvdd7v8m6qzqp9ff1zpsb-rustc-1.79.0.drv";
+    const UNWRAPPED: &str = "/nix/store/g4mwqv1hp4dl6x4p4d2jv6hzc7n1xk3y-rustc-unwrapped-1.79.0.drv";
+
+    #[test]
+    fn test_parse_and_roundtrip_samples() {
+        let mut files: Vec<PathBuf> = fs::read_dir(testdata()).unwrap().map(|e| e.unwrap().path()).collect();
+        files.sort();
+        assert_eq!(files.len(), 7);
+        for file in &files {
+            let text = fs::read_to_string(file).unwrap();
+            let drv = Derivation::parse(&text).unwrap();
+            assert_eq!(drv.to_aterm(), text, "{}", file.display());
+        }
+
+        let unwrapped = Derivation::read(&testdata().join(store_path_name_with_hash(UNWRAPPED))).unwrap();
+        assert_eq!(unwrapped.name(), Some("rustc-unwrapped-1.79.0"));
+        assert_eq!(unwrapped.system, "x86_64-linux");
+        assert!(unwrapped.builder.ends_with("-bash-5.2p26/bin/bash"));
+        assert_eq!(unwrapped.args[0], "-e");
+        assert_eq!(unwrapped.outputs.keys().collect::<Vec<_>>(), vec!["doc", "out"]);
+        assert_eq!(unwrapped.patches().len(), 2);
+        assert!(unwrapped.env["preConfigure"].contains("\"$NIX_BUILD_TOP\"\n"));
+        assert!(!unwrapped.is_fixed_output());
+
+        let src = Derivation::read(&testdata().join("lb1bm8ldqbi8fqmj1r6p2g5ll7c9r5jz-rustc-1.79.0-src.tar.gz.drv")).unwrap();
+        assert_eq!(src.outputs["out"].hash_algo, "sha256");
+        assert!(src.is_fixed_output());
+
+        assert!(Derivation::parse("Derive([],[],[],\"x\",\"b\",[],[]) trailing").is_err());
+        assert!(Derivation::parse("Derive([(\"out\",\"/nix/store/x\",\"\",\"\")],").unwrap_err().contains("expected `[`"));
+    }
+
+    fn store_path_name_with_hash(path: &str) -> &str {
+        path.rsplit('/').next().unwrap()
+    }
+
+    #[test]
+    fn test_closure_and_patch_uses() {
+        let graph = DrvGraph::load(&testdata(), RUSTC).unwrap();
+        assert_eq!(graph.nodes.len(), 7);
+        assert!(graph.missing.is_empty());
+
+        let closure = graph.closure(RUSTC);
+        assert_eq!(closure.len(), 7);
+        assert_eq!(closure.last(), Some(&RUSTC));
+        // Dependencies come before their dependents.
+        for (i, drv) in closure.iter().enumerate() {
+            for input in graph.nodes[*drv].input_drvs.keys() {
+                assert!(closure[..i].contains(&input.as_str()), "{} before {}", input, drv);
+            }
+        }
+        assert_eq!(graph.closure(UNWRAPPED).len(), 5);
+
+        let uses = graph.patch_uses("0001-rustc_ast-decl-wrappers.patch");
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].applied_by, UNWRAPPED);
+        assert_eq!(uses[0].produced_by, None);
+        assert_eq!(uses[0].chain, vec![RUSTC.to_string(), UNWRAPPED.to_string()]);
+
+        let fetched = graph.patch_uses("fix-llvm-18.patch");
+        assert_eq!(fetched.len(), 1);
+        assert!(fetched[0].produced_by.as_deref().is_some_and(|d| d.ends_with("-fix-llvm-18.patch.drv")));
+        assert!(graph.patch_uses("nonexistent.patch").is_empty());
+
+        let tree = graph.tree();
+        assert!(tree.starts_with("rustc-1.79.0\n  rustc-unwrapped-1.79.0\n"));
+        assert!(tree.contains("(see above)"));
+
+        // A store directory without some inputs still loads the rest.
+        let partial = std::env::temp_dir().join(format!("nix_core_drv_{}", std::process::id()));
+        fs::create_dir_all(&partial).unwrap();
+        for drv in [RUSTC, UNWRAPPED] {
+            fs::copy(testdata().join(store_path_name_with_hash(drv)), partial.join(store_path_name_with_hash(drv))).unwrap();
+        }
+        let graph = DrvGraph::load(&partial, RUSTC).unwrap();
+        assert_eq!((graph.nodes.len(), graph.missing.len()), (2, 5));
+        fs::remove_dir_all(&partial).unwrap();
+    }
+}
@@ -0,0 +1,672 @@
+use std::fmt;
+
+// A small typed Nix AST and its pretty-printer. Generators build an `Expr`
+// instead of formatting Nix text, so strings are always escaped, operands
+// parenthesized where Nix precedence requires it, and attribute names
+// quoted when they are not plain identifiers.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// A string; rendered as `''...''` when it spans lines.
+    Str(Vec<StrPart>),
+    /// `./relative`, `/absolute` or a search path like `<nixpkgs>`.
+    Path(String),
+    Var(String),
+    /// `expr.a.b`, with an optional `or` default.
+    Select(Box<Expr>, Vec<String>, Option<Box<Expr>>),
+    List(Vec<Expr>),
+    AttrSet { recursive: bool, bindings: Vec<Binding> },
+    Let(Vec<Binding>, Box<Expr>),
+    Lambda(Param, Box<Expr>),
+    Apply(Box<Expr>, Box<Expr>),
+    With(Box<Expr>, Box<Expr>),
+    BinOp(Box<Expr>, BinOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrPart {
+    Lit(String),
+    /// `${expr}`
+    Interp(Expr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    /// `++`
+    Concat,
+    /// `+`
+    Add,
+    /// `//`
+    Update,
+}
+
+impl BinOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Concat => "++",
+            BinOp::Add => "+",
+            BinOp::Update => "//",
+        }
+    }
+
+    fn level(self) -> u8 {
+        match self {
+            BinOp::Concat => 2,
+            BinOp::Add => 3,
+            BinOp::Update => 4,
+        }
+    }
+
+    fn right_assoc(self) -> bool {
+        !matches!(self, BinOp::Add)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    /// `a.b = value;`
+    Attr(Vec<String>, Expr),
+    /// `inherit a b;` or `inherit (from) a b;`
+    Inherit(Option<Expr>, Vec<String>),
+}
+
+impl Binding {
+    /// `path = value;`, with `path` split on `.`.
+    pub fn attr(path: &str, value: Expr) -> Self {
+        Binding::Attr(path.split('.').map(str::to_string).collect(), value)
+    }
+
+    pub fn inherit(names: &[&str]) -> Self {
+        Binding::Inherit(None, names.iter().map(|n| n.to_string()).collect())
+    }
+
+    pub fn inherit_from(from: Expr, names: &[&str]) -> Self {
+        Binding::Inherit(Some(from), names.iter().map(|n| n.to_string()).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Param {
+    /// `x: body`
+    Var(String),
+    /// `{ a, b ? default, ... }: body`
+    Pattern { fields: Vec<(String, Option<Expr>)>, ellipsis: bool },
+}
+
+impl Param {
+    pub fn pattern<'a>(fields: impl IntoIterator<Item = (&'a str, Option<Expr>)>) -> Self {
+        Param::Pattern {
+            fields: fields.into_iter().map(|(name, default)| (name.to_string(), default)).collect(),
+            ellipsis: false,
+        }
+    }
+
+    pub fn with_ellipsis(self) -> Self {
+        match self {
+            Param::Pattern { fields, .. } => Param::Pattern { fields, ellipsis: true },
+            var => var,
+        }
+    }
+}
+
+impl Expr {
+    pub fn string(s: impl Into<String>) -> Self {
+        Expr::Str(vec![StrPart::Lit(s.into())])
+    }
+
+    pub fn interpolated(parts: impl IntoIterator<Item = StrPart>) -> Self {
+        Expr::Str(parts.into_iter().collect())
+    }
+
+    pub fn var(name: &str) -> Self {
+        Expr::Var(name.to_string())
+    }
+
+    pub fn path(path: &str) -> Self {
+        Expr::Path(path.to_string())
+    }
+
+    pub fn list(items: impl IntoIterator<Item = Expr>) -> Self {
+        Expr::List(items.into_iter().collect())
+    }
+
+    pub fn attrs<'a>(bindings: impl IntoIterator<Item = (&'a str, Expr)>) -> Self {
+        Expr::AttrSet {
+            recursive: false,
+            bindings: bindings.into_iter().map(|(path, value)| Binding::attr(path, value)).collect(),
+        }
+    }
+
+    pub fn attr_set(bindings: impl IntoIterator<Item = Binding>) -> Self {
+        Expr::AttrSet { recursive: false, bindings: bindings.into_iter().collect() }
+    }
+
+    pub fn let_in(bindings: impl IntoIterator<Item = Binding>, body: Expr) -> Self {
+        Expr::Let(bindings.into_iter().collect(), Box::new(body))
+    }
+
+    pub fn lambda(param: Param, body: Expr) -> Self {
+        Expr::Lambda(param, Box::new(body))
+    }
+
+    /// `import <nixpkgs> { }`, the usual default for a `pkgs` argument.
+    pub fn import_nixpkgs() -> Self {
+        Expr::var("import").apply(Expr::path("<nixpkgs>")).apply(Expr::attr_set([]))
+    }
+
+    /// `self.a.b`, with `path` split on `.`.
+    pub fn select(self, path: &str) -> Self {
+        match self {
+            Expr::Select(base, mut attrs, None) => {
+                attrs.extend(path.split('.').map(str::to_string));
+                Expr::Select(base, attrs, None)
+            }
+            base => Expr::Select(Box::new(base), path.split('.').map(str::to_string).collect(), None),
+        }
+    }
+
+    /// `self.a.b or default`
+    pub fn select_or(self, path: &str, default: Expr) -> Self {
+        match self.select(path) {
+            Expr::Select(base, attrs, _) => Expr::Select(base, attrs, Some(Box::new(default))),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn apply(self, arg: Expr) -> Self {
+        Expr::Apply(Box::new(self), Box::new(arg))
+    }
+
+    pub fn concat(self, rhs: Expr) -> Self {
+        Expr::BinOp(Box::new(self), BinOp::Concat, Box::new(rhs))
+    }
+
+    pub fn update(self, rhs: Expr) -> Self {
+        Expr::BinOp(Box::new(self), BinOp::Update, Box::new(rhs))
+    }
+
+    /// How loosely the expression binds: 0 for atoms and selections, 1 for
+    /// application, then the operators, then `let`/`with`/lambdas.
+    fn level(&self) -> u8 {
+        match self {
+            Expr::Int(n) if *n < 0 => 1,
+            Expr::Apply(..) => 1,
+            Expr::BinOp(_, op, _) => op.level(),
+            Expr::Let(..) | Expr::Lambda(..) | Expr::With(..) => 5,
+            _ => 0,
+        }
+    }
+
+    /// The expression as Nix source, without a trailing newline.
+    pub fn to_nix(&self) -> String {
+        let mut out = String::new();
+        Printer { out: &mut out, indent: 0 }.expr(self, u8::MAX);
+        out
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_nix())
+    }
+}
+
+/// A Nix file: `# comment` lines, then `expr`.
+pub fn nix_file(comments: &[&str], expr: &Expr) -> String {
+    let mut out = String::new();
+    for comment in comments {
+        for line in comment.lines() {
+            out.push_str(if line.is_empty() { "#" } else { "# " });
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if !comments.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&expr.to_nix());
+    out.push('\n');
+    out
+}
+
+const KEYWORDS: &[&str] = &["assert", "else", "if", "in", "inherit", "let", "or", "rec", "then", "with"];
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '\'' | '-'))
+        && !KEYWORDS.contains(&name)
+}
+
+fn is_literal_path(path: &str) -> bool {
+    let search = path.strip_prefix('<').and_then(|p| p.strip_suffix('>'));
+    let body = search.unwrap_or(path);
+    let anchored = search.is_some() || path.starts_with('/') || path.starts_with("./") || path.starts_with("../") || path.starts_with("~/");
+    anchored
+        && !body.ends_with('/')
+        && !body.contains("//")
+        && body.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+' | '/' | '~'))
+}
+
+/// Minimum indentation of the non-blank lines of the literal parts.
+fn min_indent(parts: &[StrPart]) -> usize {
+    let mut text = String::new();
+    for part in parts {
+        match part {
+            StrPart::Lit(s) => text.push_str(s),
+            StrPart::Interp(_) => text.push('x'),
+        }
+    }
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0)
+}
+
+struct Printer<'a> {
+    out: &'a mut String,
+    indent: usize,
+}
+
+impl Printer<'_> {
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.indent {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Printer<'_>)) {
+        self.indent += 1;
+        f(self);
+        self.indent -= 1;
+    }
+
+    /// Prints `e`, parenthesized if it binds more loosely than `max`.
+    fn expr(&mut self, e: &Expr, max: u8) {
+        if e.level() > max {
+            self.out.push('(');
+            self.expr(e, u8::MAX);
+            self.out.push(')');
+            return;
+        }
+        match e {
+            Expr::Null => self.out.push_str("null"),
+            Expr::Bool(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            Expr::Int(n) => self.out.push_str(&n.to_string()),
+            Expr::Str(parts) => self.string(parts),
+            Expr::Path(path) => self.path(path),
+            Expr::Var(name) => self.out.push_str(name),
+            Expr::Select(base, attrs, default) => {
+                self.expr(base, 0);
+                for attr in attrs {
+                    self.out.push('.');
+                    self.attr_name(attr);
+                }
+                if let Some(default) = default {
+                    self.out.push_str(" or ");
+                    self.expr(default, 0);
+                }
+            }
+            Expr::List(items) => self.list(items),
+            Expr::AttrSet { recursive, bindings } => {
+                if *recursive {
+                    self.out.push_str("rec ");
+                }
+                self.attr_set(bindings);
+            }
+            Expr::Let(bindings, body) => {
+                self.out.push_str("let");
+                self.nested(|p| {
+                    for binding in bindings {
+                        p.newline();
+                        p.binding(binding);
+                    }
+                });
+                self.newline();
+                self.out.push_str("in");
+                self.newline();
+                self.expr(body, u8::MAX);
+            }
+            Expr::Lambda(param, body) => {
+                self.param(param);
+                self.out.push(':');
+                // Top-level functions read best with the body on its own line.
+                if self.indent == 0 && matches!(param, Param::Pattern { .. }) && matches!(**body, Expr::Let(..) | Expr::Apply(..)) {
+                    self.out.push('\n');
+                    self.newline();
+                    self.expr(body, u8::MAX);
+                } else if matches!(**body, Expr::Let(..)) {
+                    self.nested(|p| {
+                        p.newline();
+                        p.expr(body, u8::MAX);
+                    });
+                } else {
+                    self.out.push(' ');
+                    self.expr(body, u8::MAX);
+                }
+            }
+            Expr::Apply(f, arg) => {
+                self.expr(f, 1);
+                self.out.push(' ');
+                self.expr(arg, 0);
+            }
+            Expr::With(scope, body) => {
+                self.out.push_str("with ");
+                self.expr(scope, u8::MAX);
+                self.out.push_str("; ");
+                self.expr(body, u8::MAX);
+            }
+            Expr::BinOp(lhs, op, rhs) => {
+                let level = op.level();
+                let (lhs_max, rhs_max) = if op.right_assoc() { (level - 1, level) } else { (level, level - 1) };
+                self.expr(lhs, lhs_max);
+                self.out.push(' ');
+                self.out.push_str(op.symbol());
+                self.out.push(' ');
+                self.expr(rhs, rhs_max);
+            }
+        }
+    }
+
+    fn attr_name(&mut self, name: &str) {
+        if is_identifier(name) {
+            self.out.push_str(name);
+        } else {
+            self.quoted(&[StrPart::Lit(name.to_string())]);
+        }
+    }
+
+    fn path(&mut self, path: &str) {
+        if is_literal_path(path) {
+            self.out.push_str(path);
+            return;
+        }
+        // Not expressible as a path literal: build it from a string.
+        let (base, rest) = match path.strip_prefix('/') {
+            Some(rest) => ("/.", rest),
+            None => ("./.", path.trim_start_matches("./")),
+        };
+        self.out.push('(');
+        self.out.push_str(base);
+        self.out.push_str(" + ");
+        self.quoted(&[StrPart::Lit(format!("/{}", rest))]);
+        self.out.push(')');
+    }
+
+    /// Multi-line strings ending in a newline become indented strings,
+    /// unless Nix would strip their own indentation.
+    fn string(&mut self, parts: &[StrPart]) {
+        let ends_with_newline = matches!(parts.last(), Some(StrPart::Lit(s)) if s.ends_with('\n'));
+        let plain = parts.iter().all(|p| !matches!(p, StrPart::Lit(s) if s.contains('\r')));
+        if ends_with_newline && plain && min_indent(parts) == 0 {
+            self.indented(parts);
+        } else {
+            self.quoted(parts);
+        }
+    }
+
+    fn quoted(&mut self, parts: &[StrPart]) {
+        self.out.push('"');
+        for (i, part) in parts.iter().enumerate() {
+            match part {
+                StrPart::Lit(s) => {
+                    let before_interp = matches!(parts.get(i + 1), Some(StrPart::Interp(_)));
+                    let mut chars = s.chars().peekable();
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '"' => self.out.push_str("\\\""),
+                            '\\' => self.out.push_str("\\\\"),
+                            '\n' => self.out.push_str("\\n"),
+                            '\r' => self.out.push_str("\\r"),
+                            '\t' => self.out.push_str("\\t"),
+                            '$' if chars.peek() == Some(&'{') || (chars.peek().is_none() && before_interp) => {
+                                self.out.push_str("\\$")
+                            }
+                            c => self.out.push(c),
+                        }
+                    }
+                }
+                StrPart::Interp(e) => self.interpolation(e),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn indented(&mut self, parts: &[StrPart]) {
+        self.out.push_str("''");
+        self.nested(|p| {
+            p.newline();
+            for (i, part) in parts.iter().enumerate() {
+                match part {
+                    StrPart::Lit(s) => {
+                        let before_interp = matches!(parts.get(i + 1), Some(StrPart::Interp(_)));
+                        let mut chars = s.chars().peekable();
+                        while let Some(c) = chars.next() {
+                            match c {
+                                '\n' => {
+                                    // No trailing whitespace on blank lines.
+                                    let at_end = chars.peek().is_none() && i + 1 == parts.len();
+                                    if at_end || chars.peek() == Some(&'\n') {
+                                        p.out.push('\n');
+                                    } else {
+                                        p.newline();
+                                    }
+                                }
+                                '\'' if chars.peek() == Some(&'\'') => {
+                                    chars.next();
+                                    p.out.push_str("'''");
+                                }
+                                '$' if chars.peek() == Some(&'{') || (chars.peek().is_none() && before_interp) => {
+                                    p.out.push_str("''$")
+                                }
+                                c => p.out.push(c),
+                            }
+                        }
+                    }
+                    StrPart::Interp(e) => p.interpolation(e),
+                }
+            }
+        });
+        if self.out.ends_with('\n') {
+            for _ in 0..self.indent {
+                self.out.push_str("  ");
+            }
+        }
+        self.out.push_str("''");
+    }
+
+    fn interpolation(&mut self, e: &Expr) {
+        self.out.push_str("${");
+        self.expr(e, u8::MAX);
+        self.out.push('}');
+    }
+
+    /// `e` on one line, if it fits in `width`.
+    fn inline(e: &Expr, max: u8, width: usize) -> Option<String> {
+        let mut text = String::new();
+        Printer { out: &mut text, indent: 0 }.expr(e, max);
+        (!text.contains('\n') && text.len() <= width).then_some(text)
+    }
+
+    fn list(&mut self, items: &[Expr]) {
+        if items.is_empty() {
+            self.out.push_str("[ ]");
+            return;
+        }
+        let inline: Option<Vec<String>> = items.iter().map(|item| Printer::inline(item, 0, 40)).collect();
+        match inline {
+            Some(items) if items.iter().map(|i| i.len() + 1).sum::<usize>() <= 60 => {
+                self.out.push_str("[ ");
+                self.out.push_str(&items.join(" "));
+                self.out.push_str(" ]");
+            }
+            _ => {
+                self.out.push('[');
+                self.nested(|p| {
+                    for item in items {
+                        p.newline();
+                        p.expr(item, 0);
+                    }
+                });
+                self.newline();
+                self.out.push(']');
+            }
+        }
+    }
+
+    fn attr_set(&mut self, bindings: &[Binding]) {
+        if bindings.is_empty() {
+            self.out.push_str("{ }");
+            return;
+        }
+        if let [binding] = bindings {
+            let mut text = String::new();
+            Printer { out: &mut text, indent: 0 }.binding(binding);
+            if !text.contains('\n') && text.len() <= 40 {
+                self.out.push_str("{ ");
+                self.out.push_str(&text);
+                self.out.push_str(" }");
+                return;
+            }
+        }
+        self.out.push('{');
+        self.nested(|p| {
+            for binding in bindings {
+                p.newline();
+                p.binding(binding);
+            }
+        });
+        self.newline();
+        self.out.push('}');
+    }
+
+    fn binding(&mut self, binding: &Binding) {
+        match binding {
+            Binding::Attr(path, value) => {
+                for (i, attr) in path.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push('.');
+                    }
+                    self.attr_name(attr);
+                }
+                self.out.push_str(" = ");
+                self.expr(value, u8::MAX);
+                self.out.push(';');
+            }
+            Binding::Inherit(from, names) => {
+                self.out.push_str("inherit");
+                if let Some(from) = from {
+                    self.out.push_str(" (");
+                    self.expr(from, u8::MAX);
+                    self.out.push(')');
+                }
+                for name in names {
+                    self.out.push(' ');
+                    self.attr_name(name);
+                }
+                self.out.push(';');
+            }
+        }
+    }
+
+    fn param(&mut self, param: &Param) {
+        match param {
+            Param::Var(name) => self.out.push_str(name),
+            Param::Pattern { fields, ellipsis } => {
+                if fields.is_empty() && !ellipsis {
+                    self.out.push_str("{ }");
+                    return;
+                }
+                self.out.push_str("{ ");
+                for (i, (name, default)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.out.push_str(name);
+                    if let Some(default) = default {
+                        self.out.push_str(" ? ");
+                        self.expr(default, 1);
+                    }
+                }
+                if *ellipsis {
+                    self.out.push_str(if fields.is_empty() { "..." } else { ", ..." });
+                }
+                self.out.push_str(" }");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_file() {
+        let pkgs = Expr::var("pkgs");
+        let expr = Expr::lambda(
+            Param::pattern([("pkgs", Some(Expr::import_nixpkgs())), ("patches", Some(Expr::list([])))]),
+            Expr::let_in(
+                [Binding::attr("version", Expr::string("1.75.0"))],
+                pkgs.select("rustc-unwrapped.overrideAttrs").apply(Expr::lambda(
+                    Param::Var("old".to_string()),
+                    Expr::attr_set([
+                        Binding::inherit(&["version"]),
+                        Binding::attr("patches", Expr::var("old").select_or("patches", Expr::list([])).concat(Expr::var("patches"))),
+                        Binding::Attr(vec!["meta".to_string(), "weird name".to_string()], Expr::Int(-1)),
+                    ]),
+                )),
+            ),
+        );
+        assert_eq!(nix_file(&["Rust 1.75.0"], &expr), r#"# Rust 1.75.0
+
+{ pkgs ? import <nixpkgs> { }, patches ? [ ] }:
+
+let
+  version = "1.75.0";
+in
+pkgs.rustc-unwrapped.overrideAttrs (old: {
+  inherit version;
+  patches = old.patches or [ ] ++ patches;
+  meta."weird name" = -1;
+})
+"#);
+    }
+
+    #[test]
+    fn test_strings_are_escaped() {
+        let interp = Expr::interpolated([
+            StrPart::Lit("cost: $".to_string()),
+            StrPart::Interp(Expr::var("price")),
+            StrPart::Lit(" \"${not}\" \\".to_string()),
+        ]);
+        assert_eq!(interp.to_nix(), r#""cost: \$${price} \"\${not}\" \\""#);
+
+        let script = Expr::string("echo ''quoted'' ${HOME}\n\nexit 0\n");
+        let set = Expr::attrs([("a", Expr::Int(1)), ("script", script)]);
+        assert_eq!(set.to_nix(), "{\n  a = 1;\n  script = ''\n    echo '''quoted''' ''${HOME}\n\n    exit 0\n  '';\n}");
+
+        // Indentation would be stripped by Nix, so keep a quoted string.
+        assert_eq!(Expr::string("  a\n  b\n").to_nix(), r#""  a\n  b\n""#);
+    }
+
+    #[test]
+    fn test_paths_and_precedence() {
+        assert_eq!(Expr::path("./patches/0001.patch").to_nix(), "./patches/0001.patch");
+        assert_eq!(Expr::path("/tmp/my dir/x").to_nix(), r#"(/. + "/tmp/my dir/x")"#);
+        assert_eq!(Expr::path("out dir").to_nix(), r#"(./. + "/out dir")"#);
+
+        let list = Expr::list([Expr::var("f").apply(Expr::var("x")), Expr::path("./a.patch")]);
+        assert_eq!(list.to_nix(), "[ (f x) ./a.patch ]");
+        let nested = Expr::list([Expr::var("a")]).concat(Expr::list([Expr::var("b")])).update(Expr::attr_set([]));
+        assert_eq!(nested.to_nix(), "[ a ] ++ [ b ] // { }");
+        let grouped = Expr::BinOp(Box::new(Expr::attr_set([]).update(Expr::attr_set([]))), BinOp::Update, Box::new(Expr::Null));
+        assert_eq!(grouped.to_nix(), "({ } // { }) // null");
+        assert_eq!(Expr::attrs([("with", Expr::Bool(true))]).to_nix(), r#"{ "with" = true; }"#);
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use sha2::{Digest, Sha256};
+
+// SRI hashes (`sha256-<base64>`) as accepted by the `hash` argument of
+// `fetchurl` and friends. A flat file hash is what `fetchurl` checks, so the
+// hash of a local tarball can pin its download.
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn sri(digest: &[u8]) -> String {
+    format!("sha256-{}", base64(digest))
+}
+
+/// The SRI hash of `bytes`.
+pub fn sri_sha256(bytes: &[u8]) -> String {
+    sri(&Sha256::digest(bytes))
+}
+
+/// The SRI hash of the file at `path`, read in chunks.
+pub fn file_sri(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(sri(&hasher.finalize()))
+}
+
+/// Whether `hash` is a well-formed SHA-256 SRI hash.
+pub fn is_sri_sha256(hash: &str) -> bool {
+    hash.strip_prefix("sha256-").is_some_and(|b64| {
+        b64.len() == 44 && b64.ends_with('=') && b64[..43].bytes().all(|c| BASE64.contains(&c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sri_vectors() {
+        assert_eq!(sri_sha256(b""), "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+        assert_eq!(sri_sha256(b"abc"), "sha256-ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+        assert!(is_sri_sha256(&sri_sha256(b"abc")));
+        assert!(!is_sri_sha256("sha256-0000"));
+
+        let path = std::env::temp_dir().join(format!("nix_core_sri_{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(file_sri(&path).unwrap(), sri_sha256(b"abc"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,14 @@
+// Nix emission for the generators of this workspace: a typed expression
+// AST with a pretty-printer, SRI hashing for pinning sources, lookup of
+// the Rust compilers already in a Nix store, and a reader for the `.drv`
+// files behind them.
+
+pub mod drv;
+pub mod expr;
+pub mod hash;
+pub mod store_rustc;
+
+pub use drv::{Derivation, DrvOutput, DrvGraph, PatchUse, store_path_name};
+pub use expr::{Expr, StrPart, BinOp, Binding, Param, nix_file};
+pub use hash::{sri_sha256, file_sri, is_sri_sha256};
+pub use store_rustc::{StoreRustc, RustcQuery, Channel, scan_store_rustcs, default_store};
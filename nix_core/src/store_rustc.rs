@@ -0,0 +1,313 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use semver::{Version, VersionReq};
+
+// Rust compilers in Nix stores. Everything is read from the store entry
+// itself: version, channel and date from its name (`rustc-1.79.0`,
+// `rust-default-1.80.0-nightly-2024-05-01`), targets from the
+// `lib/rustlib/<triple>/lib` directories it ships. Candidates rank by
+// version, then nightly date, newest first.
+
+pub const DEFAULT_STORE: &str = "/nix/store";
+
+/// `$NIX_STORE_DIR`, else `/nix/store`.
+pub fn default_store() -> PathBuf {
+    std::env::var_os("NIX_STORE_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from(DEFAULT_STORE))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Channel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "stable" => Ok(Channel::Stable),
+            "beta" => Ok(Channel::Beta),
+            "nightly" => Ok(Channel::Nightly),
+            _ => Err(format!("unknown channel `{}` (expected stable, beta or nightly)", s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Beta => "beta",
+            Channel::Nightly => "nightly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreRustc {
+    /// The store entry, e.g. `/nix/store/<hash>-rustc-1.79.0`.
+    pub dir: PathBuf,
+    pub rustc: PathBuf,
+    /// Entry name without the hash.
+    pub name: String,
+    /// `None` for names without a version (`rust-nightly-2024-05-01`).
+    pub version: Option<Version>,
+    pub channel: Channel,
+    /// `YYYY-MM-DD` of a dated (usually nightly) toolchain.
+    pub date: Option<String>,
+    pub targets: Vec<String>,
+}
+
+impl fmt::Display for StoreRustc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}", version)?,
+            None => write!(f, "?")?,
+        }
+        write!(f, " {}", self.channel.as_str())?;
+        if let Some(date) = &self.date {
+            write!(f, " {}", date)?;
+        }
+        if !self.targets.is_empty() {
+            write!(f, " [{}]", self.targets.join(", "))?;
+        }
+        write!(f, " {}", self.rustc.display())
+    }
+}
+
+fn is_date(parts: &[&str]) -> bool {
+    let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+    matches!(parts, [y, m, d, ..] if digits(y, 4) && digits(m, 2) && digits(d, 2))
+}
+
+/// Version, channel and date from a store entry name (hash stripped).
+pub fn parse_rustc_name(name: &str) -> (Option<Version>, Channel, Option<String>) {
+    let parts: Vec<&str> = name.split('-').collect();
+    let mut version = None;
+    let mut date = None;
+    for i in 0..parts.len() {
+        if version.is_none() && parts[i].starts_with(|c: char| c.is_ascii_digit()) && parts[i].contains('.') {
+            let numbers: Vec<&str> = parts[i].split('.').collect();
+            let core = match numbers.len() {
+                2 => format!("{}.0", parts[i]),
+                _ => parts[i].to_string(),
+            };
+            let pre = parts.get(i + 1).filter(|p| p.starts_with("nightly") || p.starts_with("beta") || **p == "dev");
+            let text = match pre {
+                Some(pre) => format!("{}-{}", core, pre),
+                None => core,
+            };
+            version = Version::parse(&text).ok();
+        }
+        if date.is_none() && is_date(&parts[i..]) {
+            date = Some(parts[i..i + 3].join("-"));
+        }
+    }
+    let channel = if parts.iter().any(|p| p.starts_with("nightly")) {
+        Channel::Nightly
+    } else if parts.iter().any(|p| p.starts_with("beta")) {
+        Channel::Beta
+    } else {
+        Channel::Stable
+    };
+    (version, channel, date)
+}
+
+fn rustlib_targets(dir: &Path) -> Vec<String> {
+    let mut targets: Vec<String> = fs::read_dir(dir.join("lib").join("rustlib"))
+        .map(|entries| entries.filter_map(|e| e.ok()).filter(|e| e.path().join("lib").is_dir())
+            .map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    targets.sort();
+    targets
+}
+
+/// Every `<root>/<entry>/bin/rustc` of the given roots, best first.
+/// Missing roots are skipped.
+pub fn scan_store_rustcs(roots: &[PathBuf]) -> Vec<StoreRustc> {
+    let mut found = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else { continue };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let dir = entry.path();
+            let rustc = dir.join("bin").join("rustc");
+            if !rustc.is_file() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let name = file_name.split_once('-').map_or(file_name.as_str(), |(_, name)| name).to_string();
+            let (version, channel, date) = parse_rustc_name(&name);
+            found.push(StoreRustc { targets: rustlib_targets(&dir), dir, rustc, name, version, channel, date });
+        }
+    }
+    rank(&mut found);
+    found
+}
+
+/// Newest version first (a nightly sorts below the release it precedes),
+/// then newest date; unversioned and undated entries last.
+pub fn rank(candidates: &mut [StoreRustc]) {
+    candidates.sort_by(|a, b| {
+        (Reverse(a.version.is_some()), Reverse(&a.version), Reverse(a.date.is_some()), Reverse(&a.date), &a.rustc)
+            .cmp(&(Reverse(b.version.is_some()), Reverse(&b.version), Reverse(b.date.is_some()), Reverse(&b.date), &b.rustc))
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// What to accept. Every set field has to match.
+#[derive(Debug, Clone, Default)]
+pub struct RustcQuery {
+    /// Cargo-style requirement (`>=1.75, <1.80`). Pre-release toolchains
+    /// match by their release version, so `1.80.0-nightly` satisfies `1.80`.
+    pub version: Option<VersionReq>,
+    pub channel: Option<Channel>,
+    date: Vec<(DateOp, String)>,
+    /// A `lib/rustlib/<triple>` the toolchain has to ship.
+    pub target: Option<String>,
+    /// Substring of the rustc path.
+    pub contains: Option<String>,
+}
+
+impl RustcQuery {
+    pub fn version(mut self, req: &str) -> Result<Self, String> {
+        self.version = Some(VersionReq::parse(req).map_err(|e| format!("invalid version requirement `{}`: {}", req, e))?);
+        Ok(self)
+    }
+
+    pub fn channel(mut self, channel: &str) -> Result<Self, String> {
+        self.channel = Some(Channel::parse(channel)?);
+        Ok(self)
+    }
+
+    /// Comma-separated comparisons with `YYYY-MM-DD` dates: `2024-05-01`,
+    /// `>=2024-01-01, <2024-06-01`.
+    pub fn date(mut self, spec: &str) -> Result<Self, String> {
+        for part in spec.split(',').map(str::trim) {
+            let (op, date) = [(">=", DateOp::Ge), ("<=", DateOp::Le), (">", DateOp::Gt), ("<", DateOp::Lt), ("=", DateOp::Eq)]
+                .iter()
+                .find_map(|(prefix, op)| part.strip_prefix(prefix).map(|d| (*op, d.trim())))
+                .unwrap_or((DateOp::Eq, part));
+            let parts: Vec<&str> = date.split('-').collect();
+            if parts.len() != 3 || !is_date(&parts) {
+                return Err(format!("invalid date `{}` (expected YYYY-MM-DD)", part));
+            }
+            self.date.push((op, date.to_string()));
+        }
+        Ok(self)
+    }
+
+    pub fn target(mut self, triple: &str) -> Self {
+        self.target = Some(triple.to_string());
+        self
+    }
+
+    pub fn contains(mut self, needle: &str) -> Self {
+        self.contains = Some(needle.to_string());
+        self
+    }
+
+    /// No filter is set, so every candidate matches.
+    pub fn is_empty(&self) -> bool {
+        self.version.is_none() && self.channel.is_none() && self.date.is_empty() && self.target.is_none() && self.contains.is_none()
+    }
+
+    pub fn matches(&self, candidate: &StoreRustc) -> bool {
+        let version_ok = self.version.as_ref().is_none_or(|req| {
+            candidate.version.as_ref().is_some_and(|v| req.matches(&Version::new(v.major, v.minor, v.patch)))
+        });
+        let date_ok = self.date.is_empty() || candidate.date.as_ref().is_some_and(|date| {
+            self.date.iter().all(|(op, bound)| match op {
+                DateOp::Eq => date == bound,
+                DateOp::Lt => date < bound,
+                DateOp::Le => date <= bound,
+                DateOp::Gt => date > bound,
+                DateOp::Ge => date >= bound,
+            })
+        });
+        version_ok
+            && date_ok
+            && self.channel.is_none_or(|c| c == candidate.channel)
+            && self.target.as_ref().is_none_or(|t| candidate.targets.contains(t))
+            && self.contains.as_ref().is_none_or(|s| candidate.rustc.to_string_lossy().contains(s.as_str()))
+    }
+
+    /// The matching candidates, keeping their order.
+    pub fn select<'a>(&self, candidates: &'a [StoreRustc]) -> Vec<&'a StoreRustc> {
+        candidates.iter().filter(|c| self.matches(c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rustc_name() {
+        let (version, channel, date) = parse_rustc_name("rustc-1.79.0");
+        assert_eq!((version.unwrap().to_string(), channel, date), ("1.79.0".to_string(), Channel::Stable, None));
+        let (version, channel, date) = parse_rustc_name("rust-default-1.80.0-nightly-2024-05-01");
+        assert_eq!(version.unwrap().to_string(), "1.80.0-nightly");
+        assert_eq!((channel, date.as_deref()), (Channel::Nightly, Some("2024-05-01")));
+        let (version, channel, _) = parse_rustc_name("rustc-wrapper-1.81.0-beta.3");
+        assert_eq!((version.unwrap().to_string(), channel), ("1.81.0-beta.3".to_string(), Channel::Beta));
+        let (version, channel, date) = parse_rustc_name("rust-nightly-2024-02-29");
+        assert_eq!((version, channel, date.as_deref()), (None, Channel::Nightly, Some("2024-02-29")));
+    }
+
+    #[test]
+    fn test_scan_rank_and_query() {
+        let store = std::env::temp_dir().join(format!("nix_core_store_rustc_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&store);
+        let entries = [
+            ("0a1b-rustc-1.9.0", &["x86_64-unknown-linux-gnu"][..]),
+            ("1c2d-rustc-1.79.0", &["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"][..]),
+            ("2e3f-rustc-1.75.0", &["x86_64-unknown-linux-gnu"][..]),
+            ("3g4h-rust-default-1.80.0-nightly-2024-05-01", &["x86_64-unknown-linux-gnu"][..]),
+            ("4i5j-rust-default-1.80.0-nightly-2024-04-01", &["x86_64-unknown-linux-gnu"][..]),
+            ("5k6l-rust-nightly-2024-06-01", &[][..]),
+        ];
+        for (name, targets) in entries {
+            fs::create_dir_all(store.join(name).join("bin")).unwrap();
+            fs::write(store.join(name).join("bin/rustc"), "").unwrap();
+            for target in targets {
+                fs::create_dir_all(store.join(name).join("lib/rustlib").join(target).join("lib")).unwrap();
+            }
+        }
+        fs::create_dir_all(store.join("6m7n-hello-2.12/bin")).unwrap();
+
+        let found = scan_store_rustcs(&[store.join("missing"), store.clone()]);
+        let names: Vec<&str> = found.iter().map(|c| c.name.as_str()).collect();
+        // Semver, not string order: 1.9.0 is the oldest.
+        assert_eq!(names, vec![
+            "rust-default-1.80.0-nightly-2024-05-01",
+            "rust-default-1.80.0-nightly-2024-04-01",
+            "rustc-1.79.0",
+            "rustc-1.75.0",
+            "rustc-1.9.0",
+            "rust-nightly-2024-06-01",
+        ]);
+
+        let pick = |q: RustcQuery| q.select(&found).iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert_eq!(pick(RustcQuery::default().version(">=1.75, <1.80").unwrap()), vec!["rustc-1.79.0", "rustc-1.75.0"]);
+        assert_eq!(pick(RustcQuery::default().version("1.80").unwrap().channel("stable").unwrap()), Vec::<String>::new());
+        assert_eq!(pick(RustcQuery::default().channel("nightly").unwrap().date(">=2024-04-15").unwrap()).len(), 2);
+        assert_eq!(pick(RustcQuery::default().date("2024-04-01").unwrap()), vec!["rust-default-1.80.0-nightly-2024-04-01"]);
+        assert_eq!(pick(RustcQuery::default().target("wasm32-unknown-unknown")), vec!["rustc-1.79.0"]);
+        assert_eq!(pick(RustcQuery::default().contains("1.7")), vec!["rustc-1.79.0", "rustc-1.75.0"]);
+        assert!(RustcQuery::default().is_empty());
+        assert!(!RustcQuery::default().date(">=2024-04-15").unwrap().is_empty());
+        assert!(RustcQuery::default().date("May 1st").is_err());
+        assert!(RustcQuery::default().version("one").is_err());
+        assert!(RustcQuery::default().channel("lts").is_err());
+        assert!(found[2].to_string().starts_with("1.79.0 stable [wasm32-unknown-unknown, x86_64-unknown-linux-gnu] "));
+        fs::remove_dir_all(&store).unwrap();
+    }
+}
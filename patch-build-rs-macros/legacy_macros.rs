@@ -1,7 +1,7 @@
 // Legacy macros that need to be preserved
 
 #[proc_macro]
-#[decl(fn, name = "grast", vis = "pub", hash = "7679dd68")]
+#[decl(fn, name = "grast", vis = "pub", hash = "14f943ca")]
 pub fn grast(input: TokenStream) -> TokenStream {
     let file = parse_macro_input!(input as File);
     let mut rdf_turtle_triplets = Vec::new();
@@ -21,7 +21,7 @@ pub fn grast(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-#[decl(fn, name = "lru", vis = "pub", hash = "2877ec72")]
+#[decl(fn, name = "lru", vis = "pub", hash = "84988ae8")]
 pub fn lru(args: TokenStream, item: TokenStream) -> TokenStream {
     let item_fn = parse_macro_input!(item as ItemFn);
     let args_str = args.to_string();
@@ -32,7 +32,7 @@ pub fn lru(args: TokenStream, item: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl(fn, name = "mkbuildrs", vis = "pub", hash = "56d3457d")]
+#[decl(fn, name = "mkbuildrs", vis = "pub", hash = "7663b9f7")]
 pub fn mkbuildrs(input: TokenStream) -> TokenStream {
     let input_str = input.to_string();
     let escaped_input_str = input_str.replace("{", "{{").replace("}", "}}");
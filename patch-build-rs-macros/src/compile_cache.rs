@@ -4,7 +4,7 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[decl(struct, name = "CompileError", vis = "pub", hash = "7a2f5c14")]
+#[decl(struct, name = "CompileError", vis = "pub", hash = "8f2ba715")]
 pub struct CompileError {
     pub file: String,
     pub line: Option<u32>,
@@ -16,7 +16,7 @@ pub struct CompileError {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[decl(struct, name = "CompileCache", vis = "pub", hash = "f0273978")]
+#[decl(struct, name = "CompileCache", vis = "pub", hash = "2203b959")]
 pub struct CompileCache {
     pub errors: HashMap<String, CompileError>,
     pub successful_fixes: HashMap<String, String>, // error_hash -> fix_applied
@@ -1,13 +1,14 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr, Item}; // Added Item
+use introspector_decl2_macros::decl2 as decl;
 // use introspector_core::{Expr, EXPR_CACHE}; // Import Expr and EXPR_CACHE
 // use std::hash::{Hash, Hasher}; // For hashing
 // use std::collections::hash_map::DefaultHasher; // For hashing
 // use serde_json; // For serializing Expr for hashing
 
 
-#[decl(fn, name = "compiler_inventory_impl", vis = "pub", hash = "892478f5")]
+#[decl(fn, name = "compiler_inventory_impl", vis = "pub", hash = "0498a109")]
 pub fn compiler_inventory_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let config = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "backpack_fill_impl", vis = "pub", hash = "184edd62")]
+#[decl(fn, name = "backpack_fill_impl", vis = "pub", hash = "b2b5438e")]
 pub fn backpack_fill_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let items_data = input_str.value();
@@ -52,7 +53,7 @@ pub fn backpack_fill_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "context_optimize_impl", vis = "pub", hash = "7a973f66")]
+#[decl(fn, name = "context_optimize_impl", vis = "pub", hash = "229722ef")]
 pub fn context_optimize_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _context_items = input_str.value();
@@ -140,7 +141,7 @@ impl ContextOptimizer {{
     }.into()
 }
 
-#[decl(fn, name = "token_weight_impl", vis = "pub", hash = "6239ef14")]
+#[decl(fn, name = "token_weight_impl", vis = "pub", hash = "53199ca9")]
 pub fn token_weight_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let content = input_str.value();
@@ -175,7 +176,7 @@ pub fn token_weight_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "context_compress_impl", vis = "pub", hash = "7e09cb3e")]
+#[decl(fn, name = "context_compress_impl", vis = "pub", hash = "63a2fbdf")]
 pub fn context_compress_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let full_context = input_str.value();
@@ -10,7 +10,7 @@ use syn::{parse_macro_input, LitStr};
 // CON-001: Conceptual Paxos (single-node simulation, not distributed)
 // ═══════════════════════════════════════════════════════════════════════════════
 use introspector_decl2_macros::decl2;
-#[decl2(fn, name = "dao_vote_impl", vis = "pub", hash = "86482c9b")]
+#[decl2(fn, name = "dao_vote_impl", vis = "pub", hash = "5698b14c")]
 pub fn dao_vote_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let proposal = input_str.value();
@@ -45,7 +45,7 @@ pub fn dao_vote_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "paxos_consensus_impl", vis = "pub", hash = "fbfc0991")]
+#[decl2(fn, name = "paxos_consensus_impl", vis = "pub", hash = "f7b7dccf")]
 pub fn paxos_consensus_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let patch_data = input_str.value();
@@ -75,7 +75,7 @@ pub fn paxos_consensus_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "apply_patch_impl", vis = "pub", hash = "6c0766b6")]
+#[decl2(fn, name = "apply_patch_impl", vis = "pub", hash = "618e2071")]
 pub fn apply_patch_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let patch_vector = input_str.value();
@@ -110,7 +110,7 @@ pub fn apply_patch_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "token_governance_impl", vis = "pub", hash = "7ae06f3a")]
+#[decl2(fn, name = "token_governance_impl", vis = "pub", hash = "b7610de4")]
 pub fn token_governance_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let token_amount = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "emoji_poem_impl", vis = "pub", hash = "1b3d15bc")]
+#[decl(fn, name = "emoji_poem_impl", vis = "pub", hash = "a4d6f201")]
 pub fn emoji_poem_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let concept = input_str.value();
@@ -102,7 +103,7 @@ Unknown concept, infinite possibility
     }.into()
 }
 
-#[decl(fn, name = "math_to_emoji_impl", vis = "pub", hash = "d9c2ffb8")]
+#[decl(fn, name = "math_to_emoji_impl", vis = "pub", hash = "ff29cee2")]
 pub fn math_to_emoji_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let math_expr = input_str.value();
@@ -143,7 +144,7 @@ pub fn math_to_emoji_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "emoji_to_math_impl", vis = "pub", hash = "395aa08e")]
+#[decl(fn, name = "emoji_to_math_impl", vis = "pub", hash = "a48be5a5")]
 pub fn emoji_to_math_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let emoji_expr = input_str.value();
@@ -174,7 +175,7 @@ pub fn emoji_to_math_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "poetry_cycle_impl", vis = "pub", hash = "e7558fed")]
+#[decl(fn, name = "poetry_cycle_impl", vis = "pub", hash = "cd4e9156")]
 pub fn poetry_cycle_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _cycle_type = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "nix_event_impl", vis = "pub", hash = "5eadfd4e")]
+#[decl(fn, name = "nix_event_impl", vis = "pub", hash = "357862ac")]
 pub fn nix_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let package = input_str.value();
@@ -30,7 +31,7 @@ pub fn nix_event_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "github_event_impl", vis = "pub", hash = "2eb33304")]
+#[decl(fn, name = "github_event_impl", vis = "pub", hash = "0a242681")]
 pub fn github_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let repo = input_str.value();
@@ -58,7 +59,7 @@ pub fn github_event_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "archive_event_impl", vis = "pub", hash = "98d37693")]
+#[decl(fn, name = "archive_event_impl", vis = "pub", hash = "a536dcbb")]
 pub fn archive_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let url = input_str.value();
@@ -77,7 +78,7 @@ pub fn archive_event_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "huggingface_event_impl", vis = "pub", hash = "05948575")]
+#[decl(fn, name = "huggingface_event_impl", vis = "pub", hash = "fe122f13")]
 pub fn huggingface_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let model = input_str.value();
@@ -96,7 +97,7 @@ pub fn huggingface_event_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "twitter_event_impl", vis = "pub", hash = "9e24288b")]
+#[decl(fn, name = "twitter_event_impl", vis = "pub", hash = "dccad98b")]
 pub fn twitter_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let hashtag = input_str.value();
@@ -115,7 +116,7 @@ pub fn twitter_event_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "telegram_event_impl", vis = "pub", hash = "c6e98ffc")]
+#[decl(fn, name = "telegram_event_impl", vis = "pub", hash = "13cd1a4a")]
 pub fn telegram_event_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let channel = input_str.value();
@@ -2,6 +2,7 @@ use patch_build_rs_macros::mkbuildrs;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use nix_core::{nix_file, Binding, Expr, Param};
 
 mkbuildrs! {
     module_name: "extract";
@@ -10,7 +11,7 @@ mkbuildrs! {
     exports: ["extract"];
 }
 
-#[decl(fn, name = "extract_impl", vis = "pub", hash = "274392bc")]
+#[decl(fn, name = "extract_impl", vis = "pub", hash = "b5b4d49b")]
 pub fn extract_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let fixme_id = input_str.value();
@@ -23,18 +24,28 @@ pub fn extract_impl(input: TokenStream) -> TokenStream {
             .to_lowercase()
     );
     
-    let flake_content = format!(r#"{{
-  description = "Isolated fixme: {}";
-  inputs = {{
-    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
-    rust-overlay.url = "github:oxalica/rust-overlay";
-  }};
-  outputs = {{ self, nixpkgs, rust-overlay }}: {{
-    devShells.x86_64-linux.default = pkgs.mkShell {{
-      buildInputs = [ rust-bin.stable.latest.default ];
-    }};
-  }};
-}}"#, fixme_id);
+    let pkgs = Expr::var("pkgs");
+    let outputs = Expr::lambda(
+        Param::pattern([("self", None), ("nixpkgs", None), ("rust-overlay", None)]),
+        Expr::let_in(
+            [Binding::attr("pkgs", Expr::var("import").apply(Expr::var("nixpkgs")).apply(Expr::attrs([
+                ("system", Expr::string("x86_64-linux")),
+                ("overlays", Expr::list([Expr::var("rust-overlay").select("overlays.default")])),
+            ])))],
+            Expr::attrs([("devShells.x86_64-linux.default", pkgs.clone().select("mkShell").apply(Expr::attrs([
+                ("buildInputs", Expr::list([pkgs.select("rust-bin.stable.latest.default")])),
+            ])))]),
+        ),
+    );
+    let flake = Expr::attrs([
+        ("description", Expr::string(format!("Isolated fixme: {}", fixme_id))),
+        ("inputs", Expr::attrs([
+            ("nixpkgs.url", Expr::string("github:NixOS/nixpkgs/nixos-unstable")),
+            ("rust-overlay.url", Expr::string("github:oxalica/rust-overlay")),
+        ])),
+        ("outputs", outputs),
+    ]);
+    let flake_content = nix_file(&[], &flake);
 
     quote! {
         {
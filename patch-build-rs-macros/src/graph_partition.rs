@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "sat_group_impl", vis = "pub", hash = "1edce13c")]
+#[decl(fn, name = "sat_group_impl", vis = "pub", hash = "284c9b87")]
 pub fn sat_group_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let memory_items = input_str.value();
@@ -47,7 +48,7 @@ c Each item must belong to exactly one group
     }.into()
 }
 
-#[decl(fn, name = "metis_partition_impl", vis = "pub", hash = "76ad6c2e")]
+#[decl(fn, name = "metis_partition_impl", vis = "pub", hash = "92585219")]
 pub fn metis_partition_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let graph_data = input_str.value();
@@ -90,7 +91,7 @@ pub fn metis_partition_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "memory_select_impl", vis = "pub", hash = "dd25f716")]
+#[decl(fn, name = "memory_select_impl", vis = "pub", hash = "6e99313f")]
 pub fn memory_select_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let selection_criteria = input_str.value();
@@ -133,7 +134,7 @@ pub trait MemoryItemSelector {{
     }.into()
 }
 
-#[decl(fn, name = "code_split_impl", vis = "pub", hash = "96a7bcc0")]
+#[decl(fn, name = "code_split_impl", vis = "pub", hash = "97f3a2fa")]
 pub fn code_split_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let partition_data = input_str.value();
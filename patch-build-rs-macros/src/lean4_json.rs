@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "lean4_expr_json_impl", vis = "pub", hash = "45fba3f5")]
+#[decl(fn, name = "lean4_expr_json_impl", vis = "pub", hash = "6d5c62d1")]
 pub fn lean4_expr_json_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let lean4_expr = input_str.value();
@@ -52,7 +53,7 @@ pub fn lean4_expr_json_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "rustc_lean4_bridge_impl", vis = "pub", hash = "f11b601c")]
+#[decl(fn, name = "rustc_lean4_bridge_impl", vis = "pub", hash = "c29b4780")]
 pub fn rustc_lean4_bridge_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rustc_structure = input_str.value();
@@ -98,7 +99,7 @@ pub fn rustc_lean4_bridge_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "lean4_patch_impl", vis = "pub", hash = "1ebce52b")]
+#[decl(fn, name = "lean4_patch_impl", vis = "pub", hash = "0dd1e7ed")]
 pub fn lean4_patch_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let patch_description = input_str.value();
@@ -190,7 +191,7 @@ end Lean.Expr
     }.into()
 }
 
-#[decl(fn, name = "json_monster_proof_impl", vis = "pub", hash = "de4341c4")]
+#[decl(fn, name = "json_monster_proof_impl", vis = "pub", hash = "fec9ac2d")]
 pub fn json_monster_proof_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let proof_name = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "lean4_to_rust_impl", vis = "pub", hash = "5569884d")]
+#[decl(fn, name = "lean4_to_rust_impl", vis = "pub", hash = "81866b19")]
 pub fn lean4_to_rust_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let lean4_syntax = input_str.value();
@@ -104,7 +105,7 @@ macro_rules! lean4_expr {{
     }.into()
 }
 
-#[decl(fn, name = "rust_to_lean4_impl", vis = "pub", hash = "2a31cbbd")]
+#[decl(fn, name = "rust_to_lean4_impl", vis = "pub", hash = "beadc7eb")]
 pub fn rust_to_lean4_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rust_macro = input_str.value();
@@ -138,7 +139,7 @@ pub fn rust_to_lean4_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "proof_simulate_impl", vis = "pub", hash = "641321d7")]
+#[decl(fn, name = "proof_simulate_impl", vis = "pub", hash = "caa8983a")]
 pub fn proof_simulate_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let proof_json = input_str.value();
@@ -217,7 +218,7 @@ impl ProofSimulator {{
     }.into()
 }
 
-#[decl(fn, name = "lean4_macro_bridge_impl", vis = "pub", hash = "2a31ec59")]
+#[decl(fn, name = "lean4_macro_bridge_impl", vis = "pub", hash = "aa0e0037")]
 pub fn lean4_macro_bridge_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let bridge_config = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "lean4_theorem_impl", vis = "pub", hash = "4f428e6a")]
+#[decl(fn, name = "lean4_theorem_impl", vis = "pub", hash = "8ac45e14")]
 pub fn lean4_theorem_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let theorem_name = input_str.value();
@@ -69,7 +70,7 @@ lemma lfunction_convergence (φ : RustcRing → MonsterGroup) (R : RustcRing) :
     }.into()
 }
 
-#[decl(fn, name = "rustc_to_lean_impl", vis = "pub", hash = "e5a399d1")]
+#[decl(fn, name = "rustc_to_lean_impl", vis = "pub", hash = "f6a84d71")]
 pub fn rustc_to_lean_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rustc_code = input_str.value();
@@ -121,7 +122,7 @@ theorem embedding_preserves_structure (a b : RustcElement) :
     }.into()
 }
 
-#[decl(fn, name = "monster_proof_impl", vis = "pub", hash = "461f5435")]
+#[decl(fn, name = "monster_proof_impl", vis = "pub", hash = "8260068a")]
 pub fn monster_proof_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let claim = input_str.value();
@@ -179,7 +180,7 @@ theorem rustc_moonshine_property :
     }.into()
 }
 
-#[decl(fn, name = "lfunction_proof_impl", vis = "pub", hash = "475f8e75")]
+#[decl(fn, name = "lfunction_proof_impl", vis = "pub", hash = "0b875018")]
 pub fn lfunction_proof_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let lfunction_data = input_str.value();
@@ -236,7 +237,7 @@ theorem rustc_lfunction_decomposition (R : RustcRing) :
     }.into()
 }
 
-#[decl(fn, name = "formal_verification_impl", vis = "pub", hash = "ae0d8c6a")]
+#[decl(fn, name = "formal_verification_impl", vis = "pub", hash = "29876edd")]
 pub fn formal_verification_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let system_claims = input_str.value();
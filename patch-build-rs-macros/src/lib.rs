@@ -42,63 +42,81 @@ mod repo_analysis;
 mod macro_generator;
 mod template_checker;
 mod ollama_macros;
+mod decl_attr;
+
+#[proc_macro_attribute]
+pub fn decl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    decl_attr::decl_attr_impl(attr, item)
+}
+
+/// Suppresses audit findings on the item: `#[audit_allow(SEC-001, reason = "...")]`.
+/// The item is emitted unchanged; `cargo-audit-fix scan` reads the attribute.
+#[proc_macro_attribute]
+pub fn audit_allow(attr: TokenStream, item: TokenStream) -> TokenStream {
+    introspector_decl_core::audit_allow_logic(attr.into(), item.into()).into()
+}
+
+#[proc_macro]
+pub fn decl_of(input: TokenStream) -> TokenStream {
+    introspector_decl_core::decl_of_logic(input.into()).into()
+}
 
 #[proc_macro]
-#[decl2(fn, name = "checktemplate", vis = "pub", hash = "0ddf638b")]
+#[decl2(fn, name = "checktemplate", vis = "pub", hash = "01d61718")]
 pub fn checktemplate(input: TokenStream) -> TokenStream {
     template_checker::checktemplate_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "generate_checked_macros", vis = "pub", hash = "fa86b97d")]
+#[decl2(fn, name = "generate_checked_macros", vis = "pub", hash = "325c9466")]
 pub fn generate_checked_macros(input: TokenStream) -> TokenStream {
     template_checker::generate_checked_macros_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "mkbuildrs", vis = "pub", hash = "1f3aae8e")]
+#[decl2(fn, name = "mkbuildrs", vis = "pub", hash = "410c750a")]
 pub fn mkbuildrs_checked(input: TokenStream) -> TokenStream {
     template_checker::mkbuildrs_checked_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "generate_common_macros", vis = "pub", hash = "7a7ed877")]
+#[decl2(fn, name = "generate_common_macros", vis = "pub", hash = "d6077462")]
 pub fn generate_common_macros(input: TokenStream) -> TokenStream {
     macro_generator::generate_common_macros_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "mkbuildrs_with_macros", vis = "pub", hash = "4bdbc81f")]
+#[decl2(fn, name = "mkbuildrs_with_macros", vis = "pub", hash = "dcb224fa")]
 pub fn mkbuildrs_with_macros(input: TokenStream) -> TokenStream {
     macro_generator::mkbuildrs_with_macros_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "ca", vis = "pub", hash = "5eaef638")]
+#[decl2(fn, name = "ca", vis = "pub", hash = "3f37812d")]
 pub fn repo_duplicate_analysis(input: TokenStream) -> TokenStream {
     repo_analysis::repo_duplicate_analysis_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "pattern_similarity", vis = "pub", hash = "cd91f864")]
+#[decl2(fn, name = "pattern_similarity", vis = "pub", hash = "f0549a3d")]
 pub fn pattern_similarity(input: TokenStream) -> TokenStream {
     repo_analysis::pattern_similarity_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "macro_lattice", vis = "pub", hash = "b2195169")]
+#[decl2(fn, name = "macro_lattice", vis = "pub", hash = "3789b83b")]
 pub fn macro_lattice(input: TokenStream) -> TokenStream {
     macro_lattice::macro_lattice_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lattice_dependencies", vis = "pub", hash = "6283aa32")]
+#[decl2(fn, name = "lattice_dependencies", vis = "pub", hash = "37ab7e93")]
 pub fn lattice_dependencies(input: TokenStream) -> TokenStream {
     macro_lattice::lattice_dependencies_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lattice_path", vis = "pub", hash = "af81a817")]
+#[decl2(fn, name = "lattice_path", vis = "pub", hash = "fcfe7b8f")]
 pub fn lattice_path(input: TokenStream) -> TokenStream {
     macro_lattice::lattice_path_impl(input)
 }
@@ -109,67 +127,67 @@ pub fn mkbuildrs(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "nix_rust_version", vis = "pub", hash = "4023fc4c")]
+#[decl2(fn, name = "nix_rust_version", vis = "pub", hash = "37605943")]
 pub fn nix_rust_version(input: TokenStream) -> TokenStream {
     mkbuildrs::nix_rust_version_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "rust_cache", vis = "pub", hash = "1e1d4711")]
+#[decl2(fn, name = "rust_cache", vis = "pub", hash = "b5089cf3")]
 pub fn rust_cache(input: TokenStream) -> TokenStream {
     mkbuildrs::rust_cache_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "real_rustc_analysis", vis = "pub", hash = "27c9551f")]
+#[decl2(fn, name = "real_rustc_analysis", vis = "pub", hash = "61bf3548")]
 pub fn real_rustc_analysis(input: TokenStream) -> TokenStream {
     real_data_analysis::real_rustc_analysis_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "real_duplicate_detection", vis = "pub", hash = "e7b539f5")]
+#[decl2(fn, name = "real_duplicate_detection", vis = "pub", hash = "f5248574")]
 pub fn real_duplicate_detection(input: TokenStream) -> TokenStream {
     real_data_analysis::real_duplicate_detection_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "real_eigenmatrix", vis = "pub", hash = "4e3fc25b")]
+#[decl2(fn, name = "real_eigenmatrix", vis = "pub", hash = "3ec9d820")]
 pub fn real_eigenmatrix(input: TokenStream) -> TokenStream {
     real_data_analysis::real_eigenmatrix_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "unified_codebase", vis = "pub", hash = "70bfd79e")]
+#[decl2(fn, name = "unified_codebase", vis = "pub", hash = "7398c1e8")]
 pub fn unified_codebase(input: TokenStream) -> TokenStream {
     duplicate_analysis::unified_codebase_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "semantic_hash", vis = "pub", hash = "a92938c2")]
+#[decl2(fn, name = "semantic_hash", vis = "pub", hash = "6cb2b2a8")]
 pub fn semantic_hash(input: TokenStream) -> TokenStream {
     duplicate_analysis::semantic_hash_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "grast_structural", vis = "pub", hash = "aa982462")]
+#[decl2(fn, name = "grast_structural", vis = "pub", hash = "66b2cb28")]
 pub fn grast_structural(input: TokenStream) -> TokenStream {
     duplicate_analysis::grast_structural_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "llm_redundancy", vis = "pub", hash = "f4c786ab")]
+#[decl2(fn, name = "llm_redundancy", vis = "pub", hash = "731439e8")]
 pub fn llm_redundancy(input: TokenStream) -> TokenStream {
     duplicate_analysis::llm_redundancy_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "redundancy_stats", vis = "pub", hash = "b274d056")]
+#[decl2(fn, name = "redundancy_stats", vis = "pub", hash = "7442e088")]
 pub fn redundancy_stats(input: TokenStream) -> TokenStream {
     duplicate_analysis::redundancy_stats_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "compiler_inventory", vis = "pub", hash = "30a9d6d8")]
+#[decl2(fn, name = "compiler_inventory", vis = "pub", hash = "3faa4d76")]
 pub fn compiler_inventory(input: TokenStream) -> TokenStream {
     compiler_inventory::compiler_inventory_impl(input)
 }
@@ -180,7 +198,6 @@ pub fn compiler_inventory(input: TokenStream) -> TokenStream {
 // }
 
 // #[proc_macro]
-#[decl2(fn, name = "extract", vis = "pub", hash = "a9586077")]
 // pub fn grast_extract(input: TokenStream) -> TokenStream {
 //     compiler_inventory::grast_extract_impl(input)
 // }
@@ -191,163 +208,163 @@ pub fn compiler_inventory(input: TokenStream) -> TokenStream {
 // }
 
 #[proc_macro]
-#[decl2(fn, name = "trace_rustc", vis = "pub", hash = "b5e8dbb3")]
+#[decl2(fn, name = "trace_rustc", vis = "pub", hash = "c7ffa18b")]
 pub fn trace_rustc(input: TokenStream) -> TokenStream {
     rustc_tracer::trace_rustc_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "custom_rust_driver", vis = "pub", hash = "a135228b")]
+#[decl2(fn, name = "custom_rust_driver", vis = "pub", hash = "f5be1981")]
 pub fn custom_rust_driver(input: TokenStream) -> TokenStream {
     rustc_tracer::custom_rust_driver_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "auto_source_setup", vis = "pub", hash = "c51a589c")]
+#[decl2(fn, name = "auto_source_setup", vis = "pub", hash = "5870abe7")]
 pub fn auto_source_setup(input: TokenStream) -> TokenStream {
     rustc_tracer::auto_source_setup_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "find_rustc_source", vis = "pub", hash = "9ae2e0aa")]
+#[decl2(fn, name = "find_rustc_source", vis = "pub", hash = "7cdc4e9d")]
 pub fn find_rustc_source(input: TokenStream) -> TokenStream {
     real_rustc_analysis::find_rustc_source_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "analyze_real_source", vis = "pub", hash = "45d79d1d")]
+#[decl2(fn, name = "analyze_real_source", vis = "pub", hash = "1107dbfd")]
 pub fn analyze_real_source(input: TokenStream) -> TokenStream {
     real_rustc_analysis::analyze_real_source_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "value", vis = "pub", hash = "5299a79b")]
+#[decl2(fn, name = "value", vis = "pub", hash = "a730977d")]
 pub fn prove_eigenvalues(input: TokenStream) -> TokenStream {
     real_rustc_analysis::prove_eigenvalues_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "rust_eigenmatrix", vis = "pub", hash = "53721425")]
+#[decl2(fn, name = "rust_eigenmatrix", vis = "pub", hash = "620a6790")]
 pub fn rust_eigenmatrix(input: TokenStream) -> TokenStream {
     rust_eigenmatrix::rust_eigenmatrix_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "source_to_emoji", vis = "pub", hash = "303526a4")]
+#[decl2(fn, name = "source_to_emoji", vis = "pub", hash = "800f1636")]
 pub fn source_to_emoji(input: TokenStream) -> TokenStream {
     rust_eigenmatrix::source_to_emoji_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "eigenform_verify", vis = "pub", hash = "7ab3142e")]
+#[decl2(fn, name = "eigenform_verify", vis = "pub", hash = "b51f5514")]
 pub fn eigenform_verify(input: TokenStream) -> TokenStream {
     rust_eigenmatrix::eigenform_verify_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "emoji_poem", vis = "pub", hash = "232cff91")]
+#[decl2(fn, name = "emoji_poem", vis = "pub", hash = "13d2aa36")]
 pub fn emoji_poem(input: TokenStream) -> TokenStream {
     emoji_poetry::emoji_poem_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "math_to_emoji", vis = "pub", hash = "36cce0e6")]
+#[decl2(fn, name = "math_to_emoji", vis = "pub", hash = "a23d7b66")]
 pub fn math_to_emoji(input: TokenStream) -> TokenStream {
     emoji_poetry::math_to_emoji_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "emoji_to_math", vis = "pub", hash = "bdab912f")]
+#[decl2(fn, name = "emoji_to_math", vis = "pub", hash = "6c43985e")]
 pub fn emoji_to_math(input: TokenStream) -> TokenStream {
     emoji_poetry::emoji_to_math_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "poetry_cycle", vis = "pub", hash = "bf8a70de")]
+#[decl2(fn, name = "poetry_cycle", vis = "pub", hash = "c3a9b00a")]
 pub fn poetry_cycle(input: TokenStream) -> TokenStream {
     emoji_poetry::poetry_cycle_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "language_quine", vis = "pub", hash = "2ba57705")]
+#[decl2(fn, name = "language_quine", vis = "pub", hash = "2028c686")]
 pub fn language_quine(input: TokenStream) -> TokenStream {
     quine_relay::language_quine_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "compiler_macro", vis = "pub", hash = "e7c41b14")]
+#[decl2(fn, name = "compiler_macro", vis = "pub", hash = "874f54c8")]
 pub fn compiler_macro(input: TokenStream) -> TokenStream {
     quine_relay::compiler_macro_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "bootstrap_cycle", vis = "pub", hash = "babd5455")]
+#[decl2(fn, name = "bootstrap_cycle", vis = "pub", hash = "e6ae0886")]
 pub fn bootstrap_cycle(input: TokenStream) -> TokenStream {
     quine_relay::bootstrap_cycle_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "automorphic_orbit", vis = "pub", hash = "06a5946e")]
+#[decl2(fn, name = "automorphic_orbit", vis = "pub", hash = "fea7d7ae")]
 pub fn automorphic_orbit(input: TokenStream) -> TokenStream {
     quine_relay::automorphic_orbit_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lean4_to_rust", vis = "pub", hash = "266fd1cb")]
+#[decl2(fn, name = "lean4_to_rust", vis = "pub", hash = "7efc709a")]
 pub fn lean4_to_rust(input: TokenStream) -> TokenStream {
     lean4_mirror::lean4_to_rust_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "rust_to_lean4", vis = "pub", hash = "a0aab282")]
+#[decl2(fn, name = "rust_to_lean4", vis = "pub", hash = "6431d07a")]
 pub fn rust_to_lean4(input: TokenStream) -> TokenStream {
     lean4_mirror::rust_to_lean4_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "proof_simulate", vis = "pub", hash = "7d3e1840")]
+#[decl2(fn, name = "proof_simulate", vis = "pub", hash = "e34eb2aa")]
 pub fn proof_simulate(input: TokenStream) -> TokenStream {
     lean4_mirror::proof_simulate_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lean4_macro_bridge", vis = "pub", hash = "32d96c0a")]
+#[decl2(fn, name = "lean4_macro_bridge", vis = "pub", hash = "fe8b758a")]
 pub fn lean4_macro_bridge(input: TokenStream) -> TokenStream {
     lean4_mirror::lean4_macro_bridge_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lean4_expr_json", vis = "pub", hash = "086ed9c7")]
+#[decl2(fn, name = "lean4_expr_json", vis = "pub", hash = "262d0aa9")]
 pub fn lean4_expr_json(input: TokenStream) -> TokenStream {
     lean4_json::lean4_expr_json_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "rustc_lean4_bridge", vis = "pub", hash = "914b7981")]
+#[decl2(fn, name = "rustc_lean4_bridge", vis = "pub", hash = "9b4bded3")]
 pub fn rustc_lean4_bridge(input: TokenStream) -> TokenStream {
     lean4_json::rustc_lean4_bridge_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lean4_patch", vis = "pub", hash = "b2bd9467")]
+#[decl2(fn, name = "lean4_patch", vis = "pub", hash = "06980981")]
 pub fn lean4_patch(input: TokenStream) -> TokenStream {
     lean4_json::lean4_patch_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "monster_proof", vis = "pub", hash = "62b27247")]
+#[decl2(fn, name = "monster_proof", vis = "pub", hash = "4e91fb03")]
 pub fn json_monster_proof(input: TokenStream) -> TokenStream {
     lean4_json::json_monster_proof_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lean4_theorem", vis = "pub", hash = "d26355c2")]
+#[decl2(fn, name = "lean4_theorem", vis = "pub", hash = "465ea807")]
 pub fn lean4_theorem(input: TokenStream) -> TokenStream {
     lean4_proof::lean4_theorem_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "rustc_to_lean", vis = "pub", hash = "b7cffc51")]
+#[decl2(fn, name = "rustc_to_lean", vis = "pub", hash = "ebaa6eb3")]
 pub fn rustc_to_lean(input: TokenStream) -> TokenStream {
     lean4_proof::rustc_to_lean_impl(input)
 }
@@ -358,157 +375,157 @@ pub fn monster_proof(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lfunction_proof", vis = "pub", hash = "ce37759a")]
+#[decl2(fn, name = "lfunction_proof", vis = "pub", hash = "9ea8e9d3")]
 pub fn lfunction_proof(input: TokenStream) -> TokenStream {
     lean4_proof::lfunction_proof_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "formal_verification", vis = "pub", hash = "7d168d21")]
+#[decl2(fn, name = "formal_verification", vis = "pub", hash = "c0ddd0f3")]
 pub fn formal_verification(input: TokenStream) -> TokenStream {
     lean4_proof::formal_verification_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "zk_witness", vis = "pub", hash = "4cb7cb6f")]
+#[decl2(fn, name = "zk_witness", vis = "pub", hash = "441a9f92")]
 pub fn zk_witness(input: TokenStream) -> TokenStream {
     zk_proof::zk_witness_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "plonk_circuit", vis = "pub", hash = "473ff43a")]
+#[decl2(fn, name = "plonk_circuit", vis = "pub", hash = "439f2ddc")]
 pub fn plonk_circuit(input: TokenStream) -> TokenStream {
     zk_proof::plonk_circuit_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "stark_proof", vis = "pub", hash = "d5fae4a8")]
+#[decl2(fn, name = "stark_proof", vis = "pub", hash = "7d658558")]
 pub fn stark_proof(input: TokenStream) -> TokenStream {
     zk_proof::stark_proof_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "snark_verify", vis = "pub", hash = "2863bbef")]
+#[decl2(fn, name = "snark_verify", vis = "pub", hash = "cfbbdfd6")]
 pub fn snark_verify(input: TokenStream) -> TokenStream {
     zk_proof::snark_verify_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "backpack_fill", vis = "pub", hash = "d49e4961")]
+#[decl2(fn, name = "backpack_fill", vis = "pub", hash = "8459b878")]
 pub fn backpack_fill(input: TokenStream) -> TokenStream {
     context_knapsack::backpack_fill_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "context_optimize", vis = "pub", hash = "e731281e")]
+#[decl2(fn, name = "context_optimize", vis = "pub", hash = "b81e3406")]
 pub fn context_optimize(input: TokenStream) -> TokenStream {
     context_knapsack::context_optimize_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "token", vis = "pub", hash = "280ff363")]
+#[decl2(fn, name = "token", vis = "pub", hash = "479e831e")]
 pub fn token_weight(input: TokenStream) -> TokenStream {
     context_knapsack::token_weight_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "compress", vis = "pub", hash = "3be315c8")]
+#[decl2(fn, name = "compress", vis = "pub", hash = "2b7c71ba")]
 pub fn context_compress(input: TokenStream) -> TokenStream {
     context_knapsack::context_compress_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "nix_event", vis = "pub", hash = "a9209106")]
+#[decl2(fn, name = "nix_event", vis = "pub", hash = "6416d758")]
 pub fn nix_event(input: TokenStream) -> TokenStream {
     event_memory::nix_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "github_event", vis = "pub", hash = "405bb8fa")]
+#[decl2(fn, name = "github_event", vis = "pub", hash = "4dca774e")]
 pub fn github_event(input: TokenStream) -> TokenStream {
     event_memory::github_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "archive_event", vis = "pub", hash = "db2cb06b")]
+#[decl2(fn, name = "archive_event", vis = "pub", hash = "b919fc76")]
 pub fn archive_event(input: TokenStream) -> TokenStream {
     event_memory::archive_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "huggingface_event", vis = "pub", hash = "18e2ab0a")]
+#[decl2(fn, name = "huggingface_event", vis = "pub", hash = "8b05eaf6")]
 pub fn huggingface_event(input: TokenStream) -> TokenStream {
     event_memory::huggingface_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "twitter_event", vis = "pub", hash = "1e0eff90")]
+#[decl2(fn, name = "twitter_event", vis = "pub", hash = "bb335bb8")]
 pub fn twitter_event(input: TokenStream) -> TokenStream {
     event_memory::twitter_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "telegram_event", vis = "pub", hash = "e7939c6a")]
+#[decl2(fn, name = "telegram_event", vis = "pub", hash = "ab34b156")]
 pub fn telegram_event(input: TokenStream) -> TokenStream {
     event_memory::telegram_event_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "sat_group", vis = "pub", hash = "d93ebe48")]
+#[decl2(fn, name = "sat_group", vis = "pub", hash = "d7161ce7")]
 pub fn sat_group(input: TokenStream) -> TokenStream {
     graph_partition::sat_group_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "metis_partition", vis = "pub", hash = "86020df6")]
+#[decl2(fn, name = "metis_partition", vis = "pub", hash = "6ca95e43")]
 pub fn metis_partition(input: TokenStream) -> TokenStream {
     graph_partition::metis_partition_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "memory_select", vis = "pub", hash = "847b27a8")]
+#[decl2(fn, name = "memory_select", vis = "pub", hash = "cc0a30c7")]
 pub fn memory_select(input: TokenStream) -> TokenStream {
     graph_partition::memory_select_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "code_split", vis = "pub", hash = "489e398b")]
+#[decl2(fn, name = "code_split", vis = "pub", hash = "2f2be04f")]
 pub fn code_split(input: TokenStream) -> TokenStream {
     graph_partition::code_split_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "sandwich_detect", vis = "pub", hash = "639c2145")]
+#[decl2(fn, name = "sandwich_detect", vis = "pub", hash = "ad2e12f2")]
 pub fn sandwich_detect(input: TokenStream) -> TokenStream {
     mev_protection::sandwich_detect_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "frontrun_block", vis = "pub", hash = "35db1ddf")]
+#[decl2(fn, name = "frontrun_block", vis = "pub", hash = "6d434942")]
 pub fn frontrun_block(input: TokenStream) -> TokenStream {
     mev_protection::frontrun_block_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "mev_exclude", vis = "pub", hash = "7cb0b085")]
+#[decl2(fn, name = "mev_exclude", vis = "pub", hash = "59adb872")]
 pub fn mev_exclude(input: TokenStream) -> TokenStream {
     mev_protection::mev_exclude_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "atomic_swap", vis = "pub", hash = "fe650a05")]
+#[decl2(fn, name = "atomic_swap", vis = "pub", hash = "10383472")]
 pub fn atomic_swap(input: TokenStream) -> TokenStream {
     mev_protection::atomic_swap_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "purchase_blocks", vis = "pub", hash = "bb48e11f")]
+#[decl2(fn, name = "purchase_blocks", vis = "pub", hash = "a348f420")]
 pub fn purchase_blocks(input: TokenStream) -> TokenStream {
     solana_lift::purchase_blocks_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lift_int_code", vis = "pub", hash = "28039740")]
+#[decl2(fn, name = "lift_int_code", vis = "pub", hash = "af00c438")]
 pub fn lift_int_code(input: TokenStream) -> TokenStream {
     solana_lift::lift_int_code_impl(input)
 }
@@ -524,139 +541,139 @@ pub fn token(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "lp", vis = "pub", hash = "681b5eb9")]
+#[decl2(fn, name = "lp", vis = "pub", hash = "f1d17c6f")]
 pub fn lp(input: TokenStream) -> TokenStream {
     solana_lift::lp_macro_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "quant", vis = "pub", hash = "47276e9c")]
+#[decl2(fn, name = "quant", vis = "pub", hash = "e2d8a237")]
 pub fn quant(input: TokenStream) -> TokenStream {
     quant_trading::quant_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "trading", vis = "pub", hash = "df75a15c")]
+#[decl2(fn, name = "trading", vis = "pub", hash = "03c6bddb")]
 pub fn trading(input: TokenStream) -> TokenStream {
     quant_trading::trading_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "load_historical", vis = "pub", hash = "96076756")]
+#[decl2(fn, name = "load_historical", vis = "pub", hash = "b5ccc0bb")]
 pub fn load_historical(input: TokenStream) -> TokenStream {
     quant_trading::load_historical_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "dao_vote", vis = "pub", hash = "19f6b264")]
+#[decl2(fn, name = "dao_vote", vis = "pub", hash = "e9929609")]
 pub fn dao_vote(input: TokenStream) -> TokenStream {
     dao_governance::dao_vote_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "paxos_consensus", vis = "pub", hash = "71d8492f")]
+#[decl2(fn, name = "paxos_consensus", vis = "pub", hash = "25f531f3")]
 pub fn paxos_consensus(input: TokenStream) -> TokenStream {
     dao_governance::paxos_consensus_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "apply_patch", vis = "pub", hash = "1370bee3")]
+#[decl2(fn, name = "apply_patch", vis = "pub", hash = "ec3230d7")]
 pub fn apply_patch(input: TokenStream) -> TokenStream {
     dao_governance::apply_patch_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "token_governance", vis = "pub", hash = "52305b31")]
+#[decl2(fn, name = "token_governance", vis = "pub", hash = "0a5ae17d")]
 pub fn token_governance(input: TokenStream) -> TokenStream {
     dao_governance::token_governance_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "sat_solve_unity", vis = "pub", hash = "25395982")]
+#[decl2(fn, name = "sat_solve_unity", vis = "pub", hash = "4577b361")]
 pub fn sat_solve_unity(input: TokenStream) -> TokenStream {
     sat_lfunction::sat_solve_unity_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "extract_lfunction", vis = "pub", hash = "0bd53dc8")]
+#[decl2(fn, name = "extract_lfunction", vis = "pub", hash = "fd61fe75")]
 pub fn extract_lfunction(input: TokenStream) -> TokenStream {
     sat_lfunction::extract_lfunction_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "matrix_decompose", vis = "pub", hash = "dc5acbf3")]
+#[decl2(fn, name = "matrix_decompose", vis = "pub", hash = "01115a1f")]
 pub fn matrix_decompose(input: TokenStream) -> TokenStream {
     sat_lfunction::matrix_decompose_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "unity_proof", vis = "pub", hash = "7c9d4384")]
+#[decl2(fn, name = "unity_proof", vis = "pub", hash = "83b9af7d")]
 pub fn unity_proof(input: TokenStream) -> TokenStream {
     sat_lfunction::unity_proof_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "load_lmfdb", vis = "pub", hash = "579a101f")]
+#[decl2(fn, name = "load_lmfdb", vis = "pub", hash = "b97bc76e")]
 pub fn load_lmfdb(input: TokenStream) -> TokenStream {
     lmfdb_morph::load_lmfdb_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "conformal_map", vis = "pub", hash = "5a695325")]
+#[decl2(fn, name = "conformal_map", vis = "pub", hash = "077d33bc")]
 pub fn conformal_map(input: TokenStream) -> TokenStream {
     lmfdb_morph::conformal_map_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "hott_morph", vis = "pub", hash = "65c4cf26")]
+#[decl2(fn, name = "hott_morph", vis = "pub", hash = "6f9a53ca")]
 pub fn hott_morph(input: TokenStream) -> TokenStream {
     lmfdb_morph::hott_morph_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "monster_check", vis = "pub", hash = "c02f748d")]
+#[decl2(fn, name = "monster_check", vis = "pub", hash = "f0b9b6d4")]
 pub fn monster_check(input: TokenStream) -> TokenStream {
     lmfdb_morph::monster_check_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "analyze_rustc_ring", vis = "pub", hash = "a38b31bb")]
+#[decl2(fn, name = "analyze_rustc_ring", vis = "pub", hash = "e8550afa")]
 pub fn analyze_rustc_ring(input: TokenStream) -> TokenStream {
     rustc_ring::analyze_rustc_ring_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "crate_report", vis = "pub", hash = "7a2d32ce")]
+#[decl2(fn, name = "crate_report", vis = "pub", hash = "76415112")]
 pub fn crate_report(input: TokenStream) -> TokenStream {
     rustc_ring::crate_report_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "dependency_graph", vis = "pub", hash = "55226dc5")]
+#[decl2(fn, name = "dependency_graph", vis = "pub", hash = "80cbfe36")]
 pub fn dependency_graph(input: TokenStream) -> TokenStream {
     rustc_ring::dependency_graph_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "ring_properties", vis = "pub", hash = "2c553716")]
+#[decl2(fn, name = "ring_properties", vis = "pub", hash = "04bd7e60")]
 pub fn ring_properties(input: TokenStream) -> TokenStream {
     rustc_ring::ring_properties_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "nix_rust_src", vis = "pub", hash = "ff44fbca")]
+#[decl2(fn, name = "nix_rust_src", vis = "pub", hash = "c0459dde")]
 pub fn nix_rust_src(input: TokenStream) -> TokenStream {
     rust_nix::nix_rust_src_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "decl", vis = "pub", hash = "9cec6423")]
+#[decl2(fn, name = "decl", vis = "pub", hash = "c989cfe2")]
 pub fn extract_decl(input: TokenStream) -> TokenStream {
     rust_nix::extract_decl_impl(input)
 }
 
 #[proc_macro]
-#[decl2(fn, name = "patch_rust", vis = "pub", hash = "d60f6cd9")]
+#[decl2(fn, name = "patch_rust", vis = "pub", hash = "af37370a")]
 pub fn patch_rust(input: TokenStream) -> TokenStream {
     rust_nix::patch_rust_impl(input)
 }
@@ -677,7 +694,7 @@ pub fn extract(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "simplify", vis = "pub", hash = "4e29d5fd")]
+#[decl2(fn, name = "simplify", vis = "pub", hash = "3a67e53d")]
 pub fn simplify(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let data = input_str.value();
@@ -698,7 +715,7 @@ pub fn simplify(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "pii", vis = "pub", hash = "7f2215df")]
+#[decl2(fn, name = "pii", vis = "pub", hash = "b02b78e3")]
 pub fn pii(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let data = input_str.value();
@@ -717,7 +734,7 @@ pub fn pii(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "prune", vis = "pub", hash = "de877f0b")]
+#[decl2(fn, name = "prune", vis = "pub", hash = "00bb9595")]
 pub fn prune(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let data = input_str.value();
@@ -758,7 +775,7 @@ pub fn compress(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro]
-#[decl2(fn, name = "ticket", vis = "pub", hash = "70cd56d5")]
+#[decl2(fn, name = "ticket", vis = "pub", hash = "7520fad4")]
 pub fn ticket(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let issue = input_str.value();
@@ -781,7 +798,7 @@ pub fn value(input: TokenStream) -> TokenStream {
 
 
 #[proc_macro]
-#[decl2(fn, name = "ollama", vis = "pub", hash = "9c48b75e")]
+#[decl2(fn, name = "ollama", vis = "pub", hash = "eeb752dc")]
 pub fn ollama(input: TokenStream) -> TokenStream {
     ollama_macros::ollama_impl(input)
 }
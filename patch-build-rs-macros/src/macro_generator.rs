@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "generate_common_macros_impl", vis = "pub", hash = "0d8a5a00")]
+#[decl(fn, name = "generate_common_macros_impl", vis = "pub", hash = "f0df3078")]
 pub fn generate_common_macros_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _config = input_str.value();
@@ -69,7 +70,7 @@ macro_rules! write_file {
     }.into()
 }
 
-#[decl(fn, name = "mkbuildrs_with_macros_impl", vis = "pub", hash = "152b1c84")]
+#[decl(fn, name = "mkbuildrs_with_macros_impl", vis = "pub", hash = "e437510f")]
 pub fn mkbuildrs_with_macros_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let config = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "macro_lattice_impl", vis = "pub", hash = "fa859ff9")]
+#[decl(fn, name = "macro_lattice_impl", vis = "pub", hash = "4e065673")]
 pub fn macro_lattice_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let lattice_type = input_str.value();
@@ -125,7 +126,7 @@ Level 14: BUILD SYSTEMS (Infrastructure)
     }.into()
 }
 
-#[decl(fn, name = "lattice_dependencies_impl", vis = "pub", hash = "3fc45704")]
+#[decl(fn, name = "lattice_dependencies_impl", vis = "pub", hash = "1c3a22e3")]
 pub fn lattice_dependencies_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let macro_name = input_str.value();
@@ -200,7 +201,7 @@ Critical Path Analysis:
     }.into()
 }
 
-#[decl(fn, name = "lattice_path_impl", vis = "pub", hash = "bfeaa46f")]
+#[decl(fn, name = "lattice_path_impl", vis = "pub", hash = "f2a4584a")]
 pub fn lattice_path_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let path_spec = input_str.value();
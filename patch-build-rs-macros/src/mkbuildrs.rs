@@ -1,8 +1,12 @@
+use std::path::Path;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, LitStr};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, LitStr, Token};
+use nix_core::{nix_file, Binding, Expr, Param, StrPart};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "mkbuildrs_impl", vis = "pub", hash = "c9fe6d9c")]
+#[decl(fn, name = "mkbuildrs_impl", vis = "pub", hash = "2eaa05ad")]
 pub fn mkbuildrs_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let config = input_str.value();
@@ -40,219 +44,76 @@ fn main() {
     }.into()
 }
 
-#[decl(fn, name = "nix_rust_version_impl", vis = "pub", hash = "c6cb4b8f")]
+/// `rustc-unwrapped` built from the upstream source tarball of `version`.
+/// `hash` pins the download; without it `lib.fakeHash` fails the first
+/// build with the real hash.
+fn rust_version_nix(version: Expr, hash: Option<&str>) -> Expr {
+    let pkgs = Expr::var("pkgs");
+    let hash = match hash {
+        Some(hash) => Expr::string(hash),
+        None => pkgs.clone().select("lib.fakeHash"),
+    };
+    let src = pkgs.clone().select("fetchurl").apply(Expr::attrs([
+        ("url", Expr::interpolated([
+            StrPart::Lit("https://static.rust-lang.org/dist/rustc-".to_string()),
+            StrPart::Interp(Expr::var("rustVersion")),
+            StrPart::Lit("-src.tar.gz".to_string()),
+        ])),
+        ("hash", hash),
+    ]));
+    Expr::let_in(
+        [Binding::attr("rustVersion", version), Binding::attr("rustSrc", src)],
+        pkgs.select("rustc-unwrapped.overrideAttrs").apply(Expr::lambda(
+            Param::Var("old".to_string()),
+            Expr::attrs([("version", Expr::var("rustVersion")), ("src", Expr::var("rustSrc"))]),
+        )),
+    )
+}
+
+/// `nix_rust_version!("1.75.0")` or `nix_rust_version!("1.75.0", "rustc-1.75.0-src.tar.gz")`:
+/// a Nix expression for that rustc. A local copy of the source tarball
+/// (relative to the crate root) is hashed at expansion time to pin it.
+#[decl(fn, name = "nix_rust_version_impl", vis = "pub", hash = "19cd42e8")]
 pub fn nix_rust_version_impl(input: TokenStream) -> TokenStream {
-    let input_str = parse_macro_input!(input as LitStr);
-    let version = input_str.value();
-    
+    let args = parse_macro_input!(input with Punctuated::<LitStr, Token![,]>::parse_terminated);
+    let mut args = args.into_iter();
+    let Some(version) = args.next() else {
+        return quote! { compile_error!("nix_rust_version! expects a Rust version") }.into();
+    };
+    let hash = match args.next() {
+        Some(tarball) => {
+            let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+            match nix_core::file_sri(&Path::new(&root).join(tarball.value())) {
+                Ok(hash) => Some(hash),
+                Err(e) => return syn::Error::new(tarball.span(), e).to_compile_error().into(),
+            }
+        }
+        None => None,
+    };
+    let version = version.value();
+
+    let expr = Expr::lambda(
+        Param::pattern([("pkgs", Some(Expr::import_nixpkgs()))]),
+        rust_version_nix(Expr::string(&version), hash.as_deref()),
+    );
+    let nix_expr = nix_file(&[&format!("Nix expression for Rust {}", version)], &expr);
+
     quote! {
         {
             println!("cargo:warning=🦀 Nix Rust version: {}", #version);
-            
-            // Generate Nix expression for specific Rust version
-            let nix_expr = format!(r###"
-# Nix expression for Rust {}
-{{ pkgs ? import <nixpkgs> {{}} }}:
-
-let
-  rustVersion = "{}";
-  rustSrc = pkgs.fetchFromGitHub {{
-    owner = "rust-lang";
-    repo = "rust";
-    rev = rustVersion;
-    sha256 = "0000000000000000000000000000000000000000000000000000";
-  }};
-in
-pkgs.rustc.override {{
-  version = rustVersion;
-  src = rustSrc;
-}}
-            "###, #version, #version);
-            
-            nix_expr
+            #nix_expr.to_string()
         }
     }.into()
 }
 
-#[decl(fn, name = "rust_cache_impl", vis = "pub", hash = "2c5c0b09")]
+/// `rust_cache!()` opens the toolchain cache at its default root;
+/// `rust_cache!(root)` opens the one at `root`. Either way the result is a
+/// `Result<introspector_core::ToolchainCache, String>`.
+#[decl(fn, name = "rust_cache_impl", vis = "pub", hash = "e12ae371")]
 pub fn rust_cache_impl(input: TokenStream) -> TokenStream {
-    let input_str = parse_macro_input!(input as LitStr);
-    let cache_config = input_str.value();
-    
-    quote! {
-        {
-            println!("cargo:warning=📦 Rust cache: {}", #cache_config);
-            
-            let cache_system = format!(r###"
-// Rust Version Cache System
-// Config: {}
-
-use std::path::{{Path, PathBuf}};
-use std::collections::HashMap;
-
-pub struct RustCache {{
-    cache_dir: PathBuf,
-    versions: HashMap<String, PathBuf>,
-}}
-
-impl RustCache {{
-    pub fn new() -> Self {{
-        let cache_dir = std::env::var("CARGO_TARGET_DIR")
-            .unwrap_or_else(|_| "target".to_string())
-            .into();
-        let cache_dir = cache_dir.join("rust-cache");
-        
-        Self {{
-            cache_dir,
-            versions: HashMap::new(),
-        }}
-    }}
-    
-    pub fn get_version(&self, version: &str) -> Option<&PathBuf> {{
-        self.versions.get(version)
-    }}
-    
-    pub fn cache_version(&mut self, version: &str) -> Result<PathBuf, String> {{
-        let version_dir = self.cache_dir.join(format!("rust-{{}}", version));
-        
-        if version_dir.exists() {{
-            self.versions.insert(version.to_string(), version_dir.clone());
-            return Ok(version_dir);
-        }}
-        
-        // Download and cache the version
-        self.download_version(version, &version_dir)?;
-        self.versions.insert(version.to_string(), version_dir.clone());
-        
-        Ok(version_dir)
-    }}
-    
-    fn download_version(&self, version: &str, target: &Path) -> Result<(), String> {{
-        std::fs::create_dir_all(target)
-            .map_err(|e| format!("Failed to create cache dir: {{}}", e))?;
-        
-        // Try multiple download methods
-        if self.download_via_nix(version, target).is_ok() {{
-            return Ok(());
-        }}
-        
-        if self.download_via_rustup(version, target).is_ok() {{
-            return Ok(());
-        }}
-        
-        if self.download_via_github(version, target).is_ok() {{
-            return Ok(());
-        }}
-        
-        Err(format!("Failed to download Rust version {{}}", version))
-    }}
-    
-    fn download_via_nix(&self, version: &str, target: &Path) -> Result<(), String> {{
-        use std::process::Command;
-        
-        let nix_expr = format!(r#"
-        {{ pkgs ? import <nixpkgs> {{}} }}:
-        pkgs.rustc.override {{ version = "{}"; }}
-        "#, version);
-        
-        let temp_file = "/tmp/rust-version.nix";
-        std::fs::write(temp_file, nix_expr)
-            .map_err(|e| format!("Failed to write nix expr: {{}}", e))?;
-        
-        let output = Command::new("nix-build")
-            .arg(temp_file)
-            .arg("-o")
-            .arg(target)
-            .output()
-            .map_err(|e| format!("Nix build failed: {{}}", e))?;
-        
-        if output.status.success() {{
-            Ok(())
-        }} else {{
-            Err("Nix build failed".to_string())
-        }}
-    }}
-    
-    fn download_via_rustup(&self, version: &str, target: &Path) -> Result<(), String> {{
-        use std::process::Command;
-        
-        // Install via rustup
-        let install = Command::new("rustup")
-            .args(&["toolchain", "install", version])
-            .output()
-            .map_err(|e| format!("Rustup install failed: {{}}", e))?;
-        
-        if !install.status.success() {{
-            return Err("Rustup install failed".to_string());
-        }}
-        
-        // Copy to cache
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let rustup_path = format!("{{}}/.rustup/toolchains/{{}}", home, version);
-        
-        let copy = Command::new("cp")
-            .args(&["-r", &rustup_path, &target.to_string_lossy()])
-            .output()
-            .map_err(|e| format!("Copy failed: {{}}", e))?;
-        
-        if copy.status.success() {{
-            Ok(())
-        }} else {{
-            Err("Copy failed".to_string())
-        }}
-    }}
-    
-    fn download_via_github(&self, version: &str, target: &Path) -> Result<(), String> {{
-        use std::process::Command;
-        
-        let url = format!("https://github.com/rust-lang/rust/archive/{{}}.tar.gz", version);
-        let temp_file = "/tmp/rust-source.tar.gz";
-        
-        // Download
-        let download = Command::new("curl")
-            .args(&["-L", &url, "-o", temp_file])
-            .output()
-            .map_err(|e| format!("Download failed: {{}}", e))?;
-        
-        if !download.status.success() {{
-            return Err("Download failed".to_string());
-        }}
-        
-        // Extract
-        let extract = Command::new("tar")
-            .args(&["xzf", temp_file, "-C", &target.to_string_lossy(), "--strip-components=1"])
-            .output()
-            .map_err(|e| format!("Extract failed: {{}}", e))?;
-        
-        if extract.status.success() {{
-            Ok(())
-        }} else {{
-            Err("Extract failed".to_string())
-        }}
-    }}
-}}
-
-// Convenience macros for cached versions
-macro_rules! cached_rust {{
-    ($version:expr) => {{
-        RustCache::new().cache_version($version)
-    }};
-}}
-
-macro_rules! rust_src_path {{
-    ($version:expr) => {{
-        cached_rust!($version).map(|p| p.join("src"))
-    }};
-}}
-
-macro_rules! rust_bin_path {{
-    ($version:expr) => {{
-        cached_rust!($version).map(|p| p.join("bin/rustc"))
-    }};
-}}
-            "###, #cache_config);
-            
-            cache_system
-        }
-    }.into()
-}
\ No newline at end of file
+    if input.is_empty() {
+        return quote! { ::introspector_core::ToolchainCache::open_default() }.into();
+    }
+    let root = parse_macro_input!(input as syn::Expr);
+    quote! { ::introspector_core::ToolchainCache::open(#root) }.into()
+}
@@ -4,6 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr, Ident, Token};
 use syn::punctuated::Punctuated;
+use introspector_decl2_macros::decl2 as decl;
 
 /// Macro for interacting with Ollama.
 ///
@@ -13,7 +14,7 @@ use syn::punctuated::Punctuated;
 /// - `ollama!("model-info", "gemma3:12b")`
 /// - `ollama!("model-version", "gemma3:12b")`
 /// - `ollama!("temp", "0.8")`
-#[decl(fn, name = "ollama_impl", vis = "pub", hash = "8b6f8d81")]
+#[decl(fn, name = "ollama_impl", vis = "pub", hash = "fcf8a3af")]
 pub fn ollama_impl(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input with Punctuated::<LitStr, Token![,]>::parse_terminated);
 
@@ -9,7 +9,7 @@ use syn::{parse_macro_input, LitStr};
 // FKD-006: Hardcoded sample prices (145.32, 43250.67, etc.)
 // ═══════════════════════════════════════════════════════════════════════════════
 use introspector_decl2_macros::decl2;
-#[decl2(fn, name = "quant_impl", vis = "pub", hash = "f61e3ac4")]
+#[decl2(fn, name = "quant_impl", vis = "pub", hash = "b17c6059")]
 pub fn quant_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let strategy = input_str.value();
@@ -85,7 +85,7 @@ impl QuantStrategy {{
     }.into()
 }
 
-#[decl2(fn, name = "trading_impl", vis = "pub", hash = "829cd48f")]
+#[decl2(fn, name = "trading_impl", vis = "pub", hash = "9044cb5c")]
 pub fn trading_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let market_data = input_str.value();
@@ -145,7 +145,7 @@ impl TradingEngine {{
     }.into()
 }
 
-#[decl2(fn, name = "load_historical_impl", vis = "pub", hash = "2e4c1dc4")]
+#[decl2(fn, name = "load_historical_impl", vis = "pub", hash = "e8bf78a0")]
 pub fn load_historical_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let data_source = input_str.value();
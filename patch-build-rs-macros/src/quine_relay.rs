@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "language_quine_impl", vis = "pub", hash = "a2f1aef2")]
+#[decl(fn, name = "language_quine_impl", vis = "pub", hash = "31fc7538")]
 pub fn language_quine_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let language = input_str.value();
@@ -55,7 +56,7 @@ macro_rules! unknown_quine {
     }.into()
 }
 
-#[decl(fn, name = "compiler_macro_impl", vis = "pub", hash = "c38288b9")]
+#[decl(fn, name = "compiler_macro_impl", vis = "pub", hash = "adb8ddaa")]
 pub fn compiler_macro_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let compiler = input_str.value();
@@ -88,7 +89,7 @@ macro_rules! {}_compile {{
     }.into()
 }
 
-#[decl(fn, name = "bootstrap_cycle_impl", vis = "pub", hash = "a5105c28")]
+#[decl(fn, name = "bootstrap_cycle_impl", vis = "pub", hash = "ed821c5d")]
 pub fn bootstrap_cycle_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _cycle_desc = input_str.value();
@@ -132,7 +133,7 @@ impl BootstrapCycle {
     }.into()
 }
 
-#[decl(fn, name = "automorphic_orbit_impl", vis = "pub", hash = "3f53d389")]
+#[decl(fn, name = "automorphic_orbit_impl", vis = "pub", hash = "d5c6f310")]
 pub fn automorphic_orbit_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _orbit_config = input_str.value();
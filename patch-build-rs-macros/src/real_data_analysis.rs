@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // AUDIT TICKETS: This module attempts real analysis but has limitations
@@ -10,7 +11,7 @@ use syn::{parse_macro_input, LitStr};
 // TDO-001: Replace string matching with AST parsing
 // ═══════════════════════════════════════════════════════════════════════════════
 
-#[decl(fn, name = "real_rustc_analysis_impl", vis = "pub", hash = "3a7fcdcf")]
+#[decl(fn, name = "real_rustc_analysis_impl", vis = "pub", hash = "dea7ac1d")]
 pub fn real_rustc_analysis_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let source_path = input_str.value();
@@ -130,7 +131,7 @@ pub fn real_rustc_analysis_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "real_duplicate_detection_impl", vis = "pub", hash = "367cc850")]
+#[decl(fn, name = "real_duplicate_detection_impl", vis = "pub", hash = "dacad718")]
 pub fn real_duplicate_detection_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let source_path = input_str.value();
@@ -251,7 +252,7 @@ pub fn real_duplicate_detection_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "real_eigenmatrix_impl", vis = "pub", hash = "b0379fe6")]
+#[decl(fn, name = "real_eigenmatrix_impl", vis = "pub", hash = "d04bc629")]
 pub fn real_eigenmatrix_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let source_path = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "find_rustc_source_impl", vis = "pub", hash = "2ddd0c2a")]
+#[decl(fn, name = "find_rustc_source_impl", vis = "pub", hash = "cff92c14")]
 pub fn find_rustc_source_impl(input: TokenStream) -> TokenStream {
     let _input_str = parse_macro_input!(input as LitStr);
     
@@ -101,7 +102,7 @@ pub fn find_rustc_source_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "analyze_real_source_impl", vis = "pub", hash = "8fa19f26")]
+#[decl(fn, name = "analyze_real_source_impl", vis = "pub", hash = "a565df9a")]
 pub fn analyze_real_source_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let source_path = input_str.value();
@@ -231,7 +232,7 @@ pub fn analyze_real_source_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "prove_eigenvalues_impl", vis = "pub", hash = "8d336ae5")]
+#[decl(fn, name = "prove_eigenvalues_impl", vis = "pub", hash = "39931041")]
 pub fn prove_eigenvalues_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let analysis_data = input_str.value();
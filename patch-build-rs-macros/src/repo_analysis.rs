@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "repo_duplicate_analysis_impl", vis = "pub", hash = "eded0f3b")]
+#[decl(fn, name = "repo_duplicate_analysis_impl", vis = "pub", hash = "d2271485")]
 pub fn repo_duplicate_analysis_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let repo_path = input_str.value();
@@ -179,7 +180,7 @@ pub fn repo_duplicate_analysis_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "pattern_similarity_impl", vis = "pub", hash = "276b2c86")]
+#[decl(fn, name = "pattern_similarity_impl", vis = "pub", hash = "76470581")]
 pub fn pattern_similarity_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let patterns = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "rust_eigenmatrix_impl", vis = "pub", hash = "781ad46c")]
+#[decl(fn, name = "rust_eigenmatrix_impl", vis = "pub", hash = "b084859c")]
 pub fn rust_eigenmatrix_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rust_version = input_str.value();
@@ -89,7 +90,7 @@ pub fn rust_eigenmatrix_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "source_to_emoji_impl", vis = "pub", hash = "0a727f22")]
+#[decl(fn, name = "source_to_emoji_impl", vis = "pub", hash = "1bc8e8fd")]
 pub fn source_to_emoji_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let source_path = input_str.value();
@@ -156,7 +157,7 @@ pub fn source_to_emoji_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl(fn, name = "eigenform_verify_impl", vis = "pub", hash = "e08384d5")]
+#[decl(fn, name = "eigenform_verify_impl", vis = "pub", hash = "c1516d5a")]
 pub fn eigenform_verify_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let eigenmatrix = input_str.value();
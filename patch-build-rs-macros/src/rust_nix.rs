@@ -2,29 +2,35 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
 use introspector_decl2_macros::decl2;
-#[decl2(fn, name = "nix_rust_src_impl", vis = "pub", hash = "ba79e3cf")]
+use nix_core::{Expr, StrPart};
+
+/// The store path of the rustc source tarball of `<nixpkgs>`, evaluated
+/// (not built) when the generated code runs.
+#[decl2(fn, name = "nix_rust_src_impl", vis = "pub", hash = "d4467b41")]
 pub fn nix_rust_src_impl(_input: TokenStream) -> TokenStream {
+    let src = Expr::import_nixpkgs().select("rustc-unwrapped.src");
+    let nix_expr = Expr::interpolated([StrPart::Interp(src)]).to_nix();
+
     quote! {
         {
             use std::process::Command;
             
-            let output = Command::new("nix-store")
-                .args(&["--query", "--requisites", "/nix/store/*rustc*"])
-                .output()
-                .expect("Failed to query nix store");
+            let output = Command::new("nix")
+                .args(["--extra-experimental-features", "nix-command", "eval", "--raw", "--impure", "--expr", #nix_expr])
+                .output();
                 
-            let rust_store_path = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("rustc") && line.contains("src"))
-                .unwrap_or("/nix/store/rustc-src-not-found");
+            let rust_store_path = match output {
+                Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+                _ => "/nix/store/rustc-src-not-found".to_string(),
+            };
                 
             println!("cargo:warning=🦀 Found Rust source: {}", rust_store_path);
-            rust_store_path.to_string()
+            rust_store_path
         }
     }.into()
 }
 
-#[decl2(fn, name = "extract_decl_impl", vis = "pub", hash = "e544fc67")]
+#[decl2(fn, name = "extract_decl_impl", vis = "pub", hash = "5e6c9c4f")]
 pub fn extract_decl_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rust_file = input_str.value();
@@ -51,7 +57,7 @@ pub fn extract_decl_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "patch_rust_impl", vis = "pub", hash = "28c77f76")]
+#[decl2(fn, name = "patch_rust_impl", vis = "pub", hash = "57d9932e")]
 pub fn patch_rust_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let patch_desc = input_str.value();
@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
 use introspector_decl2_macros::decl2;
-#[decl2(fn, name = "sat_solve_unity_impl", vis = "pub", hash = "4969396d")]
+#[decl2(fn, name = "sat_solve_unity_impl", vis = "pub", hash = "73db18b3")]
 pub fn sat_solve_unity_impl(_input: TokenStream) -> TokenStream {
     quote! {
         {
@@ -49,7 +49,7 @@ c Unity constraint (everything maps to 1)
     }.into()
 }
 
-#[decl2(fn, name = "extract_lfunction_impl", vis = "pub", hash = "ea5943b4")]
+#[decl2(fn, name = "extract_lfunction_impl", vis = "pub", hash = "9a99b8c0")]
 pub fn extract_lfunction_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rust_vector = input_str.value();
@@ -88,7 +88,7 @@ pub fn extract_lfunction_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "matrix_decompose_impl", vis = "pub", hash = "6f4089f4")]
+#[decl2(fn, name = "matrix_decompose_impl", vis = "pub", hash = "e2ea97a1")]
 pub fn matrix_decompose_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let rust_data = input_str.value();
@@ -120,7 +120,7 @@ pub fn matrix_decompose_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "unity_proof_impl", vis = "pub", hash = "b68f65dd")]
+#[decl2(fn, name = "unity_proof_impl", vis = "pub", hash = "051b8718")]
 pub fn unity_proof_impl(_input: TokenStream) -> TokenStream {
     quote! {
         {
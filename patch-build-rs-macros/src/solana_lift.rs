@@ -10,7 +10,7 @@ use syn::{parse_macro_input, LitStr};
 // FKD-003: Placeholder block hash ('sample_block_hash')
 // ═══════════════════════════════════════════════════════════════════════════════
 use introspector_decl2_macros::decl2;
-#[decl2(fn, name = "purchase_blocks_impl", vis = "pub", hash = "6fdf3669")]
+#[decl2(fn, name = "purchase_blocks_impl", vis = "pub", hash = "2b17ab04")]
 pub fn purchase_blocks_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let api_provider = input_str.value();
@@ -48,7 +48,7 @@ pub fn purchase_blocks_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "lift_int_code_impl", vis = "pub", hash = "08978ec3")]
+#[decl2(fn, name = "lift_int_code_impl", vis = "pub", hash = "842b4cb3")]
 pub fn lift_int_code_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let block_data = input_str.value();
@@ -83,7 +83,7 @@ pub fn lift_int_code_impl(input: TokenStream) -> TokenStream {
     }.into()
 }
 
-#[decl2(fn, name = "ca_macro_impl", vis = "pub", hash = "b27fa6b1")]
+#[decl2(fn, name = "ca_macro_impl", vis = "pub", hash = "4e9fb306")]
 pub fn ca_macro_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let contract_address = input_str.value();
@@ -122,7 +122,7 @@ pub fn process_instruction(
     }.into()
 }
 
-#[decl2(fn, name = "token_macro_impl", vis = "pub", hash = "4b17819f")]
+#[decl2(fn, name = "token_macro_impl", vis = "pub", hash = "6f0bd582")]
 pub fn token_macro_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let token_params = input_str.value();
@@ -168,7 +168,7 @@ impl TokenContract {{
     }.into()
 }
 
-#[decl2(fn, name = "lp_macro_impl", vis = "pub", hash = "8cc3dd18")]
+#[decl2(fn, name = "lp_macro_impl", vis = "pub", hash = "bea50980")]
 pub fn lp_macro_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let lp_config = input_str.value();
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr, parse_str};
+use introspector_decl2_macros::decl2 as decl;
 
-#[decl(fn, name = "checktemplate_impl", vis = "pub", hash = "2bad40af")]
+#[decl(fn, name = "checktemplate_impl", vis = "pub", hash = "ce75a0f0")]
 pub fn checktemplate_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let template_code = input_str.value();
@@ -31,7 +32,7 @@ pub fn checktemplate_impl(input: TokenStream) -> TokenStream {
     }
 }
 
-#[decl(fn, name = "generate_checked_macros_impl", vis = "pub", hash = "08b7b728")]
+#[decl(fn, name = "generate_checked_macros_impl", vis = "pub", hash = "e9c0c4b9")]
 pub fn generate_checked_macros_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _config = input_str.value();
@@ -114,7 +115,7 @@ macro_rules! proc_macro_fn {
     }.into()
 }
 
-#[decl(fn, name = "mkbuildrs_checked_impl", vis = "pub", hash = "315bf7c5")]
+#[decl(fn, name = "mkbuildrs_checked_impl", vis = "pub", hash = "f9b2d92b")]
 pub fn mkbuildrs_checked_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let config = input_str.value();
@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, LitStr};
+use introspector_decl2_macros::decl2 as decl;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // AUDIT TICKETS: This module generates illustrative ZK proof code
@@ -11,7 +12,7 @@ use syn::{parse_macro_input, LitStr};
 // UNV-002: Metaphorical Monster group reference (196883)
 // ═══════════════════════════════════════════════════════════════════════════════
 
-#[decl(fn, name = "zk_witness_impl", vis = "pub", hash = "55aee10a")]
+#[decl(fn, name = "zk_witness_impl", vis = "pub", hash = "dc473e56")]
 pub fn zk_witness_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let graph_data = input_str.value();
@@ -85,7 +86,7 @@ impl GraphWitness {{
     }.into()
 }
 
-#[decl(fn, name = "plonk_circuit_impl", vis = "pub", hash = "1e600237")]
+#[decl(fn, name = "plonk_circuit_impl", vis = "pub", hash = "f2db63da")]
 pub fn plonk_circuit_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let circuit_desc = input_str.value();
@@ -160,7 +161,7 @@ impl<F: Field> RustcMorphismCircuit<F> {{
     }.into()
 }
 
-#[decl(fn, name = "stark_proof_impl", vis = "pub", hash = "8682a9fa")]
+#[decl(fn, name = "stark_proof_impl", vis = "pub", hash = "b9f47949")]
 pub fn stark_proof_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let _execution_trace = input_str.value();
@@ -229,7 +230,7 @@ impl RustcSTARK {{
     }.into()
 }
 
-#[decl(fn, name = "snark_verify_impl", vis = "pub", hash = "e0970718")]
+#[decl(fn, name = "snark_verify_impl", vis = "pub", hash = "da557069")]
 pub fn snark_verify_impl(input: TokenStream) -> TokenStream {
     let input_str = parse_macro_input!(input as LitStr);
     let proof_data = input_str.value();
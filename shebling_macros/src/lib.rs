@@ -38,7 +38,7 @@ impl Parse for SheblingArgs {
 }
 
 #[proc_macro]
-#[decl(fn, name = "shebling", vis = "pub", hash = "66a95163")]
+#[decl(fn, name = "shebling", vis = "pub", hash = "a5d68fd9")]
 pub fn shebling(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as SheblingArgs);
     let script_path = args.path.value();
@@ -95,7 +95,7 @@ pub fn shebling(input: TokenStream) -> TokenStream {
 // --- bash! macro definition ---
 
 #[proc_macro]
-#[decl(fn, name = "bash", vis = "pub", hash = "019a5c95")]
+#[decl(fn, name = "bash", vis = "pub", hash = "33a60607")]
 pub fn bash(input: TokenStream) -> TokenStream {
     let script_literal = parse_macro_input!(input as LitStr);
     let script_content = script_literal.value();
@@ -3,7 +3,7 @@ use patch_build_rs_macros::decl;
 use proc_macro::TokenStream;
 use quote::quote;
 
-#[decl(fn, name = "biosemiotic_impl", vis = "pub", hash = "4436be89")]
+#[decl(fn, name = "biosemiotic_impl", vis = "pub", hash = "b0bf5408")]
 pub fn biosemiotic_impl(input: TokenStream) -> TokenStream {
     let input_str = input.to_string(); // Get the raw token stream as a string
 
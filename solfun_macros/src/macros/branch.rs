@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "branch_impl", vis = "pub", hash = "70cfd19d")]
+#[decl(fn, name = "branch_impl", vis = "pub", hash = "7e60b2fe")]
 pub fn branch_impl(input: TokenStream) -> TokenStream {
     let branch_name = parse_macro_input!(input as LitStr);
     let span = branch_name.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "bug_impl", vis = "pub", hash = "cc2ff72d")]
+#[decl(fn, name = "bug_impl", vis = "pub", hash = "88393ccc")]
 pub fn bug_impl(input: TokenStream) -> TokenStream {
     let bug_description = parse_macro_input!(input as LitStr);
     let span = bug_description.span();
@@ -3,7 +3,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "codeberg_impl", vis = "pub", hash = "0d4c7787")]
+#[decl(fn, name = "codeberg_impl", vis = "pub", hash = "576a0929")]
 pub fn codeberg_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "codegen_impl", vis = "pub", hash = "0dab7d2f")]
+#[decl(fn, name = "codegen_impl", vis = "pub", hash = "36c2a060")]
 pub fn codegen_impl(input: TokenStream) -> TokenStream {
     let code_literal = parse_macro_input!(input as LitStr);
     let span = code_literal.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "compiler_parser_element_impl", vis = "pub", hash = "0eef2414")]
+#[decl(fn, name = "compiler_parser_element_impl", vis = "pub", hash = "5d58c8ec")]
 pub fn compiler_parser_element_impl(input: TokenStream) -> TokenStream {
     let element_name = parse_macro_input!(input as LitStr);
     let span = element_name.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "compiler_type_check_impl", vis = "pub", hash = "eb0a188f")]
+#[decl(fn, name = "compiler_type_check_impl", vis = "pub", hash = "4d9639a3")]
 pub fn compiler_type_check_impl(input: TokenStream) -> TokenStream {
     let code_snippet = parse_macro_input!(input as LitStr);
     let span = code_snippet.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "cwm_impl", vis = "pub", hash = "be6bed90")]
+#[decl(fn, name = "cwm_impl", vis = "pub", hash = "b4bb41b6")]
 pub fn cwm_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "daml_impl", vis = "pub", hash = "4d27600f")]
+#[decl(fn, name = "daml_impl", vis = "pub", hash = "c316c68c")]
 pub fn daml_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
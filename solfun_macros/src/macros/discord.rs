@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "discord_impl", vis = "pub", hash = "71566332")]
+#[decl(fn, name = "discord_impl", vis = "pub", hash = "13f4a824")]
 pub fn discord_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "euler_impl", vis = "pub", hash = "b26f3bf9")]
+#[decl(fn, name = "euler_impl", vis = "pub", hash = "9ab6c712")]
 pub fn euler_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "figlet_impl", vis = "pub", hash = "e0e55bdf")]
+#[decl(fn, name = "figlet_impl", vis = "pub", hash = "83b422f6")]
 pub fn figlet_impl(input: TokenStream) -> TokenStream {
     let text_literal = parse_macro_input!(input as LitStr);
     let span = text_literal.span();
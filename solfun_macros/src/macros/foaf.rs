@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "foaf_impl", vis = "pub", hash = "b71ebb67")]
+#[decl(fn, name = "foaf_impl", vis = "pub", hash = "636466cc")]
 pub fn foaf_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "fork_all_repos_impl", vis = "pub", hash = "eaae29c6")]
+#[decl(fn, name = "fork_all_repos_impl", vis = "pub", hash = "d0a9a45b")]
 pub fn fork_all_repos_impl(input: TokenStream) -> TokenStream {
     let org_name = parse_macro_input!(input as LitStr);
     let span = org_name.span();
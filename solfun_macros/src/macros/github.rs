@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "github_impl", vis = "pub", hash = "fe6f7b6e")]
+#[decl(fn, name = "github_impl", vis = "pub", hash = "7289128c")]
 pub fn github_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -3,7 +3,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "huggingface_impl", vis = "pub", hash = "5ed700cf")]
+#[decl(fn, name = "huggingface_impl", vis = "pub", hash = "e7b494c6")]
 pub fn huggingface_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "llm_impl", vis = "pub", hash = "ec706832")]
+#[decl(fn, name = "llm_impl", vis = "pub", hash = "4c3ab341")]
 pub fn llm_impl(input: TokenStream) -> TokenStream {
     let request = parse_macro_input!(input as LitStr);
     let request_content = request.value();
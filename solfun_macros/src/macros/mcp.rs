@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "mcp_impl", vis = "pub", hash = "7851d865")]
+#[decl(fn, name = "mcp_impl", vis = "pub", hash = "6c0e6258")]
 pub fn mcp_impl(input: TokenStream) -> TokenStream {
     let context_description = parse_macro_input!(input as LitStr);
     let span = context_description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "meme_impl", vis = "pub", hash = "d433e7fb")]
+#[decl(fn, name = "meme_impl", vis = "pub", hash = "d4cb7fd7")]
 pub fn meme_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "oil_impl", vis = "pub", hash = "ea1d6293")]
+#[decl(fn, name = "oil_impl", vis = "pub", hash = "bf812c98")]
 pub fn oil_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "osm_impl", vis = "pub", hash = "f3546a82")]
+#[decl(fn, name = "osm_impl", vis = "pub", hash = "e10ba13e")]
 pub fn osm_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
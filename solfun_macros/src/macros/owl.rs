@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "owl_impl", vis = "pub", hash = "6b285701")]
+#[decl(fn, name = "owl_impl", vis = "pub", hash = "17095212")]
 pub fn owl_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
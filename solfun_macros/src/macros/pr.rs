@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "pr_impl", vis = "pub", hash = "077c7684")]
+#[decl(fn, name = "pr_impl", vis = "pub", hash = "eb4dba14")]
 pub fn pr_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
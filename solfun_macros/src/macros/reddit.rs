@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "reddit_impl", vis = "pub", hash = "06cb7d7a")]
+#[decl(fn, name = "reddit_impl", vis = "pub", hash = "ca4268a0")]
 pub fn reddit_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "replace_all_git_hub_actions_impl", vis = "pub", hash = "613ea2f0")]
+#[decl(fn, name = "replace_all_git_hub_actions_impl", vis = "pub", hash = "2da6096e")]
 pub fn replace_all_git_hub_actions_impl(input: TokenStream) -> TokenStream {
     let new_workflow = parse_macro_input!(input as LitStr);
     let span = new_workflow.span();
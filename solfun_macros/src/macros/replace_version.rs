@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "replace_version_impl", vis = "pub", hash = "e5280982")]
+#[decl(fn, name = "replace_version_impl", vis = "pub", hash = "62536b3b")]
 pub fn replace_version_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "service_finder_impl", vis = "pub", hash = "7dd619c4")]
+#[decl(fn, name = "service_finder_impl", vis = "pub", hash = "536c3775")]
 pub fn service_finder_impl(input: TokenStream) -> TokenStream {
     let need_description = parse_macro_input!(input as LitStr);
     let span = need_description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "telegram_impl", vis = "pub", hash = "90c2aa78")]
+#[decl(fn, name = "telegram_impl", vis = "pub", hash = "6f49c975")]
 pub fn telegram_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "ticket_impl", vis = "pub", hash = "610caa5a")]
+#[decl(fn, name = "ticket_impl", vis = "pub", hash = "d3d54d8c")]
 pub fn ticket_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
@@ -19,7 +19,7 @@ impl Parse for ToolCallArgs {
     }
 }
 
-#[decl(fn, name = "toolcall_impl", vis = "pub", hash = "c02aaffd")]
+#[decl(fn, name = "toolcall_impl", vis = "pub", hash = "c3eecd59")]
 pub fn toolcall_impl(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as ToolCallArgs);
 
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "twitter_impl", vis = "pub", hash = "2749f031")]
+#[decl(fn, name = "twitter_impl", vis = "pub", hash = "2cecc01c")]
 pub fn twitter_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
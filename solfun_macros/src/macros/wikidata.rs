@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "wikidata_impl", vis = "pub", hash = "9f83104c")]
+#[decl(fn, name = "wikidata_impl", vis = "pub", hash = "70e08359")]
 pub fn wikidata_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
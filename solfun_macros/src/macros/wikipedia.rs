@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, LitStr};
 
-#[decl(fn, name = "wikipedia_impl", vis = "pub", hash = "c91cda37")]
+#[decl(fn, name = "wikipedia_impl", vis = "pub", hash = "7aaa54f8")]
 pub fn wikipedia_impl(input: TokenStream) -> TokenStream {
     let description = parse_macro_input!(input as LitStr);
     let span = description.span();
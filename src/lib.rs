@@ -4,3 +4,5 @@ macro_rules! fixme {
         compile_error!(concat!("FIXME: ", stringify!($($tt)*)));
     };
 }
+
+pub mod reflection;
@@ -0,0 +1,22 @@
+// src/reflection.rs
+//
+// Declarations registered by `#[decl]`/`#[decl2]` are collected at link time
+// into `introspector_decl_common`; this module is the crate-level entry point.
+
+use patch_build_rs_macros::decl;
+
+pub use introspector_decl_common::{
+    DeclInfo, ConstDeclMetadata, DeclQuery, DeclRegistry, DECL_REGISTRY,
+    get_all_declarations, get_declarations_by_name, get_declarations_by_type,
+    get_declarations_by_module, get_declarations_by_visibility, get_declaration_by_hash,
+};
+
+#[decl(fn, name = "all_decls", vis = "pub", hash = "be9f0d99")]
+pub fn all_decls() -> impl Iterator<Item = &'static DeclInfo> {
+    introspector_decl_common::all_decls()
+}
+
+#[decl(fn, name = "registry", vis = "pub", hash = "e0bc5095")]
+pub fn registry() -> &'static DeclRegistry {
+    &DECL_REGISTRY
+}